@@ -0,0 +1,59 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+
+/// 阻塞命令的等待队列，每个 key 上按阻塞的先后顺序排队
+/// 写入 key 的时候只唤醒最早的那个客户端，它处理完之后再唤醒下一个
+#[derive(Debug, Default)]
+pub struct BlockingKeys {
+    waiters: Mutex<HashMap<String, VecDeque<Arc<Notify>>>>,
+}
+
+impl BlockingKeys {
+    /// 在所有 keys 上排队，返回用于等待的 Notify
+    pub fn register(&self, keys: &[String]) -> Arc<Notify> {
+        let waiter = Arc::new(Notify::new());
+        let mut waiters = self.waiters.lock().unwrap();
+        for key in keys {
+            waiters
+                .entry(key.clone())
+                .or_default()
+                .push_back(waiter.clone());
+        }
+        waiter
+    }
+
+    pub fn unregister(&self, keys: &[String], waiter: &Arc<Notify>) {
+        let mut waiters = self.waiters.lock().unwrap();
+        for key in keys {
+            if let Some(queue) = waiters.get_mut(key) {
+                queue.retain(|w| !Arc::ptr_eq(w, waiter));
+                if queue.is_empty() {
+                    waiters.remove(key);
+                }
+            }
+        }
+    }
+
+    /// key 有新数据时唤醒最早阻塞的客户端
+    pub fn signal(&self, key: &str) {
+        let waiters = self.waiters.lock().unwrap();
+        if let Some(waiter) = waiters.get(key).and_then(|queue| queue.front()) {
+            // 如果客户端还没开始等待，notify_one 会保存一个 permit，不会丢失唤醒
+            waiter.notify_one();
+        }
+    }
+
+    /// key 有新数据时唤醒所有阻塞的客户端，例如 XADD 之后所有 XREAD 都能读到新消息
+    pub fn signal_all(&self, key: &str) {
+        let waiters = self.waiters.lock().unwrap();
+        if let Some(queue) = waiters.get(key) {
+            for waiter in queue {
+                waiter.notify_one();
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{hash::murmurhash64a, BackendError};
+
+// BF.ADD 自动创建时的默认参数，和 RedisBloom 一致
+const BF_DEFAULT_ERROR_RATE: f64 = 0.01;
+const BF_DEFAULT_CAPACITY: u64 = 100;
+const BF_DEFAULT_EXPANSION: u32 = 2;
+// 每扩容一次，新的过滤器误判率减半，保证总的误判率不超过 error_rate
+const BF_TIGHTENING_RATIO: f64 = 0.5;
+// BF.RESERVE 参数的上限，单个过滤器最多占用 512MB
+pub const BF_MAX_CAPACITY: u64 = 1 << 30;
+pub const BF_MAX_EXPANSION: u32 = 32768;
+const BF_MAX_BITS: u64 = 1 << 32;
+
+/// 可扩容的布隆过滤器，元素数量达到容量后追加一个更大的过滤器
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    layers: Vec<BloomLayer>,
+    // None 表示 NONSCALING
+    expansion: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+struct BloomLayer {
+    bits: Vec<u64>,
+    nbits: u64,
+    hashes: u32,
+    capacity: u64,
+    error_rate: f64,
+    count: u64,
+}
+
+impl BloomLayer {
+    /// bit 数超过上限时返回错误，不会分配内存
+    fn new(capacity: u64, error_rate: f64) -> Result<Self, BackendError> {
+        // 每个元素需要的 bit 数：-ln(p) / ln(2)^2
+        let bpe = -error_rate.ln() / std::f64::consts::LN_2.powi(2);
+        let nbits = (capacity as f64 * bpe).ceil();
+        if !nbits.is_finite() || nbits > BF_MAX_BITS as f64 {
+            return Err(BackendError::SizeTooLarge);
+        }
+        let nbits = (nbits as u64).max(64);
+        Ok(Self {
+            bits: vec![0; nbits.div_ceil(64) as usize],
+            nbits,
+            hashes: (std::f64::consts::LN_2 * bpe).ceil() as u32,
+            capacity,
+            error_rate,
+            count: 0,
+        })
+    }
+
+    // 双重哈希得到 k 个 bit 的位置
+    fn positions(&self, (h1, h2): (u64, u64)) -> impl Iterator<Item = u64> + '_ {
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.nbits)
+    }
+
+    fn contains(&self, hash: (u64, u64)) -> bool {
+        self.positions(hash)
+            .all(|pos| self.bits[(pos / 64) as usize] & (1 << (pos % 64)) != 0)
+    }
+
+    fn insert(&mut self, hash: (u64, u64)) {
+        let positions = self.positions(hash).collect::<Vec<_>>();
+        for pos in positions {
+            self.bits[(pos / 64) as usize] |= 1 << (pos % 64);
+        }
+        self.count += 1;
+    }
+}
+
+fn item_hash(item: &[u8]) -> (u64, u64) {
+    let h1 = murmurhash64a(item, 0xc6a4_a793_5bd1_e995);
+    (h1, murmurhash64a(item, h1))
+}
+
+impl BloomFilter {
+    pub fn new(
+        error_rate: f64,
+        capacity: u64,
+        expansion: Option<u32>,
+    ) -> Result<Self, BackendError> {
+        Ok(Self {
+            layers: vec![BloomLayer::new(capacity, error_rate)?],
+            expansion,
+        })
+    }
+
+    pub fn exists(&self, item: &[u8]) -> bool {
+        let hash = item_hash(item);
+        self.layers.iter().any(|layer| layer.contains(hash))
+    }
+
+    /// 元素可能已经存在时返回 false
+    pub fn add(&mut self, item: &[u8]) -> Result<bool, BackendError> {
+        let hash = item_hash(item);
+        if self.layers.iter().any(|layer| layer.contains(hash)) {
+            return Ok(false);
+        }
+
+        let last = self
+            .layers
+            .last()
+            .expect("bloom filter has at least one layer");
+        if last.count >= last.capacity {
+            let expansion = self.expansion.ok_or(BackendError::BloomFull)?;
+            // 扩容之后超过上限时和 NONSCALING 一样视为已满
+            let layer = last
+                .capacity
+                .checked_mul(expansion as u64)
+                .and_then(|capacity| {
+                    BloomLayer::new(capacity, last.error_rate * BF_TIGHTENING_RATIO).ok()
+                })
+                .ok_or(BackendError::BloomFull)?;
+            self.layers.push(layer);
+        }
+        if let Some(layer) = self.layers.last_mut() {
+            layer.insert(hash);
+        }
+        Ok(true)
+    }
+
+    /// 所有过滤器的总容量
+    pub fn capacity(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.capacity).sum()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn filters(&self) -> usize {
+        self.layers.len()
+    }
+}
+
+impl Default for BloomFilter {
+    fn default() -> Self {
+        Self::new(
+            BF_DEFAULT_ERROR_RATE,
+            BF_DEFAULT_CAPACITY,
+            Some(BF_DEFAULT_EXPANSION),
+        )
+        .expect("default bloom filter is within limits")
+    }
+}
+
+impl Backend {
+    /// expansion 为 None 表示 NONSCALING
+    pub fn bf_reserve(
+        &self,
+        key: String,
+        error_rate: f64,
+        capacity: u64,
+        expansion: Option<u32>,
+    ) -> Result<(), BackendError> {
+        self.write_typed(&key.clone(), "MBbloom--", || match self.bloom.entry(key) {
+            Entry::Occupied(_) => Err(BackendError::ItemExists),
+            Entry::Vacant(entry) => {
+                let filter = BloomFilter::new(error_rate, capacity, expansion)?;
+                self.watched.signal(entry.key());
+                self.notify(KeyspaceEvents::MODULE, "bf.reserve", entry.key());
+                entry.insert(filter);
+                Ok(())
+            }
+        })?
+    }
+
+    /// key 不存在时使用默认参数创建，每个元素单独返回结果
+    pub fn bf_add(
+        &self,
+        key: String,
+        items: &[Vec<u8>],
+    ) -> Result<Vec<Result<bool, BackendError>>, BackendError> {
+        self.write_typed(&key.clone(), "MBbloom--", || {
+            let mut filter = self.bloom.entry(key).or_default();
+            let ret = items
+                .iter()
+                .map(|item| filter.add(item))
+                .collect::<Vec<_>>();
+            self.watched.signal(filter.key());
+            self.notify(KeyspaceEvents::MODULE, "bf.add", filter.key());
+            ret
+        })
+    }
+
+    pub fn bf_exists(&self, key: &str, items: &[Vec<u8>]) -> Vec<bool> {
+        match self.bloom.get(key) {
+            Some(filter) => items.iter().map(|item| filter.exists(item)).collect(),
+            None => vec![false; items.len()],
+        }
+    }
+}
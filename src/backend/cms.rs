@@ -0,0 +1,148 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{hash::murmurhash64a, BackendError};
+
+// CMS.INITBYDIM 的计数器数量上限，最多占用 512MB
+pub const CMS_MAX_COUNTERS: usize = 1 << 26;
+
+/// Count-Min Sketch，depth 行 width 列的计数器，每行用不同的 seed 计算 hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u64>,
+    count: u64,
+}
+
+impl CountMinSketch {
+    pub fn new(width: usize, depth: usize) -> Result<Self, BackendError> {
+        let size = width
+            .checked_mul(depth)
+            .filter(|&size| size <= CMS_MAX_COUNTERS)
+            .ok_or(BackendError::SizeTooLarge)?;
+        Ok(Self {
+            width,
+            depth,
+            counters: vec![0; size],
+            count: 0,
+        })
+    }
+
+    fn positions<'a>(&'a self, item: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        (0..self.depth).map(move |row| {
+            row * self.width + (murmurhash64a(item, row as u64) % self.width as u64) as usize
+        })
+    }
+
+    /// 增加计数，返回增加之后的估计值
+    pub fn incr(&mut self, item: &[u8], increment: u64) -> u64 {
+        let positions = self.positions(item).collect::<Vec<_>>();
+        let mut min = u64::MAX;
+        for pos in positions {
+            self.counters[pos] = self.counters[pos].saturating_add(increment);
+            min = min.min(self.counters[pos]);
+        }
+        self.count = self.count.saturating_add(increment);
+        min
+    }
+
+    /// 所有行中最小的计数就是估计值
+    pub fn query(&self, item: &[u8]) -> u64 {
+        self.positions(item)
+            .map(|pos| self.counters[pos])
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl Backend {
+    pub fn cms_init(&self, key: String, width: usize, depth: usize) -> Result<(), BackendError> {
+        self.write_typed(&key.clone(), "CMSk-TYPE", || match self.cms.entry(key) {
+            Entry::Occupied(_) => Err(BackendError::CmsKeyExists),
+            Entry::Vacant(entry) => {
+                let cms = CountMinSketch::new(width, depth)?;
+                self.watched.signal(entry.key());
+                self.notify(KeyspaceEvents::MODULE, "cms.initbydim", entry.key());
+                entry.insert(cms);
+                Ok(())
+            }
+        })?
+    }
+
+    pub fn cms_incrby(
+        &self,
+        key: &str,
+        items: &[(Vec<u8>, u64)],
+    ) -> Result<Vec<u64>, BackendError> {
+        let mut cms = self.cms.get_mut(key).ok_or(BackendError::CmsKeyNotFound)?;
+        self.watched.signal(key);
+        self.notify(KeyspaceEvents::MODULE, "cms.incrby", key);
+        Ok(items
+            .iter()
+            .map(|(item, increment)| cms.incr(item, *increment))
+            .collect())
+    }
+
+    pub fn cms_query(&self, key: &str, items: &[Vec<u8>]) -> Result<Vec<u64>, BackendError> {
+        let cms = self.cms.get(key).ok_or(BackendError::CmsKeyNotFound)?;
+        Ok(items.iter().map(|item| cms.query(item)).collect())
+    }
+
+    /// 按权重合并 sources，覆盖 destination 原有的计数，所有 sketch 的大小必须相同
+    pub fn cms_merge(
+        &self,
+        destination: &str,
+        sources: &[String],
+        weights: &[u64],
+    ) -> Result<(), BackendError> {
+        // 先复制 source，避免同时持有多个 shard 的锁
+        let sources = sources
+            .iter()
+            .map(|key| {
+                self.cms
+                    .get(key)
+                    .map(|cms| cms.clone())
+                    .ok_or(BackendError::CmsKeyNotFound)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut dest = self
+            .cms
+            .get_mut(destination)
+            .ok_or(BackendError::CmsKeyNotFound)?;
+        if sources
+            .iter()
+            .any(|cms| cms.width != dest.width || cms.depth != dest.depth)
+        {
+            return Err(BackendError::CmsSizeMismatch);
+        }
+
+        let mut merged = CountMinSketch::new(dest.width, dest.depth)?;
+        for (cms, &weight) in sources.iter().zip(weights) {
+            for (m, c) in merged.counters.iter_mut().zip(cms.counters.iter()) {
+                *m = m.saturating_add(c.saturating_mul(weight));
+            }
+            merged.count = merged
+                .count
+                .saturating_add(cms.count.saturating_mul(weight));
+        }
+        *dest = merged;
+        self.watched.signal(destination);
+        self.notify(KeyspaceEvents::MODULE, "cms.merge", destination);
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::{Backend, WasmLimits};
+
+use super::{pubsub::glob_match, BackendError};
+
+/// CONFIG GET 和 CONFIG SET 支持的参数
+const PARAMETERS: &[&str] = &[
+    "client-output-buffer-limit",
+    "lua-time-limit",
+    "notify-keyspace-events",
+    "wasm-fuel-limit",
+    "wasm-memory-limit",
+];
+
+impl Backend {
+    /// 返回名字匹配 pattern 的参数和它们的值，参数名不区分大小写
+    pub fn config_get(&self, pattern: &str) -> Vec<(&'static str, String)> {
+        let pattern = pattern.to_ascii_lowercase();
+        PARAMETERS
+            .iter()
+            .filter(|name| glob_match(pattern.as_bytes(), name.as_bytes()))
+            .map(|&name| (name, self.config_value(name)))
+            .collect()
+    }
+
+    pub fn config_set(&self, name: &str, value: &str) -> Result<(), BackendError> {
+        let failed = |reason: &str| BackendError::ConfigSetFailed(name.to_string(), reason.into());
+        match name.to_ascii_lowercase().as_str() {
+            "lua-time-limit" => {
+                let ms: u64 = value
+                    .parse()
+                    .map_err(|_| failed("argument couldn't be parsed into an integer"))?;
+                self.set_lua_time_limit(Duration::from_millis(ms));
+            }
+            "client-output-buffer-limit" => {
+                let limit = parse_output_buffer_limit(value).map_err(failed)?;
+                if let Some(limit) = limit {
+                    self.set_pubsub_output_limit(limit);
+                }
+            }
+            "notify-keyspace-events" => {
+                self.set_notify_keyspace_events(value)
+                    .map_err(|_| failed("Invalid event class character. Use 'Ag$lshzxeKEtmdn'."))?;
+            }
+            "wasm-fuel-limit" => {
+                let fuel = value
+                    .parse()
+                    .map_err(|_| failed("argument couldn't be parsed into an integer"))?;
+                let limits = self.wasm_limits();
+                self.set_wasm_limits(WasmLimits { fuel, ..limits });
+            }
+            "wasm-memory-limit" => {
+                let memory =
+                    parse_memory(value).ok_or_else(|| failed("argument must be a memory value"))?;
+                let limits = self.wasm_limits();
+                self.set_wasm_limits(WasmLimits { memory, ..limits });
+            }
+            _ => return Err(BackendError::ConfigUnknownOption(name.to_string())),
+        }
+        Ok(())
+    }
+
+    fn config_value(&self, name: &str) -> String {
+        match name {
+            // 只实现了 pubsub 的硬限制
+            "client-output-buffer-limit" => format!("pubsub {} 0 0", self.pubsub_output_limit()),
+            "lua-time-limit" => self.lua_time_limit().as_millis().to_string(),
+            "notify-keyspace-events" => self.notify_keyspace_events().to_string(),
+            "wasm-fuel-limit" => self.wasm_limits().fuel.to_string(),
+            "wasm-memory-limit" => self.wasm_limits().memory.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+// 格式是 <class> <hard> <soft> <seconds> 的重复，返回 pubsub 的硬限制
+// 其它类型的客户端和软限制只做校验，不生效
+fn parse_output_buffer_limit(value: &str) -> Result<Option<usize>, &'static str> {
+    let args = value.split_whitespace().collect::<Vec<_>>();
+    if args.is_empty() || args.len() % 4 != 0 {
+        return Err("Wrong number of arguments in buffer limit configuration.");
+    }
+    let mut limit = None;
+    for chunk in args.chunks(4) {
+        let class = chunk[0].to_ascii_lowercase();
+        if !matches!(class.as_str(), "normal" | "replica" | "slave" | "pubsub") {
+            return Err("Invalid client class specified in buffer limit configuration.");
+        }
+        let hard = parse_memory(chunk[1]);
+        let soft = parse_memory(chunk[2]);
+        let seconds = chunk[3].parse::<u64>();
+        let (Some(hard), Some(_), Ok(_)) = (hard, soft, seconds) else {
+            return Err(
+                "Error in hard, soft or soft_seconds setting in buffer limit configuration.",
+            );
+        };
+        if class == "pubsub" {
+            limit = Some(hard);
+        }
+    }
+    Ok(limit)
+}
+
+// 和 Redis 一样支持 1k、1kb、1m、1mb、1g、1gb 这样的单位，k 是 1000，kb 是 1024
+fn parse_memory(s: &str) -> Option<usize> {
+    let s = s.to_ascii_lowercase();
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let unit = match &s[digits..] {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    s[..digits].parse::<usize>().ok()?.checked_mul(unit)
+}
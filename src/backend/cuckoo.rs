@@ -0,0 +1,246 @@
+use dashmap::mapref::entry::Entry;
+use rand::Rng;
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{hash::murmurhash64a, BackendError};
+
+// CF.ADD 自动创建时的默认参数，和 RedisBloom 一致
+const CF_DEFAULT_CAPACITY: u64 = 1024;
+const CF_DEFAULT_BUCKET_SIZE: usize = 2;
+const CF_DEFAULT_MAX_ITERATIONS: usize = 20;
+const CF_DEFAULT_EXPANSION: usize = 1;
+// 过滤器参数的上限，和 RedisBloom 一样桶最多 255 个槽位，单个过滤器最多占用 1GB
+pub const CF_MAX_BUCKET_SIZE: usize = 255;
+pub const CF_MAX_SLOTS: usize = 1 << 30;
+
+/// 布谷鸟过滤器，每个元素保存 8 bit 的指纹，支持删除
+/// 踢出次数达到上限时追加一个新的过滤器
+#[derive(Debug, Clone)]
+pub struct CuckooFilter {
+    tables: Vec<CuckooTable>,
+    bucket_size: usize,
+    max_iterations: usize,
+    // 0 表示不扩容
+    expansion: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CuckooTable {
+    // num_buckets * bucket_size 个槽位，0 表示空
+    slots: Vec<u8>,
+    num_buckets: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuckooOptions {
+    pub capacity: u64,
+    pub bucket_size: usize,
+    pub max_iterations: usize,
+    pub expansion: usize,
+}
+
+impl Default for CuckooOptions {
+    fn default() -> Self {
+        Self {
+            capacity: CF_DEFAULT_CAPACITY,
+            bucket_size: CF_DEFAULT_BUCKET_SIZE,
+            max_iterations: CF_DEFAULT_MAX_ITERATIONS,
+            expansion: CF_DEFAULT_EXPANSION,
+        }
+    }
+}
+
+// 指纹和第一个桶的 hash，指纹不能为 0
+fn fingerprint(item: &[u8]) -> (u8, u64) {
+    let hash = murmurhash64a(item, 0);
+    let fp = (hash >> 56) as u8;
+    (fp.max(1), hash)
+}
+
+fn fp_hash(fp: u8) -> u64 {
+    murmurhash64a(&[fp], 0)
+}
+
+impl CuckooTable {
+    fn new(num_buckets: usize, bucket_size: usize) -> Result<Self, BackendError> {
+        let num_buckets = num_buckets
+            .max(1)
+            .checked_next_power_of_two()
+            .ok_or(BackendError::SizeTooLarge)?;
+        let size = num_buckets
+            .checked_mul(bucket_size)
+            .filter(|&size| size <= CF_MAX_SLOTS)
+            .ok_or(BackendError::SizeTooLarge)?;
+        Ok(Self {
+            slots: vec![0; size],
+            num_buckets,
+        })
+    }
+
+    fn index(&self, hash: u64) -> usize {
+        (hash as usize) & (self.num_buckets - 1)
+    }
+
+    // 另一个桶只依赖当前桶和指纹，所以踢出时不需要原始元素
+    fn alt_index(&self, index: usize, fp: u8) -> usize {
+        self.index(index as u64 ^ fp_hash(fp))
+    }
+
+    fn bucket(&mut self, index: usize, bucket_size: usize) -> &mut [u8] {
+        &mut self.slots[index * bucket_size..(index + 1) * bucket_size]
+    }
+
+    fn contains(&self, fp: u8, hash: u64, bucket_size: usize) -> bool {
+        let i1 = self.index(hash);
+        let i2 = self.alt_index(i1, fp);
+        [i1, i2]
+            .iter()
+            .any(|&i| self.slots[i * bucket_size..(i + 1) * bucket_size].contains(&fp))
+    }
+
+    fn insert_free(&mut self, index: usize, fp: u8, bucket_size: usize) -> bool {
+        match self
+            .bucket(index, bucket_size)
+            .iter_mut()
+            .find(|s| **s == 0)
+        {
+            Some(slot) => {
+                *slot = fp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove(&mut self, fp: u8, hash: u64, bucket_size: usize) -> bool {
+        let i1 = self.index(hash);
+        let i2 = self.alt_index(i1, fp);
+        for i in [i1, i2] {
+            if let Some(slot) = self.bucket(i, bucket_size).iter_mut().find(|s| **s == fp) {
+                *slot = 0;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl CuckooFilter {
+    /// 参数超过上限时返回错误
+    pub fn new(opts: CuckooOptions) -> Result<Self, BackendError> {
+        if opts.bucket_size == 0
+            || opts.bucket_size > CF_MAX_BUCKET_SIZE
+            || opts.capacity > CF_MAX_SLOTS as u64
+        {
+            return Err(BackendError::SizeTooLarge);
+        }
+        let num_buckets = (opts.capacity as usize).div_ceil(opts.bucket_size);
+        Ok(Self {
+            tables: vec![CuckooTable::new(num_buckets, opts.bucket_size)?],
+            bucket_size: opts.bucket_size,
+            max_iterations: opts.max_iterations,
+            expansion: opts.expansion,
+        })
+    }
+
+    /// 允许重复添加，同一个元素添加多次需要删除多次
+    pub fn add(&mut self, item: &[u8]) -> Result<(), BackendError> {
+        let (fp, hash) = fingerprint(item);
+        let bucket_size = self.bucket_size;
+
+        // 先找空的槽位
+        for table in self.tables.iter_mut() {
+            let i1 = table.index(hash);
+            let i2 = table.alt_index(i1, fp);
+            if table.insert_free(i1, fp, bucket_size) || table.insert_free(i2, fp, bucket_size) {
+                return Ok(());
+            }
+        }
+
+        // 在最后一个过滤器中随机踢出已有的指纹
+        let mut rng = rand::thread_rng();
+        let table = self
+            .tables
+            .last_mut()
+            .expect("cuckoo filter has at least one table");
+        let (mut victim, mut index) = (fp, table.index(hash));
+        let mut path = Vec::with_capacity(self.max_iterations);
+        for _ in 0..self.max_iterations {
+            let slot = rng.gen_range(0..bucket_size);
+            std::mem::swap(&mut table.bucket(index, bucket_size)[slot], &mut victim);
+            path.push((index, slot));
+            index = table.alt_index(index, victim);
+            if table.insert_free(index, victim, bucket_size) {
+                return Ok(());
+            }
+        }
+
+        // 踢出失败，按相反的顺序恢复，然后把新元素放到一个新的过滤器中
+        for (index, slot) in path.into_iter().rev() {
+            std::mem::swap(&mut table.bucket(index, bucket_size)[slot], &mut victim);
+        }
+        if self.expansion == 0 {
+            return Err(BackendError::CuckooFull);
+        }
+        // 扩容之后超过上限时视为已满
+        let mut table = table
+            .num_buckets
+            .checked_mul(self.expansion)
+            .and_then(|num_buckets| CuckooTable::new(num_buckets, bucket_size).ok())
+            .ok_or(BackendError::CuckooFull)?;
+        let index = table.index(hash);
+        table.insert_free(index, fp, bucket_size);
+        self.tables.push(table);
+        Ok(())
+    }
+
+    pub fn exists(&self, item: &[u8]) -> bool {
+        let (fp, hash) = fingerprint(item);
+        self.tables
+            .iter()
+            .any(|table| table.contains(fp, hash, self.bucket_size))
+    }
+
+    /// 删除一次添加，元素不存在时返回 false
+    pub fn remove(&mut self, item: &[u8]) -> bool {
+        let (fp, hash) = fingerprint(item);
+        let bucket_size = self.bucket_size;
+        self.tables
+            .iter_mut()
+            .rev()
+            .any(|table| table.remove(fp, hash, bucket_size))
+    }
+}
+
+impl Backend {
+    pub fn cf_add(&self, key: String, item: &[u8]) -> Result<(), BackendError> {
+        self.write_typed(&key.clone(), "MBbloomCF", || {
+            let mut filter = match self.cuckoo.entry(key) {
+                Entry::Occupied(entry) => entry.into_ref(),
+                Entry::Vacant(entry) => entry.insert(CuckooFilter::new(CuckooOptions::default())?),
+            };
+            filter.add(item)?;
+            self.watched.signal(filter.key());
+            self.notify(KeyspaceEvents::MODULE, "cf.add", filter.key());
+            Ok(())
+        })?
+    }
+
+    pub fn cf_del(&self, key: &str, item: &[u8]) -> Result<bool, BackendError> {
+        let mut filter = self.cuckoo.get_mut(key).ok_or(BackendError::NotFound)?;
+        let removed = filter.remove(item);
+        if removed {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::MODULE, "cf.del", key);
+        }
+        Ok(removed)
+    }
+
+    pub fn cf_exists(&self, key: &str, item: &[u8]) -> bool {
+        self.cuckoo
+            .get(key)
+            .map(|filter| filter.exists(item))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use mlua::Lua;
+
+use crate::{Backend, BackendError};
+
+// FUNCTION DUMP 的格式：魔数 + 版本号 + 每个库的代码（4 字节长度 + 内容）+ 前面所有字节的 SHA1 的前 8 字节
+const DUMP_MAGIC: &[u8] = b"FUNC";
+const DUMP_VERSION: u8 = 1;
+const DUMP_CHECKSUM_LEN: usize = 8;
+
+/// 函数可以声明的标记，只有 no-writes 会影响执行
+pub const FUNCTION_FLAGS: [&str; 5] = [
+    "no-writes",
+    "allow-oom",
+    "allow-stale",
+    "no-cluster",
+    "allow-cross-slot-keys",
+];
+
+/// FUNCTION LOAD 加载的函数库，执行库代码时通过 redis.register_function 注册函数
+#[derive(Debug, Clone)]
+pub struct Library {
+    pub name: String,
+    pub code: String,
+    pub functions: Vec<FunctionInfo>,
+    // 执行过库代码的解释器，FCALL 直接调用其中注册的函数，不再重新执行库代码
+    pub(crate) lua: Arc<Mutex<Lua>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub flags: Vec<String>,
+}
+
+impl FunctionInfo {
+    pub fn no_writes(&self) -> bool {
+        self.flags.iter().any(|flag| flag == "no-writes")
+    }
+}
+
+/// FUNCTION RESTORE 遇到同名库时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestorePolicy {
+    // 有同名的库时返回错误
+    Append,
+    // 替换同名的库
+    Replace,
+    // 先删除所有的库
+    Flush,
+}
+
+/// 库名 -> 函数库，函数名在所有库中唯一
+#[derive(Debug, Default)]
+pub struct Functions {
+    libraries: Mutex<BTreeMap<String, Library>>,
+}
+
+// 检查库名和函数名是否和已有的库冲突，replace 为 true 时忽略同名的库
+fn check_conflict(
+    libraries: &BTreeMap<String, Library>,
+    library: &Library,
+    replace: bool,
+) -> Result<(), BackendError> {
+    if !replace && libraries.contains_key(&library.name) {
+        return Err(BackendError::LibraryExists(library.name.clone()));
+    }
+    for other in libraries.values().filter(|l| l.name != library.name) {
+        for function in &library.functions {
+            if other.functions.iter().any(|f| f.name == function.name) {
+                return Err(BackendError::FunctionExists(function.name.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解析 FUNCTION DUMP 生成的数据，返回每个库的代码
+pub fn decode_function_dump(payload: &[u8]) -> Result<Vec<String>, BackendError> {
+    let body_len = payload
+        .len()
+        .checked_sub(DUMP_CHECKSUM_LEN)
+        .ok_or(BackendError::BadDumpPayload)?;
+    let (body, checksum) = payload.split_at(body_len);
+    if !body.starts_with(DUMP_MAGIC)
+        || body.get(DUMP_MAGIC.len()) != Some(&DUMP_VERSION)
+        || dump_checksum(body) != checksum
+    {
+        return Err(BackendError::BadDumpPayload);
+    }
+
+    let mut codes = Vec::new();
+    let mut rest = &body[DUMP_MAGIC.len() + 1..];
+    while !rest.is_empty() {
+        let (len, tail) = rest
+            .split_at_checked(4)
+            .ok_or(BackendError::BadDumpPayload)?;
+        let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+        let (code, tail) = tail
+            .split_at_checked(len)
+            .ok_or(BackendError::BadDumpPayload)?;
+        let code = String::from_utf8(code.to_vec()).map_err(|_| BackendError::BadDumpPayload)?;
+        codes.push(code);
+        rest = tail;
+    }
+    Ok(codes)
+}
+
+fn dump_checksum(body: &[u8]) -> [u8; DUMP_CHECKSUM_LEN] {
+    let digest = sha1_smol::Sha1::from(body).digest().bytes();
+    digest[..DUMP_CHECKSUM_LEN].try_into().unwrap()
+}
+
+impl Backend {
+    /// 保存函数库，返回库名
+    pub fn function_load(&self, library: Library, replace: bool) -> Result<String, BackendError> {
+        let mut libraries = self.functions.libraries.lock().unwrap();
+        check_conflict(&libraries, &library, replace)?;
+        let name = library.name.clone();
+        libraries.insert(name.clone(), library);
+        Ok(name)
+    }
+
+    /// 查找函数所在的库
+    pub fn function_get(&self, name: &str) -> Option<(Library, FunctionInfo)> {
+        let libraries = self.functions.libraries.lock().unwrap();
+        libraries.values().find_map(|library| {
+            library
+                .functions
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| (library.clone(), f.clone()))
+        })
+    }
+
+    /// 按库名排序返回所有的库
+    pub fn function_list(&self) -> Vec<Library> {
+        let libraries = self.functions.libraries.lock().unwrap();
+        libraries.values().cloned().collect()
+    }
+
+    pub fn function_delete(&self, name: &str) -> Result<(), BackendError> {
+        let mut libraries = self.functions.libraries.lock().unwrap();
+        libraries
+            .remove(name)
+            .map(|_| ())
+            .ok_or(BackendError::LibraryNotFound)
+    }
+
+    pub fn function_flush(&self) {
+        self.functions.libraries.lock().unwrap().clear();
+    }
+
+    /// 序列化所有库的代码，恢复时重新执行代码注册函数
+    pub fn function_dump(&self) -> Vec<u8> {
+        let libraries = self.functions.libraries.lock().unwrap();
+        let mut payload = DUMP_MAGIC.to_vec();
+        payload.push(DUMP_VERSION);
+        for library in libraries.values() {
+            payload.extend_from_slice(&(library.code.len() as u32).to_be_bytes());
+            payload.extend_from_slice(library.code.as_bytes());
+        }
+        let checksum = dump_checksum(&payload);
+        payload.extend_from_slice(&checksum);
+        payload
+    }
+
+    /// 恢复 FUNCTION DUMP 导出的库，任何一个库冲突时都不做修改
+    pub fn function_restore(
+        &self,
+        restored: Vec<Library>,
+        policy: RestorePolicy,
+    ) -> Result<(), BackendError> {
+        let mut libraries = self.functions.libraries.lock().unwrap();
+        let mut merged = match policy {
+            RestorePolicy::Flush => BTreeMap::new(),
+            _ => libraries.clone(),
+        };
+        for library in restored {
+            check_conflict(&merged, &library, policy == RestorePolicy::Replace)?;
+            merged.insert(library.name.clone(), library);
+        }
+        *libraries = merged;
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+use std::{collections::HashSet, ops::Bound};
+
+use crate::Backend;
+
+use super::{BackendError, ZAddFlags, ZRangeBy};
+
+// 和 redis 保持一致，纬度的范围是 web mercator 可以表示的范围
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+const GEO_STEP_MAX: u32 = 26;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const MERCATOR_MAX: f64 = 20037726.37;
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// 搜索的中心点
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoFrom {
+    Member(String),
+    LonLat(f64, f64),
+}
+
+/// 搜索的范围，单位都是米
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoShape {
+    Radius(f64),
+    Box { width: f64, height: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoSearchOptions {
+    pub from: GeoFrom,
+    pub shape: GeoShape,
+    // None 表示不排序，Some(true) 表示按距离从远到近
+    pub desc: Option<bool>,
+    // 0 表示不限制
+    pub count: usize,
+    // 找到 count 个之后立即返回，不保证是最近的
+    pub any: bool,
+}
+
+/// 搜索结果，dist 的单位是米
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoResult {
+    pub member: String,
+    pub dist: f64,
+    pub hash: u64,
+    pub lon: f64,
+    pub lat: f64,
+}
+
+// 一个 geohash 格子的经纬度范围
+#[derive(Debug, Clone, Copy)]
+struct GeoArea {
+    lon: (f64, f64),
+    lat: (f64, f64),
+}
+
+// 把 x 放在偶数位，y 放在奇数位
+fn interleave64(x: u32, y: u32) -> u64 {
+    fn spread(v: u32) -> u64 {
+        let mut v = v as u64;
+        v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+        v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+        v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+        v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+        (v | (v << 1)) & 0x5555_5555_5555_5555
+    }
+    spread(x) | (spread(y) << 1)
+}
+
+fn deinterleave64(v: u64) -> (u32, u32) {
+    fn squash(v: u64) -> u32 {
+        let mut v = v & 0x5555_5555_5555_5555;
+        v = (v | (v >> 1)) & 0x3333_3333_3333_3333;
+        v = (v | (v >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+        v = (v | (v >> 4)) & 0x00FF_00FF_00FF_00FF;
+        v = (v | (v >> 8)) & 0x0000_FFFF_0000_FFFF;
+        ((v | (v >> 16)) & 0x0000_0000_FFFF_FFFF) as u32
+    }
+    (squash(v), squash(v >> 1))
+}
+
+fn encode_with(lon: f64, lat: f64, lon_range: (f64, f64), lat_range: (f64, f64), step: u32) -> u64 {
+    let scale = (1u64 << step) as f64;
+    let lat_offset = (lat - lat_range.0) / (lat_range.1 - lat_range.0) * scale;
+    let lon_offset = (lon - lon_range.0) / (lon_range.1 - lon_range.0) * scale;
+    interleave64(lat_offset as u32, lon_offset as u32)
+}
+
+fn encode(lon: f64, lat: f64, step: u32) -> u64 {
+    encode_with(
+        lon,
+        lat,
+        (GEO_LONG_MIN, GEO_LONG_MAX),
+        (GEO_LAT_MIN, GEO_LAT_MAX),
+        step,
+    )
+}
+
+fn decode_area(bits: u64, step: u32) -> GeoArea {
+    let (ilat, ilon) = deinterleave64(bits);
+    let scale = (1u64 << step) as f64;
+    let lat_scale = GEO_LAT_MAX - GEO_LAT_MIN;
+    let lon_scale = GEO_LONG_MAX - GEO_LONG_MIN;
+    GeoArea {
+        lat: (
+            GEO_LAT_MIN + (ilat as f64 / scale) * lat_scale,
+            GEO_LAT_MIN + ((ilat as f64 + 1.0) / scale) * lat_scale,
+        ),
+        lon: (
+            GEO_LONG_MIN + (ilon as f64 / scale) * lon_scale,
+            GEO_LONG_MIN + ((ilon as f64 + 1.0) / scale) * lon_scale,
+        ),
+    }
+}
+
+/// 经纬度编码成 52 bit 的 geohash，作为有序集合的分数
+pub fn geohash_encode(lon: f64, lat: f64) -> u64 {
+    encode(lon, lat, GEO_STEP_MAX)
+}
+
+/// 分数解码成格子中心点的经纬度
+pub fn geohash_decode(bits: u64) -> (f64, f64) {
+    let area = decode_area(bits, GEO_STEP_MAX);
+    let lon = ((area.lon.0 + area.lon.1) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+    let lat = ((area.lat.0 + area.lat.1) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+    (lon, lat)
+}
+
+/// 标准的 11 位 geohash 字符串，纬度的范围是 -90 到 90
+pub fn geohash_string(bits: u64) -> String {
+    let (lon, lat) = geohash_decode(bits);
+    let bits = encode_with(lon, lat, (-180.0, 180.0), (-90.0, 90.0), GEO_STEP_MAX);
+    (0..11)
+        .map(|i| {
+            // 52 bit 只够 10 个字符，最后一个字符用 0 补齐
+            let idx = if i == 10 {
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEOHASH_ALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// haversine 公式计算两点之间的距离，单位是米
+pub fn geo_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1r, lat2r) = (lat1.to_radians(), lat2.to_radians());
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let v = ((lon2 - lon1).to_radians() / 2.0).sin();
+    let a = u * u + lat1r.cos() * lat2r.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+impl GeoShape {
+    // 中心点到边界的最远距离，用于估算 geohash 的精度
+    fn radius(&self) -> f64 {
+        match *self {
+            GeoShape::Radius(radius) => radius,
+            GeoShape::Box { width, height } => {
+                ((width / 2.0).powi(2) + (height / 2.0).powi(2)).sqrt()
+            }
+        }
+    }
+
+    // 返回 (min_lon, min_lat, max_lon, max_lat)
+    fn bounding_box(&self, lon: f64, lat: f64) -> (f64, f64, f64, f64) {
+        let (width, height) = match *self {
+            GeoShape::Radius(radius) => (radius, radius),
+            GeoShape::Box { width, height } => (width / 2.0, height / 2.0),
+        };
+        let lat_delta = (height / EARTH_RADIUS_IN_METERS).to_degrees();
+        let lon_delta_top =
+            (width / EARTH_RADIUS_IN_METERS / (lat + lat_delta).to_radians().cos()).to_degrees();
+        let lon_delta_bottom =
+            (width / EARTH_RADIUS_IN_METERS / (lat - lat_delta).to_radians().cos()).to_degrees();
+        // 南半球越往南经度方向越宽
+        let lon_delta = if lat < 0.0 {
+            lon_delta_bottom
+        } else {
+            lon_delta_top
+        };
+        (
+            lon - lon_delta,
+            lat - lat_delta,
+            lon + lon_delta,
+            lat + lat_delta,
+        )
+    }
+
+    // 在范围内时返回距离
+    fn distance(&self, lon: f64, lat: f64, x: f64, y: f64) -> Option<f64> {
+        match *self {
+            GeoShape::Radius(radius) => {
+                let dist = geo_distance(lon, lat, x, y);
+                (dist <= radius).then_some(dist)
+            }
+            GeoShape::Box { width, height } => {
+                let lat_dist = geo_distance(x, y, x, lat);
+                if lat_dist > height / 2.0 {
+                    return None;
+                }
+                let lon_dist = geo_distance(x, y, lon, y);
+                if lon_dist > width / 2.0 {
+                    return None;
+                }
+                Some(geo_distance(lon, lat, x, y))
+            }
+        }
+    }
+}
+
+fn estimate_steps(mut range: f64, lat: f64) -> u32 {
+    if range == 0.0 {
+        return GEO_STEP_MAX;
+    }
+    let mut step: i32 = 1;
+    while range < MERCATOR_MAX {
+        range *= 2.0;
+        step += 1;
+    }
+    // 保证搜索的圆在 9 个格子以内
+    step -= 2;
+    if !(-66.0..=66.0).contains(&lat) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&lat) {
+            step -= 1;
+        }
+    }
+    step.clamp(1, GEO_STEP_MAX as i32) as u32
+}
+
+// 中心格子和周围 8 个格子，下标为 (纬度偏移, 经度偏移)
+const NEIGHBORS: [(i64, i64); 9] = [
+    (0, 0),
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+fn neighbor(bits: u64, step: u32, (dlat, dlon): (i64, i64)) -> u64 {
+    let (ilat, ilon) = deinterleave64(bits);
+    let mask = (1i64 << step) - 1;
+    let ilat = (ilat as i64 + dlat) & mask;
+    let ilon = (ilon as i64 + dlon) & mask;
+    interleave64(ilat as u32, ilon as u32)
+}
+
+// 和 redis 的 geohashCalculateAreasByShapeWGS84 一样计算需要查询的格子，返回分数的范围 [min, max)
+fn search_ranges(lon: f64, lat: f64, shape: &GeoShape) -> Vec<(u64, u64)> {
+    let (min_lon, min_lat, max_lon, max_lat) = shape.bounding_box(lon, lat);
+    let mut step = estimate_steps(shape.radius(), lat);
+    let mut bits = encode(lon, lat, step);
+
+    // 周围的格子不能覆盖搜索范围时降低精度
+    let area = |dir| decode_area(neighbor(bits, step, dir), step);
+    let decrease = area((1, 0)).lat.1 < max_lat
+        || area((-1, 0)).lat.0 > min_lat
+        || area((0, 1)).lon.1 < max_lon
+        || area((0, -1)).lon.0 > min_lon;
+    if step > 1 && decrease {
+        step -= 1;
+        bits = encode(lon, lat, step);
+    }
+
+    // 排除完全在搜索范围之外的格子
+    let center = decode_area(bits, step);
+    let excluded = |(dlat, dlon): (i64, i64)| {
+        step >= 2
+            && ((dlat < 0 && center.lat.0 < min_lat)
+                || (dlat > 0 && center.lat.1 > max_lat)
+                || (dlon < 0 && center.lon.0 < min_lon)
+                || (dlon > 0 && center.lon.1 > max_lon))
+    };
+
+    let shift = (GEO_STEP_MAX - step) * 2;
+    let mut seen = HashSet::new();
+    NEIGHBORS
+        .iter()
+        .filter(|&&dir| !excluded(dir))
+        .map(|&dir| neighbor(bits, step, dir))
+        .filter(|&bits| seen.insert(bits))
+        .map(|bits| (bits << shift, (bits + 1) << shift))
+        .collect()
+}
+
+impl Backend {
+    /// 添加位置，返回新增的数量，CH 时返回修改的数量
+    pub fn geoadd(
+        &self,
+        key: String,
+        items: Vec<(f64, f64, String)>,
+        flags: ZAddFlags,
+    ) -> Result<usize, BackendError> {
+        let members = items
+            .into_iter()
+            .map(|(lon, lat, member)| (geohash_encode(lon, lat) as f64, member))
+            .collect();
+        self.zadd_with_event(key, members, flags, "geoadd")
+    }
+
+    pub fn geopos(&self, key: &str, member: &str) -> Option<(f64, f64)> {
+        self.zscore(key, member)
+            .map(|score| geohash_decode(score as u64))
+    }
+
+    /// 两个位置之间的距离，单位是米
+    pub fn geodist(&self, key: &str, member1: &str, member2: &str) -> Option<f64> {
+        let (lon1, lat1) = self.geopos(key, member1)?;
+        let (lon2, lat2) = self.geopos(key, member2)?;
+        Some(geo_distance(lon1, lat1, lon2, lat2))
+    }
+
+    pub fn geohash(&self, key: &str, member: &str) -> Option<String> {
+        self.zscore(key, member)
+            .map(|score| geohash_string(score as u64))
+    }
+
+    pub fn geosearch(
+        &self,
+        key: &str,
+        opts: &GeoSearchOptions,
+    ) -> Result<Vec<GeoResult>, BackendError> {
+        let zset = match self.zset.get(key) {
+            Some(zset) => zset,
+            None => return Ok(vec![]),
+        };
+        let (lon, lat) = match &opts.from {
+            GeoFrom::LonLat(lon, lat) => (*lon, *lat),
+            GeoFrom::Member(member) => zset
+                .score(member)
+                .map(|score| geohash_decode(score as u64))
+                .ok_or(BackendError::GeoMemberNotFound)?,
+        };
+
+        let mut results = Vec::new();
+        'search: for (min, max) in search_ranges(lon, lat, &opts.shape) {
+            let by = ZRangeBy::Score(Bound::Included(min as f64), Bound::Excluded(max as f64));
+            for (member, score) in zset.range(&by, false) {
+                let hash = score as u64;
+                let (x, y) = geohash_decode(hash);
+                if let Some(dist) = opts.shape.distance(lon, lat, x, y) {
+                    results.push(GeoResult {
+                        member,
+                        dist,
+                        hash,
+                        lon: x,
+                        lat: y,
+                    });
+                    if opts.any && results.len() == opts.count {
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        // 指定 COUNT 但是没有指定排序时，默认按距离从近到远
+        let desc = match opts.desc {
+            None if opts.count > 0 && !opts.any => Some(false),
+            desc => desc,
+        };
+        match desc {
+            Some(false) => results.sort_by(|a, b| a.dist.total_cmp(&b.dist)),
+            Some(true) => results.sort_by(|a, b| b.dist.total_cmp(&a.dist)),
+            None => {}
+        }
+        if opts.count > 0 {
+            results.truncate(opts.count);
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,190 @@
+/// Gorilla 压缩的数据块：时间戳保存二阶差分，值保存和上一个值异或之后的有效位
+/// 只能按时间戳递增的顺序追加，乱序写入时需要解码之后重新编码
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Chunk {
+    data: Vec<u8>,
+    bits: usize,
+    count: usize,
+    first_ts: i64,
+    last_ts: i64,
+    last_delta: i64,
+    last_value: u64,
+    leading: u32,
+    trailing: u32,
+}
+
+// 二阶差分的编码方式：(前缀, 前缀长度, 数据位数)
+const DOD_ENCODINGS: [(u64, usize, usize); 3] = [(0b10, 2, 7), (0b110, 3, 9), (0b1110, 4, 12)];
+
+impl Chunk {
+    pub(crate) fn from_samples(samples: &[(i64, f64)]) -> Self {
+        let mut chunk = Chunk::default();
+        for &(ts, value) in samples {
+            chunk.push(ts, value);
+        }
+        chunk
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.count
+    }
+
+    pub(crate) fn first_ts(&self) -> i64 {
+        self.first_ts
+    }
+
+    pub(crate) fn last_ts(&self) -> i64 {
+        self.last_ts
+    }
+
+    pub(crate) fn last_value(&self) -> f64 {
+        f64::from_bits(self.last_value)
+    }
+
+    /// 压缩之后占用的字节数
+    pub(crate) fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write_bits(&mut self, value: u64, n: usize) {
+        for i in (0..n).rev() {
+            if self.bits.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                let last = self.data.len() - 1;
+                self.data[last] |= 1 << (7 - self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+
+    /// 调用方保证 ts 大于最后一个时间戳
+    pub(crate) fn push(&mut self, ts: i64, value: f64) {
+        let value = value.to_bits();
+        if self.count == 0 {
+            self.write_bits(ts as u64, 64);
+            self.write_bits(value, 64);
+            self.first_ts = ts;
+            self.leading = u32::MAX;
+        } else {
+            let delta = ts - self.last_ts;
+            self.write_dod(delta - self.last_delta);
+            self.write_value(value ^ self.last_value);
+            self.last_delta = delta;
+        }
+        self.last_ts = ts;
+        self.last_value = value;
+        self.count += 1;
+    }
+
+    fn write_dod(&mut self, dod: i64) {
+        if dod == 0 {
+            self.write_bits(0, 1);
+            return;
+        }
+        for (prefix, prefix_len, n) in DOD_ENCODINGS {
+            let limit = 1i64 << (n - 1);
+            if (-limit..limit).contains(&dod) {
+                self.write_bits(prefix, prefix_len);
+                self.write_bits(dod as u64 & ((1 << n) - 1), n);
+                return;
+            }
+        }
+        self.write_bits(0b1111, 4);
+        self.write_bits(dod as u64, 64);
+    }
+
+    fn write_value(&mut self, xor: u64) {
+        if xor == 0 {
+            self.write_bits(0, 1);
+            return;
+        }
+        let leading = xor.leading_zeros().min(31);
+        let trailing = xor.trailing_zeros();
+        // 有效位落在上一次的范围内时复用上一次的 leading 和 trailing
+        if self.leading != u32::MAX && leading >= self.leading && trailing >= self.trailing {
+            self.write_bits(0b10, 2);
+            let n = 64 - self.leading - self.trailing;
+            self.write_bits(xor >> self.trailing, n as usize);
+        } else {
+            let n = 64 - leading - trailing;
+            self.write_bits(0b11, 2);
+            self.write_bits(leading as u64, 5);
+            // 长度 64 保存为 0
+            self.write_bits(n as u64 & 0x3f, 6);
+            self.write_bits(xor >> trailing, n as usize);
+            self.leading = leading;
+            self.trailing = trailing;
+        }
+    }
+
+    /// 解码所有的数据点
+    pub(crate) fn samples(&self) -> Vec<(i64, f64)> {
+        let mut reader = BitReader {
+            data: &self.data,
+            pos: 0,
+        };
+        let mut samples = Vec::with_capacity(self.count);
+        if self.count == 0 {
+            return samples;
+        }
+
+        let mut ts = reader.read(64) as i64;
+        let mut value = reader.read(64);
+        let (mut delta, mut leading, mut trailing) = (0i64, 0u32, 0u32);
+        samples.push((ts, f64::from_bits(value)));
+        for _ in 1..self.count {
+            delta += reader.read_dod();
+            ts += delta;
+
+            if reader.read(1) == 1 {
+                if reader.read(1) == 1 {
+                    leading = reader.read(5) as u32;
+                    let n = match reader.read(6) as u32 {
+                        0 => 64,
+                        n => n,
+                    };
+                    trailing = 64 - leading - n;
+                }
+                let n = 64 - leading - trailing;
+                value ^= reader.read(n as usize) << trailing;
+            }
+            samples.push((ts, f64::from_bits(value)));
+        }
+        samples
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, n: usize) -> u64 {
+        let mut value = 0u64;
+        for _ in 0..n {
+            let bit = (self.data[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.pos += 1;
+        }
+        value
+    }
+
+    fn read_dod(&mut self) -> i64 {
+        // 前缀中 1 的个数决定数据的位数
+        let mut ones = 0;
+        while ones < 4 && self.read(1) == 1 {
+            ones += 1;
+        }
+        let n = match ones {
+            0 => return 0,
+            1..=3 => DOD_ENCODINGS[ones - 1].2,
+            _ => return self.read(64) as i64,
+        };
+        // 符号扩展
+        let shift = 64 - n;
+        ((self.read(n) << shift) as i64) >> shift
+    }
+}
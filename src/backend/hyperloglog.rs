@@ -0,0 +1,381 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, BulkString, KeyspaceEvents, RespFrame};
+
+use super::{hash::murmurhash64a, BackendError};
+
+// 和 redis 保持一致：2^14 个 6 bit 的寄存器，16 字节的头部
+const HLL_P: u32 = 14;
+const HLL_Q: u32 = 64 - HLL_P;
+const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_BITS: usize = 6;
+const HLL_REGISTER_MAX: u8 = (1 << HLL_BITS) - 1;
+const HLL_HDR_SIZE: usize = 16;
+const HLL_DENSE_SIZE: usize = HLL_HDR_SIZE + (HLL_REGISTERS * HLL_BITS).div_ceil(8);
+const HLL_MAGIC: &[u8; 4] = b"HYLL";
+const HLL_DENSE: u8 = 0;
+const HLL_SPARSE: u8 = 1;
+// 对应 redis 的 hll-sparse-max-bytes 默认值
+const HLL_SPARSE_MAX_BYTES: usize = 3000;
+const HLL_SPARSE_VAL_MAX_VALUE: u8 = 32;
+const HLL_SPARSE_VAL_MAX_LEN: usize = 4;
+const HLL_SPARSE_ZERO_MAX_LEN: usize = 64;
+const HLL_SPARSE_XZERO_MAX_LEN: usize = 16384;
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+const HLL_HASH_SEED: u64 = 0xadc8_3b19;
+
+/// HyperLogLog，读写时和 redis 的 sparse / dense 字节格式相互转换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    // 一旦转换成 dense 就不会再变回 sparse
+    dense: bool,
+    // 缓存的基数，最高位为 1 表示缓存失效
+    card: [u8; 8],
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+            dense: false,
+            card: [0; 8],
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析 redis 格式的 HLL 字符串
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, BackendError> {
+        if buf.len() < HLL_HDR_SIZE || &buf[..4] != HLL_MAGIC {
+            return Err(BackendError::WrongTypeHll);
+        }
+        let mut card = [0; 8];
+        card.copy_from_slice(&buf[8..HLL_HDR_SIZE]);
+
+        let data = &buf[HLL_HDR_SIZE..];
+        let (registers, dense) = match buf[4] {
+            HLL_DENSE if buf.len() == HLL_DENSE_SIZE => {
+                let registers = (0..HLL_REGISTERS).map(|i| dense_get(data, i)).collect();
+                (registers, true)
+            }
+            HLL_SPARSE => (sparse_decode(data)?, false),
+            _ => return Err(BackendError::WrongTypeHll),
+        };
+
+        Ok(Self {
+            registers,
+            dense,
+            card,
+        })
+    }
+
+    /// 按 redis 的格式编码，sparse 超过大小限制或者有寄存器超过 32 时转换成 dense
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let sparse = if self.dense {
+            None
+        } else {
+            sparse_encode(&self.registers)
+        };
+
+        let mut buf = Vec::with_capacity(HLL_DENSE_SIZE);
+        buf.extend_from_slice(HLL_MAGIC);
+        buf.push(if sparse.is_some() {
+            HLL_SPARSE
+        } else {
+            HLL_DENSE
+        });
+        buf.extend_from_slice(&[0; 3]);
+        buf.extend_from_slice(&self.card);
+        match sparse {
+            Some(data) => buf.extend_from_slice(&data),
+            None => {
+                buf.resize(HLL_DENSE_SIZE, 0);
+                let data = &mut buf[HLL_HDR_SIZE..];
+                for (i, &value) in self.registers.iter().enumerate() {
+                    dense_set(data, i, value);
+                }
+            }
+        }
+        buf
+    }
+
+    /// 添加元素，有寄存器被修改时返回 true 并让缓存失效
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let (index, count) = pattern_len(element);
+        if count <= self.registers[index] {
+            return false;
+        }
+        self.registers[index] = count;
+        self.invalidate();
+        true
+    }
+
+    /// 每个寄存器取最大值
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (r, &o) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *r = (*r).max(o);
+        }
+        self.dense |= other.dense;
+        self.invalidate();
+    }
+
+    /// 估算基数，缓存有效时直接返回缓存
+    pub fn count(&mut self) -> u64 {
+        if self.card[7] & 0x80 == 0 {
+            return u64::from_le_bytes(self.card);
+        }
+        let card = estimate(&self.registers);
+        self.card = card.to_le_bytes();
+        card
+    }
+
+    fn invalidate(&mut self) {
+        self.card[7] |= 0x80;
+    }
+}
+
+impl TryFrom<&RespFrame> for HyperLogLog {
+    type Error = BackendError;
+
+    fn try_from(frame: &RespFrame) -> Result<Self, Self::Error> {
+        match frame {
+            RespFrame::BulkString(s) => HyperLogLog::from_bytes(s),
+            _ => Err(BackendError::WrongTypeHll),
+        }
+    }
+}
+
+impl From<HyperLogLog> for RespFrame {
+    fn from(hll: HyperLogLog) -> Self {
+        BulkString::new(hll.to_bytes()).into()
+    }
+}
+
+// 寄存器 i 在 dense 数据中从第 i * 6 个 bit 开始，低位在前
+fn dense_get(data: &[u8], i: usize) -> u8 {
+    let pos = i * HLL_BITS;
+    let (b, fb) = (pos / 8, pos % 8);
+    let hi = data.get(b + 1).copied().unwrap_or(0) as u16;
+    let v = ((data[b] as u16) >> fb) | (hi << (8 - fb));
+    (v as u8) & HLL_REGISTER_MAX
+}
+
+fn dense_set(data: &mut [u8], i: usize, value: u8) {
+    let pos = i * HLL_BITS;
+    let (b, fb) = (pos / 8, pos % 8);
+    let v = value as u16;
+    data[b] &= !((HLL_REGISTER_MAX as u16) << fb) as u8;
+    data[b] |= (v << fb) as u8;
+    if fb + HLL_BITS > 8 {
+        data[b + 1] &= !((HLL_REGISTER_MAX as u16) >> (8 - fb)) as u8;
+        data[b + 1] |= (v >> (8 - fb)) as u8;
+    }
+}
+
+// sparse 格式的三种操作码：
+// ZERO:  00xxxxxx，连续 1 - 64 个寄存器为 0
+// XZERO: 01xxxxxx yyyyyyyy，连续 1 - 16384 个寄存器为 0
+// VAL:   1vvvvvxx，连续 1 - 4 个寄存器的值为 1 - 32
+fn sparse_decode(data: &[u8]) -> Result<Vec<u8>, BackendError> {
+    let mut registers = Vec::with_capacity(HLL_REGISTERS);
+    let mut iter = data.iter();
+    while let Some(&op) = iter.next() {
+        let (value, len) = if op & 0x80 != 0 {
+            (((op >> 2) & 0x1f) + 1, (op & 0x03) as usize + 1)
+        } else if op & 0x40 != 0 {
+            let next = *iter.next().ok_or(BackendError::CorruptedHll)?;
+            (0, (((op & 0x3f) as usize) << 8 | next as usize) + 1)
+        } else {
+            (0, (op & 0x3f) as usize + 1)
+        };
+        if registers.len() + len > HLL_REGISTERS {
+            return Err(BackendError::CorruptedHll);
+        }
+        registers.resize(registers.len() + len, value);
+    }
+
+    if registers.len() != HLL_REGISTERS {
+        return Err(BackendError::CorruptedHll);
+    }
+    Ok(registers)
+}
+
+// 无法用 sparse 表示或者超过大小限制时返回 None
+fn sparse_encode(registers: &[u8]) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut i = 0;
+    while i < registers.len() {
+        let value = registers[i];
+        if value > HLL_SPARSE_VAL_MAX_VALUE {
+            return None;
+        }
+        let run = registers[i..].iter().take_while(|&&v| v == value).count();
+        i += run;
+
+        let mut run = run;
+        while run > 0 {
+            if value != 0 {
+                let len = run.min(HLL_SPARSE_VAL_MAX_LEN);
+                data.push(0x80 | ((value - 1) << 2) | (len - 1) as u8);
+                run -= len;
+            } else if run > HLL_SPARSE_ZERO_MAX_LEN {
+                let len = run.min(HLL_SPARSE_XZERO_MAX_LEN) - 1;
+                data.push(0x40 | (len >> 8) as u8);
+                data.push((len & 0xff) as u8);
+                run -= len + 1;
+            } else {
+                data.push((run - 1) as u8);
+                run = 0;
+            }
+        }
+
+        if HLL_HDR_SIZE + data.len() > HLL_SPARSE_MAX_BYTES {
+            return None;
+        }
+    }
+    Some(data)
+}
+
+// 返回元素对应的寄存器下标，以及 hash 剩余部分从低位开始第一个 1 的位置
+fn pattern_len(element: &[u8]) -> (usize, u8) {
+    let hash = murmurhash64a(element, HLL_HASH_SEED);
+    let index = (hash & (HLL_REGISTERS as u64 - 1)) as usize;
+    let hash = (hash >> HLL_P) | (1 << HLL_Q);
+    (index, hash.trailing_zeros() as u8 + 1)
+}
+
+// redis 使用的 Otmar Ertl 改进的估算方法
+fn estimate(registers: &[u8]) -> u64 {
+    let mut histogram = [0u32; 64];
+    for &r in registers {
+        histogram[r as usize] += 1;
+    }
+
+    let m = HLL_REGISTERS as f64;
+    let q = HLL_Q as usize;
+    let mut z = m * tau((m - histogram[q + 1] as f64) / m);
+    for j in (1..=q).rev() {
+        z += histogram[j] as f64;
+        z *= 0.5;
+    }
+    z += m * sigma(histogram[0] as f64 / m);
+    (HLL_ALPHA_INF * m * m / z).round() as u64
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let (mut y, mut z) = (1.0, x);
+    loop {
+        x *= x;
+        let prev = z;
+        z += x * y;
+        y += y;
+        if prev == z {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let (mut y, mut z) = (1.0, 1.0 - x);
+    loop {
+        x = x.sqrt();
+        let prev = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if prev == z {
+            return z / 3.0;
+        }
+    }
+}
+
+impl Backend {
+    /// 有寄存器被修改或者新建了 key 时返回 true
+    pub fn pfadd(&self, key: String, elements: &[Vec<u8>]) -> Result<bool, BackendError> {
+        let _guard = self.lock_keys(&[&key]);
+        self.check_key_type(&key, "string")?;
+        match self.map.entry(key) {
+            Entry::Occupied(mut entry) => {
+                let mut hll = HyperLogLog::try_from(entry.get())?;
+                let mut updated = false;
+                for element in elements {
+                    updated |= hll.add(element);
+                }
+                if updated {
+                    self.touch(entry.key());
+                    self.notify(KeyspaceEvents::STRING, "pfadd", entry.key());
+                    entry.insert(hll.into());
+                }
+                Ok(updated)
+            }
+            Entry::Vacant(entry) => {
+                let mut hll = HyperLogLog::new();
+                for element in elements {
+                    hll.add(element);
+                }
+                self.touch(entry.key());
+                self.notify(KeyspaceEvents::STRING, "pfadd", entry.key());
+                entry.insert(hll.into());
+                Ok(true)
+            }
+        }
+    }
+
+    /// 多个 key 时先合并再估算，只有一个 key 时会把结果缓存到头部
+    pub fn pfcount(&self, keys: &[String]) -> Result<u64, BackendError> {
+        if let [key] = keys {
+            let mut value = match self.map.get_mut(key) {
+                Some(value) => value,
+                None => return Ok(0),
+            };
+            let mut hll = HyperLogLog::try_from(value.value())?;
+            let cached = hll.card;
+            let count = hll.count();
+            if hll.card != cached {
+                *value = hll.into();
+            }
+            return Ok(count);
+        }
+
+        let mut merged = HyperLogLog::new();
+        for key in keys {
+            if let Some(value) = self.map.get(key) {
+                merged.merge(&HyperLogLog::try_from(value.value())?);
+            }
+        }
+        Ok(merged.count())
+    }
+
+    /// 合并 destination 和所有 source，结果保存到 destination
+    pub fn pfmerge(&self, destination: String, sources: &[String]) -> Result<(), BackendError> {
+        // 先读取 source，避免同时持有多个 shard 的锁
+        let mut merged = match self.map.get(&destination) {
+            Some(value) => HyperLogLog::try_from(value.value())?,
+            None => HyperLogLog::new(),
+        };
+        for key in sources {
+            if let Some(value) = self.map.get(key) {
+                merged.merge(&HyperLogLog::try_from(value.value())?);
+            }
+        }
+        merged.invalidate();
+        let _guard = self.lock_keys(&[&destination]);
+        self.check_key_type(&destination, "string")?;
+        let entry = self.map.entry(destination);
+        self.touch(entry.key());
+        // 和 Redis 一样 PFMERGE 也发布 pfadd 事件
+        self.notify(KeyspaceEvents::STRING, "pfadd", entry.key());
+        entry.insert(merged.into());
+        Ok(())
+    }
+}
@@ -0,0 +1,617 @@
+use std::{fmt, str::FromStr};
+
+use dashmap::mapref::entry::Entry;
+use serde_json::{Map, Number, Value};
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::BackendError;
+
+/// JSONPath 或者 RedisJSON v1 的旧路径语法
+/// 以 "$" 开头的是 JSONPath，返回所有匹配的值；其它的是旧语法，只返回第一个匹配的值
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    raw: String,
+    legacy: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    // .name [0] [*] 等，选择直接的子节点
+    Child(Selector),
+    // ..name，选择所有层级的子节点
+    Descendant(Selector),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Indexes(Vec<i64>),
+    Slice(Option<i64>, Option<i64>, i64),
+}
+
+// 匹配到的值在文档中的位置
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum PathKey {
+    Key(String),
+    Index(usize),
+}
+
+/// JSON.SET 的可选参数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct JsonSetFlags {
+    pub nx: bool,
+    pub xx: bool,
+}
+
+struct PathParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl PathParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_segments(&mut self) -> Option<Vec<Segment>> {
+        let mut segments = Vec::new();
+        while self.pos < self.input.len() {
+            let segment = if self.eat(b'.') {
+                if self.eat(b'.') {
+                    let selector = if self.peek() == Some(b'[') {
+                        self.pos += 1;
+                        self.parse_bracket()?
+                    } else {
+                        self.parse_dot_selector()?
+                    };
+                    Segment::Descendant(selector)
+                } else {
+                    Segment::Child(self.parse_dot_selector()?)
+                }
+            } else if self.eat(b'[') {
+                Segment::Child(self.parse_bracket()?)
+            } else {
+                return None;
+            };
+            segments.push(segment);
+        }
+        Some(segments)
+    }
+
+    // .* 或者 .name，name 到下一个 . 或者 [ 为止
+    fn parse_dot_selector(&mut self) -> Option<Selector> {
+        if self.eat(b'*') {
+            return Some(Selector::Wildcard);
+        }
+        let start = self.pos;
+        while !matches!(self.peek(), None | Some(b'.') | Some(b'[')) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return None;
+        }
+        let name = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+        Some(Selector::Name(name.to_string()))
+    }
+
+    // [*] ['name'] ["name"] [1,2] [start:end:step]，开头的 [ 已经被读取
+    fn parse_bracket(&mut self) -> Option<Selector> {
+        self.skip_spaces();
+        let selector = match self.peek()? {
+            b'*' => {
+                self.pos += 1;
+                Selector::Wildcard
+            }
+            quote @ (b'\'' | b'"') => {
+                self.pos += 1;
+                let start = self.pos;
+                while self.peek()? != quote {
+                    self.pos += 1;
+                }
+                let name = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+                self.pos += 1;
+                Selector::Name(name.to_string())
+            }
+            _ => {
+                let first = self.parse_int();
+                self.skip_spaces();
+                if self.eat(b':') {
+                    let end = self.parse_int();
+                    self.skip_spaces();
+                    let step = if self.eat(b':') {
+                        self.parse_int().unwrap_or(1)
+                    } else {
+                        1
+                    };
+                    Selector::Slice(first, end, step)
+                } else {
+                    let mut indexes = vec![first?];
+                    while self.eat(b',') {
+                        indexes.push(self.parse_int()?);
+                    }
+                    Selector::Indexes(indexes)
+                }
+            }
+        };
+        self.skip_spaces();
+        self.eat(b']').then_some(selector)
+    }
+
+    fn parse_int(&mut self) -> Option<i64> {
+        self.skip_spaces();
+        let start = self.pos;
+        self.eat(b'-');
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = BackendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (legacy, rest) = match s.strip_prefix('$') {
+            Some(rest) => (false, rest.to_string()),
+            None if s == "." => (true, String::new()),
+            // 旧语法可以省略开头的 .，例如 "a.b"
+            None if s.starts_with('.') || s.starts_with('[') => (true, s.to_string()),
+            None => (true, format!(".{}", s)),
+        };
+
+        let mut parser = PathParser {
+            input: rest.as_bytes(),
+            pos: 0,
+        };
+        let segments = parser
+            .parse_segments()
+            .ok_or_else(|| BackendError::JsonPathSyntax(s.to_string()))?;
+
+        Ok(JsonPath {
+            raw: s.to_string(),
+            legacy,
+            segments,
+        })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+// 负数从末尾开始计算，超出范围返回 None
+fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { index + len as i64 } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+impl Selector {
+    fn select(&self, value: &Value, prefix: &[PathKey], out: &mut Vec<Vec<PathKey>>) {
+        let mut push = |key: PathKey| {
+            let mut path = prefix.to_vec();
+            path.push(key);
+            out.push(path);
+        };
+        match (self, value) {
+            (Selector::Name(name), Value::Object(map)) if map.contains_key(name) => {
+                push(PathKey::Key(name.clone()))
+            }
+            (Selector::Wildcard, Value::Object(map)) => {
+                map.keys().for_each(|k| push(PathKey::Key(k.clone())))
+            }
+            (Selector::Wildcard, Value::Array(arr)) => {
+                (0..arr.len()).for_each(|i| push(PathKey::Index(i)))
+            }
+            (Selector::Indexes(indexes), Value::Array(arr)) => indexes
+                .iter()
+                .filter_map(|&i| normalize_index(i, arr.len()))
+                .for_each(|i| push(PathKey::Index(i))),
+            (Selector::Slice(start, end, step), Value::Array(arr)) if *step > 0 => {
+                let len = arr.len() as i64;
+                let clamp = |i: i64| if i < 0 { (i + len).max(0) } else { i.min(len) };
+                let start = clamp(start.unwrap_or(0));
+                let end = clamp(end.unwrap_or(len));
+                (start..end)
+                    .step_by(*step as usize)
+                    .for_each(|i| push(PathKey::Index(i as usize)))
+            }
+            _ => {}
+        }
+    }
+}
+
+// 当前节点和所有后代节点的位置
+fn descendants<'a>(
+    value: &'a Value,
+    prefix: Vec<PathKey>,
+    out: &mut Vec<(Vec<PathKey>, &'a Value)>,
+) {
+    out.push((prefix.clone(), value));
+    match value {
+        Value::Object(map) => map.iter().for_each(|(k, v)| {
+            let mut path = prefix.clone();
+            path.push(PathKey::Key(k.clone()));
+            descendants(v, path, out)
+        }),
+        Value::Array(arr) => arr.iter().enumerate().for_each(|(i, v)| {
+            let mut path = prefix.clone();
+            path.push(PathKey::Index(i));
+            descendants(v, path, out)
+        }),
+        _ => {}
+    }
+}
+
+fn get<'a>(root: &'a Value, path: &[PathKey]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |value, key| match (key, value) {
+        (PathKey::Key(k), Value::Object(map)) => map.get(k),
+        (PathKey::Index(i), Value::Array(arr)) => arr.get(*i),
+        _ => None,
+    })
+}
+
+fn get_mut<'a>(root: &'a mut Value, path: &[PathKey]) -> Option<&'a mut Value> {
+    path.iter().try_fold(root, |value, key| match (key, value) {
+        (PathKey::Key(k), Value::Object(map)) => map.get_mut(k),
+        (PathKey::Index(i), Value::Array(arr)) => arr.get_mut(*i),
+        _ => None,
+    })
+}
+
+impl JsonPath {
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn locate_segments(segments: &[Segment], root: &Value) -> Vec<Vec<PathKey>> {
+        let mut current = vec![vec![]];
+        for segment in segments {
+            let mut next = Vec::new();
+            for path in &current {
+                let Some(value) = get(root, path) else {
+                    continue;
+                };
+                match segment {
+                    Segment::Child(selector) => selector.select(value, path, &mut next),
+                    Segment::Descendant(selector) => {
+                        let mut nodes = Vec::new();
+                        descendants(value, path.clone(), &mut nodes);
+                        for (path, node) in nodes {
+                            selector.select(node, &path, &mut next);
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+        current
+    }
+
+    fn locate(&self, root: &Value) -> Vec<Vec<PathKey>> {
+        let mut paths = Self::locate_segments(&self.segments, root);
+        if self.legacy {
+            paths.truncate(1);
+        }
+        paths
+    }
+
+    /// 所有匹配的值，旧语法最多返回一个
+    pub fn query<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        self.locate(root)
+            .iter()
+            .filter_map(|path| get(root, path))
+            .collect()
+    }
+}
+
+// JSON.TYPE 返回的类型名称
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// 两个整数相加结果还是整数，溢出或者有浮点数时按浮点数计算
+fn add_number(a: &Number, b: &Number) -> Option<Number> {
+    if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        if let Some(n) = a.checked_add(b) {
+            return Some(n.into());
+        }
+    }
+    Number::from_f64(a.as_f64()? + b.as_f64()?)
+}
+
+impl Backend {
+    /// 根路径会替换整个文档，其它路径只能修改已经存在的文档
+    /// 路径的最后一级是不存在的字段时，在父对象中添加这个字段
+    pub fn json_set(
+        &self,
+        key: String,
+        path: &JsonPath,
+        value: Value,
+        flags: JsonSetFlags,
+    ) -> Result<bool, BackendError> {
+        self.write_typed(&key.clone(), "ReJSON-RL", || match self.json.entry(key) {
+            Entry::Vacant(entry) => {
+                if !path.is_root() {
+                    return Err(BackendError::JsonNewObjectNotRoot);
+                }
+                if flags.xx {
+                    return Ok(false);
+                }
+                self.watched.signal(entry.key());
+                self.notify(KeyspaceEvents::MODULE, "json.set", entry.key());
+                entry.insert(value);
+                Ok(true)
+            }
+            Entry::Occupied(mut entry) => {
+                let root = entry.get_mut();
+                let paths = path.locate(root);
+                if !paths.is_empty() {
+                    if flags.nx {
+                        return Ok(false);
+                    }
+                    for p in paths {
+                        if let Some(v) = get_mut(root, &p) {
+                            *v = value.clone();
+                        }
+                    }
+                    self.watched.signal(entry.key());
+                    self.notify(KeyspaceEvents::MODULE, "json.set", entry.key());
+                    return Ok(true);
+                }
+
+                let (Some(Segment::Child(Selector::Name(name))), false) =
+                    (path.segments.last(), flags.xx)
+                else {
+                    return Ok(false);
+                };
+                let parents =
+                    JsonPath::locate_segments(&path.segments[..path.segments.len() - 1], root);
+                let mut updated = false;
+                for p in parents {
+                    if let Some(Value::Object(map)) = get_mut(root, &p) {
+                        map.insert(name.clone(), value.clone());
+                        updated = true;
+                    }
+                }
+                if updated {
+                    self.watched.signal(entry.key());
+                    self.notify(KeyspaceEvents::MODULE, "json.set", entry.key());
+                }
+                Ok(updated)
+            }
+        })?
+    }
+
+    /// key 不存在时返回 None，只有一个路径时直接返回结果，多个路径时返回以路径为 key 的对象
+    /// 旧语法返回匹配的值，JSONPath 返回所有匹配的值组成的数组
+    pub fn json_get(&self, key: &str, paths: &[JsonPath]) -> Result<Option<Value>, BackendError> {
+        let Some(root) = self.json.get(key) else {
+            return Ok(None);
+        };
+
+        // 只要有一个是 JSONPath，所有路径都按 JSONPath 的格式返回
+        let legacy = paths.iter().all(|p| p.is_legacy());
+        let mut results = Map::new();
+        for path in paths {
+            let values = path.query(&root);
+            let value = if legacy {
+                values
+                    .first()
+                    .map(|v| (*v).clone())
+                    .ok_or_else(|| BackendError::JsonPathNotFound(path.to_string()))?
+            } else {
+                Value::Array(values.into_iter().cloned().collect())
+            };
+            if paths.len() == 1 {
+                return Ok(Some(value));
+            }
+            results.insert(path.to_string(), value);
+        }
+        Ok(Some(Value::Object(results)))
+    }
+
+    /// 返回删除的值的数量，根路径删除整个 key
+    pub fn json_del(&self, key: &str, path: &JsonPath) -> usize {
+        if path.is_root() {
+            let Some(_) = self.json.remove(key) else {
+                return 0;
+            };
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::MODULE, "json.del", key);
+            return 1;
+        }
+        let Some(mut root) = self.json.get_mut(key) else {
+            return 0;
+        };
+
+        // 先删除后面的数组元素和子节点，避免前面的删除影响后面的位置
+        let mut paths = path.locate(&root);
+        paths.sort_by(|a, b| b.cmp(a));
+        paths.dedup();
+        let mut deleted = 0;
+        for mut p in paths {
+            let Some(last) = p.pop() else {
+                continue;
+            };
+            let removed = match (get_mut(&mut root, &p), last) {
+                (Some(Value::Object(map)), PathKey::Key(k)) => map.shift_remove(&k).is_some(),
+                (Some(Value::Array(arr)), PathKey::Index(i)) if i < arr.len() => {
+                    arr.remove(i);
+                    true
+                }
+                _ => false,
+            };
+            deleted += removed as usize;
+        }
+        if deleted > 0 {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::MODULE, "json.del", key);
+        }
+        deleted
+    }
+
+    // 对每个匹配的值执行只读操作，key 不存在时返回 None
+    fn json_read<T>(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        f: impl Fn(&Value) -> Option<T>,
+    ) -> Option<Vec<Option<T>>> {
+        let root = self.json.get(key)?;
+        Some(path.query(&root).into_iter().map(f).collect())
+    }
+
+    // 对每个匹配的值执行修改操作，f 返回 None 表示这个值的类型不匹配，event 是发布的事件名
+    fn json_write<T>(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        event: &str,
+        mut f: impl FnMut(&mut Value) -> Option<T>,
+    ) -> Result<Vec<Option<T>>, BackendError> {
+        let mut root = self
+            .json
+            .get_mut(key)
+            .ok_or(BackendError::JsonKeyNotFound)?;
+        let paths = path.locate(&root);
+        let ret = paths
+            .into_iter()
+            .map(|p| get_mut(&mut root, &p).and_then(&mut f))
+            .collect::<Vec<_>>();
+        if ret.iter().any(|v| v.is_some()) {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::MODULE, event, key);
+        }
+        Ok(ret)
+    }
+
+    pub fn json_type(&self, key: &str, path: &JsonPath) -> Option<Vec<Option<&'static str>>> {
+        self.json_read(key, path, |v| Some(json_type_name(v)))
+    }
+
+    pub fn json_objlen(&self, key: &str, path: &JsonPath) -> Option<Vec<Option<usize>>> {
+        self.json_read(key, path, |v| v.as_object().map(|map| map.len()))
+    }
+
+    pub fn json_objkeys(&self, key: &str, path: &JsonPath) -> Option<Vec<Option<Vec<String>>>> {
+        self.json_read(key, path, |v| {
+            v.as_object().map(|map| map.keys().cloned().collect())
+        })
+    }
+
+    pub fn json_arrlen(&self, key: &str, path: &JsonPath) -> Option<Vec<Option<usize>>> {
+        self.json_read(key, path, |v| v.as_array().map(|arr| arr.len()))
+    }
+
+    /// 返回增加之后的值，不是数字的返回 None
+    pub fn json_numincrby(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        by: &Number,
+    ) -> Result<Vec<Option<Number>>, BackendError> {
+        self.json_write(key, path, "json.numincrby", |v| match v {
+            Value::Number(n) => {
+                *n = add_number(n, by)?;
+                Some(n.clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// 返回追加之后数组的长度，不是数组的返回 None
+    pub fn json_arrappend(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        values: &[Value],
+    ) -> Result<Vec<Option<usize>>, BackendError> {
+        self.json_write(key, path, "json.arrappend", |v| {
+            let arr = v.as_array_mut()?;
+            arr.extend_from_slice(values);
+            Some(arr.len())
+        })
+    }
+
+    /// 在 index 之前插入，index 可以是负数，等于数组长度时追加在末尾
+    pub fn json_arrinsert(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        index: i64,
+        values: &[Value],
+    ) -> Result<Vec<Option<usize>>, BackendError> {
+        let mut out_of_range = false;
+        let ret = self.json_write(key, path, "json.arrinsert", |v| {
+            let arr = v.as_array_mut()?;
+            let len = arr.len() as i64;
+            let index = if index < 0 { index + len } else { index };
+            if !(0..=len).contains(&index) {
+                out_of_range = true;
+                return None;
+            }
+            let tail = arr.split_off(index as usize);
+            arr.extend_from_slice(values);
+            arr.extend(tail);
+            Some(arr.len())
+        })?;
+        if out_of_range {
+            return Err(BackendError::JsonIndexOutOfRange);
+        }
+        Ok(ret)
+    }
+
+    /// 弹出 index 位置的元素，超出范围时取最近的一端，不是数组或者数组为空时返回 None
+    pub fn json_arrpop(
+        &self,
+        key: &str,
+        path: &JsonPath,
+        index: i64,
+    ) -> Result<Vec<Option<Value>>, BackendError> {
+        self.json_write(key, path, "json.arrpop", |v| {
+            let arr = v.as_array_mut()?;
+            if arr.is_empty() {
+                return None;
+            }
+            let len = arr.len() as i64;
+            let index = if index < 0 { index + len } else { index };
+            Some(arr.remove(index.clamp(0, len - 1) as usize))
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use dashmap::SharedValue;
+
+use crate::{Backend, BackendError, KeyspaceEvents};
+
+/// 列表的操作方向，LEFT 对应队头，RIGHT 对应队尾
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListDirection {
+    Left,
+    Right,
+}
+
+impl Backend {
+    pub fn lpush(&self, key: String, values: Vec<String>) -> Result<usize, BackendError> {
+        self.write_typed(&key.clone(), "list", || {
+            let mut list = self.list.entry(key).or_default();
+            for value in values {
+                list.push_front(value);
+            }
+            self.watched.signal(list.key());
+            self.notify(KeyspaceEvents::LIST, "lpush", list.key());
+            list.len()
+        })
+    }
+
+    pub fn rpush(&self, key: String, values: Vec<String>) -> Result<usize, BackendError> {
+        self.write_typed(&key.clone(), "list", || {
+            let mut list = self.list.entry(key).or_default();
+            list.extend(values);
+            self.watched.signal(list.key());
+            self.notify(KeyspaceEvents::LIST, "rpush", list.key());
+            list.len()
+        })
+    }
+
+    pub fn lrange(&self, key: &str, start: i64, stop: i64) -> Vec<String> {
+        let list = match self.list.get(key) {
+            Some(list) => list,
+            None => return vec![],
+        };
+
+        let len = list.len() as i64;
+        let start = if start < 0 {
+            (len + start).max(0)
+        } else {
+            start
+        };
+        let stop = if stop < 0 {
+            len + stop
+        } else {
+            stop.min(len - 1)
+        };
+        if start > stop || start >= len {
+            return vec![];
+        }
+
+        list.range(start as usize..=stop as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// 从 key 对应列表的 direction 端弹出最多 count 个元素，列表为空时删除 key
+    pub fn lpop_count(
+        &self,
+        key: &str,
+        direction: ListDirection,
+        count: usize,
+    ) -> Option<Vec<String>> {
+        let values = {
+            let mut list = self.list.get_mut(key)?;
+            let count = count.min(list.len());
+            match direction {
+                ListDirection::Left => list.drain(..count).collect::<Vec<_>>(),
+                ListDirection::Right => {
+                    let at = list.len() - count;
+                    list.split_off(at).into_iter().rev().collect()
+                }
+            }
+        };
+        // 必须先释放上面的 RefMut，否则 remove_if 会死锁
+        let emptied = self
+            .list
+            .remove_if(key, |_, list| list.is_empty())
+            .is_some();
+
+        if values.is_empty() {
+            None
+        } else {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::LIST, pop_event(direction), key);
+            if emptied {
+                self.notify(KeyspaceEvents::GENERIC, "del", key);
+            }
+            Some(values)
+        }
+    }
+
+    /// 从 source 的 from 端弹出一个元素，压入 destination 的 to 端
+    /// source 或 destination 是其它类型的 key 时返回 WRONGTYPE，不会弹出元素
+    pub fn lmove(
+        &self,
+        source: &str,
+        destination: String,
+        from: ListDirection,
+        to: ListDirection,
+    ) -> Result<Option<String>, BackendError> {
+        // 检查类型到写入完成之间其它类型不能创建同名的 key
+        let _guards = self.lock_keys(&[source, destination.as_str()]);
+        for key in [source, destination.as_str()] {
+            self.check_key_type(key, "list")?;
+        }
+
+        // source 和 destination 相同时在同一个锁内完成，用于列表旋转
+        if source == destination {
+            let Some(mut list) = self.list.get_mut(source) else {
+                return Ok(None);
+            };
+            let Some(value) = pop(&mut list, from) else {
+                return Ok(None);
+            };
+            push(&mut list, to, value.clone());
+            self.watched.signal(source);
+            self.notify(KeyspaceEvents::LIST, pop_event(from), source);
+            self.notify(KeyspaceEvents::LIST, push_event(to), source);
+            return Ok(Some(value));
+        }
+
+        // 按下标顺序锁住两个 key 所在的 shard，弹出和压入之间其它客户端看不到中间状态
+        // 同一个 shard 只能加一次锁，否则会死锁
+        let shards = self.list.shards();
+        let (src, dst) = (
+            self.list.determine_map(source),
+            self.list.determine_map(destination.as_str()),
+        );
+        let mut first = shards[src.min(dst)].write();
+        let mut second = (src != dst).then(|| shards[src.max(dst)].write());
+        let (value, emptied) = {
+            let shard = match second.as_deref_mut() {
+                Some(shard) if src > dst => shard,
+                _ => &mut *first,
+            };
+            let Some(list) = shard.get_mut(source).map(|list| list.get_mut()) else {
+                return Ok(None);
+            };
+            let Some(value) = pop(list, from) else {
+                return Ok(None);
+            };
+            let emptied = list.is_empty();
+            if emptied {
+                shard.remove(source);
+            }
+            (value, emptied)
+        };
+        let shard = match second.as_deref_mut() {
+            Some(shard) if dst > src => shard,
+            _ => &mut *first,
+        };
+        let list = shard
+            .entry(destination.clone())
+            .or_insert_with(|| SharedValue::new(VecDeque::new()))
+            .get_mut();
+        push(list, to, value.clone());
+        drop(second);
+        drop(first);
+
+        self.watched.signal(source);
+        self.notify(KeyspaceEvents::LIST, pop_event(from), source);
+        if emptied {
+            self.notify(KeyspaceEvents::GENERIC, "del", source);
+        }
+        self.watched.signal(&destination);
+        self.notify(KeyspaceEvents::LIST, push_event(to), &destination);
+        Ok(Some(value))
+    }
+
+    /// 返回 element 在列表中的下标，rank 为负数时从队尾开始查找
+    /// count 为 0 表示返回全部匹配，maxlen 为 0 表示不限制比较次数
+    pub fn lpos(
+        &self,
+        key: &str,
+        element: &str,
+        rank: i64,
+        count: usize,
+        maxlen: usize,
+    ) -> Vec<usize> {
+        let list = match self.list.get(key) {
+            Some(list) => list,
+            None => return vec![],
+        };
+
+        let maxlen = if maxlen == 0 { list.len() } else { maxlen };
+        let skip = (rank.unsigned_abs() - 1) as usize;
+        let count = if count == 0 { usize::MAX } else { count };
+
+        let indexes: Box<dyn Iterator<Item = usize>> = if rank > 0 {
+            Box::new(0..list.len())
+        } else {
+            Box::new((0..list.len()).rev())
+        };
+
+        indexes
+            .take(maxlen)
+            .filter(|&i| list[i] == element)
+            .skip(skip)
+            .take(count)
+            .collect()
+    }
+}
+
+fn pop(list: &mut VecDeque<String>, direction: ListDirection) -> Option<String> {
+    match direction {
+        ListDirection::Left => list.pop_front(),
+        ListDirection::Right => list.pop_back(),
+    }
+}
+
+fn push(list: &mut VecDeque<String>, direction: ListDirection, value: String) {
+    match direction {
+        ListDirection::Left => list.push_front(value),
+        ListDirection::Right => list.push_back(value),
+    }
+}
+
+// 和 Redis 一样 LMOVE 在 source 上发布 lpop/rpop，在 destination 上发布 lpush/rpush
+fn pop_event(direction: ListDirection) -> &'static str {
+    match direction {
+        ListDirection::Left => "lpop",
+        ListDirection::Right => "rpop",
+    }
+}
+
+fn push_event(direction: ListDirection) -> &'static str {
+    match direction {
+        ListDirection::Left => "lpush",
+        ListDirection::Right => "rpush",
+    }
+}
@@ -1,9 +1,207 @@
-use std::{ops::Deref, sync::Arc};
+mod blocking;
+mod bloom;
+mod cms;
+mod config;
+mod cuckoo;
+mod function;
+mod geo;
+mod gorilla;
+mod hash;
+mod hyperloglog;
+mod json;
+mod list;
+mod notify;
+mod plugin;
+mod pubsub;
+mod script;
+mod search;
+mod skiplist;
+mod stream;
+mod stream_group;
+mod string;
+mod throttle;
+mod timeseries;
+mod topk;
+mod vector;
+mod wasm;
+mod watch;
+mod zset;
+
+use std::{
+    collections::VecDeque,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
 
 use dashmap::{DashMap, DashSet};
+use thiserror::Error;
+use tokio::sync::RwLock;
 
 use crate::RespFrame;
 
+use hash::murmurhash64a;
+use plugin::CustomValue;
+use stream::now_ms;
+
+pub use blocking::BlockingKeys;
+pub use bloom::{BloomFilter, BF_MAX_CAPACITY, BF_MAX_EXPANSION};
+pub use cms::{CountMinSketch, CMS_MAX_COUNTERS};
+pub use cuckoo::{CuckooFilter, CuckooOptions, CF_MAX_BUCKET_SIZE, CF_MAX_SLOTS};
+pub use function::{
+    decode_function_dump, FunctionInfo, Functions, Library, RestorePolicy, FUNCTION_FLAGS,
+};
+pub use geo::{
+    geo_distance, geohash_decode, geohash_encode, geohash_string, GeoFrom, GeoResult,
+    GeoSearchOptions, GeoShape, GEO_LAT_MAX, GEO_LAT_MIN, GEO_LONG_MAX, GEO_LONG_MIN,
+};
+pub use hyperloglog::HyperLogLog;
+pub use json::{JsonPath, JsonSetFlags};
+pub use list::ListDirection;
+pub use notify::KeyspaceEvents;
+pub use plugin::{CommandFlags, CommandHandler, CustomType, Plugins};
+pub use pubsub::{MessageReceiver, PubSub, PubSubMessage, Subscriber};
+pub use script::{sha1_hex, Scripts};
+pub use search::{
+    FieldSchema, FieldType, IndexDefinition, IndexInfo, KnnQuery, Query, SearchDocument,
+    SearchIndex, SearchOptions, SearchResult,
+};
+pub use stream::{Stream, StreamEntry, StreamId, StreamInfo, StreamTrim, TrimStrategy, XAddId};
+pub use stream_group::{
+    AutoClaimResult, ConsumerGroup, ConsumerInfo, GroupInfo, GroupStreamEntry, PendingInfo,
+    PendingSummary, XClaimOptions,
+};
+pub use string::SetCondition;
+pub use throttle::{ThrottleParams, ThrottleResult};
+pub use timeseries::{
+    DuplicatePolicy, TimeSeries, TsAggregation, TsCreateOptions, TsFilter, TsRangeOptions,
+    TsRangeResult,
+};
+pub use topk::{TopK, TOPK_MAX_BUCKETS, TOPK_MAX_K};
+pub use vector::{DistanceMetric, VectorAlgorithm, VectorParams};
+pub use wasm::{WasmLimits, WasmModules};
+pub use watch::WatchedKeys;
+pub use zset::{Aggregate, SortedSet, ZAddFlags, ZRangeBy};
+
+/// 命令执行过程中 Backend 返回的错误，会作为 SimpleError 返回给客户端
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BackendError {
+    #[error("ERR resulting score is not a number (NaN)")]
+    NotANumber,
+    #[error("ERR The ID specified in XADD is equal or smaller than the target stream top item")]
+    StreamIdTooSmall,
+    #[error("ERR The ID specified in XADD must be greater than 0-0")]
+    StreamIdZero,
+    #[error("ERR no such key")]
+    NoSuchKey,
+    #[error("ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.")]
+    StreamKeyRequired,
+    #[error("BUSYGROUP Consumer Group name already exists")]
+    BusyGroup,
+    #[error("NOGROUP No such key '{key}' or consumer group '{group}'")]
+    NoGroup { key: String, group: String },
+    #[error("ERR could not decode requested zset member")]
+    GeoMemberNotFound,
+    #[error("WRONGTYPE Key is not a valid HyperLogLog string value.")]
+    WrongTypeHll,
+    #[error("INVALIDOBJ Corrupted HLL object detected")]
+    CorruptedHll,
+    #[error("ERR item exists")]
+    ItemExists,
+    #[error("ERR not found")]
+    NotFound,
+    #[error("ERR non scaling filter is full")]
+    BloomFull,
+    #[error("ERR filter is full")]
+    CuckooFull,
+    #[error("ERR requested size is too large")]
+    SizeTooLarge,
+    #[error("CMS: key already exists")]
+    CmsKeyExists,
+    #[error("CMS: key does not exist")]
+    CmsKeyNotFound,
+    #[error("CMS: width/depth is not equal")]
+    CmsSizeMismatch,
+    #[error("TopK: key already exists")]
+    TopKKeyExists,
+    #[error("TopK: key does not exist")]
+    TopKKeyNotFound,
+    #[error("ERR invalid JSONPath '{0}'")]
+    JsonPathSyntax(String),
+    #[error("ERR Path '{0}' does not exist")]
+    JsonPathNotFound(String),
+    #[error("ERR new objects must be created at the root")]
+    JsonNewObjectNotRoot,
+    #[error("ERR could not perform this operation on a key that doesn't exist")]
+    JsonKeyNotFound,
+    #[error("ERR index out of bounds")]
+    JsonIndexOutOfRange,
+    #[error("WRONGTYPE wrong type of path value")]
+    JsonWrongType,
+    #[error("ERR TSDB: key already exists")]
+    TsKeyExists,
+    #[error("ERR TSDB: the key does not exist")]
+    TsKeyNotFound,
+    #[error("ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode")]
+    TsDuplicateBlocked,
+    #[error("ERR TSDB: Timestamp is older than retention")]
+    TsTooOld,
+    #[error("ERR TSDB: timestamp must be equal to or higher than the maximum existing timestamp")]
+    TsTimestampTooSmall,
+    #[error("ERR TSDB: the source key and destination key should be different")]
+    TsRuleSameKey,
+    #[error("ERR TSDB: the destination key already has a src rule")]
+    TsRuleExists,
+    #[error("ERR TSDB: compaction rule does not exist")]
+    TsRuleNotFound,
+    #[error("Index already exists")]
+    SearchIndexExists,
+    #[error("Unknown index name")]
+    SearchUnknownIndex,
+    #[error("Syntax error at offset {0} near {1}")]
+    SearchSyntax(usize, String),
+    #[error("Unknown field `{0}`")]
+    SearchUnknownField(String),
+    #[error("Field `{0}` does not support this query")]
+    SearchFieldType(String),
+    #[error("Error parsing vector similarity query: query vector blob size does not match index's expected size")]
+    VectorSizeMismatch,
+    #[error("ERR value is not an integer or out of range")]
+    NotAnInteger,
+    #[error("NOTBUSY No scripts in execution right now.")]
+    ScriptNotBusy,
+    #[error("UNKILLABLE Sorry the script already executed write commands against the dataset. You can either wait the script termination or kill the server in a hard way using the SHUTDOWN NOSAVE command.")]
+    ScriptUnkillable,
+    #[error("ERR Library '{0}' already exists")]
+    LibraryExists(String),
+    #[error("ERR Function {0} already exists")]
+    FunctionExists(String),
+    #[error("ERR Library not found")]
+    LibraryNotFound,
+    #[error("ERR DUMP payload version or checksum are wrong")]
+    BadDumpPayload,
+    #[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+    WrongType,
+    #[error("ERR command '{0}' already exists")]
+    CommandExists(String),
+    #[error("ERR type '{0}' already exists")]
+    TypeExists(String),
+    #[error("ERR unknown type '{0}'")]
+    UnknownType(String),
+    #[error("ERR Invalid WASM module: {0}")]
+    WasmInvalidModule(String),
+    #[error("ERR Invalid event class character. Use 'Ag$lshzxeKEtmdn'.")]
+    InvalidKeyspaceEvents,
+    #[error("ERR Unknown option or number of arguments for CONFIG SET - '{0}'")]
+    ConfigUnknownOption(String),
+    #[error("ERR CONFIG SET failed (possibly related to argument '{0}') - {1}")]
+    ConfigSetFailed(String, String),
+}
+
+const KEY_LOCK_STRIPES: usize = 256;
+
 #[derive(Debug, Clone)]
 pub struct Backend(Arc<BackendInner>);
 
@@ -12,6 +210,39 @@ pub struct BackendInner {
     pub(crate) map: DashMap<String, RespFrame>,
     pub(crate) hmap: DashMap<String, DashMap<String, RespFrame>>,
     pub(crate) set: DashMap<String, DashSet<String>>, // DashSet 里面元素要求实现 Eq，RespFrame 不符合，我们这里用 String
+    pub(crate) list: DashMap<String, VecDeque<String>>,
+    pub(crate) zset: DashMap<String, SortedSet>,
+    pub(crate) stream: DashMap<String, Stream>,
+    pub(crate) bloom: DashMap<String, BloomFilter>,
+    pub(crate) cuckoo: DashMap<String, CuckooFilter>,
+    pub(crate) cms: DashMap<String, CountMinSketch>,
+    pub(crate) topk: DashMap<String, TopK>,
+    pub(crate) json: DashMap<String, serde_json::Value>,
+    pub(crate) timeseries: DashMap<String, TimeSeries>,
+    // 插件注册的自定义类型的值
+    pub(crate) custom: DashMap<String, CustomValue>,
+    // key 的过期时间，毫秒时间戳
+    pub(crate) expires: DashMap<String, u64>,
+    // 主动过期下一次检查的 expires 分片
+    pub(crate) expire_cursor: AtomicUsize,
+    // 字符串 key 的版本号，所有 key 共用一个递增的计数器，删除之后重新创建的 key 版本号也不会重复
+    pub(crate) versions: DashMap<String, u64>,
+    pub(crate) version_counter: AtomicU64,
+    pub(crate) search: DashMap<String, SearchIndex>,
+    pub(crate) scripts: Scripts,
+    pub(crate) functions: Functions,
+    pub(crate) plugins: Plugins,
+    pub(crate) wasm: WasmModules,
+    pub(crate) pubsub: PubSub,
+    // notify-keyspace-events 的标记，默认不发布
+    pub(crate) notify_flags: AtomicU32,
+    pub(crate) blocking: BlockingKeys,
+    pub(crate) watched: WatchedKeys,
+    // 普通命令执行时持有读锁，EXEC 和脚本持有写锁，保证它们不会和其它客户端的写入交错
+    // 使用 tokio 的锁，等待脚本执行的客户端不会占用运行时的线程
+    pub(crate) exec_lock: RwLock<()>,
+    // 按 key 的哈希分段的锁，检查 key 的类型和写入之间持有，同一个 key 不会出现在两个 map 中
+    pub(crate) key_locks: Vec<Mutex<()>>,
 }
 
 impl Deref for Backend {
@@ -34,6 +265,31 @@ impl Default for BackendInner {
             map: DashMap::new(),
             hmap: DashMap::new(),
             set: DashMap::new(),
+            list: DashMap::new(),
+            zset: DashMap::new(),
+            stream: DashMap::new(),
+            bloom: DashMap::new(),
+            cuckoo: DashMap::new(),
+            cms: DashMap::new(),
+            topk: DashMap::new(),
+            json: DashMap::new(),
+            timeseries: DashMap::new(),
+            custom: DashMap::new(),
+            expires: DashMap::new(),
+            expire_cursor: AtomicUsize::new(0),
+            versions: DashMap::new(),
+            version_counter: AtomicU64::new(0),
+            search: DashMap::new(),
+            scripts: Scripts::default(),
+            functions: Functions::default(),
+            plugins: Plugins::default(),
+            wasm: WasmModules::default(),
+            pubsub: PubSub::default(),
+            notify_flags: AtomicU32::new(0),
+            blocking: BlockingKeys::default(),
+            watched: WatchedKeys::default(),
+            exec_lock: RwLock::new(()),
+            key_locks: (0..KEY_LOCK_STRIPES).map(|_| Mutex::new(())).collect(),
         }
     }
 }
@@ -44,12 +300,28 @@ impl Backend {
     }
 
     pub fn get(&self, key: &str) -> Option<RespFrame> {
+        self.expire_if_needed(key);
         // dash map 和 普通的 hashmap 在这里用法有点不一样，dashmap 有个 value 方法取出字段值
         self.map.get(key).map(|v| v.value().clone())
     }
 
+    /// 和 Redis 一样，SET 会覆盖任意类型的 key
     pub fn set(&self, key: String, value: RespFrame) {
-        self.map.insert(key, value);
+        self.overwrite_typed(&key.clone(), "string", || {
+            let entry = self.map.entry(key);
+            // 和 Redis 一样，SET 会清除 key 的过期时间
+            self.expires.remove(entry.key());
+            self.touch(entry.key());
+            self.notify(KeyspaceEvents::STRING, "set", entry.key());
+            entry.insert(value);
+        })
+    }
+
+    /// 字符串 key 写入之后更新版本号并通知 WATCH 它的连接，调用方持有 key 的写锁
+    pub(crate) fn touch(&self, key: &str) {
+        let version = self.version_counter.fetch_add(1, Ordering::Relaxed) + 1;
+        self.versions.insert(key.to_string(), version);
+        self.watched.signal(key);
     }
 
     pub fn hget(&self, key: &str, field: &str) -> Option<RespFrame> {
@@ -58,18 +330,201 @@ impl Backend {
             .and_then(|v| v.get(field).map(|v| v.value().clone()))
     }
 
-    pub fn hset(&self, key: String, field: String, value: RespFrame) -> Option<RespFrame> {
-        let hmap = self.hmap.entry(key).or_default();
-        hmap.insert(field, value)
+    pub fn hset(
+        &self,
+        key: String,
+        field: String,
+        value: RespFrame,
+    ) -> Result<Option<RespFrame>, BackendError> {
+        let old = self.write_typed(&key, "hash", || {
+            self.hmap
+                .entry(key.clone())
+                .or_default()
+                .insert(field, value)
+        })?;
+        self.watched.signal(&key);
+        self.notify(KeyspaceEvents::HASH, "hset", &key);
+        self.ft_reindex(&key);
+        Ok(old)
+    }
+
+    /// 返回删除的字段数量，字段全部删除之后 key 也会被删除
+    pub fn hdel(&self, key: &str, fields: &[String]) -> i64 {
+        let deleted = match self.hmap.get(key) {
+            Some(hmap) => fields.iter().filter(|f| hmap.remove(*f).is_some()).count(),
+            None => return 0,
+        };
+        let emptied = self
+            .hmap
+            .remove_if(key, |_, hmap| hmap.is_empty())
+            .is_some();
+        if deleted > 0 {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::HASH, "hdel", key);
+            if emptied {
+                self.notify(KeyspaceEvents::GENERIC, "del", key);
+            }
+            self.ft_reindex(key);
+        }
+        deleted as i64
     }
 
     pub fn hgetall(&self, key: &str) -> Option<DashMap<String, RespFrame>> {
         self.hmap.get(key).map(|v| v.clone())
     }
 
-    pub fn sadd(&self, key: String, member: String) -> bool {
-        let set = self.set.entry(key).or_default();
-        set.insert(member)
+    /// 删除任意类型的 key，返回 key 是否存在
+    pub fn del(&self, key: &str) -> bool {
+        if self.expire_if_needed(key) {
+            return false;
+        }
+        let deleted = self.remove_key(key);
+        if deleted {
+            self.notify(KeyspaceEvents::GENERIC, "del", key);
+        }
+        deleted
+    }
+
+    /// 设置 key 的过期时间（毫秒时间戳），只在内部使用，不发布 expire 事件
+    /// 过期的 key 在下次访问或者 expire_sweep 时删除
+    pub(crate) fn expire_at(&self, key: String, at: u64) {
+        self.watched.signal(&key);
+        self.expires.insert(key, at);
+    }
+
+    /// key 已经过期时删除并返回 true
+    pub(crate) fn expire_if_needed(&self, key: &str) -> bool {
+        let now = now_ms();
+        if self.expires.remove_if(key, |_, at| *at <= now).is_none() {
+            return false;
+        }
+        self.remove_key(key);
+        self.notify(KeyspaceEvents::EXPIRED, "expired", key);
+        true
+    }
+
+    /// 主动过期：每次检查 expires 的一个分片，删除其中已经过期的 key，返回删除的数量
+    /// 脚本或者 EXEC 执行期间跳过，不和它们的写入交错
+    pub fn expire_sweep(&self) -> usize {
+        let Ok(_guard) = self.exec_lock.try_read() else {
+            return 0;
+        };
+        let shards = self.expires.shards();
+        let idx = self.expire_cursor.fetch_add(1, Ordering::Relaxed) % shards.len();
+        let now = now_ms();
+        let keys = shards[idx]
+            .read()
+            .iter()
+            .filter(|(_, at)| *at.get() <= now)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        keys.iter().filter(|key| self.expire_if_needed(key)).count()
+    }
+
+    fn remove_key(&self, key: &str) -> bool {
+        self.expires.remove(key);
+        self.versions.remove(key);
+        let deleted = [
+            self.map.remove(key).is_some(),
+            self.set.remove(key).is_some(),
+            self.list.remove(key).is_some(),
+            self.zset.remove(key).is_some(),
+            self.stream.remove(key).is_some(),
+            self.bloom.remove(key).is_some(),
+            self.cuckoo.remove(key).is_some(),
+            self.cms.remove(key).is_some(),
+            self.topk.remove(key).is_some(),
+            self.json.remove(key).is_some(),
+            self.timeseries.remove(key).is_some(),
+            self.custom.remove(key).is_some(),
+        ];
+        let hash_deleted = self.hmap.remove(key).is_some();
+        if hash_deleted {
+            self.ft_reindex(key);
+        }
+        let deleted = hash_deleted || deleted.contains(&true);
+        if deleted {
+            self.watched.signal(key);
+        }
+        deleted
+    }
+
+    /// 和 TYPE 命令返回的类型名一样，key 不存在时返回 None
+    pub fn key_type(&self, key: &str) -> Option<&'static str> {
+        self.expire_if_needed(key);
+        let types = [
+            (self.map.contains_key(key), "string"),
+            (self.hmap.contains_key(key), "hash"),
+            (self.set.contains_key(key), "set"),
+            (self.list.contains_key(key), "list"),
+            (self.zset.contains_key(key), "zset"),
+            (self.stream.contains_key(key), "stream"),
+            (self.bloom.contains_key(key), "MBbloom--"),
+            (self.cuckoo.contains_key(key), "MBbloomCF"),
+            (self.cms.contains_key(key), "CMSk-TYPE"),
+            (self.topk.contains_key(key), "TopK-TYPE"),
+            (self.json.contains_key(key), "ReJSON-RL"),
+            (self.timeseries.contains_key(key), "TSDB-TYPE"),
+        ];
+        types
+            .into_iter()
+            .find_map(|(exists, name)| exists.then_some(name))
+            .or_else(|| self.custom_type(key))
+    }
+
+    /// key 已经是 kind 以外的类型时返回 WRONGTYPE，kind 和 key_type 返回的类型名相同
+    pub(crate) fn check_key_type(&self, key: &str, kind: &str) -> Result<(), BackendError> {
+        match self.key_type(key) {
+            Some(current) if current != kind => Err(BackendError::WrongType),
+            _ => Ok(()),
+        }
+    }
+
+    /// 以 kind 类型写入 key：key 是其它类型时返回 WRONGTYPE，否则持有 key 的锁执行 f
+    /// 所有可能创建 key 的写入都要经过这里，f 中不能再对同一个 key 调用 write_typed
+    pub(crate) fn write_typed<R>(
+        &self,
+        key: &str,
+        kind: &str,
+        f: impl FnOnce() -> R,
+    ) -> Result<R, BackendError> {
+        let _guard = self.lock_keys(&[key]);
+        self.check_key_type(key, kind)?;
+        Ok(f())
+    }
+
+    /// 和 write_typed 相同，但是 key 是其它类型时先删除，用于 SET、ZUNIONSTORE 这样覆盖 key 的写入
+    pub(crate) fn overwrite_typed<R>(&self, key: &str, kind: &str, f: impl FnOnce() -> R) -> R {
+        let _guard = self.lock_keys(&[key]);
+        if self.key_type(key).is_some_and(|current| current != kind) {
+            self.remove_key(key);
+        }
+        f()
+    }
+
+    /// 按分段的顺序加锁，同时锁住多个 key 时不会死锁
+    pub(crate) fn lock_keys(&self, keys: &[&str]) -> Vec<MutexGuard<'_, ()>> {
+        let mut stripes = keys
+            .iter()
+            .map(|key| murmurhash64a(key.as_bytes(), 0) as usize % KEY_LOCK_STRIPES)
+            .collect::<Vec<_>>();
+        stripes.sort_unstable();
+        stripes.dedup();
+        stripes
+            .into_iter()
+            .map(|i| self.key_locks[i].lock().unwrap())
+            .collect()
+    }
+
+    pub fn sadd(&self, key: String, member: String) -> Result<bool, BackendError> {
+        let added = self.write_typed(&key, "set", || {
+            self.set.entry(key.clone()).or_default().insert(member)
+        })?;
+        if added {
+            self.watched.signal(&key);
+            self.notify(KeyspaceEvents::SET, "sadd", &key);
+        }
+        Ok(added)
     }
 
     pub fn sismember(&self, key: &str, member: &str) -> Option<bool> {
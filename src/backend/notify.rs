@@ -0,0 +1,236 @@
+use std::{fmt, sync::atomic::Ordering};
+
+use crate::{Backend, BackendError};
+
+// 只有一个数据库
+const DB: u32 = 0;
+
+/// notify-keyspace-events 的标记，和 Redis 使用相同的字母
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyspaceEvents(u32);
+
+impl KeyspaceEvents {
+    // K：发布到 __keyspace@<db>__:<key>，消息是事件名
+    pub const KEYSPACE: Self = Self(1 << 0);
+    // E：发布到 __keyevent@<db>__:<event>，消息是 key
+    pub const KEYEVENT: Self = Self(1 << 1);
+    // g：DEL、EXPIRE 等和类型无关的命令
+    pub const GENERIC: Self = Self(1 << 2);
+    pub const STRING: Self = Self(1 << 3);
+    pub const LIST: Self = Self(1 << 4);
+    pub const SET: Self = Self(1 << 5);
+    pub const HASH: Self = Self(1 << 6);
+    pub const ZSET: Self = Self(1 << 7);
+    pub const EXPIRED: Self = Self(1 << 8);
+    // 没有内存淘汰，不会产生 evicted 事件
+    pub const EVICTED: Self = Self(1 << 9);
+    pub const STREAM: Self = Self(1 << 10);
+    // d：布隆过滤器、JSON、时间序列和插件注册的自定义类型
+    pub const MODULE: Self = Self(1 << 11);
+    // m 和 n 可以设置，但是不会产生 keymiss 和 new 事件
+    pub const KEY_MISS: Self = Self(1 << 12);
+    pub const NEW: Self = Self(1 << 13);
+    // A：g$lshzxetd
+    pub const ALL: Self = Self(
+        Self::GENERIC.0
+            | Self::STRING.0
+            | Self::LIST.0
+            | Self::SET.0
+            | Self::HASH.0
+            | Self::ZSET.0
+            | Self::EXPIRED.0
+            | Self::EVICTED.0
+            | Self::STREAM.0
+            | Self::MODULE.0,
+    );
+
+    const LETTERS: [(char, Self); 15] = [
+        ('g', Self::GENERIC),
+        ('$', Self::STRING),
+        ('l', Self::LIST),
+        ('s', Self::SET),
+        ('h', Self::HASH),
+        ('z', Self::ZSET),
+        ('x', Self::EXPIRED),
+        ('e', Self::EVICTED),
+        ('t', Self::STREAM),
+        ('d', Self::MODULE),
+        ('K', Self::KEYSPACE),
+        ('E', Self::KEYEVENT),
+        ('m', Self::KEY_MISS),
+        ('n', Self::NEW),
+        ('A', Self::ALL),
+    ];
+
+    pub fn parse(s: &str) -> Result<Self, BackendError> {
+        s.chars().try_fold(Self::default(), |flags, c| {
+            Self::LETTERS
+                .iter()
+                .find(|(letter, _)| *letter == c)
+                .map(|(_, flag)| Self(flags.0 | flag.0))
+                .ok_or(BackendError::InvalidKeyspaceEvents)
+        })
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// 和 CONFIG GET 的格式一样，包含所有类型时用 A 表示
+impl fmt::Display for KeyspaceEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = self.contains(Self::ALL);
+        if all {
+            write!(f, "A")?;
+        }
+        for (letter, flag) in Self::LETTERS {
+            let in_all = Self::ALL.contains(flag);
+            if flag != Self::ALL && !(all && in_all) && self.contains(flag) {
+                write!(f, "{}", letter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Backend {
+    pub fn notify_keyspace_events(&self) -> KeyspaceEvents {
+        KeyspaceEvents(self.notify_flags.load(Ordering::Relaxed))
+    }
+
+    /// 设置 notify-keyspace-events，空字符串关闭通知
+    pub fn set_notify_keyspace_events(&self, flags: &str) -> Result<(), BackendError> {
+        let flags = KeyspaceEvents::parse(flags)?;
+        self.notify_flags.store(flags.0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 写入 key 之后调用，class 没有开启或者 K 和 E 都没有开启时不发布
+    pub(crate) fn notify(&self, class: KeyspaceEvents, event: &str, key: &str) {
+        let flags = self.notify_keyspace_events();
+        if !flags.contains(class) {
+            return;
+        }
+        if flags.contains(KeyspaceEvents::KEYSPACE) {
+            let channel = format!("__keyspace@{}__:{}", DB, key);
+            self.publish(&channel, event.as_bytes().to_vec());
+        }
+        if flags.contains(KeyspaceEvents::KEYEVENT) {
+            let channel = format!("__keyevent@{}__:{}", DB, event);
+            self.publish(&channel, key.as_bytes().to_vec());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backend::stream::now_ms, BulkString, ListDirection, MessageReceiver, PubSubMessage,
+        Subscriber,
+    };
+
+    use super::*;
+
+    fn received(rx: &mut MessageReceiver) -> Vec<(String, String)> {
+        let mut messages = Vec::new();
+        while let Some(PubSubMessage::PMessage {
+            channel, payload, ..
+        }) = rx.try_recv()
+        {
+            messages.push((channel, String::from_utf8(payload).unwrap()));
+        }
+        messages
+    }
+
+    fn keyspace(key: &str, event: &str) -> (String, String) {
+        (format!("__keyspace@0__:{}", key), event.to_string())
+    }
+
+    fn keyevent(event: &str, key: &str) -> (String, String) {
+        (format!("__keyevent@0__:{}", event), key.to_string())
+    }
+
+    #[test]
+    fn test_notify() {
+        let backend = Backend::new();
+        let (tx, mut rx) = Subscriber::new();
+        backend.psubscribe("__key*__:*".to_string(), 1, tx);
+
+        // 默认不发布
+        backend.set("k".to_string(), BulkString::from("v").into());
+        assert!(received(&mut rx).is_empty());
+
+        backend.set_notify_keyspace_events("KEA").unwrap();
+        backend.set("k".to_string(), BulkString::from("v").into());
+        assert_eq!(
+            received(&mut rx),
+            vec![keyspace("k", "set"), keyevent("set", "k")]
+        );
+
+        // 弹出最后一个元素时还会发布 del
+        backend.set_notify_keyspace_events("Kl").unwrap();
+        backend
+            .rpush("list".to_string(), vec!["a".to_string()])
+            .unwrap();
+        backend.lpop_count("list", ListDirection::Left, 1);
+        assert_eq!(
+            received(&mut rx),
+            vec![keyspace("list", "rpush"), keyspace("list", "lpop")]
+        );
+
+        backend.set_notify_keyspace_events("Eghx").unwrap();
+        backend
+            .hset(
+                "h".to_string(),
+                "f".to_string(),
+                BulkString::from("v").into(),
+            )
+            .unwrap();
+        backend.hdel("h", &["f".to_string()]);
+        assert_eq!(
+            received(&mut rx),
+            vec![
+                keyevent("hset", "h"),
+                keyevent("hdel", "h"),
+                keyevent("del", "h")
+            ]
+        );
+
+        backend.expire_at("k".to_string(), now_ms() - 1);
+        assert!(backend.get("k").is_none());
+        backend
+            .zadd(
+                "z".to_string(),
+                vec![(1.0, "a".to_string())],
+                Default::default(),
+            )
+            .unwrap();
+        assert_eq!(received(&mut rx), vec![keyevent("expired", "k")]);
+
+        // GEOADD 发布 geoadd 而不是 zadd
+        backend.set_notify_keyspace_events("Ez").unwrap();
+        let items = vec![(13.361389, 38.115556, "Palermo".to_string())];
+        backend
+            .geoadd("g".to_string(), items, Default::default())
+            .unwrap();
+        assert_eq!(received(&mut rx), vec![keyevent("geoadd", "g")]);
+    }
+
+    #[test]
+    fn test_keyspace_events_parse() {
+        let flags = KeyspaceEvents::parse("KEA").unwrap();
+        assert!(flags.contains(KeyspaceEvents::KEYSPACE));
+        assert!(flags.contains(KeyspaceEvents::STREAM));
+        assert!(!flags.contains(KeyspaceEvents::NEW));
+        assert_eq!(flags.to_string(), "AKE");
+
+        let flags = KeyspaceEvents::parse("Ex$g").unwrap();
+        assert_eq!(flags.to_string(), "g$xE");
+        assert_eq!(KeyspaceEvents::parse("").unwrap().to_string(), "");
+        assert_eq!(
+            KeyspaceEvents::parse("KEw"),
+            Err(BackendError::InvalidKeyspaceEvents)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    sync::Arc,
+};
+
+use dashmap::DashMap;
+
+use crate::{
+    cmd::{Command, ConnectionCommand},
+    Backend, BackendError, BulkString, KeyspaceEvents, RespArray, RespFrame,
+};
+
+/// 自定义命令，嵌入 simple_redis 的应用在启动时通过 Backend::register_command 注册
+pub trait CommandHandler: Send + Sync + 'static {
+    /// 命令名，不区分大小写
+    fn name(&self) -> &str;
+
+    /// 包括命令名在内的参数个数，和 Redis 一样负数表示至少 -arity 个
+    fn arity(&self) -> i64;
+
+    fn flags(&self) -> CommandFlags {
+        CommandFlags::default()
+    }
+
+    /// args 不包括命令名，只能通过 Backend 的公开方法访问数据
+    fn execute(&self, backend: &Backend, args: Vec<Vec<u8>>) -> RespFrame;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandFlags {
+    // 不修改数据，可以在 EVAL_RO 中调用
+    pub readonly: bool,
+    // 不能在脚本中调用
+    pub noscript: bool,
+}
+
+/// 自定义的值类型，和内置类型共用 key 空间，使用之前需要用 Backend::register_type 注册
+pub trait CustomType: Any + Send + Sync {
+    /// 类型名，不同的类型不能重名
+    const NAME: &'static str;
+}
+
+pub(crate) struct CustomValue {
+    type_name: &'static str,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl fmt::Debug for CustomValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomValue")
+            .field("type_name", &self.type_name)
+            .finish()
+    }
+}
+
+/// 注册的自定义命令和类型
+#[derive(Default)]
+pub struct Plugins {
+    commands: DashMap<String, Arc<dyn CommandHandler>>,
+    types: DashMap<&'static str, TypeId>,
+}
+
+impl fmt::Debug for Plugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let commands = self.commands.iter().map(|c| c.key().clone());
+        f.debug_struct("Plugins")
+            .field("commands", &commands.collect::<Vec<_>>())
+            .field("types", &self.types)
+            .finish()
+    }
+}
+
+impl Backend {
+    /// 注册自定义命令，不能和内置命令或者已经注册的命令重名
+    pub fn register_command(&self, handler: impl CommandHandler) -> Result<(), BackendError> {
+        let name = handler.name().to_ascii_lowercase();
+        // 只有命令名的请求能被解析成内置命令（包括参数错误），说明命令名已经被占用
+        let frame: RespFrame = RespArray::new([BulkString::from(name.as_str()).into()]).into();
+        let builtin = ConnectionCommand::matches(&frame)
+            || !matches!(Command::try_from(frame), Ok(Command::Unrecognized(_)));
+        if builtin || self.plugins.commands.contains_key(&name) {
+            return Err(BackendError::CommandExists(name));
+        }
+        self.plugins.commands.insert(name, Arc::new(handler));
+        Ok(())
+    }
+
+    pub fn custom_command(&self, name: &str) -> Option<Arc<dyn CommandHandler>> {
+        let name = name.to_ascii_lowercase();
+        self.plugins.commands.get(&name).map(|c| c.clone())
+    }
+
+    pub fn register_type<T: CustomType>(&self) -> Result<(), BackendError> {
+        let id = *self
+            .plugins
+            .types
+            .entry(T::NAME)
+            .or_insert(TypeId::of::<T>());
+        if id != TypeId::of::<T>() {
+            return Err(BackendError::TypeExists(T::NAME.to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_type<T: CustomType>(&self) -> Result<(), BackendError> {
+        match self.plugins.types.get(T::NAME) {
+            Some(id) if *id == TypeId::of::<T>() => Ok(()),
+            _ => Err(BackendError::UnknownType(T::NAME.to_string())),
+        }
+    }
+
+    /// 读取自定义类型的值，key 不存在时返回 None，类型不同时返回 WRONGTYPE
+    pub fn custom_get<T: CustomType, R>(
+        &self,
+        key: &str,
+        f: impl FnOnce(&T) -> R,
+    ) -> Result<Option<R>, BackendError> {
+        self.check_type::<T>()?;
+        self.expire_if_needed(key);
+        match self.custom.get(key) {
+            Some(v) => match v.value.downcast_ref::<T>() {
+                Some(value) => Ok(Some(f(value))),
+                None => Err(BackendError::WrongType),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// 修改自定义类型的值，key 不存在时先用 init 创建
+    pub fn custom_update<T: CustomType, R>(
+        &self,
+        key: &str,
+        init: impl FnOnce() -> T,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, BackendError> {
+        self.check_type::<T>()?;
+        let _guard = self.lock_keys(&[key]);
+        self.check_key_type(key, T::NAME)?;
+        let mut entry = self
+            .custom
+            .entry(key.to_string())
+            .or_insert_with(|| CustomValue {
+                type_name: T::NAME,
+                value: Box::new(init()),
+            });
+        let value = entry
+            .value
+            .downcast_mut::<T>()
+            .ok_or(BackendError::WrongType)?;
+        let ret = f(value);
+        self.watched.signal(key);
+        // 不知道插件执行的是什么操作，用类型名作为事件名
+        self.notify(KeyspaceEvents::MODULE, T::NAME, key);
+        Ok(ret)
+    }
+
+    /// 返回自定义类型的类型名
+    pub fn custom_type(&self, key: &str) -> Option<&'static str> {
+        self.expire_if_needed(key);
+        self.custom.get(key).map(|v| v.type_name)
+    }
+}
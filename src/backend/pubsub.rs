@@ -0,0 +1,476 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use dashmap::DashMap;
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Notify,
+};
+
+use crate::{Backend, BulkString, RespFrame};
+
+/// 推送给订阅者的消息，由连接按照协议版本编码成 array 或者 push
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PubSubMessage {
+    Message {
+        channel: String,
+        payload: Vec<u8>,
+    },
+    // 通过 PSUBSCRIBE 的模式匹配到的消息
+    PMessage {
+        pattern: String,
+        channel: String,
+        payload: Vec<u8>,
+    },
+}
+
+impl PubSubMessage {
+    pub fn into_frames(self) -> Vec<RespFrame> {
+        match self {
+            PubSubMessage::Message { channel, payload } => vec![
+                BulkString::from("message").into(),
+                BulkString::from(channel).into(),
+                BulkString::new(payload).into(),
+            ],
+            PubSubMessage::PMessage {
+                pattern,
+                channel,
+                payload,
+            } => vec![
+                BulkString::from("pmessage").into(),
+                BulkString::from(pattern).into(),
+                BulkString::from(channel).into(),
+                BulkString::new(payload).into(),
+            ],
+        }
+    }
+
+    // 计入输出缓冲区的大小
+    fn size(&self) -> usize {
+        match self {
+            PubSubMessage::Message { channel, payload } => channel.len() + payload.len(),
+            PubSubMessage::PMessage {
+                pattern,
+                channel,
+                payload,
+            } => pattern.len() + channel.len() + payload.len(),
+        }
+    }
+}
+
+/// 订阅者的发送端，记录已经发送但是还没有写给客户端的消息大小
+#[derive(Debug, Clone)]
+pub struct Subscriber {
+    sender: UnboundedSender<PubSubMessage>,
+    pending: Arc<AtomicUsize>,
+    overflow: Arc<Notify>,
+}
+
+/// 连接接收订阅消息的一端
+#[derive(Debug)]
+pub struct MessageReceiver {
+    receiver: UnboundedReceiver<PubSubMessage>,
+    pending: Arc<AtomicUsize>,
+    overflow: Arc<Notify>,
+}
+
+impl Subscriber {
+    pub fn new() -> (Self, MessageReceiver) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let overflow = Arc::new(Notify::new());
+        let subscriber = Self {
+            sender,
+            pending: pending.clone(),
+            overflow: overflow.clone(),
+        };
+        let receiver = MessageReceiver {
+            receiver,
+            pending,
+            overflow,
+        };
+        (subscriber, receiver)
+    }
+
+    // 超过输出缓冲区限制时不再发送，通知连接断开，limit 为 0 表示不限制
+    fn send(&self, message: PubSubMessage, limit: usize) -> bool {
+        let size = message.size();
+        let pending = self.pending.fetch_add(size, Ordering::Relaxed) + size;
+        if limit > 0 && pending > limit {
+            self.pending.fetch_sub(size, Ordering::Relaxed);
+            self.overflow.notify_one();
+            return false;
+        }
+        self.sender.send(message).is_ok()
+    }
+}
+
+impl MessageReceiver {
+    /// 返回 None 表示积压的消息超过了输出缓冲区限制，连接需要断开
+    pub async fn recv(&mut self) -> Option<PubSubMessage> {
+        tokio::select! {
+            biased;
+            _ = self.overflow.notified() => None,
+            message = self.receiver.recv() => {
+                let message = message?;
+                self.pending.fetch_sub(message.size(), Ordering::Relaxed);
+                Some(message)
+            }
+        }
+    }
+
+    /// 积压的消息超过输出缓冲区限制时返回
+    pub async fn overflowed(&self) {
+        self.overflow.notified().await
+    }
+
+    pub fn try_recv(&mut self) -> Option<PubSubMessage> {
+        let message = self.receiver.try_recv().ok()?;
+        self.pending.fetch_sub(message.size(), Ordering::Relaxed);
+        Some(message)
+    }
+}
+
+/// channel -> 订阅者的连接 id -> 发送消息的 sender
+#[derive(Debug)]
+pub struct PubSub {
+    channels: DashMap<String, HashMap<u64, Subscriber>>,
+    patterns: RwLock<PatternIndex>,
+    // 每个订阅者积压的消息字节数上限，和 client-output-buffer-limit pubsub 的硬限制一样
+    output_limit: AtomicUsize,
+}
+
+impl Default for PubSub {
+    fn default() -> Self {
+        Self {
+            channels: DashMap::new(),
+            patterns: RwLock::new(PatternIndex::default()),
+            output_limit: AtomicUsize::new(32 * 1024 * 1024),
+        }
+    }
+}
+
+// 按模式中第一个通配符之前的字面前缀分组，发布时只需要检查前缀是 channel 前缀的模式，
+// 而不是遍历所有的模式
+#[derive(Debug, Default)]
+struct PatternIndex {
+    // 前缀 -> 模式 -> 订阅者
+    prefixes: HashMap<String, BTreeMap<String, HashMap<u64, Subscriber>>>,
+    // 出现过的前缀长度 -> 前缀个数，发布时只查找这些长度的前缀
+    lengths: BTreeMap<usize, usize>,
+}
+
+fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['*', '?', '[', '\\']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// 和 Redis 的 stringmatchlen 一样支持 *、?、[abc]、[^a]、[a-z] 和 \ 转义
+/// 匹配失败时只回溯到最近的 *，时间复杂度是 O(模式长度 * 字符串长度)
+pub(crate) fn glob_match(pattern: &[u8], s: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // 最近一个 * 之后的模式位置，以及这个 * 匹配结束的字符串位置
+    let mut star: Option<(usize, usize)> = None;
+    while i < s.len() {
+        let next = match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                star = Some((p, i));
+                continue;
+            }
+            Some(b'?') => Some(p + 1),
+            Some(b'[') => match match_class(&pattern[p + 1..], s[i]) {
+                Some((true, rest)) => Some(pattern.len() - rest.len()),
+                _ => None,
+            },
+            Some(b'\\') if p + 1 < pattern.len() => (pattern[p + 1] == s[i]).then_some(p + 2),
+            Some(&c) => (c == s[i]).then_some(p + 1),
+            None => None,
+        };
+        match (next, star.as_mut()) {
+            (Some(next), _) => {
+                p = next;
+                i += 1;
+            }
+            // 让最近的 * 多匹配一个字符，从它后面的模式重新开始
+            (None, Some((star_p, star_i))) => {
+                *star_i += 1;
+                p = *star_p;
+                i = *star_i;
+            }
+            (None, None) => return false,
+        }
+    }
+    // 字符串匹配完之后剩下的模式只能是 *
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+// 匹配 [ 之后的字符集合，返回是否匹配和 ] 之后的模式，没有 ] 时把剩下的都当作集合
+fn match_class(pattern: &[u8], c: u8) -> Option<(bool, &[u8])> {
+    let (negate, mut pattern) = match pattern.split_first() {
+        Some((b'^', rest)) => (true, rest),
+        _ => (false, pattern),
+    };
+    let mut matched = false;
+    loop {
+        match pattern {
+            [] => break,
+            [b']', rest @ ..] => {
+                pattern = rest;
+                break;
+            }
+            [b'\\', p, rest @ ..] => {
+                matched |= *p == c;
+                pattern = rest;
+            }
+            [start, b'-', end, rest @ ..] if *end != b']' => {
+                let (low, high) = (*start.min(end), *start.max(end));
+                matched |= (low..=high).contains(&c);
+                pattern = rest;
+            }
+            [p, rest @ ..] => {
+                matched |= *p == c;
+                pattern = rest;
+            }
+        }
+    }
+    Some((matched != negate, pattern))
+}
+
+impl Backend {
+    pub fn subscribe(&self, channel: String, id: u64, subscriber: Subscriber) {
+        self.pubsub
+            .channels
+            .entry(channel)
+            .or_default()
+            .insert(id, subscriber);
+    }
+
+    /// 最后一个订阅者退订时删除 channel
+    pub fn unsubscribe(&self, channel: &str, id: u64) {
+        if let Some(mut subscribers) = self.pubsub.channels.get_mut(channel) {
+            subscribers.remove(&id);
+        }
+        self.pubsub
+            .channels
+            .remove_if(channel, |_, subscribers| subscribers.is_empty());
+    }
+
+    /// 有订阅者的 channel，按名字排序，pattern 为 None 时返回所有的 channel
+    pub fn pubsub_channels(&self, pattern: Option<&str>) -> Vec<String> {
+        let mut channels = self
+            .pubsub
+            .channels
+            .iter()
+            .filter(|entry| match pattern {
+                Some(pattern) => glob_match(pattern.as_bytes(), entry.key().as_bytes()),
+                None => true,
+            })
+            .map(|entry| entry.key().clone())
+            .collect::<Vec<_>>();
+        channels.sort();
+        channels
+    }
+
+    /// channel 的订阅者数量，不包括模式订阅
+    pub fn pubsub_numsub(&self, channel: &str) -> usize {
+        self.pubsub
+            .channels
+            .get(channel)
+            .map_or(0, |subscribers| subscribers.len())
+    }
+
+    /// 被订阅的模式数量，多个连接订阅同一个模式只算一次
+    pub fn pubsub_numpat(&self) -> usize {
+        let index = self.pubsub.patterns.read().unwrap();
+        index.prefixes.values().map(|patterns| patterns.len()).sum()
+    }
+
+    pub fn pubsub_output_limit(&self) -> usize {
+        self.pubsub.output_limit.load(Ordering::Relaxed)
+    }
+
+    /// 设置每个订阅者积压的消息字节数上限，0 表示不限制
+    pub fn set_pubsub_output_limit(&self, limit: usize) {
+        self.pubsub.output_limit.store(limit, Ordering::Relaxed);
+    }
+
+    pub fn psubscribe(&self, pattern: String, id: u64, subscriber: Subscriber) {
+        let mut index = self.pubsub.patterns.write().unwrap();
+        let prefix = literal_prefix(&pattern).to_string();
+        if !index.prefixes.contains_key(&prefix) {
+            *index.lengths.entry(prefix.len()).or_default() += 1;
+        }
+        index
+            .prefixes
+            .entry(prefix)
+            .or_default()
+            .entry(pattern)
+            .or_default()
+            .insert(id, subscriber);
+    }
+
+    pub fn punsubscribe(&self, pattern: &str, id: u64) {
+        let mut index = self.pubsub.patterns.write().unwrap();
+        let prefix = literal_prefix(pattern);
+        let Some(patterns) = index.prefixes.get_mut(prefix) else {
+            return;
+        };
+        if let Some(subscribers) = patterns.get_mut(pattern) {
+            subscribers.remove(&id);
+            if subscribers.is_empty() {
+                patterns.remove(pattern);
+            }
+        }
+        if patterns.is_empty() {
+            index.prefixes.remove(prefix);
+            if let Some(count) = index.lengths.get_mut(&prefix.len()) {
+                *count -= 1;
+                if *count == 0 {
+                    index.lengths.remove(&prefix.len());
+                }
+            }
+        }
+    }
+
+    /// 返回收到消息的次数，同时通过 channel 和模式订阅的连接会收到多次，
+    /// 连接已经断开或者超过输出缓冲区限制的订阅者不计算在内
+    pub fn publish(&self, channel: &str, payload: Vec<u8>) -> usize {
+        let limit = self.pubsub_output_limit();
+        let mut received = 0;
+        if let Some(subscribers) = self.pubsub.channels.get(channel) {
+            let message = PubSubMessage::Message {
+                channel: channel.to_string(),
+                payload: payload.clone(),
+            };
+            received += subscribers
+                .values()
+                .filter(|subscriber| subscriber.send(message.clone(), limit))
+                .count();
+        }
+
+        let index = self.pubsub.patterns.read().unwrap();
+        for &len in index.lengths.keys() {
+            let Some(patterns) = channel
+                .get(..len)
+                .and_then(|prefix| index.prefixes.get(prefix))
+            else {
+                continue;
+            };
+            for (pattern, subscribers) in patterns {
+                if !glob_match(pattern.as_bytes(), channel.as_bytes()) {
+                    continue;
+                }
+                let message = PubSubMessage::PMessage {
+                    pattern: pattern.clone(),
+                    channel: channel.to_string(),
+                    payload: payload.clone(),
+                };
+                received += subscribers
+                    .values()
+                    .filter(|subscriber| subscriber.send(message.clone(), limit))
+                    .count();
+            }
+        }
+        received
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        let cases = [
+            ("events.*", "events.login", true),
+            ("events.*", "events", false),
+            ("*", "", true),
+            ("h?llo", "hello", true),
+            ("h?llo", "hllo", false),
+            ("h[ae]llo", "hallo", true),
+            ("h[ae]llo", "hillo", false),
+            ("h[^e]llo", "hallo", true),
+            ("h[^e]llo", "hello", false),
+            ("h[a-b]llo", "hbllo", true),
+            ("h[a-b]llo", "hcllo", false),
+            ("a\\*b", "a*b", true),
+            ("a\\*b", "axb", false),
+            ("*.log.*", "app.log.error", true),
+            ("a*b*c", "aXbYc", true),
+            ("a*b*c", "aXbY", false),
+            ("*[", "a[", false),
+            ("a\\", "a\\", true),
+        ];
+        for (pattern, s, expected) in cases {
+            assert_eq!(
+                glob_match(pattern.as_bytes(), s.as_bytes()),
+                expected,
+                "{pattern} {s}"
+            );
+        }
+
+        // 很多个 * 时不会指数级回溯
+        let pattern = "a*".repeat(30) + "b";
+        let s = "a".repeat(100);
+        assert!(!glob_match(pattern.as_bytes(), s.as_bytes()));
+    }
+
+    #[test]
+    fn test_pattern_index() {
+        let backend = Backend::new();
+        let (tx, mut rx) = Subscriber::new();
+        backend.psubscribe("events.*".to_string(), 1, tx.clone());
+        backend.psubscribe("events.log*".to_string(), 1, tx.clone());
+        backend.psubscribe("*".to_string(), 1, tx.clone());
+        backend.subscribe("events.login".to_string(), 1, tx);
+
+        // 同一个连接的每个匹配都会收到一次消息
+        assert_eq!(backend.publish("events.login", b"x".to_vec()), 4);
+        assert_eq!(backend.publish("other", b"x".to_vec()), 1);
+        let mut received = Vec::new();
+        while let Some(message) = rx.try_recv() {
+            received.push(message);
+        }
+        assert_eq!(received.len(), 5);
+        assert!(received.contains(&PubSubMessage::PMessage {
+            pattern: "events.log*".to_string(),
+            channel: "events.login".to_string(),
+            payload: b"x".to_vec(),
+        }));
+
+        backend.punsubscribe("events.*", 1);
+        backend.punsubscribe("events.log*", 1);
+        backend.punsubscribe("*", 1);
+        assert_eq!(backend.publish("events.login", b"x".to_vec()), 1);
+        let index = backend.pubsub.patterns.read().unwrap();
+        assert!(index.prefixes.is_empty() && index.lengths.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_output_limit() {
+        let backend = Backend::new();
+        backend.set_pubsub_output_limit(10);
+        let (tx, mut rx) = Subscriber::new();
+        backend.subscribe("news".to_string(), 1, tx);
+
+        // "news" + "abc" 占 7 个字节，第二条消息超过限制，连接被通知断开
+        assert_eq!(backend.publish("news", b"abc".to_vec()), 1);
+        assert_eq!(backend.publish("news", b"abc".to_vec()), 0);
+        assert_eq!(rx.recv().await, None);
+
+        // 取走积压的消息之后可以继续发送
+        assert!(rx.try_recv().is_some());
+        assert_eq!(backend.publish("news", b"abc".to_vec()), 1);
+
+        backend.set_pubsub_output_limit(0);
+        for _ in 0..10 {
+            assert_eq!(backend.publish("news", b"abc".to_vec()), 1);
+        }
+    }
+}
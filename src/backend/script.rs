@@ -0,0 +1,116 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+
+use crate::{Backend, BackendError};
+
+/// Lua 脚本的缓存和正在执行的脚本的状态
+/// 脚本持有 exec_lock 的写锁执行，同一时间最多只有一个脚本在执行
+#[derive(Debug)]
+pub struct Scripts {
+    // SHA1 -> 脚本内容
+    cache: DashMap<String, String>,
+    running: Mutex<Option<RunningScript>>,
+    // 脚本执行超过这个时间之后，其它客户端的命令返回 BUSY，可以用 SCRIPT KILL 终止脚本
+    time_limit: Mutex<Duration>,
+}
+
+#[derive(Debug)]
+struct RunningScript {
+    started: Instant,
+    // 执行过写命令的脚本不能被终止，否则数据会处于中间状态
+    wrote: bool,
+    killed: bool,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        Self {
+            cache: DashMap::new(),
+            running: Mutex::new(None),
+            time_limit: Mutex::new(Duration::from_secs(5)),
+        }
+    }
+}
+
+pub fn sha1_hex(source: &str) -> String {
+    sha1_smol::Sha1::from(source).digest().to_string()
+}
+
+impl Backend {
+    /// 缓存脚本并返回它的 SHA1
+    pub fn script_load(&self, source: String) -> String {
+        let sha = sha1_hex(&source);
+        self.scripts.cache.insert(sha.clone(), source);
+        sha
+    }
+
+    pub fn script_get(&self, sha: &str) -> Option<String> {
+        let sha = sha.to_ascii_lowercase();
+        self.scripts.cache.get(&sha).map(|s| s.clone())
+    }
+
+    pub fn script_exists(&self, sha: &str) -> bool {
+        self.scripts.cache.contains_key(&sha.to_ascii_lowercase())
+    }
+
+    pub fn script_flush(&self) {
+        self.scripts.cache.clear();
+    }
+
+    /// 标记正在执行的脚本需要终止，脚本在下一次检查时返回错误
+    pub fn script_kill(&self) -> Result<(), BackendError> {
+        let mut running = self.scripts.running.lock().unwrap();
+        match running.as_mut() {
+            None => Err(BackendError::ScriptNotBusy),
+            Some(script) if script.wrote => Err(BackendError::ScriptUnkillable),
+            Some(script) => {
+                script.killed = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// 是否有脚本执行超过了 lua-time-limit
+    pub fn script_busy(&self) -> bool {
+        let limit = *self.scripts.time_limit.lock().unwrap();
+        let running = self.scripts.running.lock().unwrap();
+        running
+            .as_ref()
+            .is_some_and(|script| script.started.elapsed() >= limit)
+    }
+
+    pub fn lua_time_limit(&self) -> Duration {
+        *self.scripts.time_limit.lock().unwrap()
+    }
+
+    pub fn set_lua_time_limit(&self, limit: Duration) {
+        *self.scripts.time_limit.lock().unwrap() = limit;
+    }
+
+    pub(crate) fn script_begin(&self) {
+        *self.scripts.running.lock().unwrap() = Some(RunningScript {
+            started: Instant::now(),
+            wrote: false,
+            killed: false,
+        });
+    }
+
+    pub(crate) fn script_end(&self) {
+        self.scripts.running.lock().unwrap().take();
+    }
+
+    pub(crate) fn script_wrote(&self) {
+        if let Some(script) = self.scripts.running.lock().unwrap().as_mut() {
+            script.wrote = true;
+        }
+    }
+
+    pub(crate) fn script_killed(&self) -> bool {
+        let running = self.scripts.running.lock().unwrap();
+        running.as_ref().is_some_and(|script| script.killed)
+    }
+}
@@ -0,0 +1,894 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::Bound,
+    str::FromStr,
+};
+
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, BulkString, KeyspaceEvents, RespFrame};
+
+use super::{
+    vector::{parse_vector, VectorIndex, VectorParams},
+    BackendError,
+};
+
+/// 索引字段的类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Text,
+    Numeric,
+    Tag { separator: char },
+    Vector(VectorParams),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+    pub name: String,
+    pub field_type: FieldType,
+}
+
+/// FT.CREATE 的参数：索引哪些前缀的 hash 以及哪些字段
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexDefinition {
+    // 为空时索引所有的 hash
+    pub prefixes: Vec<String>,
+    pub fields: Vec<FieldSchema>,
+}
+
+/// FT.SEARCH 的查询条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    All,
+    // field 为 None 时匹配所有 TEXT 字段
+    Term {
+        field: Option<String>,
+        term: String,
+        prefix: bool,
+    },
+    Numeric {
+        field: String,
+        min: Bound<f64>,
+        max: Bound<f64>,
+    },
+    Tag {
+        field: String,
+        tags: Vec<String>,
+    },
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+/// FT.SEARCH 中的 `=>[KNN k @field $blob]`，blob 是小端序的 float32 数组
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnnQuery {
+    pub k: usize,
+    pub field: String,
+    pub blob: Vec<u8>,
+    // 返回结果中保存距离的字段名，默认是 __<field>_score
+    pub score_field: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub offset: usize,
+    pub num: usize,
+    // 字段名和是否降序
+    pub sort_by: Option<(String, bool)>,
+    pub return_fields: Option<Vec<String>>,
+    pub no_content: bool,
+    pub knn: Option<KnnQuery>,
+}
+
+/// FT.SEARCH 的结果，fields 在 NOCONTENT 时为 None
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchDocument {
+    pub key: String,
+    pub fields: Option<Vec<(String, RespFrame)>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub total: usize,
+    pub docs: Vec<SearchDocument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    pub definition: IndexDefinition,
+    pub num_docs: usize,
+    pub num_terms: usize,
+    pub num_records: usize,
+}
+
+// 每个字段的倒排索引
+#[derive(Debug, Clone)]
+enum FieldIndex {
+    // BTreeMap 方便做前缀匹配
+    Text(BTreeMap<String, HashSet<String>>),
+    Numeric(BTreeMap<u64, HashSet<String>>),
+    Tag(BTreeMap<String, HashSet<String>>),
+    Vector(VectorIndex),
+}
+
+// 文档中被索引的值，删除文档和排序时使用
+#[derive(Debug, Clone)]
+enum FieldValue {
+    Text(String, Vec<String>),
+    Numeric(f64),
+    Tag(Vec<String>),
+    // 向量保存在 VectorIndex 中
+    Vector,
+}
+
+// 命中的 key 和 KNN 的距离
+type SearchHit = (String, Option<f32>);
+
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    definition: IndexDefinition,
+    indexes: Vec<FieldIndex>,
+    docs: HashMap<String, Vec<Option<FieldValue>>>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            num: 10,
+            sort_by: None,
+            return_fields: None,
+            no_content: false,
+            knn: None,
+        }
+    }
+}
+
+// 把 f64 映射成保持大小顺序的 u64
+fn ordered_key(value: f64) -> u64 {
+    let bits = (value + 0.0).to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | 1 << 63
+    }
+}
+
+fn ordered_bound(bound: Bound<f64>) -> Bound<u64> {
+    match bound {
+        Bound::Included(v) => Bound::Included(ordered_key(v)),
+        Bound::Excluded(v) => Bound::Excluded(ordered_key(v)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// TEXT 字段的分词：按非字母数字的字符切分并转为小写
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
+
+/// hash 中的值转为字符串，无法转换的值不会被索引
+pub(crate) fn frame_to_string(frame: &RespFrame) -> Option<String> {
+    match frame {
+        RespFrame::BulkString(s) => String::from_utf8(s.0.clone()).ok(),
+        RespFrame::SimpleString(s) => Some(s.0.clone()),
+        RespFrame::Integer(i) => Some(i.to_string()),
+        RespFrame::Double(d) => Some(d.to_string()),
+        _ => None,
+    }
+}
+
+fn hash_vector(hash: &HashMap<String, RespFrame>, field: &str, dim: usize) -> Option<Vec<f32>> {
+    match hash.get(field)? {
+        RespFrame::BulkString(blob) => parse_vector(blob, dim),
+        _ => None,
+    }
+}
+
+// 从倒排索引中删除文档，没有文档的词也一起删除
+fn remove_doc<K: Ord>(map: &mut BTreeMap<K, HashSet<String>>, key: &K, doc: &str) {
+    if let Some(docs) = map.get_mut(key) {
+        docs.remove(doc);
+        if docs.is_empty() {
+            map.remove(key);
+        }
+    }
+}
+
+impl SearchIndex {
+    pub fn new(definition: IndexDefinition) -> Self {
+        let indexes = definition
+            .fields
+            .iter()
+            .map(|f| match f.field_type {
+                FieldType::Text => FieldIndex::Text(BTreeMap::new()),
+                FieldType::Numeric => FieldIndex::Numeric(BTreeMap::new()),
+                FieldType::Tag { .. } => FieldIndex::Tag(BTreeMap::new()),
+                FieldType::Vector(params) => FieldIndex::Vector(VectorIndex::new(params)),
+            })
+            .collect();
+        Self {
+            definition,
+            indexes,
+            docs: HashMap::new(),
+        }
+    }
+
+    pub fn definition(&self) -> &IndexDefinition {
+        &self.definition
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        self.definition.prefixes.is_empty()
+            || self.definition.prefixes.iter().any(|p| key.starts_with(p))
+    }
+
+    fn field_position(&self, name: &str) -> Result<usize, BackendError> {
+        self.definition
+            .fields
+            .iter()
+            .position(|f| f.name == name)
+            .ok_or_else(|| BackendError::SearchUnknownField(name.to_string()))
+    }
+
+    /// 重新索引一个文档，先删除旧的索引数据，向量没有变化时不会重新插入
+    fn add(&mut self, key: &str, hash: &HashMap<String, RespFrame>) {
+        if let Some(values) = self.docs.remove(key) {
+            self.unindex(key, values, true);
+        }
+        let values = self
+            .definition
+            .fields
+            .iter()
+            .map(|f| {
+                if let FieldType::Vector(params) = f.field_type {
+                    return hash_vector(hash, &f.name, params.dim).map(|_| FieldValue::Vector);
+                }
+                let value = frame_to_string(hash.get(&f.name)?)?;
+                match f.field_type {
+                    FieldType::Text => {
+                        let terms = tokenize(&value);
+                        Some(FieldValue::Text(value, terms))
+                    }
+                    FieldType::Numeric => value.trim().parse().ok().map(FieldValue::Numeric),
+                    FieldType::Tag { separator } => Some(FieldValue::Tag(
+                        value
+                            .split(separator)
+                            .map(|t| t.trim().to_lowercase())
+                            .filter(|t| !t.is_empty())
+                            .collect(),
+                    )),
+                    FieldType::Vector(_) => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let fields = self.definition.fields.iter();
+        for ((index, value), field) in self.indexes.iter_mut().zip(values.iter()).zip(fields) {
+            match (index, value) {
+                (FieldIndex::Text(map), Some(FieldValue::Text(_, terms))) => {
+                    for term in terms {
+                        map.entry(term.clone()).or_default().insert(key.to_string());
+                    }
+                }
+                (FieldIndex::Numeric(map), Some(FieldValue::Numeric(v))) => {
+                    map.entry(ordered_key(*v))
+                        .or_default()
+                        .insert(key.to_string());
+                }
+                (FieldIndex::Tag(map), Some(FieldValue::Tag(tags))) => {
+                    for tag in tags {
+                        map.entry(tag.clone()).or_default().insert(key.to_string());
+                    }
+                }
+                (FieldIndex::Vector(index), value) => {
+                    let dim = index.params().dim;
+                    match value.as_ref().and(hash_vector(hash, &field.name, dim)) {
+                        Some(vector) => index.insert(key, vector),
+                        None => index.remove(key),
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.docs.insert(key.to_string(), values);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(values) = self.docs.remove(key) {
+            self.unindex(key, values, false);
+        }
+    }
+
+    // 从倒排索引中删除文档的数据，keep_vectors 为 true 时由 add 决定是否更新向量
+    fn unindex(&mut self, key: &str, values: Vec<Option<FieldValue>>, keep_vectors: bool) {
+        for (index, value) in self.indexes.iter_mut().zip(values) {
+            match (index, value) {
+                (FieldIndex::Text(map), Some(FieldValue::Text(_, terms))) => {
+                    for term in terms {
+                        remove_doc(map, &term, key);
+                    }
+                }
+                (FieldIndex::Numeric(map), Some(FieldValue::Numeric(v))) => {
+                    remove_doc(map, &ordered_key(v), key);
+                }
+                (FieldIndex::Tag(map), Some(FieldValue::Tag(tags))) => {
+                    for tag in tags {
+                        remove_doc(map, &tag, key);
+                    }
+                }
+                (FieldIndex::Vector(index), Some(FieldValue::Vector)) if !keep_vectors => {
+                    index.remove(key);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn info(&self) -> IndexInfo {
+        let (mut num_terms, mut num_records) = (0, 0);
+        for index in &self.indexes {
+            if let FieldIndex::Text(map) = index {
+                num_terms += map.len();
+                num_records += map.values().map(|docs| docs.len()).sum::<usize>();
+            }
+        }
+        IndexInfo {
+            definition: self.definition.clone(),
+            num_docs: self.docs.len(),
+            num_terms,
+            num_records,
+        }
+    }
+
+    fn eval(&self, query: &Query) -> Result<HashSet<String>, BackendError> {
+        match query {
+            Query::All => Ok(self.docs.keys().cloned().collect()),
+            Query::Term {
+                field,
+                term,
+                prefix,
+            } => {
+                let positions = match field {
+                    Some(name) => vec![self.field_position(name)?],
+                    None => (0..self.indexes.len()).collect(),
+                };
+                let mut ret = HashSet::new();
+                for pos in positions {
+                    let map = match &self.indexes[pos] {
+                        FieldIndex::Text(map) => map,
+                        _ if field.is_some() => {
+                            return Err(BackendError::SearchFieldType(
+                                self.definition.fields[pos].name.clone(),
+                            ))
+                        }
+                        _ => continue,
+                    };
+                    if *prefix {
+                        map.range::<String, _>(term..)
+                            .take_while(|(t, _)| t.starts_with(term.as_str()))
+                            .for_each(|(_, docs)| ret.extend(docs.iter().cloned()));
+                    } else if let Some(docs) = map.get(term) {
+                        ret.extend(docs.iter().cloned());
+                    }
+                }
+                Ok(ret)
+            }
+            Query::Numeric { field, min, max } => {
+                let pos = self.field_position(field)?;
+                let FieldIndex::Numeric(map) = &self.indexes[pos] else {
+                    return Err(BackendError::SearchFieldType(field.clone()));
+                };
+                let (min, max) = (ordered_bound(*min), ordered_bound(*max));
+                // BTreeMap::range 在区间为空时会 panic
+                let empty = match (min, max) {
+                    (Bound::Included(a), Bound::Included(b)) => a > b,
+                    (
+                        Bound::Included(a) | Bound::Excluded(a),
+                        Bound::Included(b) | Bound::Excluded(b),
+                    ) => a >= b,
+                    _ => false,
+                };
+                if empty {
+                    return Ok(HashSet::new());
+                }
+                Ok(map
+                    .range((min, max))
+                    .flat_map(|(_, docs)| docs.iter().cloned())
+                    .collect())
+            }
+            Query::Tag { field, tags } => {
+                let pos = self.field_position(field)?;
+                let FieldIndex::Tag(map) = &self.indexes[pos] else {
+                    return Err(BackendError::SearchFieldType(field.clone()));
+                };
+                Ok(tags
+                    .iter()
+                    .filter_map(|t| map.get(t))
+                    .flat_map(|docs| docs.iter().cloned())
+                    .collect())
+            }
+            Query::And(queries) => {
+                let mut ret: Option<HashSet<String>> = None;
+                for q in queries {
+                    let docs = self.eval(q)?;
+                    ret = Some(match ret {
+                        Some(prev) => prev.intersection(&docs).cloned().collect(),
+                        None => docs,
+                    });
+                }
+                Ok(ret.unwrap_or_default())
+            }
+            Query::Or(queries) => {
+                let mut ret = HashSet::new();
+                for q in queries {
+                    ret.extend(self.eval(q)?);
+                }
+                Ok(ret)
+            }
+            Query::Not(query) => {
+                let excluded = self.eval(query)?;
+                Ok(self
+                    .docs
+                    .keys()
+                    .filter(|k| !excluded.contains(*k))
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
+
+    /// 返回命中的 key 的总数和排序分页之后的 key，KNN 查询时同时返回距离
+    fn search(
+        &self,
+        query: &Query,
+        opts: &SearchOptions,
+    ) -> Result<(usize, Vec<SearchHit>), BackendError> {
+        let docs = self.eval(query)?;
+        let mut hits = match &opts.knn {
+            Some(knn) => {
+                let pos = self.field_position(&knn.field)?;
+                let FieldIndex::Vector(index) = &self.indexes[pos] else {
+                    return Err(BackendError::SearchFieldType(knn.field.clone()));
+                };
+                // 只在满足过滤条件的文档中查找最近的 k 个
+                let filter = (*query != Query::All).then_some(&docs);
+                index
+                    .knn(&knn.blob, knn.k, filter)?
+                    .into_iter()
+                    .map(|(key, score)| (key, Some(score)))
+                    .collect::<Vec<_>>()
+            }
+            None => docs.into_iter().map(|key| (key, None)).collect(),
+        };
+        let total = hits.len();
+
+        let by_score = |a: &SearchHit, b: &SearchHit| {
+            let (a, b) = (a.1.unwrap_or(f32::MAX), b.1.unwrap_or(f32::MAX));
+            a.total_cmp(&b)
+        };
+        match &opts.sort_by {
+            Some((field, desc)) if opts.knn.as_ref().is_some_and(|k| k.score_field == *field) => {
+                hits.sort_by(|a, b| {
+                    let ord = by_score(a, b);
+                    let ord = if *desc { ord.reverse() } else { ord };
+                    ord.then_with(|| a.0.cmp(&b.0))
+                });
+            }
+            Some((field, desc)) => {
+                let pos = self.field_position(field)?;
+                let mut sorted = hits
+                    .into_iter()
+                    .map(|hit| {
+                        let value = self.docs.get(&hit.0).and_then(|v| v[pos].clone());
+                        (value, hit)
+                    })
+                    .collect::<Vec<_>>();
+                // 没有这个字段的文档总是排在最后
+                sorted.sort_by(|(a, ha), (b, hb)| {
+                    let ord = match (a, b) {
+                        (Some(a), Some(b)) => {
+                            let ord = compare_values(a, b);
+                            if *desc {
+                                ord.reverse()
+                            } else {
+                                ord
+                            }
+                        }
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    };
+                    ord.then_with(|| ha.0.cmp(&hb.0))
+                });
+                hits = sorted.into_iter().map(|(_, hit)| hit).collect();
+            }
+            // KNN 查询默认按距离排序
+            None if opts.knn.is_some() => {
+                hits.sort_by(|a, b| by_score(a, b).then_with(|| a.0.cmp(&b.0)))
+            }
+            // 没有 SORTBY 时按 key 排序，保证结果稳定
+            None => hits.sort_by(|a, b| a.0.cmp(&b.0)),
+        }
+        Ok((
+            total,
+            hits.into_iter().skip(opts.offset).take(opts.num).collect(),
+        ))
+    }
+}
+
+fn compare_values(a: &FieldValue, b: &FieldValue) -> std::cmp::Ordering {
+    match (a, b) {
+        (FieldValue::Numeric(a), FieldValue::Numeric(b)) => a.total_cmp(b),
+        (FieldValue::Text(a, _), FieldValue::Text(b, _)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (FieldValue::Tag(a), FieldValue::Tag(b)) => a.cmp(b),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+// 查询语法：空格表示交集，| 表示并集（优先级低于交集），- 表示取反
+// @field:term @field:prefix* @field:[min max] @field:{tag1|tag2} @field:(a|b c)
+struct QueryParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    fn error(&self) -> BackendError {
+        BackendError::SearchSyntax(self.pos, self.input.to_string())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), BackendError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error());
+        }
+        self.pos += expected.len_utf8();
+        Ok(())
+    }
+
+    // 读取到 end 为止的内容，end 本身也会被跳过
+    fn take_until(&mut self, end: char) -> Result<&'a str, BackendError> {
+        let rest = &self.input[self.pos..];
+        let len = rest.find(end).ok_or_else(|| self.error())?;
+        self.pos += len + end.len_utf8();
+        Ok(&rest[..len])
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn union(&mut self, field: Option<&str>) -> Result<Query, BackendError> {
+        let mut queries = vec![self.intersect(field)?];
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('|') {
+                break;
+            }
+            self.pos += 1;
+            queries.push(self.intersect(field)?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    fn intersect(&mut self, field: Option<&str>) -> Result<Query, BackendError> {
+        let mut queries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some('|') | Some(')') => break,
+                _ => queries.push(self.unary(field)?),
+            }
+        }
+        match queries.len() {
+            0 => Err(self.error()),
+            1 => Ok(queries.remove(0)),
+            _ => Ok(Query::And(queries)),
+        }
+    }
+
+    fn unary(&mut self, field: Option<&str>) -> Result<Query, BackendError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Query::Not(Box::new(self.unary(field)?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let query = self.union(field)?;
+                self.expect(')')?;
+                Ok(query)
+            }
+            Some('*') if field.is_none() => {
+                self.pos += 1;
+                Ok(Query::All)
+            }
+            Some('@') => {
+                self.pos += 1;
+                let name = self.word();
+                if name.is_empty() {
+                    return Err(self.error());
+                }
+                self.expect(':')?;
+                self.skip_whitespace();
+                self.field_expr(name)
+            }
+            _ => self.term(field),
+        }
+    }
+
+    fn field_expr(&mut self, name: &str) -> Result<Query, BackendError> {
+        match self.peek() {
+            Some('[') => {
+                self.pos += 1;
+                let start = self.pos;
+                let range = self.take_until(']')?;
+                let bounds = range.split_whitespace().collect::<Vec<_>>();
+                let [min, max] = bounds[..] else {
+                    self.pos = start;
+                    return Err(self.error());
+                };
+                let parse = |s: &str| -> Option<Bound<f64>> {
+                    let (s, exclusive) = match s.strip_prefix('(') {
+                        Some(s) => (s, true),
+                        None => (s, false),
+                    };
+                    let v = match s.to_ascii_lowercase().as_str() {
+                        "-inf" => return Some(Bound::Unbounded),
+                        "+inf" | "inf" => return Some(Bound::Unbounded),
+                        v => v.parse::<f64>().ok().filter(|v| !v.is_nan())?,
+                    };
+                    Some(if exclusive {
+                        Bound::Excluded(v)
+                    } else {
+                        Bound::Included(v)
+                    })
+                };
+                match (parse(min), parse(max)) {
+                    (Some(min), Some(max)) => Ok(Query::Numeric {
+                        field: name.to_string(),
+                        min,
+                        max,
+                    }),
+                    _ => {
+                        self.pos = start;
+                        Err(self.error())
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let tags = self
+                    .take_until('}')?
+                    .split('|')
+                    .map(|t| t.replace('\\', "").trim().to_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>();
+                if tags.is_empty() {
+                    return Err(self.error());
+                }
+                Ok(Query::Tag {
+                    field: name.to_string(),
+                    tags,
+                })
+            }
+            _ => self.unary(Some(name)),
+        }
+    }
+
+    fn term(&mut self, field: Option<&str>) -> Result<Query, BackendError> {
+        let word = self.word();
+        if word.is_empty() {
+            return Err(self.error());
+        }
+        let prefix = self.peek() == Some('*');
+        if prefix {
+            self.pos += 1;
+        }
+        // 和文档使用相同的分词规则，例如 a.b 会被拆成 a 和 b 两个词
+        let mut terms = tokenize(word)
+            .into_iter()
+            .map(|term| Query::Term {
+                field: field.map(|f| f.to_string()),
+                term,
+                prefix: false,
+            })
+            .collect::<Vec<_>>();
+        if prefix {
+            if let Some(Query::Term { prefix, .. }) = terms.last_mut() {
+                *prefix = true;
+            }
+        }
+        match terms.len() {
+            0 => Err(self.error()),
+            1 => Ok(terms.remove(0)),
+            _ => Ok(Query::And(terms)),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = BackendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = QueryParser { input: s, pos: 0 };
+        let query = parser.union(None)?;
+        parser.skip_whitespace();
+        if parser.pos != s.len() {
+            return Err(parser.error());
+        }
+        Ok(query)
+    }
+}
+
+impl Backend {
+    pub fn ft_create(&self, name: String, definition: IndexDefinition) -> Result<(), BackendError> {
+        // 先插入空索引再扫描 hash，扫描期间的 HSET、HDEL 会通过 ft_reindex 更新它，不会丢失
+        match self.search.entry(name.clone()) {
+            Entry::Occupied(_) => return Err(BackendError::SearchIndexExists),
+            Entry::Vacant(entry) => {
+                entry.insert(SearchIndex::new(definition));
+            }
+        }
+        let keys = self
+            .hmap
+            .iter()
+            .map(|e| e.key().clone())
+            .collect::<Vec<_>>();
+
+        // 持有索引的写锁读取 hash 的最新内容，和 ft_reindex 的更新互斥
+        for key in keys {
+            let Some(mut index) = self.search.get_mut(&name) else {
+                break;
+            };
+            if !index.matches(&key) {
+                continue;
+            }
+            match self.hash_snapshot(&key) {
+                Some(hash) => index.add(&key, &hash),
+                None => index.remove(&key),
+            }
+        }
+        Ok(())
+    }
+
+    /// hash 修改或删除之后更新所有匹配的索引
+    /// 和 ft_create 一样先持有索引的写锁再读取 hash，索引中不会留下旧的内容
+    pub(crate) fn ft_reindex(&self, key: &str) {
+        if self.search.is_empty() {
+            return;
+        }
+        for mut index in self.search.iter_mut() {
+            if !index.matches(key) {
+                continue;
+            }
+            match self.hash_snapshot(key) {
+                Some(hash) => index.add(key, &hash),
+                None => index.remove(key),
+            }
+        }
+    }
+
+    fn hash_snapshot(&self, key: &str) -> Option<HashMap<String, RespFrame>> {
+        self.hmap.get(key).map(|h| {
+            h.iter()
+                .map(|f| (f.key().clone(), f.value().clone()))
+                .collect()
+        })
+    }
+
+    pub fn ft_search(
+        &self,
+        name: &str,
+        query: &Query,
+        opts: &SearchOptions,
+    ) -> Result<SearchResult, BackendError> {
+        let (total, keys) = {
+            let index = self
+                .search
+                .get(name)
+                .ok_or(BackendError::SearchUnknownIndex)?;
+            index.search(query, opts)?
+        };
+
+        let docs =
+            keys.into_iter()
+                .map(|(key, score)| {
+                    let fields = (!opts.no_content).then(|| {
+                        let mut fields = Vec::new();
+                        // KNN 的距离放在最前面
+                        if let (Some(score), Some(knn)) = (score, &opts.knn) {
+                            let returned = opts
+                                .return_fields
+                                .as_ref()
+                                .is_none_or(|names| names.contains(&knn.score_field));
+                            if returned {
+                                let score = BulkString::from(score.to_string()).into();
+                                fields.push((knn.score_field.clone(), score));
+                            }
+                        }
+                        let Some(hash) = self.hmap.get(&key) else {
+                            return fields;
+                        };
+                        match &opts.return_fields {
+                            Some(names) => fields.extend(names.iter().filter_map(|n| {
+                                hash.get(n).map(|v| (n.clone(), v.value().clone()))
+                            })),
+                            None => {
+                                let mut hash_fields = hash
+                                    .iter()
+                                    .map(|f| (f.key().clone(), f.value().clone()))
+                                    .collect::<Vec<_>>();
+                                hash_fields.sort_by(|a, b| a.0.cmp(&b.0));
+                                fields.extend(hash_fields);
+                            }
+                        }
+                        fields
+                    });
+                    SearchDocument { key, fields }
+                })
+                .collect();
+        Ok(SearchResult { total, docs })
+    }
+
+    pub fn ft_info(&self, name: &str) -> Result<IndexInfo, BackendError> {
+        self.search
+            .get(name)
+            .map(|index| index.info())
+            .ok_or(BackendError::SearchUnknownIndex)
+    }
+
+    /// delete_docs 为 true 时同时删除索引中的 hash
+    pub fn ft_dropindex(&self, name: &str, delete_docs: bool) -> Result<(), BackendError> {
+        let (_, index) = self
+            .search
+            .remove(name)
+            .ok_or(BackendError::SearchUnknownIndex)?;
+        if delete_docs {
+            for key in index.docs.keys() {
+                if self.hmap.remove(key).is_some() {
+                    self.watched.signal(key);
+                    self.notify(KeyspaceEvents::GENERIC, "del", key);
+                }
+                self.ft_reindex(key);
+            }
+        }
+        Ok(())
+    }
+}
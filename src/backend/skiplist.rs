@@ -0,0 +1,332 @@
+use std::ops::Range;
+
+// 和 Redis 的 zskiplist 相同的参数
+const MAX_LEVEL: usize = 32;
+const P: f64 = 0.25;
+const HEAD: usize = 0;
+const NIL: usize = usize::MAX;
+
+/// 带跨度的跳表，和 Redis 的 zskiplist 相同，每一层的指针记录跨过的元素数量
+/// 插入、删除、排名和按排名查找都是 O(log n)，节点保存在 Vec 中，用下标代替指针
+#[derive(Debug, Clone)]
+pub(crate) struct SkipList<K> {
+    // nodes[0] 是头节点，没有 key
+    nodes: Vec<Node<K>>,
+    free: Vec<usize>,
+    level: usize,
+    tail: usize,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<K> {
+    key: Option<K>,
+    backward: usize,
+    levels: Vec<Level>,
+}
+
+// 指向 NIL 的跨度等于到末尾的元素数量，插入和删除时不需要特殊处理
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    next: usize,
+    span: usize,
+}
+
+impl<K: Ord> SkipList<K> {
+    pub(crate) fn new() -> Self {
+        let head = Node {
+            key: None,
+            backward: NIL,
+            levels: vec![Level { next: NIL, span: 0 }; MAX_LEVEL],
+        };
+        Self {
+            nodes: vec![head],
+            free: Vec::new(),
+            level: 1,
+            tail: NIL,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    fn key(&self, node: usize) -> &K {
+        self.nodes[node]
+            .key
+            .as_ref()
+            .expect("skiplist node has a key")
+    }
+
+    fn random_level() -> usize {
+        let mut level = 1;
+        while level < MAX_LEVEL && rand::random::<f64>() < P {
+            level += 1;
+        }
+        level
+    }
+
+    // 每一层最后一个小于 key 的节点，以及它的排名
+    fn find(&self, key: &K) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            loop {
+                let Level { next, span } = self.nodes[x].levels[i];
+                if next == NIL || self.key(next) >= key {
+                    break;
+                }
+                rank[i] += span;
+                x = next;
+            }
+            update[i] = x;
+        }
+        (update, rank)
+    }
+
+    /// 调用方保证 key 不在跳表中
+    pub(crate) fn insert(&mut self, key: K) {
+        let (mut update, mut rank) = self.find(&key);
+        let level = Self::random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let node = Node {
+            key: Some(key),
+            backward: if update[0] == HEAD { NIL } else { update[0] },
+            levels: vec![Level { next: NIL, span: 0 }; level],
+        };
+        let x = match self.free.pop() {
+            Some(x) => {
+                self.nodes[x] = node;
+                x
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        for i in 0..level {
+            let prev = self.nodes[update[i]].levels[i];
+            self.nodes[x].levels[i] = Level {
+                next: prev.next,
+                span: prev.span - (rank[0] - rank[i]),
+            };
+            self.nodes[update[i]].levels[i] = Level {
+                next: x,
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+        for (i, &u) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[u].levels[i].span += 1;
+        }
+
+        match self.nodes[x].levels[0].next {
+            NIL => self.tail = x,
+            next => self.nodes[next].backward = x,
+        }
+        self.len += 1;
+    }
+
+    /// 删除 key，不存在时返回 None
+    pub(crate) fn remove(&mut self, key: &K) -> Option<K> {
+        let (update, _) = self.find(key);
+        let x = self.nodes[update[0]].levels[0].next;
+        if x == NIL || self.key(x) != key {
+            return None;
+        }
+
+        for (i, &u) in update.iter().enumerate().take(self.level) {
+            if self.nodes[u].levels[i].next == x {
+                let removed = self.nodes[x].levels[i];
+                let level = &mut self.nodes[u].levels[i];
+                level.span = level.span + removed.span - 1;
+                level.next = removed.next;
+            } else {
+                self.nodes[u].levels[i].span -= 1;
+            }
+        }
+        let backward = self.nodes[x].backward;
+        match self.nodes[x].levels[0].next {
+            NIL => self.tail = backward,
+            next => self.nodes[next].backward = backward,
+        }
+        while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].next == NIL {
+            self.level -= 1;
+        }
+        self.len -= 1;
+
+        self.free.push(x);
+        self.nodes[x].levels = Vec::new();
+        self.nodes[x].key.take()
+    }
+
+    /// 开头连续满足 pred 的元素数量，pred 必须是前面为 true 后面为 false 的单调条件
+    pub(crate) fn count_while(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut x = HEAD;
+        let mut rank = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { next, span } = self.nodes[x].levels[i];
+                if next == NIL || !pred(self.key(next)) {
+                    break;
+                }
+                rank += span;
+                x = next;
+            }
+        }
+        rank
+    }
+
+    // 排名为 index 的节点，从 0 开始
+    fn select(&self, index: usize) -> usize {
+        if index >= self.len {
+            return NIL;
+        }
+        let target = index + 1;
+        let mut x = HEAD;
+        let mut traversed = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { next, span } = self.nodes[x].levels[i];
+                if next == NIL || traversed + span > target {
+                    break;
+                }
+                traversed += span;
+                x = next;
+            }
+            if traversed == target {
+                return x;
+            }
+        }
+        NIL
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&K> {
+        match self.select(index) {
+            NIL => None,
+            x => Some(self.key(x)),
+        }
+    }
+
+    /// 按排名区间遍历，定位起点是 O(log n)
+    pub(crate) fn range(&self, range: Range<usize>) -> Iter<'_, K> {
+        let end = range.end.min(self.len);
+        let start = range.start.min(end);
+        Iter {
+            list: self,
+            front: self.select(start),
+            back: if end > start {
+                self.select(end - 1)
+            } else {
+                NIL
+            },
+            remaining: end - start,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, K> {
+        Iter {
+            list: self,
+            front: self.nodes[HEAD].levels[0].next,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<K: Ord> Default for SkipList<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) struct Iter<'a, K> {
+    list: &'a SkipList<K>,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a, K: Ord> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let x = self.front;
+        self.front = self.list.nodes[x].levels[0].next;
+        self.remaining -= 1;
+        Some(self.list.key(x))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K: Ord> DoubleEndedIterator for Iter<'_, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let x = self.back;
+        self.back = self.list.nodes[x].backward;
+        self.remaining -= 1;
+        Some(self.list.key(x))
+    }
+}
+
+impl<K: Ord> ExactSizeIterator for Iter<'_, K> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_skiplist_matches_btreeset() {
+        let mut list = SkipList::new();
+        let mut expected = BTreeSet::new();
+        for i in 0..5000u32 {
+            let key = rand::random::<u16>() % 1000;
+            if i % 3 == 0 {
+                assert_eq!(list.remove(&key), expected.take(&key));
+            } else if expected.insert(key) {
+                list.insert(key);
+            }
+        }
+
+        assert_eq!(list.len(), expected.len());
+        assert!(list.iter().eq(expected.iter()));
+        assert!(list.iter().rev().eq(expected.iter().rev()));
+        for (rank, key) in expected.iter().enumerate() {
+            assert_eq!(list.count_while(|k| k < key), rank);
+            assert_eq!(list.get(rank), Some(key));
+        }
+        assert_eq!(list.get(expected.len()), None);
+
+        let (start, end) = (expected.len() / 3, expected.len() / 2);
+        assert!(list
+            .range(start..end)
+            .eq(expected.iter().skip(start).take(end - start)));
+        assert!(list.range(start..end).rev().eq(expected
+            .iter()
+            .skip(start)
+            .take(end - start)
+            .rev()));
+        assert_eq!(list.range(end..start).count(), 0);
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::Bound,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{
+    stream_group::{valid_range, ConsumerGroup},
+    BackendError,
+};
+
+/// 消息 ID，格式为 <毫秒时间戳>-<序列号>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+pub type StreamEntry = (StreamId, Vec<(String, String)>);
+
+/// XADD 指定的 ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XAddId {
+    // *
+    Auto,
+    // <ms>-*
+    AutoSeq(u64),
+    Explicit(StreamId),
+}
+
+/// XADD 和 XTRIM 的裁剪策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrimStrategy {
+    MaxLen(usize),
+    MinId(StreamId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTrim {
+    pub strategy: TrimStrategy,
+    // "~" 近似裁剪，这里没有 redis 的宏节点，只是允许配合 LIMIT 限制每次删除的数量
+    pub approximate: bool,
+    // 0 表示不限制
+    pub limit: usize,
+}
+
+/// XINFO STREAM 返回的信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamInfo {
+    pub length: usize,
+    pub last_generated_id: StreamId,
+    pub max_deleted_entry_id: StreamId,
+    pub entries_added: u64,
+    pub first_entry: Option<StreamEntry>,
+    pub last_entry: Option<StreamEntry>,
+    pub groups: usize,
+}
+
+/// Stream 用 BTreeMap 按 ID 有序保存消息
+#[derive(Debug, Default, Clone)]
+pub struct Stream {
+    pub(crate) entries: BTreeMap<StreamId, Vec<(String, String)>>,
+    pub(crate) last_id: StreamId,
+    pub(crate) max_deleted_id: StreamId,
+    pub(crate) entries_added: u64,
+    pub(crate) groups: BTreeMap<String, ConsumerGroup>,
+}
+
+impl StreamId {
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        Self { ms, seq }
+    }
+
+    /// 下一个 ID，用于把不包含的区间转换成包含的区间
+    pub fn next(&self) -> Option<StreamId> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_add(1)?, 0)),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl Stream {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn first_entry(&self) -> Option<StreamEntry> {
+        self.entries
+            .first_key_value()
+            .map(|(id, fields)| (*id, fields.clone()))
+    }
+
+    pub fn last_entry(&self) -> Option<StreamEntry> {
+        self.entries
+            .last_key_value()
+            .map(|(id, fields)| (*id, fields.clone()))
+    }
+
+    /// 根据 XADD 的 ID 参数生成新的 ID，新 ID 必须大于 last_id
+    fn next_id(&self, id: XAddId) -> Result<StreamId, BackendError> {
+        let id = match id {
+            XAddId::Auto => {
+                let ms = now_ms();
+                if ms > self.last_id.ms {
+                    StreamId::new(ms, 0)
+                } else {
+                    self.last_id.next().ok_or(BackendError::StreamIdTooSmall)?
+                }
+            }
+            XAddId::AutoSeq(ms) if ms == self.last_id.ms => {
+                self.last_id.next().ok_or(BackendError::StreamIdTooSmall)?
+            }
+            XAddId::AutoSeq(ms) => StreamId::new(ms, 0),
+            XAddId::Explicit(id) => id,
+        };
+
+        if id == StreamId::default() {
+            return Err(BackendError::StreamIdZero);
+        }
+        if id <= self.last_id {
+            return Err(BackendError::StreamIdTooSmall);
+        }
+        Ok(id)
+    }
+
+    pub fn trim(&mut self, trim: &StreamTrim) -> usize {
+        let limit = if trim.approximate && trim.limit > 0 {
+            trim.limit
+        } else {
+            usize::MAX
+        };
+
+        let mut count = 0;
+        while count < limit {
+            let first = match self.entries.first_key_value() {
+                Some((id, _)) => *id,
+                None => break,
+            };
+            let evict = match trim.strategy {
+                TrimStrategy::MaxLen(max) => self.entries.len() > max,
+                TrimStrategy::MinId(min) => first < min,
+            };
+            if !evict {
+                break;
+            }
+            self.entries.pop_first();
+            count += 1;
+        }
+        count
+    }
+
+    pub fn range(
+        &self,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+        count: usize,
+        rev: bool,
+    ) -> Vec<StreamEntry> {
+        if !valid_range(start, end) {
+            return vec![];
+        }
+
+        let range = self.entries.range((start, end));
+        let entries: Box<dyn Iterator<Item = _>> = if rev {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+        entries
+            .take(count)
+            .map(|(id, fields)| (*id, fields.clone()))
+            .collect()
+    }
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl Backend {
+    /// 添加消息并按 trim 裁剪，nomkstream 为 true 且 key 不存在时返回 None
+    pub fn xadd(
+        &self,
+        key: String,
+        id: XAddId,
+        fields: Vec<(String, String)>,
+        nomkstream: bool,
+        trim: Option<StreamTrim>,
+    ) -> Result<Option<StreamId>, BackendError> {
+        if nomkstream && !self.stream.contains_key(&key) {
+            return Ok(None);
+        }
+
+        let (id, trimmed) = self.write_typed(&key, "stream", || {
+            let mut stream = self.stream.entry(key.clone()).or_default();
+            let id = match stream.next_id(id) {
+                Ok(id) => id,
+                Err(e) => {
+                    drop(stream);
+                    self.stream.remove_if(&key, |_, s| {
+                        s.is_empty() && s.entries_added == 0 && s.groups.is_empty()
+                    });
+                    return Err(e);
+                }
+            };
+            stream.entries.insert(id, fields);
+            stream.last_id = id;
+            stream.entries_added += 1;
+            let trimmed = trim.map_or(0, |trim| stream.trim(&trim));
+            Ok((id, trimmed))
+        })??;
+
+        self.watched.signal(&key);
+        self.notify(KeyspaceEvents::STREAM, "xadd", &key);
+        if trimmed > 0 {
+            self.notify(KeyspaceEvents::STREAM, "xtrim", &key);
+        }
+        // 所有 XREAD 阻塞的客户端都需要收到新消息
+        self.blocking.signal_all(&key);
+        Ok(Some(id))
+    }
+
+    pub fn xtrim(&self, key: &str, trim: &StreamTrim) -> usize {
+        let count = self
+            .stream
+            .get_mut(key)
+            .map(|mut stream| stream.trim(trim))
+            .unwrap_or(0);
+        if count > 0 {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::STREAM, "xtrim", key);
+        }
+        count
+    }
+
+    pub fn xdel(&self, key: &str, ids: &[StreamId]) -> usize {
+        let mut stream = match self.stream.get_mut(key) {
+            Some(stream) => stream,
+            None => return 0,
+        };
+
+        let mut count = 0;
+        for id in ids {
+            if stream.entries.remove(id).is_some() {
+                count += 1;
+                stream.max_deleted_id = stream.max_deleted_id.max(*id);
+            }
+        }
+        if count > 0 {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::STREAM, "xdel", key);
+        }
+        count
+    }
+
+    pub fn xlen(&self, key: &str) -> usize {
+        self.stream.get(key).map(|s| s.len()).unwrap_or(0)
+    }
+
+    pub fn xrange(
+        &self,
+        key: &str,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+        count: usize,
+        rev: bool,
+    ) -> Vec<StreamEntry> {
+        self.stream
+            .get(key)
+            .map(|s| s.range(start, end, count, rev))
+            .unwrap_or_default()
+    }
+
+    /// 最后生成的 ID，XREAD 用它解析 "$"
+    pub fn xlast_id(&self, key: &str) -> Option<StreamId> {
+        self.stream.get(key).map(|s| s.last_id)
+    }
+
+    pub fn xinfo(&self, key: &str) -> Option<StreamInfo> {
+        self.stream.get(key).map(|s| StreamInfo {
+            length: s.len(),
+            last_generated_id: s.last_id,
+            max_deleted_entry_id: s.max_deleted_id,
+            entries_added: s.entries_added,
+            first_entry: s.first_entry(),
+            last_entry: s.last_entry(),
+            groups: s.groups.len(),
+        })
+    }
+}
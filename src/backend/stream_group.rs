@@ -0,0 +1,630 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Bound,
+};
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{
+    stream::{now_ms, Stream},
+    BackendError, StreamEntry, StreamId,
+};
+
+/// 消费者组，记录最后投递的 ID 和所有已投递未确认的消息（PEL）
+#[derive(Debug, Default, Clone)]
+pub struct ConsumerGroup {
+    pub(crate) last_delivered: StreamId,
+    // None 表示无法确定已读数量，例如 SETID 指定了中间的 ID
+    pub(crate) entries_read: Option<u64>,
+    pub(crate) pending: BTreeMap<StreamId, PendingEntry>,
+    pub(crate) consumers: BTreeMap<String, Consumer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingEntry {
+    pub consumer: String,
+    // 最后一次投递的时间，毫秒
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Consumer {
+    // 最后一次尝试读取的时间
+    pub(crate) seen_time: u64,
+    // 最后一次成功读到消息的时间
+    pub(crate) active_time: Option<u64>,
+    pub(crate) pending: BTreeSet<StreamId>,
+}
+
+/// XPENDING 扩展形式返回的一条记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingInfo {
+    pub id: StreamId,
+    pub consumer: String,
+    pub idle: u64,
+    pub delivery_count: u64,
+}
+
+/// XPENDING 简略形式的统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSummary {
+    pub count: usize,
+    pub min_max: Option<(StreamId, StreamId)>,
+    pub consumers: Vec<(String, usize)>,
+}
+
+/// XCLAIM 的可选参数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct XClaimOptions {
+    pub idle: Option<u64>,
+    pub time: Option<u64>,
+    pub retry_count: Option<u64>,
+    pub force: bool,
+    pub justid: bool,
+    pub last_id: Option<StreamId>,
+}
+
+/// XREADGROUP 返回的消息，已经被删除的消息内容为 None
+pub type GroupStreamEntry = (StreamId, Option<Vec<(String, String)>>);
+
+/// XAUTOCLAIM 的结果：下一次扫描的起点，认领的消息，已经被删除的消息 ID
+pub type AutoClaimResult = (StreamId, Vec<StreamEntry>, Vec<StreamId>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupInfo {
+    pub name: String,
+    pub consumers: usize,
+    pub pending: usize,
+    pub last_delivered_id: StreamId,
+    pub entries_read: Option<u64>,
+    pub lag: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerInfo {
+    pub name: String,
+    pub pending: usize,
+    pub idle: u64,
+    pub inactive: Option<u64>,
+}
+
+impl Consumer {
+    fn new(now: u64) -> Self {
+        Self {
+            seen_time: now,
+            active_time: None,
+            pending: BTreeSet::new(),
+        }
+    }
+}
+
+impl ConsumerGroup {
+    fn consumer(&mut self, name: &str, now: u64) -> &mut Consumer {
+        self.consumers
+            .entry(name.to_string())
+            .or_insert_with(|| Consumer::new(now))
+    }
+
+    // 把消息交给 consumer，原来的 consumer 不再持有这条消息
+    fn assign(&mut self, id: StreamId, consumer: &str, delivery_time: u64, delivery_count: u64) {
+        if let Some(old) = self.pending.get(&id) {
+            if let Some(old) = self.consumers.get_mut(&old.consumer) {
+                old.pending.remove(&id);
+            }
+        }
+        self.consumer(consumer, delivery_time).pending.insert(id);
+        self.pending.insert(
+            id,
+            PendingEntry {
+                consumer: consumer.to_string(),
+                delivery_time,
+                delivery_count,
+            },
+        );
+    }
+
+    fn remove_pending(&mut self, id: &StreamId) -> bool {
+        match self.pending.remove(id) {
+            Some(entry) => {
+                if let Some(consumer) = self.consumers.get_mut(&entry.consumer) {
+                    consumer.pending.remove(id);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Stream {
+    fn group_mut(&mut self, key: &str, group: &str) -> Result<&mut ConsumerGroup, BackendError> {
+        self.groups
+            .get_mut(group)
+            .ok_or_else(|| no_group(key, group))
+    }
+
+    // 计算 last_delivered 为 id 时的已读数量
+    fn entries_read_at(&self, id: StreamId) -> Option<u64> {
+        if id == StreamId::default() {
+            Some(0)
+        } else if id == self.last_id {
+            Some(self.entries_added)
+        } else {
+            None
+        }
+    }
+}
+
+fn no_group(key: &str, group: &str) -> BackendError {
+    BackendError::NoGroup {
+        key: key.to_string(),
+        group: group.to_string(),
+    }
+}
+
+impl Backend {
+    /// 创建消费者组，id 为 None 表示 "$"
+    pub fn xgroup_create(
+        &self,
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    ) -> Result<(), BackendError> {
+        let _guard = self.lock_keys(&[&key]);
+        self.check_key_type(&key, "stream")?;
+        let mut stream = match self.stream.get_mut(&key) {
+            Some(stream) => stream,
+            None if mkstream => self.stream.entry(key.clone()).or_default(),
+            None => return Err(BackendError::StreamKeyRequired),
+        };
+        if stream.groups.contains_key(&group) {
+            return Err(BackendError::BusyGroup);
+        }
+
+        let id = id.unwrap_or(stream.last_id);
+        let entries_read = entries_read.or_else(|| stream.entries_read_at(id));
+        stream.groups.insert(
+            group,
+            ConsumerGroup {
+                last_delivered: id,
+                entries_read,
+                ..Default::default()
+            },
+        );
+        self.watched.signal(&key);
+        self.notify(KeyspaceEvents::STREAM, "xgroup-create", &key);
+        Ok(())
+    }
+
+    pub fn xgroup_setid(
+        &self,
+        key: &str,
+        group: &str,
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    ) -> Result<(), BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or(BackendError::StreamKeyRequired)?;
+        let id = id.unwrap_or(stream.last_id);
+        let entries_read = entries_read.or_else(|| stream.entries_read_at(id));
+        let group = stream.group_mut(key, group)?;
+        group.last_delivered = id;
+        group.entries_read = entries_read;
+        self.watched.signal(key);
+        self.notify(KeyspaceEvents::STREAM, "xgroup-setid", key);
+        Ok(())
+    }
+
+    pub fn xgroup_destroy(&self, key: &str, group: &str) -> Result<bool, BackendError> {
+        let destroyed = self
+            .stream
+            .get_mut(key)
+            .ok_or(BackendError::StreamKeyRequired)?
+            .groups
+            .remove(group)
+            .is_some();
+        // 阻塞在这个组上的 XREADGROUP 需要返回错误
+        if destroyed {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::STREAM, "xgroup-destroy", key);
+            self.blocking.signal_all(key);
+        }
+        Ok(destroyed)
+    }
+
+    pub fn xgroup_createconsumer(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+    ) -> Result<bool, BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or(BackendError::StreamKeyRequired)?;
+        let group = stream.group_mut(key, group)?;
+        if group.consumers.contains_key(consumer) {
+            return Ok(false);
+        }
+        group.consumer(consumer, now_ms());
+        self.watched.signal(key);
+        self.notify(KeyspaceEvents::STREAM, "xgroup-createconsumer", key);
+        Ok(true)
+    }
+
+    /// 删除消费者，返回它名下未确认的消息数量，这些消息也会从 PEL 中删除
+    pub fn xgroup_delconsumer(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+    ) -> Result<usize, BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or(BackendError::StreamKeyRequired)?;
+        let group = stream.group_mut(key, group)?;
+        let consumer = match group.consumers.remove(consumer) {
+            Some(consumer) => consumer,
+            None => return Ok(0),
+        };
+        for id in &consumer.pending {
+            group.pending.remove(id);
+        }
+        self.watched.signal(key);
+        self.notify(KeyspaceEvents::STREAM, "xgroup-delconsumer", key);
+        Ok(consumer.pending.len())
+    }
+
+    /// start 为 None 表示 ">"，读取从未投递过的新消息并加入 PEL；
+    /// 否则读取 consumer 自己 PEL 中大于 start 的消息，已经被删除的消息内容为 None
+    pub fn xreadgroup(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        start: Option<StreamId>,
+        count: usize,
+        noack: bool,
+    ) -> Result<Vec<GroupStreamEntry>, BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or_else(|| no_group(key, group))?;
+        let stream = &mut *stream;
+        let now = now_ms();
+        let group = stream
+            .groups
+            .get_mut(group)
+            .ok_or_else(|| no_group(key, group))?;
+        group.consumer(consumer, now).seen_time = now;
+
+        let entries = match start {
+            None => {
+                let entries = stream
+                    .entries
+                    .range((Bound::Excluded(group.last_delivered), Bound::Unbounded));
+                let entries = entries
+                    .take(count)
+                    .map(|(id, fields)| (*id, Some(fields.clone())))
+                    .collect::<Vec<_>>();
+                for (id, _) in &entries {
+                    group.last_delivered = *id;
+                    group.entries_read = group.entries_read.map(|n| n + 1);
+                    if !noack {
+                        group.assign(*id, consumer, now, 1);
+                    }
+                }
+                entries
+            }
+            Some(start) => {
+                let ids = group.consumers[consumer]
+                    .pending
+                    .range((Bound::Excluded(start), Bound::Unbounded))
+                    .take(count)
+                    .copied()
+                    .collect::<Vec<_>>();
+                ids.into_iter()
+                    .map(|id| {
+                        let fields = stream.entries.get(&id).cloned();
+                        if let Some(entry) = group.pending.get_mut(&id) {
+                            entry.delivery_time = now;
+                            entry.delivery_count += 1;
+                        }
+                        (id, fields)
+                    })
+                    .collect()
+            }
+        };
+
+        if !entries.is_empty() {
+            group.consumer(consumer, now).active_time = Some(now);
+            self.watched.signal(key);
+        }
+        Ok(entries)
+    }
+
+    pub fn xack(&self, key: &str, group: &str, ids: &[StreamId]) -> usize {
+        let mut stream = match self.stream.get_mut(key) {
+            Some(stream) => stream,
+            None => return 0,
+        };
+        let count = match stream.groups.get_mut(group) {
+            Some(group) => ids.iter().filter(|id| group.remove_pending(id)).count(),
+            None => 0,
+        };
+        if count > 0 {
+            self.watched.signal(key);
+        }
+        count
+    }
+
+    pub fn xpending_summary(&self, key: &str, group: &str) -> Result<PendingSummary, BackendError> {
+        let stream = self.stream.get(key).ok_or_else(|| no_group(key, group))?;
+        let group = stream
+            .groups
+            .get(group)
+            .ok_or_else(|| no_group(key, group))?;
+
+        let min_max = match (
+            group.pending.first_key_value(),
+            group.pending.last_key_value(),
+        ) {
+            (Some((min, _)), Some((max, _))) => Some((*min, *max)),
+            _ => None,
+        };
+        let consumers = group
+            .consumers
+            .iter()
+            .filter(|(_, c)| !c.pending.is_empty())
+            .map(|(name, c)| (name.clone(), c.pending.len()))
+            .collect();
+        Ok(PendingSummary {
+            count: group.pending.len(),
+            min_max,
+            consumers,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn xpending(
+        &self,
+        key: &str,
+        group: &str,
+        start: Bound<StreamId>,
+        end: Bound<StreamId>,
+        count: usize,
+        consumer: Option<&str>,
+        min_idle: u64,
+    ) -> Result<Vec<PendingInfo>, BackendError> {
+        let stream = self.stream.get(key).ok_or_else(|| no_group(key, group))?;
+        let group = stream
+            .groups
+            .get(group)
+            .ok_or_else(|| no_group(key, group))?;
+        if !valid_range(start, end) {
+            return Ok(vec![]);
+        }
+
+        let now = now_ms();
+        Ok(group
+            .pending
+            .range((start, end))
+            .filter(|(_, p)| consumer.is_none_or(|c| c == p.consumer))
+            .map(|(id, p)| PendingInfo {
+                id: *id,
+                consumer: p.consumer.clone(),
+                idle: now.saturating_sub(p.delivery_time),
+                delivery_count: p.delivery_count,
+            })
+            .filter(|p| p.idle >= min_idle)
+            .take(count)
+            .collect())
+    }
+
+    /// 把空闲时间不小于 min_idle 的消息转给 consumer，已经被删除的消息会从 PEL 中删除
+    pub fn xclaim(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        min_idle: u64,
+        ids: &[StreamId],
+        opts: XClaimOptions,
+    ) -> Result<Vec<StreamEntry>, BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or_else(|| no_group(key, group))?;
+        let stream = &mut *stream;
+        let group = stream
+            .groups
+            .get_mut(group)
+            .ok_or_else(|| no_group(key, group))?;
+
+        let now = now_ms();
+        let delivery_time = match (opts.idle, opts.time) {
+            (Some(idle), _) => now.saturating_sub(idle),
+            (None, Some(time)) => time,
+            (None, None) => now,
+        };
+
+        if let Some(last_id) = opts.last_id {
+            group.last_delivered = group.last_delivered.max(last_id);
+        }
+
+        let mut claimed = Vec::new();
+        for id in ids {
+            let fields = match stream.entries.get(id) {
+                Some(fields) => fields,
+                None => {
+                    group.remove_pending(id);
+                    continue;
+                }
+            };
+            let delivery_count = match group.pending.get(id) {
+                Some(p) if now.saturating_sub(p.delivery_time) < min_idle => continue,
+                Some(p) => p.delivery_count,
+                None if opts.force => 0,
+                None => continue,
+            };
+            // JUSTID 不算一次投递
+            let delivery_count = match opts.retry_count {
+                Some(n) => n,
+                None if opts.justid => delivery_count,
+                None => delivery_count + 1,
+            };
+            group.assign(*id, consumer, delivery_time, delivery_count);
+            claimed.push((*id, fields.clone()));
+        }
+
+        let consumer = group.consumer(consumer, now);
+        consumer.seen_time = now;
+        if !claimed.is_empty() {
+            consumer.active_time = Some(now);
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::STREAM, "xclaim", key);
+        }
+        Ok(claimed)
+    }
+
+    /// 从 start 开始扫描 PEL，最多认领 count 条消息
+    #[allow(clippy::too_many_arguments)]
+    pub fn xautoclaim(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        min_idle: u64,
+        start: StreamId,
+        count: usize,
+        justid: bool,
+    ) -> Result<AutoClaimResult, BackendError> {
+        let mut stream = self
+            .stream
+            .get_mut(key)
+            .ok_or_else(|| no_group(key, group))?;
+        let stream = &mut *stream;
+        let group = stream
+            .groups
+            .get_mut(group)
+            .ok_or_else(|| no_group(key, group))?;
+
+        let now = now_ms();
+        // 和 redis 一样，最多扫描 count * 10 条记录
+        let scan = group
+            .pending
+            .range(start..)
+            .map(|(id, p)| (*id, now.saturating_sub(p.delivery_time), p.delivery_count))
+            .take(count.saturating_mul(10))
+            .collect::<Vec<_>>();
+
+        let (mut claimed, mut deleted, mut last) = (Vec::new(), Vec::new(), None);
+        for (id, idle, delivery_count) in scan {
+            if claimed.len() >= count {
+                break;
+            }
+            last = Some(id);
+            let fields = match stream.entries.get(&id) {
+                Some(fields) => fields,
+                None => {
+                    group.remove_pending(&id);
+                    deleted.push(id);
+                    continue;
+                }
+            };
+            if idle < min_idle {
+                continue;
+            }
+            let delivery_count = if justid {
+                delivery_count
+            } else {
+                delivery_count + 1
+            };
+            group.assign(id, consumer, now, delivery_count);
+            claimed.push((id, fields.clone()));
+        }
+
+        // 下一次从最后扫描的记录之后开始，扫描完整个 PEL 时返回 0-0
+        let next = last
+            .and_then(|last| {
+                group
+                    .pending
+                    .range((Bound::Excluded(last), Bound::Unbounded))
+                    .next()
+                    .map(|(id, _)| *id)
+            })
+            .unwrap_or_default();
+
+        let consumer = group.consumer(consumer, now);
+        consumer.seen_time = now;
+        if !claimed.is_empty() || !deleted.is_empty() {
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::STREAM, "xautoclaim", key);
+        }
+        if !claimed.is_empty() {
+            consumer.active_time = Some(now);
+        }
+        Ok((next, claimed, deleted))
+    }
+
+    pub fn xinfo_groups(&self, key: &str) -> Option<Vec<GroupInfo>> {
+        let stream = self.stream.get(key)?;
+        Some(
+            stream
+                .groups
+                .iter()
+                .map(|(name, group)| GroupInfo {
+                    name: name.clone(),
+                    consumers: group.consumers.len(),
+                    pending: group.pending.len(),
+                    last_delivered_id: group.last_delivered,
+                    entries_read: group.entries_read,
+                    lag: stream
+                        .entries
+                        .range((Bound::Excluded(group.last_delivered), Bound::Unbounded))
+                        .count(),
+                })
+                .collect(),
+        )
+    }
+
+    pub fn xinfo_consumers(
+        &self,
+        key: &str,
+        group: &str,
+    ) -> Result<Vec<ConsumerInfo>, BackendError> {
+        let stream = self.stream.get(key).ok_or_else(|| no_group(key, group))?;
+        let group = stream
+            .groups
+            .get(group)
+            .ok_or_else(|| no_group(key, group))?;
+
+        let now = now_ms();
+        Ok(group
+            .consumers
+            .iter()
+            .map(|(name, c)| ConsumerInfo {
+                name: name.clone(),
+                pending: c.pending.len(),
+                idle: now.saturating_sub(c.seen_time),
+                inactive: c.active_time.map(|t| now.saturating_sub(t)),
+            })
+            .collect())
+    }
+}
+
+// BTreeMap::range 在 start > end 时会 panic
+pub(crate) fn valid_range(start: Bound<StreamId>, end: Bound<StreamId>) -> bool {
+    match (start, end) {
+        (Bound::Included(s), Bound::Included(e)) => s <= e,
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Excluded(e))
+        | (Bound::Excluded(s), Bound::Included(e)) => s < e,
+        _ => true,
+    }
+}
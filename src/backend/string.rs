@@ -0,0 +1,75 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, BackendError, KeyspaceEvents, RespFrame};
+
+/// SET 和 DELEX 的条件，和 key 当前的值比较
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetCondition {
+    IfEq(RespFrame),
+    IfNe(RespFrame),
+}
+
+impl SetCondition {
+    // key 不存在时 IFEQ 不满足，IFNE 满足
+    fn matches(&self, current: Option<&RespFrame>) -> bool {
+        match self {
+            SetCondition::IfEq(value) => current == Some(value),
+            SetCondition::IfNe(value) => current != Some(value),
+        }
+    }
+}
+
+impl Backend {
+    /// 条件满足时写入并返回 true，比较和写入在同一个 entry 锁内完成
+    /// 只能比较字符串的值，key 是其它类型时返回 WRONGTYPE
+    pub fn set_if(
+        &self,
+        key: String,
+        value: RespFrame,
+        condition: &SetCondition,
+    ) -> Result<bool, BackendError> {
+        let _guard = self.lock_keys(&[&key]);
+        self.check_key_type(&key, "string")?;
+        let entry = self.map.entry(key);
+        let current = match &entry {
+            Entry::Occupied(e) => Some(e.get()),
+            Entry::Vacant(_) => None,
+        };
+        if !condition.matches(current) {
+            return Ok(false);
+        }
+        self.expires.remove(entry.key());
+        self.touch(entry.key());
+        self.notify(KeyspaceEvents::STRING, "set", entry.key());
+        entry.insert(value);
+        Ok(true)
+    }
+
+    /// 没有条件时和 DEL 一样删除任意类型的 key，有条件时只比较字符串的值
+    pub fn delex(&self, key: &str, condition: Option<&SetCondition>) -> bool {
+        let Some(condition) = condition else {
+            return self.del(key);
+        };
+        if self.expire_if_needed(key) {
+            return false;
+        }
+        let removed = self
+            .map
+            .remove_if(key, |_, value| condition.matches(Some(value)))
+            .is_some();
+        if removed {
+            self.expires.remove(key);
+            self.versions.remove(key);
+            self.watched.signal(key);
+            self.notify(KeyspaceEvents::GENERIC, "del", key);
+        }
+        removed
+    }
+
+    /// 字符串 key 的版本号，每次写入都会增加，key 不存在时返回 None
+    pub fn object_version(&self, key: &str) -> Option<u64> {
+        self.expire_if_needed(key);
+        let _value = self.map.get(key)?;
+        self.versions.get(key).map(|v| *v)
+    }
+}
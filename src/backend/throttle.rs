@@ -0,0 +1,101 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, BulkString, KeyspaceEvents, RespFrame};
+
+use super::{stream::now_ms, BackendError};
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+/// CL.THROTTLE 的参数：每 period 秒允许 count 个请求，最多突发 max_burst 个，本次请求消耗 quantity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleParams {
+    pub max_burst: i64,
+    pub count: i64,
+    pub period: i64,
+    pub quantity: i64,
+}
+
+/// retry_after 和 reset_after 的单位是秒，没有被限流时 retry_after 为 -1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottleResult {
+    pub limited: bool,
+    pub limit: i64,
+    pub remaining: i64,
+    pub retry_after: i64,
+    pub reset_after: i64,
+}
+
+fn now_ns() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+// 纳秒向上取整到秒
+fn ceil_secs(ns: i64) -> i64 {
+    ns.saturating_add(NANOS_PER_SEC - 1) / NANOS_PER_SEC
+}
+
+impl Backend {
+    /// GCRA 限流：key 中保存理论到达时间 TAT（纳秒），并在 TAT 之后过期，过期等价于没有请求
+    /// 整个计算过程持有 key 所在分片的锁，同一个 key 的请求不会交错执行
+    pub fn cl_throttle(
+        &self,
+        key: String,
+        params: ThrottleParams,
+    ) -> Result<ThrottleResult, BackendError> {
+        let _guard = self.lock_keys(&[&key]);
+        self.check_key_type(&key, "string")?;
+
+        let emission = params.period.saturating_mul(NANOS_PER_SEC) / params.count;
+        let tolerance = emission.saturating_mul(params.max_burst.saturating_add(1));
+        let increment = emission.saturating_mul(params.quantity);
+
+        let entry = self.map.entry(key.clone());
+        let now = now_ns();
+        let tat = match &entry {
+            Entry::Occupied(e) => match e.get() {
+                RespFrame::BulkString(s) => std::str::from_utf8(s)
+                    .ok()
+                    .and_then(|s| s.parse::<i64>().ok())
+                    .ok_or(BackendError::NotAnInteger)?,
+                RespFrame::Integer(i) => *i,
+                _ => return Err(BackendError::NotAnInteger),
+            },
+            Entry::Vacant(_) => now,
+        }
+        .max(now);
+
+        let new_tat = tat.saturating_add(increment);
+        let diff = now.saturating_sub(new_tat.saturating_sub(tolerance));
+        let (limited, retry_after, ttl) = if diff < 0 {
+            // 一次请求的数量超过了突发上限时永远不会被允许
+            let retry_after = if increment <= tolerance {
+                ceil_secs(diff.saturating_neg())
+            } else {
+                -1
+            };
+            (true, retry_after, tat - now)
+        } else {
+            // 持有写锁直到设置好过期时间
+            self.touch(entry.key());
+            self.notify(KeyspaceEvents::STRING, "set", entry.key());
+            let _guard = entry.insert(BulkString::from(new_tat.to_string()).into());
+            let ttl = new_tat - now;
+            self.expire_at(key, now_ms() + (ttl as u64).div_ceil(1_000_000));
+            (false, -1, ttl)
+        };
+
+        let remaining = (tolerance - ttl).checked_div(emission).unwrap_or(0).max(0);
+        Ok(ThrottleResult {
+            limited,
+            limit: params.max_burst.saturating_add(1),
+            remaining,
+            retry_after,
+            reset_after: ceil_secs(ttl),
+        })
+    }
+}
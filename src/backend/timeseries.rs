@@ -0,0 +1,476 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{gorilla::Chunk, stream::now_ms, BackendError};
+
+// 每个数据块最多保存的数据点数量
+const CHUNK_SAMPLES: usize = 256;
+
+/// 时间戳相同的数据点的处理方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    #[default]
+    Block,
+    First,
+    Last,
+    Min,
+    Max,
+    Sum,
+}
+
+/// TS.RANGE 和压缩规则使用的聚合方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsAggregation {
+    Avg,
+    Sum,
+    Min,
+    Max,
+    Count,
+    First,
+    Last,
+}
+
+/// TS.CREATE 和 TS.ADD 自动创建时的参数
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TsCreateOptions {
+    // 毫秒，0 表示永久保存
+    pub retention: u64,
+    pub duplicate_policy: DuplicatePolicy,
+    pub labels: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TsRangeOptions {
+    pub count: Option<usize>,
+    // 聚合方式和桶的大小（毫秒）
+    pub aggregation: Option<(TsAggregation, i64)>,
+    pub rev: bool,
+}
+
+/// TS.MRANGE 的 FILTER 条件，不存在的 label 当作空字符串
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TsFilter {
+    Eq(String, Vec<String>),
+    Ne(String, Vec<String>),
+}
+
+/// 压缩规则，源时间序列的一个桶结束后把聚合结果写入目标时间序列
+#[derive(Debug, Clone, PartialEq)]
+struct CompactionRule {
+    destination: String,
+    aggregation: TsAggregation,
+    bucket: i64,
+    // 当前还没有结束的桶的开始时间
+    current: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TimeSeries {
+    chunks: Vec<Chunk>,
+    retention: u64,
+    duplicate_policy: DuplicatePolicy,
+    labels: Vec<(String, String)>,
+    rules: Vec<CompactionRule>,
+    // 作为压缩规则的目标时，源时间序列的 key
+    source: Option<String>,
+}
+
+/// MRANGE 的结果：key, labels, 数据点
+pub type TsRangeResult = (String, Vec<(String, String)>, Vec<(i64, f64)>);
+
+// 压缩规则产生的需要写入目标时间序列的数据点
+type Compacted = Vec<(String, i64, f64)>;
+
+impl TsAggregation {
+    pub fn apply(&self, values: &[f64]) -> f64 {
+        match self {
+            TsAggregation::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            TsAggregation::Sum => values.iter().sum(),
+            TsAggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            TsAggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            TsAggregation::Count => values.len() as f64,
+            TsAggregation::First => values.first().copied().unwrap_or(f64::NAN),
+            TsAggregation::Last => values.last().copied().unwrap_or(f64::NAN),
+        }
+    }
+}
+
+impl DuplicatePolicy {
+    fn resolve(&self, old: f64, new: f64) -> Result<f64, BackendError> {
+        match self {
+            DuplicatePolicy::Block => Err(BackendError::TsDuplicateBlocked),
+            DuplicatePolicy::First => Ok(old),
+            DuplicatePolicy::Last => Ok(new),
+            DuplicatePolicy::Min => Ok(old.min(new)),
+            DuplicatePolicy::Max => Ok(old.max(new)),
+            DuplicatePolicy::Sum => Ok(old + new),
+        }
+    }
+}
+
+impl TsFilter {
+    fn matches(&self, labels: &[(String, String)]) -> bool {
+        let get = |name: &str| {
+            labels
+                .iter()
+                .find(|(l, _)| l == name)
+                .map_or("", |(_, v)| v.as_str())
+        };
+        match self {
+            TsFilter::Eq(label, values) => values.iter().any(|v| v == get(label)),
+            TsFilter::Ne(label, values) => values.iter().all(|v| v != get(label)),
+        }
+    }
+}
+
+fn bucket_start(ts: i64, bucket: i64) -> i64 {
+    ts - ts.rem_euclid(bucket)
+}
+
+// 按桶聚合，桶的时间戳是开始时间
+fn aggregate(samples: &[(i64, f64)], aggregation: TsAggregation, bucket: i64) -> Vec<(i64, f64)> {
+    let mut ret = Vec::new();
+    let mut start = 0;
+    while start < samples.len() {
+        let current = bucket_start(samples[start].0, bucket);
+        let end = samples[start..]
+            .iter()
+            .position(|(ts, _)| bucket_start(*ts, bucket) != current)
+            .map_or(samples.len(), |n| start + n);
+        let values = samples[start..end]
+            .iter()
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        ret.push((current, aggregation.apply(&values)));
+        start = end;
+    }
+    ret
+}
+
+impl TimeSeries {
+    pub fn new(opts: TsCreateOptions) -> Self {
+        Self {
+            retention: opts.retention,
+            duplicate_policy: opts.duplicate_policy,
+            labels: opts.labels,
+            ..Default::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.iter().map(|c| c.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// 所有数据块压缩之后的字节数
+    pub fn memory_usage(&self) -> usize {
+        self.chunks.iter().map(|c| c.size()).sum()
+    }
+
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    pub fn last(&self) -> Option<(i64, f64)> {
+        self.chunks.last().map(|c| (c.last_ts(), c.last_value()))
+    }
+
+    /// 写入一个数据点，policy 为 None 时使用时间序列自己的 DUPLICATE_POLICY
+    fn add(
+        &mut self,
+        ts: i64,
+        value: f64,
+        policy: Option<DuplicatePolicy>,
+    ) -> Result<Compacted, BackendError> {
+        if let Some((last_ts, _)) = self.last() {
+            if self.retention > 0 && ts < last_ts.saturating_sub(self.retention as i64) {
+                return Err(BackendError::TsTooOld);
+            }
+            if ts <= last_ts {
+                self.upsert(ts, value, policy.unwrap_or(self.duplicate_policy))?;
+                return Ok(Vec::new());
+            }
+        }
+
+        let compacted = self.compact(ts);
+        match self.chunks.last_mut() {
+            Some(chunk) if chunk.len() < CHUNK_SAMPLES => chunk.push(ts, value),
+            _ => self.chunks.push(Chunk::from_samples(&[(ts, value)])),
+        }
+        self.trim();
+        Ok(compacted)
+    }
+
+    // 乱序写入，解码所在的数据块，插入或者合并之后重新编码
+    fn upsert(&mut self, ts: i64, value: f64, policy: DuplicatePolicy) -> Result<(), BackendError> {
+        let idx = self
+            .chunks
+            .iter()
+            .rposition(|c| c.first_ts() <= ts)
+            .unwrap_or(0);
+        let mut samples = self.chunks[idx].samples();
+        match samples.binary_search_by_key(&ts, |(t, _)| *t) {
+            Ok(pos) => samples[pos].1 = policy.resolve(samples[pos].1, value)?,
+            Err(pos) => samples.insert(pos, (ts, value)),
+        }
+        let chunks = samples.chunks(CHUNK_SAMPLES).map(Chunk::from_samples);
+        self.chunks.splice(idx..=idx, chunks);
+        Ok(())
+    }
+
+    // 新的数据点进入了下一个桶时，把上一个桶的聚合结果写入目标时间序列
+    fn compact(&mut self, ts: i64) -> Compacted {
+        let mut compacted = Vec::new();
+        for i in 0..self.rules.len() {
+            let rule = &self.rules[i];
+            let start = bucket_start(ts, rule.bucket);
+            match rule.current {
+                Some(current) if current < start => {
+                    let samples = self.range(current, current + rule.bucket - 1);
+                    if !samples.is_empty() {
+                        let values = samples.iter().map(|(_, v)| *v).collect::<Vec<_>>();
+                        let value = rule.aggregation.apply(&values);
+                        compacted.push((rule.destination.clone(), current, value));
+                    }
+                }
+                Some(_) => continue,
+                None => {}
+            }
+            self.rules[i].current = Some(start);
+        }
+        compacted
+    }
+
+    // 删除超过保留时间的数据点
+    fn trim(&mut self) {
+        let Some((last_ts, _)) = self.last() else {
+            return;
+        };
+        if self.retention == 0 {
+            return;
+        }
+        let min_ts = last_ts.saturating_sub(self.retention as i64);
+        self.chunks.retain(|c| c.last_ts() >= min_ts);
+        if let Some(first) = self.chunks.first_mut() {
+            if first.first_ts() < min_ts {
+                let samples = first.samples();
+                let pos = samples.partition_point(|(ts, _)| *ts < min_ts);
+                *first = Chunk::from_samples(&samples[pos..]);
+            }
+        }
+    }
+
+    /// [from, to] 范围内的数据点，按时间戳从小到大
+    pub fn range(&self, from: i64, to: i64) -> Vec<(i64, f64)> {
+        self.chunks
+            .iter()
+            .filter(|c| c.last_ts() >= from && c.first_ts() <= to)
+            .flat_map(|c| c.samples())
+            .filter(|(ts, _)| (from..=to).contains(ts))
+            .collect()
+    }
+
+    fn query(&self, from: i64, to: i64, opts: &TsRangeOptions) -> Vec<(i64, f64)> {
+        let mut samples = self.range(from, to);
+        if let Some((aggregation, bucket)) = opts.aggregation {
+            samples = aggregate(&samples, aggregation, bucket);
+        }
+        if opts.rev {
+            samples.reverse();
+        }
+        if let Some(count) = opts.count {
+            samples.truncate(count);
+        }
+        samples
+    }
+}
+
+impl Backend {
+    pub fn ts_create(&self, key: String, opts: TsCreateOptions) -> Result<(), BackendError> {
+        self.write_typed(&key.clone(), "TSDB-TYPE", || {
+            match self.timeseries.entry(key) {
+                Entry::Occupied(_) => Err(BackendError::TsKeyExists),
+                Entry::Vacant(entry) => {
+                    self.watched.signal(entry.key());
+                    self.notify(KeyspaceEvents::MODULE, "ts.create", entry.key());
+                    entry.insert(TimeSeries::new(opts));
+                    Ok(())
+                }
+            }
+        })?
+    }
+
+    /// key 不存在时使用 opts 创建，on_duplicate 覆盖时间序列的 DUPLICATE_POLICY
+    /// ts 为 None 时使用当前时间
+    pub fn ts_add(
+        &self,
+        key: String,
+        ts: Option<i64>,
+        value: f64,
+        opts: Option<TsCreateOptions>,
+        on_duplicate: Option<DuplicatePolicy>,
+    ) -> Result<i64, BackendError> {
+        let ts = ts.unwrap_or_else(|| now_ms() as i64);
+        let compacted = self.write_typed(&key.clone(), "TSDB-TYPE", || {
+            let mut series = match (self.timeseries.get_mut(&key), opts) {
+                (Some(series), _) => series,
+                (None, Some(opts)) => self
+                    .timeseries
+                    .entry(key)
+                    .or_insert_with(|| TimeSeries::new(opts)),
+                (None, None) => return Err(BackendError::TsKeyNotFound),
+            };
+            let compacted = series.add(ts, value, on_duplicate)?;
+            self.watched.signal(series.key());
+            self.notify(KeyspaceEvents::MODULE, "ts.add", series.key());
+            Ok(compacted)
+        })??;
+        self.ts_write_compacted(compacted);
+        Ok(ts)
+    }
+
+    // 释放源时间序列的锁之后再写入目标时间序列，目标时间序列可能还有自己的压缩规则
+    fn ts_write_compacted(&self, compacted: Compacted) {
+        for (dest, ts, value) in compacted {
+            let _ = self.ts_add(dest, Some(ts), value, None, Some(DuplicatePolicy::Last));
+        }
+    }
+
+    /// 在最后一个值的基础上增加，时间戳不能小于最后一个时间戳
+    pub fn ts_incrby(
+        &self,
+        key: String,
+        ts: Option<i64>,
+        increment: f64,
+        opts: TsCreateOptions,
+    ) -> Result<i64, BackendError> {
+        let ts = ts.unwrap_or_else(|| now_ms() as i64);
+        let compacted = self.write_typed(&key.clone(), "TSDB-TYPE", || {
+            let mut series = self
+                .timeseries
+                .entry(key)
+                .or_insert_with(|| TimeSeries::new(opts));
+            let last = series.last();
+            if last.is_some_and(|(last_ts, _)| ts < last_ts) {
+                return Err(BackendError::TsTimestampTooSmall);
+            }
+            let value = last.map_or(0.0, |(_, v)| v) + increment;
+            let compacted = series.add(ts, value, Some(DuplicatePolicy::Last))?;
+            self.watched.signal(series.key());
+            self.notify(KeyspaceEvents::MODULE, "ts.incrby", series.key());
+            Ok(compacted)
+        })??;
+        self.ts_write_compacted(compacted);
+        Ok(ts)
+    }
+
+    pub fn ts_get(&self, key: &str) -> Result<Option<(i64, f64)>, BackendError> {
+        let series = self
+            .timeseries
+            .get(key)
+            .ok_or(BackendError::TsKeyNotFound)?;
+        Ok(series.last())
+    }
+
+    pub fn ts_range(
+        &self,
+        key: &str,
+        from: i64,
+        to: i64,
+        opts: &TsRangeOptions,
+    ) -> Result<Vec<(i64, f64)>, BackendError> {
+        let series = self
+            .timeseries
+            .get(key)
+            .ok_or(BackendError::TsKeyNotFound)?;
+        Ok(series.query(from, to, opts))
+    }
+
+    /// 所有 labels 满足 filters 的时间序列，按 key 排序
+    pub fn ts_mrange(
+        &self,
+        from: i64,
+        to: i64,
+        filters: &[TsFilter],
+        opts: &TsRangeOptions,
+    ) -> Vec<TsRangeResult> {
+        let mut ret = self
+            .timeseries
+            .iter()
+            .filter(|entry| filters.iter().all(|f| f.matches(entry.labels())))
+            .map(|entry| {
+                let samples = entry.query(from, to, opts);
+                (entry.key().clone(), entry.labels.clone(), samples)
+            })
+            .collect::<Vec<_>>();
+        ret.sort_by(|a, b| a.0.cmp(&b.0));
+        ret
+    }
+
+    pub fn ts_createrule(
+        &self,
+        source: &str,
+        destination: String,
+        aggregation: TsAggregation,
+        bucket: i64,
+    ) -> Result<(), BackendError> {
+        if source == destination {
+            return Err(BackendError::TsRuleSameKey);
+        }
+        if !self.timeseries.contains_key(source) {
+            return Err(BackendError::TsKeyNotFound);
+        }
+        {
+            let mut dest = self
+                .timeseries
+                .get_mut(&destination)
+                .ok_or(BackendError::TsKeyNotFound)?;
+            if dest.source.is_some() {
+                return Err(BackendError::TsRuleExists);
+            }
+            dest.source = Some(source.to_string());
+        }
+        self.watched.signal(source);
+        self.notify(KeyspaceEvents::MODULE, "ts.createrule", source);
+        self.watched.signal(&destination);
+        self.notify(KeyspaceEvents::MODULE, "ts.createrule", &destination);
+
+        let mut series = self
+            .timeseries
+            .get_mut(source)
+            .ok_or(BackendError::TsKeyNotFound)?;
+        series.rules.push(CompactionRule {
+            destination,
+            aggregation,
+            bucket,
+            current: None,
+        });
+        Ok(())
+    }
+
+    pub fn ts_deleterule(&self, source: &str, destination: &str) -> Result<(), BackendError> {
+        {
+            let mut series = self
+                .timeseries
+                .get_mut(source)
+                .ok_or(BackendError::TsKeyNotFound)?;
+            let len = series.rules.len();
+            series.rules.retain(|r| r.destination != destination);
+            if series.rules.len() == len {
+                return Err(BackendError::TsRuleNotFound);
+            }
+        }
+        if let Some(mut dest) = self.timeseries.get_mut(destination) {
+            dest.source = None;
+        }
+        self.watched.signal(source);
+        self.notify(KeyspaceEvents::MODULE, "ts.deleterule", source);
+        self.watched.signal(destination);
+        self.notify(KeyspaceEvents::MODULE, "ts.deleterule", destination);
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use dashmap::mapref::entry::Entry;
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{hash::murmurhash64a, BackendError};
+
+// TOPK.RESERVE 参数的上限
+pub const TOPK_MAX_K: usize = 100_000;
+pub const TOPK_MAX_BUCKETS: usize = 1 << 25;
+
+/// HeavyKeeper 算法实现的 Top-K，depth 行 width 列的 (指纹, 计数) 桶
+/// 加上一个最多 k 个元素的候选列表
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    width: usize,
+    depth: usize,
+    decay: f64,
+    buckets: Vec<(u32, u64)>,
+    // 按计数从大到小排列
+    heap: Vec<(Vec<u8>, u64)>,
+}
+
+impl TopK {
+    pub fn new(k: usize, width: usize, depth: usize, decay: f64) -> Result<Self, BackendError> {
+        let size = width
+            .checked_mul(depth)
+            .filter(|&size| size <= TOPK_MAX_BUCKETS && k <= TOPK_MAX_K)
+            .ok_or(BackendError::SizeTooLarge)?;
+        Ok(Self {
+            k,
+            width,
+            depth,
+            decay,
+            buckets: vec![(0, 0); size],
+            heap: Vec::with_capacity(k),
+        })
+    }
+
+    /// 添加一次元素，返回被挤出 Top-K 列表的元素
+    pub fn add(&mut self, item: &[u8]) -> Option<Vec<u8>> {
+        let fp = murmurhash64a(item, 0) as u32;
+        let mut max_count = 0;
+        for row in 0..self.depth {
+            let pos = row * self.width
+                + (murmurhash64a(item, row as u64 + 1) % self.width as u64) as usize;
+            let (bucket_fp, count) = &mut self.buckets[pos];
+            if *count == 0 {
+                *bucket_fp = fp;
+                *count = 1;
+            } else if *bucket_fp == fp {
+                *count += 1;
+            } else if rand::random::<f64>() < self.decay.powf(*count as f64) {
+                // 以 decay^count 的概率衰减其它元素的计数，减到 0 时占用这个桶
+                *count -= 1;
+                if *count == 0 {
+                    *bucket_fp = fp;
+                    *count = 1;
+                }
+            }
+            if *bucket_fp == fp {
+                max_count = max_count.max(*count);
+            }
+        }
+
+        if let Some(pos) = self.heap.iter().position(|(i, _)| i == item) {
+            self.heap[pos].1 = self.heap[pos].1.max(max_count);
+            self.heap
+                .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            return None;
+        }
+
+        let mut expelled = None;
+        if self.heap.len() >= self.k {
+            match self.heap.last() {
+                Some((_, min)) if max_count > *min => expelled = self.heap.pop().map(|(i, _)| i),
+                _ => return None,
+            }
+        }
+        self.heap.push((item.to_vec(), max_count));
+        self.heap
+            .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        expelled
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.heap.iter().any(|(i, _)| i == item)
+    }
+
+    /// 按计数从大到小返回
+    pub fn list(&self) -> Vec<(Vec<u8>, u64)> {
+        self.heap.clone()
+    }
+}
+
+impl Backend {
+    pub fn topk_reserve(
+        &self,
+        key: String,
+        k: usize,
+        width: usize,
+        depth: usize,
+        decay: f64,
+    ) -> Result<(), BackendError> {
+        self.write_typed(&key.clone(), "TopK-TYPE", || match self.topk.entry(key) {
+            Entry::Occupied(_) => Err(BackendError::TopKKeyExists),
+            Entry::Vacant(entry) => {
+                let topk = TopK::new(k, width, depth, decay)?;
+                self.watched.signal(entry.key());
+                self.notify(KeyspaceEvents::MODULE, "topk.reserve", entry.key());
+                entry.insert(topk);
+                Ok(())
+            }
+        })?
+    }
+
+    pub fn topk_add(
+        &self,
+        key: &str,
+        items: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, BackendError> {
+        let mut topk = self
+            .topk
+            .get_mut(key)
+            .ok_or(BackendError::TopKKeyNotFound)?;
+        self.watched.signal(key);
+        self.notify(KeyspaceEvents::MODULE, "topk.add", key);
+        Ok(items.iter().map(|item| topk.add(item)).collect())
+    }
+
+    pub fn topk_query(&self, key: &str, items: &[Vec<u8>]) -> Result<Vec<bool>, BackendError> {
+        let topk = self.topk.get(key).ok_or(BackendError::TopKKeyNotFound)?;
+        Ok(items.iter().map(|item| topk.contains(item)).collect())
+    }
+
+    pub fn topk_list(&self, key: &str) -> Result<Vec<(Vec<u8>, u64)>, BackendError> {
+        let topk = self.topk.get(key).ok_or(BackendError::TopKKeyNotFound)?;
+        Ok(topk.list())
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use rand::Rng;
+
+use super::BackendError;
+
+/// 向量之间距离的计算方式，距离越小越相似
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    // 欧式距离的平方
+    L2,
+    // 1 - 内积
+    IP,
+    // 1 - 余弦相似度
+    Cosine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorAlgorithm {
+    Flat,
+    Hnsw {
+        m: usize,
+        ef_construction: usize,
+        ef_runtime: usize,
+    },
+}
+
+/// VECTOR 字段的参数，只支持 FLOAT32 类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VectorParams {
+    pub algorithm: VectorAlgorithm,
+    pub dim: usize,
+    pub metric: DistanceMetric,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum VectorIndex {
+    Flat {
+        params: VectorParams,
+        vectors: HashMap<String, Vec<f32>>,
+    },
+    Hnsw(Hnsw),
+}
+
+impl DistanceMetric {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::L2 => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum(),
+            DistanceMetric::IP => 1.0 - a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>(),
+            DistanceMetric::Cosine => {
+                let (mut dot, mut na, mut nb) = (0.0, 0.0, 0.0);
+                for (x, y) in a.iter().zip(b) {
+                    dot += x * y;
+                    na += x * x;
+                    nb += y * y;
+                }
+                if na == 0.0 || nb == 0.0 {
+                    return 1.0;
+                }
+                1.0 - dot / (na.sqrt() * nb.sqrt())
+            }
+        }
+    }
+}
+
+impl Default for VectorAlgorithm {
+    fn default() -> Self {
+        VectorAlgorithm::Hnsw {
+            m: 16,
+            ef_construction: 200,
+            ef_runtime: 10,
+        }
+    }
+}
+
+/// 小端序的 float32 数组，长度不等于 dim 时返回 None
+pub(crate) fn parse_vector(blob: &[u8], dim: usize) -> Option<Vec<f32>> {
+    if blob.len() != dim * 4 {
+        return None;
+    }
+    Some(
+        blob.chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    )
+}
+
+// 按距离排序的 (距离, 节点) ，用于 BinaryHeap
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate(f32, usize);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HnswNode {
+    key: String,
+    vector: Vec<f32>,
+    // 每一层的邻居
+    neighbors: Vec<Vec<usize>>,
+    // 每一层指向这个节点的节点，删除时用来修复它们的邻居
+    incoming: Vec<HashSet<usize>>,
+}
+
+/// HNSW 图，删除节点时把它的邻居补给指向它的节点，删除之后的位置留给新插入的节点
+#[derive(Debug, Clone)]
+pub(crate) struct Hnsw {
+    params: VectorParams,
+    m: usize,
+    ef_construction: usize,
+    ef_runtime: usize,
+    nodes: Vec<HnswNode>,
+    free: Vec<usize>,
+    ids: HashMap<String, usize>,
+    entry: Option<usize>,
+    max_level: usize,
+}
+
+impl Hnsw {
+    fn new(params: VectorParams, m: usize, ef_construction: usize, ef_runtime: usize) -> Self {
+        Self {
+            params,
+            m: m.max(2),
+            ef_construction: ef_construction.max(1),
+            ef_runtime: ef_runtime.max(1),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            ids: HashMap::new(),
+            entry: None,
+            max_level: 0,
+        }
+    }
+
+    fn distance(&self, query: &[f32], id: usize) -> f32 {
+        self.params.metric.distance(query, &self.nodes[id].vector)
+    }
+
+    fn max_neighbors(&self, level: usize) -> usize {
+        if level == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn random_level(&self) -> usize {
+        let ml = 1.0 / (self.m as f64).ln();
+        let r: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        (-r.ln() * ml) as usize
+    }
+
+    // 在一层中从 entries 开始查找和 query 最近的 ef 个节点，按距离从小到大返回
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[usize],
+        ef: usize,
+        level: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = entries.iter().copied().collect::<HashSet<_>>();
+        let mut candidates = BinaryHeap::new();
+        let mut results = BinaryHeap::new();
+        for &id in entries {
+            let c = Candidate(self.distance(query, id), id);
+            candidates.push(std::cmp::Reverse(c));
+            results.push(c);
+        }
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            let furthest = results.peek().map(|c: &Candidate| c.0).unwrap_or(f32::MAX);
+            if current.0 > furthest && results.len() >= ef {
+                break;
+            }
+            for &n in &self.nodes[current.1].neighbors[level] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let d = self.distance(query, n);
+                let furthest = results.peek().map(|c: &Candidate| c.0).unwrap_or(f32::MAX);
+                if results.len() < ef || d < furthest {
+                    candidates.push(std::cmp::Reverse(Candidate(d, n)));
+                    results.push(Candidate(d, n));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        results.into_sorted_vec()
+    }
+
+    // 从最高层贪心地下降到 level + 1 层，返回下一层的入口
+    fn descend(&self, query: &[f32], level: usize) -> Option<usize> {
+        let mut ep = self.entry?;
+        for lc in (level + 1..=self.max_level).rev() {
+            ep = self.search_layer(query, &[ep], 1, lc)[0].1;
+        }
+        Some(ep)
+    }
+
+    fn insert(&mut self, key: &str, vector: Vec<f32>) {
+        if let Some(&id) = self.ids.get(key) {
+            if self.nodes[id].vector == vector {
+                return;
+            }
+            self.remove(key);
+        }
+
+        let level = self.random_level();
+        let entry = self.descend(&vector, level);
+        let node = HnswNode {
+            key: key.to_string(),
+            vector: vector.clone(),
+            neighbors: vec![Vec::new(); level + 1],
+            incoming: vec![HashSet::new(); level + 1],
+        };
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        self.ids.insert(key.to_string(), id);
+
+        let Some(mut ep) = entry else {
+            self.entry = Some(id);
+            self.max_level = level;
+            return;
+        };
+        for lc in (0..=level.min(self.max_level)).rev() {
+            let found = self.search_layer(&vector, &[ep], self.ef_construction, lc);
+            ep = found[0].1;
+            let neighbors = found
+                .iter()
+                .take(self.max_neighbors(lc))
+                .map(|c| c.1)
+                .collect::<Vec<_>>();
+            for &n in &neighbors {
+                let mut list = self.nodes[n].neighbors[lc].clone();
+                list.push(id);
+                self.set_neighbors(n, lc, list);
+                self.shrink(n, lc);
+            }
+            self.set_neighbors(id, lc, neighbors);
+        }
+        if level > self.max_level {
+            self.entry = Some(id);
+            self.max_level = level;
+        }
+    }
+
+    // 邻居超过上限时只保留最近的
+    fn shrink(&mut self, id: usize, level: usize) {
+        let max = self.max_neighbors(level);
+        if self.nodes[id].neighbors[level].len() <= max {
+            return;
+        }
+        let vector = &self.nodes[id].vector;
+        let mut neighbors = self.nodes[id].neighbors[level]
+            .iter()
+            .map(|&n| {
+                Candidate(
+                    self.params.metric.distance(vector, &self.nodes[n].vector),
+                    n,
+                )
+            })
+            .collect::<Vec<_>>();
+        neighbors.sort();
+        neighbors.truncate(max);
+        self.set_neighbors(id, level, neighbors.into_iter().map(|c| c.1).collect());
+    }
+
+    // 替换 id 在 level 层的邻居，同时更新反向的边
+    fn set_neighbors(&mut self, id: usize, level: usize, neighbors: Vec<usize>) {
+        let old = std::mem::replace(&mut self.nodes[id].neighbors[level], neighbors);
+        for n in old {
+            if !self.nodes[id].neighbors[level].contains(&n) {
+                self.nodes[n].incoming[level].remove(&id);
+            }
+        }
+        for i in 0..self.nodes[id].neighbors[level].len() {
+            let n = self.nodes[id].neighbors[level][i];
+            self.nodes[n].incoming[level].insert(id);
+        }
+    }
+
+    // 每个指向删除节点的节点从原有的邻居和删除节点的邻居中重新选出最近的，开销只和删除节点的度数有关
+    fn remove(&mut self, key: &str) {
+        let Some(id) = self.ids.remove(key) else {
+            return;
+        };
+        let levels = self.nodes[id].neighbors.len();
+        let mut removed = Vec::with_capacity(levels);
+        for lc in 0..levels {
+            let neighbors = std::mem::take(&mut self.nodes[id].neighbors[lc]);
+            for &n in &neighbors {
+                self.nodes[n].incoming[lc].remove(&id);
+            }
+            let incoming = std::mem::take(&mut self.nodes[id].incoming[lc]);
+            for p in incoming {
+                let mut list = self.nodes[p].neighbors[lc]
+                    .iter()
+                    .copied()
+                    .filter(|&n| n != id)
+                    .collect::<Vec<_>>();
+                for &n in &neighbors {
+                    if n != p && !list.contains(&n) {
+                        list.push(n);
+                    }
+                }
+                self.set_neighbors(p, lc, list);
+                self.shrink(p, lc);
+            }
+            removed.push(neighbors);
+        }
+
+        // 入口被删除时从它最高层的邻居中选一个层数最高的作为新的入口
+        if self.entry == Some(id) {
+            let entry = removed
+                .iter()
+                .rev()
+                .find(|neighbors| !neighbors.is_empty())
+                .and_then(|neighbors| {
+                    neighbors
+                        .iter()
+                        .copied()
+                        .max_by_key(|&n| self.nodes[n].neighbors.len())
+                })
+                .or_else(|| self.ids.values().next().copied());
+            self.entry = entry;
+            self.max_level = entry.map_or(0, |e| self.nodes[e].neighbors.len() - 1);
+        }
+        self.nodes[id] = HnswNode::default();
+        self.free.push(id);
+    }
+
+    fn knn(&self, query: &[f32], k: usize, filter: Option<&HashSet<String>>) -> Vec<(String, f32)> {
+        let Some(ep) = self.descend(query, 0) else {
+            return Vec::new();
+        };
+        let ef = self.ef_runtime.max(k);
+        let mut ret = self
+            .search_layer(query, &[ep], ef, 0)
+            .into_iter()
+            .map(|c| &self.nodes[c.1])
+            .filter(|n| filter.is_none_or(|f| f.contains(&n.key)))
+            .take(k)
+            .map(|n| (n.key.clone(), self.params.metric.distance(query, &n.vector)))
+            .collect::<Vec<_>>();
+        // 过滤条件排除了太多的节点，或者图中找不到 k 个节点时退化成暴力搜索
+        let expected = k.min(filter.map_or(self.ids.len(), |f| f.len()));
+        if ret.len() < expected {
+            ret = match filter {
+                Some(filter) => brute_force(
+                    filter.iter().filter_map(|key| {
+                        let id = *self.ids.get(key)?;
+                        Some((key, self.nodes[id].vector.as_slice()))
+                    }),
+                    query,
+                    k,
+                    self.params.metric,
+                ),
+                None => brute_force(
+                    self.ids
+                        .iter()
+                        .map(|(key, &id)| (key, self.nodes[id].vector.as_slice())),
+                    query,
+                    k,
+                    self.params.metric,
+                ),
+            };
+        }
+        ret
+    }
+}
+
+fn brute_force<'a>(
+    vectors: impl Iterator<Item = (&'a String, &'a [f32])>,
+    query: &[f32],
+    k: usize,
+    metric: DistanceMetric,
+) -> Vec<(String, f32)> {
+    let mut ret = vectors
+        .map(|(key, v)| (key.clone(), metric.distance(query, v)))
+        .collect::<Vec<_>>();
+    ret.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    ret.truncate(k);
+    ret
+}
+
+impl VectorIndex {
+    pub(crate) fn new(params: VectorParams) -> Self {
+        match params.algorithm {
+            VectorAlgorithm::Flat => VectorIndex::Flat {
+                params,
+                vectors: HashMap::new(),
+            },
+            VectorAlgorithm::Hnsw {
+                m,
+                ef_construction,
+                ef_runtime,
+            } => VectorIndex::Hnsw(Hnsw::new(params, m, ef_construction, ef_runtime)),
+        }
+    }
+
+    pub(crate) fn params(&self) -> &VectorParams {
+        match self {
+            VectorIndex::Flat { params, .. } => params,
+            VectorIndex::Hnsw(hnsw) => &hnsw.params,
+        }
+    }
+
+    /// 向量没有变化时不会更新索引
+    pub(crate) fn insert(&mut self, key: &str, vector: Vec<f32>) {
+        match self {
+            VectorIndex::Flat { vectors, .. } => {
+                vectors.insert(key.to_string(), vector);
+            }
+            VectorIndex::Hnsw(hnsw) => hnsw.insert(key, vector),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &str) {
+        match self {
+            VectorIndex::Flat { vectors, .. } => {
+                vectors.remove(key);
+            }
+            VectorIndex::Hnsw(hnsw) => hnsw.remove(key),
+        }
+    }
+
+    /// 返回距离最近的 k 个 key 和距离，filter 不为 None 时只返回其中的 key
+    pub(crate) fn knn(
+        &self,
+        blob: &[u8],
+        k: usize,
+        filter: Option<&HashSet<String>>,
+    ) -> Result<Vec<(String, f32)>, BackendError> {
+        let params = self.params();
+        let query = parse_vector(blob, params.dim).ok_or(BackendError::VectorSizeMismatch)?;
+        Ok(match self {
+            VectorIndex::Flat { vectors, .. } => brute_force(
+                vectors
+                    .iter()
+                    .filter(|(key, _)| filter.is_none_or(|f| f.contains(*key)))
+                    .map(|(key, v)| (key, v.as_slice())),
+                &query,
+                k,
+                params.metric,
+            ),
+            VectorIndex::Hnsw(hnsw) => hnsw.knn(&query, k, filter),
+        })
+    }
+}
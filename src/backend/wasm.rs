@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex};
+
+use dashmap::DashMap;
+use wasmi::{Config, Engine, ExternType, Module};
+
+use crate::{Backend, BackendError};
+
+/// WASM.LOAD 加载的模块，所有模块共用一个开启了 fuel 计量的 Engine
+#[derive(Debug)]
+pub struct WasmModules {
+    engine: Engine,
+    modules: DashMap<String, WasmModule>,
+    // 没有在 WASM.LOAD 中指定限制的模块使用的默认值
+    limits: Mutex<WasmLimits>,
+}
+
+// WASM.LOAD 指定的限制，None 表示使用调用时的默认值
+#[derive(Debug)]
+struct WasmModule {
+    module: Arc<Module>,
+    fuel: Option<u64>,
+    memory: Option<usize>,
+}
+
+/// 每次 WASM.CALL 可以消耗的 fuel 和模块可以使用的线性内存
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    // 大部分指令消耗 1 个 fuel
+    pub fuel: u64,
+    // 字节数
+    pub memory: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            memory: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for WasmModules {
+    fn default() -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config),
+            modules: DashMap::new(),
+            limits: Mutex::new(WasmLimits::default()),
+        }
+    }
+}
+
+impl Backend {
+    /// 编译并保存模块，同名的模块会被替换，模块需要导出入口函数 call
+    /// fuel 和 memory 是这个模块的限制，为 None 时使用 wasm_limits 的默认值
+    pub fn wasm_load(
+        &self,
+        name: String,
+        wasm: &[u8],
+        fuel: Option<u64>,
+        memory: Option<usize>,
+    ) -> Result<(), BackendError> {
+        let module = Module::new(&self.wasm.engine, wasm)
+            .map_err(|e| BackendError::WasmInvalidModule(e.to_string()))?;
+        let has_entry = module
+            .exports()
+            .any(|e| e.name() == "call" && matches!(e.ty(), ExternType::Func(_)));
+        if !has_entry {
+            return Err(BackendError::WasmInvalidModule(
+                "missing exported function 'call'".to_string(),
+            ));
+        }
+        let module = WasmModule {
+            module: Arc::new(module),
+            fuel,
+            memory,
+        };
+        self.wasm.modules.insert(name, module);
+        Ok(())
+    }
+
+    /// 返回模块和调用它时使用的限制
+    pub fn wasm_module(&self, name: &str) -> Option<(Arc<Module>, WasmLimits)> {
+        let module = self.wasm.modules.get(name)?;
+        let defaults = self.wasm_limits();
+        let limits = WasmLimits {
+            fuel: module.fuel.unwrap_or(defaults.fuel),
+            memory: module.memory.unwrap_or(defaults.memory),
+        };
+        Some((module.module.clone(), limits))
+    }
+
+    pub fn wasm_engine(&self) -> &Engine {
+        &self.wasm.engine
+    }
+
+    /// 默认的限制，也可以通过 CONFIG SET wasm-fuel-limit 和 wasm-memory-limit 修改
+    pub fn wasm_limits(&self) -> WasmLimits {
+        *self.wasm.limits.lock().unwrap()
+    }
+
+    pub fn set_wasm_limits(&self, limits: WasmLimits) {
+        *self.wasm.limits.lock().unwrap() = limits;
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use dashmap::DashMap;
+
+use crate::Backend;
+
+/// WATCH 的 key 和监视它们的连接，key 被修改时把这些连接标记为 dirty
+/// 每次写入都会调用 signal，所以用 DashMap 避免所有写入竞争同一把锁
+#[derive(Debug, Default)]
+pub struct WatchedKeys {
+    watchers: DashMap<String, Vec<Arc<AtomicBool>>>,
+}
+
+impl WatchedKeys {
+    pub fn watch(&self, key: &str, dirty: &Arc<AtomicBool>) {
+        let mut watchers = self.watchers.entry(key.to_string()).or_default();
+        if !watchers.iter().any(|w| Arc::ptr_eq(w, dirty)) {
+            watchers.push(dirty.clone());
+        }
+    }
+
+    pub fn unwatch(&self, keys: &[String], dirty: &Arc<AtomicBool>) {
+        for key in keys {
+            if let Some(mut watchers) = self.watchers.get_mut(key) {
+                watchers.retain(|w| !Arc::ptr_eq(w, dirty));
+            }
+            self.watchers
+                .remove_if(key, |_, watchers| watchers.is_empty());
+        }
+    }
+
+    /// key 被修改或删除时调用，调用方可能持有其它 DashMap 的锁，这里只锁 watchers
+    pub fn signal(&self, key: &str) {
+        if let Some(watchers) = self.watchers.get(key) {
+            for dirty in watchers.iter() {
+                dirty.store(true, Ordering::Release);
+            }
+        }
+    }
+}
+
+impl Backend {
+    /// 开始监视 keys，已经过期的 key 先删除，避免之后的惰性删除把连接标记为 dirty
+    pub fn watch(&self, keys: &[String], dirty: &Arc<AtomicBool>) {
+        for key in keys {
+            self.expire_if_needed(key);
+            self.watched.watch(key, dirty);
+        }
+    }
+
+    pub fn unwatch(&self, keys: &[String], dirty: &Arc<AtomicBool>) {
+        self.watched.unwatch(keys, dirty);
+    }
+
+    /// EXEC 时检查监视的 key 是否被修改过，监视期间过期的 key 也算被修改
+    pub fn watched_changed(&self, keys: &[String], dirty: &AtomicBool) -> bool {
+        for key in keys {
+            self.expire_if_needed(key);
+        }
+        dirty.load(Ordering::Acquire)
+    }
+}
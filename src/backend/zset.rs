@@ -0,0 +1,514 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::HashMap,
+    ops::{Bound, Range},
+};
+
+use crate::{Backend, KeyspaceEvents};
+
+use super::{skiplist::SkipList, BackendError};
+
+/// 跳表要求元素实现 Ord，f64 只有 PartialOrd，这里用 total_cmp 包装一下
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // 0.0 和 -0.0 在 total_cmp 里面不相等，这里按相等处理
+        if self.0 == other.0 {
+            Ordering::Equal
+        } else {
+            self.0.total_cmp(&other.0)
+        }
+    }
+}
+
+/// 有序集合：HashMap 提供 O(1) 的分数查询，跳表按 (score, member) 排序提供排名和范围查询
+#[derive(Debug, Default, Clone)]
+pub struct SortedSet {
+    scores: HashMap<String, f64>,
+    list: SkipList<(Score, String)>,
+}
+
+/// ZRANGE 等命令的范围类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZRangeBy {
+    Index(i64, i64),
+    Score(Bound<f64>, Bound<f64>),
+    Lex(Bound<String>, Bound<String>),
+}
+
+/// ZUNION 和 ZINTER 合并分数的方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            // inf + -inf 得到 NaN，和 redis 一样按 0 处理
+            Aggregate::Sum => zero_if_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+fn zero_if_nan(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+/// ZADD 的可选参数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ZAddFlags {
+    pub nx: bool,
+    pub xx: bool,
+    pub gt: bool,
+    pub lt: bool,
+    pub ch: bool,
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// 插入或者更新 member 的分数，返回旧的分数
+    pub fn insert(&mut self, member: String, score: f64) -> Option<f64> {
+        let old = self.scores.insert(member.clone(), score);
+        if let Some(old) = old {
+            self.list.remove(&(Score(old), member.clone()));
+        }
+        self.list.insert((Score(score), member));
+        old
+    }
+
+    pub fn remove(&mut self, member: &str) -> Option<f64> {
+        let score = self.scores.remove(member)?;
+        self.list.remove(&(Score(score), member.to_string()));
+        Some(score)
+    }
+
+    /// 按分数从小到大的排名，从 0 开始
+    pub fn rank(&self, member: &str) -> Option<usize> {
+        let score = Score(self.score(member)?);
+        Some(
+            self.list
+                .count_while(|(s, m)| (*s, m.as_str()) < (score, member)),
+        )
+    }
+
+    /// 弹出分数最小或者最大的元素
+    pub fn pop(&mut self, max: bool) -> Option<(String, f64)> {
+        let index = if max {
+            self.list.len().checked_sub(1)?
+        } else {
+            0
+        };
+        let key = self.list.get(index)?.clone();
+        let (score, member) = self.list.remove(&key)?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// 按分数从小到大遍历
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, f64)> {
+        self.list.iter().map(|(s, m)| (m.as_str(), s.0))
+    }
+
+    /// 返回范围内的元素，rev 为 true 时按分数从大到小排列
+    pub fn range(&self, by: &ZRangeBy, rev: bool) -> Vec<(String, f64)> {
+        let items = self.list.range(self.ranks(by, rev));
+        let items: Box<dyn Iterator<Item = &(Score, String)>> = if rev {
+            Box::new(items.rev())
+        } else {
+            Box::new(items)
+        };
+        items.map(|(s, m)| (m.clone(), s.0)).collect()
+    }
+
+    /// 范围内的元素数量，O(log n)
+    pub fn count(&self, by: &ZRangeBy) -> usize {
+        self.ranks(by, false).len()
+    }
+
+    // 范围对应的排名区间，按 Index 查询且 rev 为 true 时下标从分数最大的一端开始计算
+    fn ranks(&self, by: &ZRangeBy, rev: bool) -> Range<usize> {
+        let len = self.len() as i64;
+        let (start, end) = match by {
+            ZRangeBy::Index(start, stop) => {
+                let start = if *start < 0 {
+                    (len + start).max(0)
+                } else {
+                    *start
+                };
+                let stop = if *stop < 0 {
+                    len + stop
+                } else {
+                    (*stop).min(len - 1)
+                };
+                if start > stop || start >= len {
+                    return 0..0;
+                }
+                match rev {
+                    true => ((len - 1 - stop) as usize, (len - start) as usize),
+                    false => (start as usize, (stop + 1) as usize),
+                }
+            }
+            ZRangeBy::Score(min, max) => (
+                self.list.count_while(|(s, _)| below(min, &s.0)),
+                self.list.count_while(|(s, _)| !above(max, &s.0)),
+            ),
+            // 和 redis 一样假设所有成员的分数相同，按 (score, member) 排序后字典序区间是连续的
+            ZRangeBy::Lex(min, max) => (
+                self.list.count_while(|(_, m)| below(min, m)),
+                self.list.count_while(|(_, m)| !above(max, m)),
+            ),
+        };
+        start..end.max(start)
+    }
+}
+
+// value 是否在下界 min 的左侧
+fn below<T: PartialOrd + ?Sized>(min: &Bound<impl Borrow<T>>, value: &T) -> bool {
+    match min {
+        Bound::Included(min) => value < min.borrow(),
+        Bound::Excluded(min) => value <= min.borrow(),
+        Bound::Unbounded => false,
+    }
+}
+
+// value 是否在上界 max 的右侧
+fn above<T: PartialOrd + ?Sized>(max: &Bound<impl Borrow<T>>, value: &T) -> bool {
+    match max {
+        Bound::Included(max) => value > max.borrow(),
+        Bound::Excluded(max) => value >= max.borrow(),
+        Bound::Unbounded => false,
+    }
+}
+
+impl FromIterator<(String, f64)> for SortedSet {
+    fn from_iter<I: IntoIterator<Item = (String, f64)>>(iter: I) -> Self {
+        let mut zset = SortedSet::new();
+        for (member, score) in iter {
+            zset.insert(member, score);
+        }
+        zset
+    }
+}
+
+impl Backend {
+    /// 按 flags 添加或者更新成员，返回新增的数量，设置了 CH 时返回新增和更新的数量
+    pub fn zadd(
+        &self,
+        key: String,
+        members: Vec<(f64, String)>,
+        flags: ZAddFlags,
+    ) -> Result<usize, BackendError> {
+        self.zadd_with_event(key, members, flags, "zadd")
+    }
+
+    /// 和 zadd 相同，event 是发布的事件名，GEOADD 发布 geoadd
+    pub(crate) fn zadd_with_event(
+        &self,
+        key: String,
+        members: Vec<(f64, String)>,
+        flags: ZAddFlags,
+        event: &str,
+    ) -> Result<usize, BackendError> {
+        let (count, modified) = self.write_typed(&key, "zset", || {
+            let mut zset = self.zset.entry(key.clone()).or_default();
+            let (mut count, mut modified) = (0, false);
+            for (score, member) in members {
+                match zset.score(&member) {
+                    Some(old) => {
+                        if flags.nx || (flags.gt && score <= old) || (flags.lt && score >= old) {
+                            continue;
+                        }
+                        if old != score {
+                            zset.insert(member, score);
+                            modified = true;
+                            if flags.ch {
+                                count += 1;
+                            }
+                        }
+                    }
+                    None if flags.xx => continue,
+                    None => {
+                        zset.insert(member, score);
+                        count += 1;
+                        modified = true;
+                    }
+                }
+            }
+            drop(zset);
+            // XX 等参数可能导致插入了一个空集合，释放上面的 RefMut 之后再清理
+            self.zset.remove_if(&key, |_, zset| zset.is_empty());
+            (count, modified)
+        })?;
+        if modified {
+            self.watched.signal(&key);
+            self.notify(KeyspaceEvents::ZSET, event, &key);
+        }
+        self.blocking.signal(&key);
+        Ok(count)
+    }
+
+    /// 给 member 的分数加上 increment，返回新的分数，被 flags 阻止时返回 None
+    pub fn zincrby(
+        &self,
+        key: String,
+        increment: f64,
+        member: String,
+        flags: ZAddFlags,
+    ) -> Result<Option<f64>, BackendError> {
+        let ret = self.write_typed(&key, "zset", || {
+            let mut zset = self.zset.entry(key.clone()).or_default();
+            let old = zset.score(&member);
+            let score = old.unwrap_or(0.0) + increment;
+            let abort = match old {
+                Some(old) => flags.nx || (flags.gt && score <= old) || (flags.lt && score >= old),
+                None => flags.xx,
+            };
+
+            if score.is_nan() {
+                Err(BackendError::NotANumber)
+            } else if abort {
+                Ok(None)
+            } else {
+                zset.insert(member, score);
+                Ok(Some(score))
+            }
+        })?;
+        self.zset.remove_if(&key, |_, zset| zset.is_empty());
+        if let Ok(Some(_)) = ret {
+            self.watched.signal(&key);
+            self.notify(KeyspaceEvents::ZSET, "zincr", &key);
+        }
+        self.blocking.signal(&key);
+        ret
+    }
+
+    pub fn zscore(&self, key: &str, member: &str) -> Option<f64> {
+        self.zset.get(key).and_then(|zset| zset.score(member))
+    }
+
+    pub fn zcard(&self, key: &str) -> usize {
+        self.zset.get(key).map(|zset| zset.len()).unwrap_or(0)
+    }
+
+    /// 返回排名和分数，rev 为 true 时按分数从大到小排名
+    pub fn zrank(&self, key: &str, member: &str, rev: bool) -> Option<(usize, f64)> {
+        let zset = self.zset.get(key)?;
+        let rank = zset.rank(member)?;
+        let score = zset.score(member)?;
+        if rev {
+            Some((zset.len() - 1 - rank, score))
+        } else {
+            Some((rank, score))
+        }
+    }
+
+    pub fn zrem(&self, key: &str, members: &[String]) -> usize {
+        let count = match self.zset.get_mut(key) {
+            Some(mut zset) => members.iter().filter(|m| zset.remove(m).is_some()).count(),
+            None => return 0,
+        };
+        let emptied = self
+            .zset
+            .remove_if(key, |_, zset| zset.is_empty())
+            .is_some();
+        if count > 0 {
+            self.watched.signal(key);
+            self.notify_zset_removed(key, "zrem", emptied);
+        }
+        count
+    }
+
+    pub fn zrange(&self, key: &str, by: &ZRangeBy, rev: bool) -> Vec<(String, f64)> {
+        self.zset
+            .get(key)
+            .map(|zset| zset.range(by, rev))
+            .unwrap_or_default()
+    }
+
+    pub fn zcount(&self, key: &str, by: &ZRangeBy) -> usize {
+        self.zset.get(key).map(|zset| zset.count(by)).unwrap_or(0)
+    }
+
+    /// 删除范围内的元素，返回删除的数量
+    pub fn zremrange(&self, key: &str, by: &ZRangeBy) -> usize {
+        let count = match self.zset.get_mut(key) {
+            Some(mut zset) => {
+                let items = zset.range(by, false);
+                for (member, _) in items.iter() {
+                    zset.remove(member);
+                }
+                items.len()
+            }
+            None => return 0,
+        };
+        let emptied = self
+            .zset
+            .remove_if(key, |_, zset| zset.is_empty())
+            .is_some();
+        if count > 0 {
+            self.watched.signal(key);
+            let event = match by {
+                ZRangeBy::Index(..) => "zremrangebyrank",
+                ZRangeBy::Score(..) => "zremrangebyscore",
+                ZRangeBy::Lex(..) => "zremrangebylex",
+            };
+            self.notify_zset_removed(key, event, emptied);
+        }
+        count
+    }
+
+    /// 多个 key 的并集，weights 为空时权重都是 1
+    pub fn zunion(&self, keys: &[String], weights: &[f64], aggregate: Aggregate) -> SortedSet {
+        let mut ret = SortedSet::new();
+        for (i, key) in keys.iter().enumerate() {
+            let zset = match self.zset.get(key) {
+                Some(zset) => zset,
+                None => continue,
+            };
+            let weight = weights.get(i).copied().unwrap_or(1.0);
+            for (member, score) in zset.iter() {
+                let score = zero_if_nan(score * weight);
+                let score = match ret.score(member) {
+                    Some(old) => aggregate.apply(old, score),
+                    None => score,
+                };
+                ret.insert(member.to_string(), score);
+            }
+        }
+        ret
+    }
+
+    /// 多个 key 的交集，任意一个 key 不存在时返回空集合
+    pub fn zinter(&self, keys: &[String], weights: &[f64], aggregate: Aggregate) -> SortedSet {
+        let sets = keys
+            .iter()
+            .map(|key| self.zset.get(key).map(|zset| zset.clone()))
+            .collect::<Option<Vec<_>>>();
+        let sets = match sets {
+            Some(sets) if !sets.is_empty() => sets,
+            _ => return SortedSet::new(),
+        };
+
+        let mut ret = SortedSet::new();
+        'outer: for (member, score) in sets[0].iter() {
+            let mut score = zero_if_nan(score * weights.first().copied().unwrap_or(1.0));
+            for (i, zset) in sets.iter().enumerate().skip(1) {
+                let other = match zset.score(member) {
+                    Some(other) => other,
+                    None => continue 'outer,
+                };
+                let weight = weights.get(i).copied().unwrap_or(1.0);
+                score = aggregate.apply(score, zero_if_nan(other * weight));
+            }
+            ret.insert(member.to_string(), score);
+        }
+        ret
+    }
+
+    /// 第一个 key 中存在，其它 key 中都不存在的元素
+    pub fn zdiff(&self, keys: &[String]) -> SortedSet {
+        let mut ret = match keys.first().and_then(|key| self.zset.get(key)) {
+            Some(zset) => zset.clone(),
+            None => return SortedSet::new(),
+        };
+        for key in keys.iter().skip(1) {
+            if let Some(zset) = self.zset.get(key) {
+                for (member, _) in zset.iter() {
+                    ret.remove(member);
+                }
+            }
+        }
+        ret
+    }
+
+    /// 用 zset 覆盖 destination，zset 为空时删除 destination，返回元素数量
+    /// event 是发布的事件名，和命令名相同
+    pub fn zstore(&self, destination: String, zset: SortedSet, event: &str) -> usize {
+        let len = zset.len();
+        // 和 Redis 一样覆盖任意类型的 destination
+        let _guard = self.lock_keys(&[&destination]);
+        if self.key_type(&destination).is_some_and(|t| t != "zset") {
+            self.remove_key(&destination);
+        }
+        if zset.is_empty() {
+            if self.zset.remove(&destination).is_some() {
+                self.watched.signal(&destination);
+                self.notify(KeyspaceEvents::GENERIC, "del", &destination);
+            }
+        } else {
+            self.zset.insert(destination.clone(), zset);
+            self.watched.signal(&destination);
+            self.notify(KeyspaceEvents::ZSET, event, &destination);
+            self.blocking.signal(&destination);
+        }
+        len
+    }
+
+    /// 弹出最多 count 个分数最小或者最大的元素
+    pub fn zpop(&self, key: &str, max: bool, count: usize) -> Vec<(String, f64)> {
+        let items = match self.zset.get_mut(key) {
+            Some(mut zset) => (0..count).map_while(|_| zset.pop(max)).collect::<Vec<_>>(),
+            None => return vec![],
+        };
+        let emptied = self
+            .zset
+            .remove_if(key, |_, zset| zset.is_empty())
+            .is_some();
+        if !items.is_empty() {
+            self.watched.signal(key);
+            let event = if max { "zpopmax" } else { "zpopmin" };
+            self.notify_zset_removed(key, event, emptied);
+        }
+        items
+    }
+
+    // 最后一个元素被删除时 key 也被删除，和 Redis 一样再发布一个 del 事件
+    fn notify_zset_removed(&self, key: &str, event: &str, emptied: bool) {
+        self.notify(KeyspaceEvents::ZSET, event, key);
+        if emptied {
+            self.notify(KeyspaceEvents::GENERIC, "del", key);
+        }
+    }
+}
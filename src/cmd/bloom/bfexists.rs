@@ -0,0 +1,80 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, validate_min_args,
+        CommandError, CommandExecutor,
+    },
+    RespArray, RespFrame,
+};
+
+/// BF.EXISTS 和 BF.MEXISTS 共用，multi 为 true 时返回数组
+#[derive(Debug)]
+pub struct BFExists {
+    pub(crate) key: String,
+    pub(crate) items: Vec<Vec<u8>>,
+    pub(crate) multi: bool,
+}
+
+impl CommandExecutor for BFExists {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let mut ret = backend
+            .bf_exists(&self.key, &self.items)
+            .into_iter()
+            .map(|exists| (exists as i64).into())
+            .collect::<Vec<RespFrame>>();
+
+        if self.multi {
+            RespArray::new(ret).into()
+        } else {
+            ret.remove(0)
+        }
+    }
+}
+
+impl TryFrom<RespArray> for BFExists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let multi = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"bf.mexists"));
+        if multi {
+            validate_min_args(&value, &["bf.mexists"], 2)?;
+        } else {
+            validate_command(&value, &["bf.exists"], 2)?;
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let items = args.map(parse_bytes).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BFExists { key, items, multi })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_bf_exists_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$9\r\nbf.exists\r\n$2\r\nbf\r\n$1\r\na\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: BFExists = frame.try_into()?;
+        assert_eq!(result.key, "bf");
+        assert_eq!(result.items, vec![b"a".to_vec()]);
+        assert!(!result.multi);
+
+        buf.extend_from_slice(b"*4\r\n$10\r\nBF.MEXISTS\r\n$2\r\nbf\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: BFExists = frame.try_into()?;
+        assert_eq!(result.items.len(), 2);
+        assert!(result.multi);
+
+        Ok(())
+    }
+}
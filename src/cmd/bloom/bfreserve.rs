@@ -0,0 +1,125 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError, BF_MAX_CAPACITY, BF_MAX_EXPANSION,
+};
+
+/// BF.RESERVE key error_rate capacity [EXPANSION expansion] [NONSCALING]
+#[derive(Debug)]
+pub struct BFReserve {
+    pub(crate) key: String,
+    pub(crate) error_rate: f64,
+    pub(crate) capacity: u64,
+    // None 表示 NONSCALING
+    pub(crate) expansion: Option<u32>,
+}
+
+impl CommandExecutor for BFReserve {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.bf_reserve(self.key, self.error_rate, self.capacity, self.expansion) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for BFReserve {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["bf.reserve"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let error_rate: f64 = parse_number(next_arg(&mut args)?)?;
+        if !(error_rate > 0.0 && error_rate < 1.0) {
+            return Err(CommandError::InvalidArgument(
+                "(0 < error rate range < 1)".to_string(),
+            ));
+        }
+        let capacity: u64 = parse_number(next_arg(&mut args)?)?;
+        if capacity == 0 {
+            return Err(CommandError::InvalidArgument(
+                "(capacity should be larger than 0)".to_string(),
+            ));
+        }
+        if capacity > BF_MAX_CAPACITY {
+            return Err(CommandError::InvalidArgument(format!(
+                "(capacity should be at most {})",
+                BF_MAX_CAPACITY
+            )));
+        }
+
+        let mut expansion = Some(2);
+        let mut nonscaling = false;
+        while let Some(arg) = args.next() {
+            let arg = parse_string(arg)?.to_ascii_lowercase();
+            match arg.as_str() {
+                "expansion" => {
+                    let n: u32 = parse_number(next_arg(&mut args)?)?;
+                    if n == 0 || n > BF_MAX_EXPANSION {
+                        return Err(CommandError::InvalidArgument(format!(
+                            "expansion should be between 1 and {}",
+                            BF_MAX_EXPANSION
+                        )));
+                    }
+                    expansion = Some(n);
+                }
+                "nonscaling" => nonscaling = true,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+        if nonscaling {
+            expansion = None;
+        }
+
+        Ok(BFReserve {
+            key,
+            error_rate,
+            capacity,
+            expansion,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_bf_reserve_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*6\r\n$10\r\nBF.RESERVE\r\n$2\r\nbf\r\n$4\r\n0.01\r\n$4\r\n1000\r\n$9\r\nEXPANSION\r\n$1\r\n4\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: BFReserve = frame.try_into()?;
+        assert_eq!(result.key, "bf");
+        assert_eq!(result.error_rate, 0.01);
+        assert_eq!(result.capacity, 1000);
+        assert_eq!(result.expansion, Some(4));
+
+        buf.extend_from_slice(b"*5\r\n$10\r\nbf.reserve\r\n$2\r\nbf\r\n$3\r\n0.1\r\n$2\r\n10\r\n$10\r\nNONSCALING\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: BFReserve = frame.try_into()?;
+        assert_eq!(result.expansion, None);
+
+        buf.extend_from_slice(b"*4\r\n$10\r\nbf.reserve\r\n$2\r\nbf\r\n$1\r\n1\r\n$2\r\n10\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(BFReserve::try_from(frame).is_err());
+
+        buf.extend_from_slice(
+            b"*4\r\n$10\r\nbf.reserve\r\n$2\r\nbf\r\n$4\r\n0.01\r\n$20\r\n18446744073709551615\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(BFReserve::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+mod bfadd;
+mod bfexists;
+mod bfreserve;
+
+pub use bfadd::*;
+pub use bfexists::*;
+pub use bfreserve::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, RespArray, RespFrame, SimpleError, SimpleString};
+
+    use super::*;
+
+    fn bf_add(backend: &Backend, key: &str, items: &[&str]) -> RespFrame {
+        BFAdd {
+            key: key.to_string(),
+            items: items.iter().map(|i| i.as_bytes().to_vec()).collect(),
+            multi: true,
+        }
+        .execute(backend)
+    }
+
+    fn bf_exists(backend: &Backend, key: &str, items: &[&str]) -> RespFrame {
+        BFExists {
+            key: key.to_string(),
+            items: items.iter().map(|i| i.as_bytes().to_vec()).collect(),
+            multi: true,
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_bf_commands() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = BFAdd {
+            key: "bf".to_string(),
+            items: vec![b"a".to_vec()],
+            multi: false,
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        assert_eq!(
+            bf_add(&backend, "bf", &["a", "b"]),
+            RespArray::new([0.into(), 1.into()]).into()
+        );
+        assert_eq!(
+            bf_exists(&backend, "bf", &["a", "b", "c"]),
+            RespArray::new([1.into(), 1.into(), 0.into()]).into()
+        );
+        let cmd = BFExists {
+            key: "nokey".to_string(),
+            items: vec![b"a".to_vec()],
+            multi: false,
+        };
+        assert_eq!(cmd.execute(&backend), 0.into());
+
+        let cmd = BFReserve {
+            key: "bf".to_string(),
+            error_rate: 0.01,
+            capacity: 100,
+            expansion: Some(2),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR item exists").into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bf_scaling() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = BFReserve {
+            key: "bf".to_string(),
+            error_rate: 0.01,
+            capacity: 100,
+            expansion: Some(2),
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+
+        // 超过容量后追加新的过滤器，已经添加的元素一定存在
+        let items = (0..1000).map(|i| format!("item-{}", i)).collect::<Vec<_>>();
+        for item in &items {
+            backend.bf_add("bf".to_string(), &[item.as_bytes().to_vec()])?;
+        }
+        {
+            let filter = backend.bloom.get("bf").unwrap();
+            assert!(filter.filters() > 1);
+            assert!(filter.capacity() >= 1000);
+        }
+        let all = items
+            .iter()
+            .map(|i| i.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        assert!(backend.bf_exists("bf", &all).into_iter().all(|e| e));
+
+        // 误判率不超过 error_rate 太多
+        let others = (0..10000)
+            .map(|i| format!("other-{}", i).into_bytes())
+            .collect::<Vec<_>>();
+        let false_positives = backend
+            .bf_exists("bf", &others)
+            .into_iter()
+            .filter(|e| *e)
+            .count();
+        assert!(
+            false_positives < 200,
+            "false positives: {}",
+            false_positives
+        );
+
+        // NONSCALING 的过滤器满了之后返回错误
+        let cmd = BFReserve {
+            key: "fixed".to_string(),
+            error_rate: 0.01,
+            capacity: 2,
+            expansion: None,
+        };
+        cmd.execute(&backend);
+        let ret = bf_add(&backend, "fixed", &["a", "b", "c"]);
+        assert_eq!(
+            ret,
+            RespArray::new([
+                1.into(),
+                1.into(),
+                SimpleError::new("ERR non scaling filter is full").into()
+            ])
+            .into()
+        );
+
+        // 需要的内存超过上限时返回错误，不会分配
+        let cmd = BFReserve {
+            key: "huge".to_string(),
+            error_rate: 1e-300,
+            capacity: 1 << 30,
+            expansion: None,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR requested size is too large").into()
+        );
+        assert!(backend.bloom.get("huge").is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_number, parse_string, validate_min_args,
+        CommandError, CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+/// CMS.INCRBY key item increment [item increment ...]
+#[derive(Debug)]
+pub struct CMSIncrBy {
+    pub(crate) key: String,
+    pub(crate) items: Vec<(Vec<u8>, u64)>,
+}
+
+impl CommandExecutor for CMSIncrBy {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cms_incrby(&self.key, &self.items) {
+            Ok(counts) => RespArray::new(
+                counts
+                    .into_iter()
+                    .map(|count| (count as i64).into())
+                    .collect::<Vec<RespFrame>>(),
+            )
+            .into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CMSIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["cms.incrby"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let mut items = Vec::new();
+        while let Some(item) = args.next() {
+            let item = parse_bytes(item)?;
+            let increment = parse_number(next_arg(&mut args)?)?;
+            items.push((item, increment));
+        }
+
+        Ok(CMSIncrBy { key, items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cms_incrby_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$10\r\nCMS.INCRBY\r\n$3\r\ncms\r\n$1\r\na\r\n$1\r\n5\r\n$1\r\nb\r\n$1\r\n1\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CMSIncrBy = frame.try_into()?;
+        assert_eq!(result.key, "cms");
+        assert_eq!(result.items, vec![(b"a".to_vec(), 5), (b"b".to_vec(), 1)]);
+
+        buf.extend_from_slice(
+            b"*5\r\n$10\r\ncms.incrby\r\n$3\r\ncms\r\n$1\r\na\r\n$1\r\n5\r\n$1\r\nb\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(CMSIncrBy::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
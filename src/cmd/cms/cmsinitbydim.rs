@@ -0,0 +1,80 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_command, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError, CMS_MAX_COUNTERS,
+};
+
+/// CMS.INITBYDIM key width depth
+#[derive(Debug)]
+pub struct CMSInitByDim {
+    pub(crate) key: String,
+    pub(crate) width: usize,
+    pub(crate) depth: usize,
+}
+
+impl CommandExecutor for CMSInitByDim {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cms_init(self.key, self.width, self.depth) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CMSInitByDim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cms.initbydim"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let width: usize = parse_number(next_arg(&mut args)?)?;
+        let depth: usize = parse_number(next_arg(&mut args)?)?;
+        let size = width.saturating_mul(depth);
+        if width == 0 || depth == 0 || size > CMS_MAX_COUNTERS {
+            return Err(CommandError::InvalidArgument(
+                "CMS: invalid width/depth value".to_string(),
+            ));
+        }
+
+        Ok(CMSInitByDim { key, width, depth })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cms_initbydim_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*4\r\n$13\r\nCMS.INITBYDIM\r\n$3\r\ncms\r\n$4\r\n2000\r\n$1\r\n5\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CMSInitByDim = frame.try_into()?;
+        assert_eq!(result.key, "cms");
+        assert_eq!(result.width, 2000);
+        assert_eq!(result.depth, 5);
+
+        buf.extend_from_slice(b"*4\r\n$13\r\ncms.initbydim\r\n$3\r\ncms\r\n$1\r\n0\r\n$1\r\n5\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(CMSInitByDim::try_from(frame).is_err());
+
+        // width * depth 溢出或超过上限
+        buf.extend_from_slice(b"*4\r\n$13\r\ncms.initbydim\r\n$3\r\ncms\r\n$20\r\n18446744073709551615\r\n$1\r\n2\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(CMSInitByDim::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
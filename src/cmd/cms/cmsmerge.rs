@@ -0,0 +1,96 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+/// CMS.MERGE destination numKeys source [source ...] [WEIGHTS weight [weight ...]]
+#[derive(Debug)]
+pub struct CMSMerge {
+    pub(crate) destination: String,
+    pub(crate) sources: Vec<String>,
+    pub(crate) weights: Vec<u64>,
+}
+
+impl CommandExecutor for CMSMerge {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cms_merge(&self.destination, &self.sources, &self.weights) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CMSMerge {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["cms.merge"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let destination = parse_string(next_arg(&mut args)?)?;
+        let numkeys: usize = parse_number(next_arg(&mut args)?)?;
+        if numkeys == 0 {
+            return Err(CommandError::InvalidArgument(
+                "CMS: Number of keys must be positive".to_string(),
+            ));
+        }
+        let sources = (0..numkeys)
+            .map(|_| parse_string(next_arg(&mut args)?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let weights = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("weights") => (0..numkeys)
+                .map(|_| parse_number(next_arg(&mut args)?))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![1; numkeys],
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(CMSMerge {
+            destination,
+            sources,
+            weights,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cms_merge_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$9\r\nCMS.MERGE\r\n$4\r\ndest\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CMSMerge = frame.try_into()?;
+        assert_eq!(result.destination, "dest");
+        assert_eq!(result.sources, vec!["a", "b"]);
+        assert_eq!(result.weights, vec![1, 1]);
+
+        buf.extend_from_slice(b"*8\r\n$9\r\ncms.merge\r\n$4\r\ndest\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\nb\r\n$7\r\nWEIGHTS\r\n$1\r\n3\r\n$1\r\n2\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: CMSMerge = frame.try_into()?;
+        assert_eq!(result.weights, vec![3, 2]);
+
+        buf.extend_from_slice(b"*4\r\n$9\r\ncms.merge\r\n$4\r\ndest\r\n$1\r\n2\r\n$1\r\na\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(CMSMerge::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
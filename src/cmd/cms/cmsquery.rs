@@ -0,0 +1,65 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct CMSQuery {
+    pub(crate) key: String,
+    pub(crate) items: Vec<Vec<u8>>,
+}
+
+impl CommandExecutor for CMSQuery {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cms_query(&self.key, &self.items) {
+            Ok(counts) => RespArray::new(
+                counts
+                    .into_iter()
+                    .map(|count| (count as i64).into())
+                    .collect::<Vec<RespFrame>>(),
+            )
+            .into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CMSQuery {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["cms.query"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let items = args.map(parse_bytes).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CMSQuery { key, items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cms_query_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$9\r\ncms.query\r\n$3\r\ncms\r\n$1\r\na\r\n$1\r\nb\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CMSQuery = frame.try_into()?;
+        assert_eq!(result.key, "cms");
+        assert_eq!(result.items, vec![b"a".to_vec(), b"b".to_vec()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+mod cmsincrby;
+mod cmsinitbydim;
+mod cmsmerge;
+mod cmsquery;
+
+pub use cmsincrby::*;
+pub use cmsinitbydim::*;
+pub use cmsmerge::*;
+pub use cmsquery::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, RespArray, RespFrame, SimpleError, SimpleString};
+
+    use super::*;
+
+    fn cms_init(backend: &Backend, key: &str, width: usize, depth: usize) -> RespFrame {
+        CMSInitByDim {
+            key: key.to_string(),
+            width,
+            depth,
+        }
+        .execute(backend)
+    }
+
+    fn cms_query(backend: &Backend, key: &str, items: &[&str]) -> RespFrame {
+        CMSQuery {
+            key: key.to_string(),
+            items: items.iter().map(|i| i.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_cms_commands() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            cms_query(&backend, "cms", &["a"]),
+            SimpleError::new("CMS: key does not exist").into()
+        );
+        assert_eq!(
+            cms_init(&backend, "cms", 2000, 5),
+            SimpleString::new("OK").into()
+        );
+        assert_eq!(
+            cms_init(&backend, "cms", 2000, 5),
+            SimpleError::new("CMS: key already exists").into()
+        );
+
+        let cmd = CMSIncrBy {
+            key: "cms".to_string(),
+            items: vec![(b"a".to_vec(), 5), (b"b".to_vec(), 2), (b"a".to_vec(), 1)],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([5.into(), 2.into(), 6.into()]).into()
+        );
+        assert_eq!(
+            cms_query(&backend, "cms", &["a", "b", "c"]),
+            RespArray::new([6.into(), 2.into(), 0.into()]).into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cms_merge_command() -> Result<()> {
+        let backend = Backend::new();
+        cms_init(&backend, "a", 100, 4);
+        cms_init(&backend, "b", 100, 4);
+        cms_init(&backend, "dest", 100, 4);
+        cms_init(&backend, "small", 10, 4);
+        backend.cms_incrby("a", &[(b"x".to_vec(), 3)])?;
+        backend.cms_incrby("b", &[(b"x".to_vec(), 1), (b"y".to_vec(), 4)])?;
+
+        let cmd = CMSMerge {
+            destination: "dest".to_string(),
+            sources: vec!["a".to_string(), "b".to_string()],
+            weights: vec![2, 1],
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+        assert_eq!(
+            cms_query(&backend, "dest", &["x", "y"]),
+            RespArray::new([7.into(), 4.into()]).into()
+        );
+
+        let cmd = CMSMerge {
+            destination: "dest".to_string(),
+            sources: vec!["a".to_string(), "small".to_string()],
+            weights: vec![1, 1],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("CMS: width/depth is not equal").into()
+        );
+
+        Ok(())
+    }
+}
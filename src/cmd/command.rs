@@ -1,9 +1,20 @@
 use enum_dispatch::enum_dispatch;
 
-use crate::{RespArray, RespFrame};
+use crate::{Backend, RespArray, RespFrame, RespNull};
 
 use super::{
-    CommandError, Echo, Get, HGet, HGetAll, HMGet, HSet, SAdd, SIsmember, Set, Unrecognized,
+    BFAdd, BFExists, BFReserve, BZMPop, BZPop, BlockingCommandExecutor, CFAdd, CFDel, CFExists,
+    CLThrottle, CMSIncrBy, CMSInitByDim, CMSMerge, CMSQuery, CommandError, CommandExecutor, Config,
+    CustomCommand, Del, DelEx, Echo, Eval, EvalSha, FCall, FTCreate, FTDropIndex, FTInfo, FTSearch,
+    Function, GeoAdd, GeoDist, GeoHash, GeoPos, GeoSearch, GeoSearchStore, Get, HDel, HGet,
+    HGetAll, HMGet, HSet, JsonArrAppend, JsonArrInsert, JsonArrPop, JsonDel, JsonGet, JsonLen,
+    JsonNumIncrBy, JsonObjKeys, JsonSet, JsonType, LMPop, LMove, LPos, LPush, LRange,
+    ObjectVersion, PFAdd, PFCount, PFMerge, PubSubCommand, Publish, RPush, SAdd, SIsmember, Script,
+    Set, TSAdd, TSCreate, TSCreateRule, TSDeleteRule, TSGet, TSIncrBy, TSMAdd, TSMRange, TSRange,
+    TopKAdd, TopKList, TopKQuery, TopKReserve, Unrecognized, WasmCall, WasmLoad, XAck, XAdd,
+    XAutoClaim, XClaim, XDel, XGroup, XInfo, XLen, XPending, XRange, XRead, XReadGroup, XTrim,
+    ZAdd, ZCard, ZCount, ZIncrBy, ZInterCard, ZMPop, ZMScore, ZPop, ZRange, ZRangeStore, ZRank,
+    ZRem, ZRemRange, ZScore, ZSetOp,
 };
 
 #[enum_dispatch(CommandExecutor)]
@@ -11,20 +22,245 @@ use super::{
 pub enum Command {
     Get(Get),
     Set(Set),
+    Del(Del),
+    DelEx(DelEx),
+    ObjectVersion(ObjectVersion),
     HGet(HGet),
     HSet(HSet),
     HGetAll(HGetAll),
     HMGet(HMGet),
+    HDel(HDel),
 
     SAdd(SAdd),
     SIsmember(SIsmember),
 
+    LPush(LPush),
+    RPush(RPush),
+    LRange(LRange),
+    LMove(LMove),
+    LMPop(LMPop),
+    LPos(LPos),
+
+    ZAdd(ZAdd),
+    ZScore(ZScore),
+    ZMScore(ZMScore),
+    ZRank(ZRank),
+    ZRem(ZRem),
+    ZIncrBy(ZIncrBy),
+    ZCard(ZCard),
+    ZRange(ZRange),
+    ZSetOp(ZSetOp),
+    ZInterCard(ZInterCard),
+    ZRangeStore(ZRangeStore),
+    ZRemRange(ZRemRange),
+    ZCount(ZCount),
+    ZPop(ZPop),
+    ZMPop(ZMPop),
+    BZPop(BZPop),
+    BZMPop(BZMPop),
+
+    XAdd(XAdd),
+    XRange(XRange),
+    XLen(XLen),
+    XTrim(XTrim),
+    XDel(XDel),
+    XRead(XRead),
+    XInfo(XInfo),
+    XGroup(XGroup),
+    XReadGroup(XReadGroup),
+    XAck(XAck),
+    XPending(XPending),
+    XClaim(XClaim),
+    XAutoClaim(XAutoClaim),
+
+    PFAdd(PFAdd),
+    PFCount(PFCount),
+    PFMerge(PFMerge),
+
+    GeoAdd(GeoAdd),
+    GeoPos(GeoPos),
+    GeoDist(GeoDist),
+    GeoHash(GeoHash),
+    GeoSearch(GeoSearch),
+    GeoSearchStore(GeoSearchStore),
+
+    BFReserve(BFReserve),
+    BFAdd(BFAdd),
+    BFExists(BFExists),
+    CFAdd(CFAdd),
+    CFDel(CFDel),
+    CFExists(CFExists),
+    CMSInitByDim(CMSInitByDim),
+    CMSIncrBy(CMSIncrBy),
+    CMSQuery(CMSQuery),
+    CMSMerge(CMSMerge),
+    TopKReserve(TopKReserve),
+    TopKAdd(TopKAdd),
+    TopKQuery(TopKQuery),
+    TopKList(TopKList),
+
+    JsonSet(JsonSet),
+    JsonGet(JsonGet),
+    JsonDel(JsonDel),
+    JsonNumIncrBy(JsonNumIncrBy),
+    JsonArrAppend(JsonArrAppend),
+    JsonArrInsert(JsonArrInsert),
+    JsonArrPop(JsonArrPop),
+    JsonType(JsonType),
+    JsonLen(JsonLen),
+    JsonObjKeys(JsonObjKeys),
+
+    TSCreate(TSCreate),
+    TSAdd(TSAdd),
+    TSMAdd(TSMAdd),
+    TSIncrBy(TSIncrBy),
+    TSGet(TSGet),
+    TSRange(TSRange),
+    TSMRange(TSMRange),
+    TSCreateRule(TSCreateRule),
+    TSDeleteRule(TSDeleteRule),
+
+    FTCreate(FTCreate),
+    FTSearch(FTSearch),
+    FTInfo(FTInfo),
+    FTDropIndex(FTDropIndex),
+
+    CLThrottle(CLThrottle),
+    Eval(Eval),
+    EvalSha(EvalSha),
+    Script(Script),
+    Function(Function),
+    FCall(FCall),
+
+    WasmLoad(WasmLoad),
+    WasmCall(WasmCall),
+
+    Publish(Publish),
+    PubSub(PubSubCommand),
+
+    Custom(CustomCommand),
+
     Echo(Echo),
+    Config(Config),
 
     // unrecognized command
     Unrecognized(Unrecognized),
 }
 
+impl Command {
+    /// 阻塞命令需要 network 层等待数据，其它命令直接执行
+    pub fn as_blocking(&mut self) -> Option<&mut dyn BlockingCommandExecutor> {
+        match self {
+            Command::BZPop(cmd) => Some(cmd),
+            Command::BZMPop(cmd) => Some(cmd),
+            Command::XRead(cmd) if cmd.block.is_some() => Some(cmd),
+            Command::XReadGroup(cmd) if cmd.is_blocking() => Some(cmd),
+            _ => None,
+        }
+    }
+
+    /// 先查找注册的自定义命令，再解析内置命令
+    pub fn parse(frame: RespFrame, backend: &Backend) -> Result<Self, CommandError> {
+        let handler = match &frame {
+            RespFrame::Array(value) => match value.first() {
+                Some(RespFrame::BulkString(name)) => {
+                    backend.custom_command(&String::from_utf8_lossy(name))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match (handler, frame) {
+            (Some(handler), RespFrame::Array(value)) => {
+                Ok(Command::Custom(CustomCommand::new(handler, value)?))
+            }
+            (_, frame) => Command::try_from(frame),
+        }
+    }
+
+    /// 事务和脚本中的命令立即执行，阻塞命令没有数据时直接返回 nil
+    pub fn execute_now(mut self, backend: &Backend) -> RespFrame {
+        match self.as_blocking() {
+            Some(cmd) => {
+                cmd.prepare(backend);
+                cmd.try_execute(backend)
+                    .unwrap_or(RespFrame::Null(RespNull))
+            }
+            None => self.execute(backend),
+        }
+    }
+
+    /// 不修改数据的命令，只有这些命令可以在 EVAL_RO 中调用
+    pub fn is_readonly(&self) -> bool {
+        matches!(
+            self,
+            Command::Get(_)
+                | Command::ObjectVersion(_)
+                | Command::HGet(_)
+                | Command::HGetAll(_)
+                | Command::HMGet(_)
+                | Command::SIsmember(_)
+                | Command::LRange(_)
+                | Command::LPos(_)
+                | Command::ZScore(_)
+                | Command::ZMScore(_)
+                | Command::ZRank(_)
+                | Command::ZCard(_)
+                | Command::ZRange(_)
+                | Command::ZInterCard(_)
+                | Command::ZCount(_)
+                | Command::XRange(_)
+                | Command::XLen(_)
+                | Command::XRead(_)
+                | Command::XInfo(_)
+                | Command::XPending(_)
+                | Command::PFCount(_)
+                | Command::GeoPos(_)
+                | Command::GeoDist(_)
+                | Command::GeoHash(_)
+                | Command::GeoSearch(_)
+                | Command::BFExists(_)
+                | Command::CFExists(_)
+                | Command::CMSQuery(_)
+                | Command::TopKQuery(_)
+                | Command::TopKList(_)
+                | Command::JsonGet(_)
+                | Command::JsonType(_)
+                | Command::JsonLen(_)
+                | Command::JsonObjKeys(_)
+                | Command::TSGet(_)
+                | Command::TSRange(_)
+                | Command::TSMRange(_)
+                | Command::FTSearch(_)
+                | Command::FTInfo(_)
+                | Command::Echo(_)
+        ) || matches!(self, Command::ZSetOp(cmd) if cmd.destination.is_none())
+            || matches!(self, Command::Custom(cmd) if cmd.handler().flags().readonly)
+    }
+
+    /// 命令执行时需要的 exec_lock
+    pub fn lock_mode(&self) -> LockMode {
+        match self {
+            Command::Eval(_) | Command::EvalSha(_) | Command::FCall(_) | Command::WasmCall(_) => {
+                LockMode::Exclusive
+            }
+            // 加载库时要执行库代码
+            Command::Function(cmd) if cmd.runs_code() => LockMode::Exclusive,
+            Command::Script(_) => LockMode::Unlocked,
+            _ => LockMode::Shared,
+        }
+    }
+}
+
+/// 普通命令共享 exec_lock，脚本需要独占执行
+/// SCRIPT 命令不加锁，否则 SCRIPT KILL 需要等待正在执行的脚本结束
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+    Unlocked,
+}
+
 impl TryFrom<RespFrame> for Command {
     type Error = CommandError;
 
@@ -46,13 +282,171 @@ impl TryFrom<RespArray> for Command {
             Some(RespFrame::BulkString(ref cmd)) => match cmd.as_ref() {
                 b"GET" | b"get" => Ok(Command::Get(Get::try_from(v)?)),
                 b"SET" | b"set" => Ok(Command::Set(Set::try_from(v)?)),
+                b"DEL" | b"del" => Ok(Command::Del(Del::try_from(v)?)),
+                b"DELEX" | b"delex" => Ok(Command::DelEx(DelEx::try_from(v)?)),
+                b"OBJECT" | b"object" => Ok(Command::ObjectVersion(ObjectVersion::try_from(v)?)),
                 b"HGET" | b"hget" => Ok(Command::HGet(HGet::try_from(v)?)),
                 b"HSET" | b"hset" => Ok(Command::HSet(HSet::try_from(v)?)),
                 b"HGETALL" | b"hgetall" => Ok(Command::HGetAll(HGetAll::try_from(v)?)),
                 b"HMGET" | b"hmget" => Ok(Command::HMGet(HMGet::try_from(v)?)),
+                b"HDEL" | b"hdel" => Ok(Command::HDel(HDel::try_from(v)?)),
                 b"ECHO" | b"echo" => Ok(Command::Echo(Echo::try_from(v)?)),
+                b"CONFIG" | b"config" => Ok(Command::Config(Config::try_from(v)?)),
                 b"SADD" | b"sadd" => Ok(Command::SAdd(SAdd::try_from(v)?)),
                 b"SISMEMBER" | b"sismember" => Ok(Command::SIsmember(SIsmember::try_from(v)?)),
+                b"LPUSH" | b"lpush" => Ok(Command::LPush(LPush::try_from(v)?)),
+                b"RPUSH" | b"rpush" => Ok(Command::RPush(RPush::try_from(v)?)),
+                b"LRANGE" | b"lrange" => Ok(Command::LRange(LRange::try_from(v)?)),
+                b"LMOVE" | b"lmove" => Ok(Command::LMove(LMove::try_from(v)?)),
+                b"LMPOP" | b"lmpop" => Ok(Command::LMPop(LMPop::try_from(v)?)),
+                b"LPOS" | b"lpos" => Ok(Command::LPos(LPos::try_from(v)?)),
+                b"ZADD" | b"zadd" => Ok(Command::ZAdd(ZAdd::try_from(v)?)),
+                b"ZSCORE" | b"zscore" => Ok(Command::ZScore(ZScore::try_from(v)?)),
+                b"ZMSCORE" | b"zmscore" => Ok(Command::ZMScore(ZMScore::try_from(v)?)),
+                b"ZRANK" | b"zrank" | b"ZREVRANK" | b"zrevrank" => {
+                    Ok(Command::ZRank(ZRank::try_from(v)?))
+                }
+                b"ZREM" | b"zrem" => Ok(Command::ZRem(ZRem::try_from(v)?)),
+                b"ZINCRBY" | b"zincrby" => Ok(Command::ZIncrBy(ZIncrBy::try_from(v)?)),
+                b"ZCARD" | b"zcard" => Ok(Command::ZCard(ZCard::try_from(v)?)),
+                b"ZRANGE" | b"zrange" => Ok(Command::ZRange(ZRange::try_from(v)?)),
+                b"ZUNION" | b"zunion" | b"ZUNIONSTORE" | b"zunionstore" | b"ZINTER" | b"zinter"
+                | b"ZINTERSTORE" | b"zinterstore" | b"ZDIFF" | b"zdiff" | b"ZDIFFSTORE"
+                | b"zdiffstore" => Ok(Command::ZSetOp(ZSetOp::try_from(v)?)),
+                b"ZINTERCARD" | b"zintercard" => Ok(Command::ZInterCard(ZInterCard::try_from(v)?)),
+                b"ZRANGESTORE" | b"zrangestore" => {
+                    Ok(Command::ZRangeStore(ZRangeStore::try_from(v)?))
+                }
+                b"ZREMRANGEBYSCORE" | b"zremrangebyscore" | b"ZREMRANGEBYRANK"
+                | b"zremrangebyrank" | b"ZREMRANGEBYLEX" | b"zremrangebylex" => {
+                    Ok(Command::ZRemRange(ZRemRange::try_from(v)?))
+                }
+                b"ZCOUNT" | b"zcount" | b"ZLEXCOUNT" | b"zlexcount" => {
+                    Ok(Command::ZCount(ZCount::try_from(v)?))
+                }
+                b"ZPOPMIN" | b"zpopmin" | b"ZPOPMAX" | b"zpopmax" => {
+                    Ok(Command::ZPop(ZPop::try_from(v)?))
+                }
+                b"ZMPOP" | b"zmpop" => Ok(Command::ZMPop(ZMPop::try_from(v)?)),
+                b"BZPOPMIN" | b"bzpopmin" | b"BZPOPMAX" | b"bzpopmax" => {
+                    Ok(Command::BZPop(BZPop::try_from(v)?))
+                }
+                b"BZMPOP" | b"bzmpop" => Ok(Command::BZMPop(BZMPop::try_from(v)?)),
+                b"XADD" | b"xadd" => Ok(Command::XAdd(XAdd::try_from(v)?)),
+                b"XRANGE" | b"xrange" | b"XREVRANGE" | b"xrevrange" => {
+                    Ok(Command::XRange(XRange::try_from(v)?))
+                }
+                b"XLEN" | b"xlen" => Ok(Command::XLen(XLen::try_from(v)?)),
+                b"XTRIM" | b"xtrim" => Ok(Command::XTrim(XTrim::try_from(v)?)),
+                b"XDEL" | b"xdel" => Ok(Command::XDel(XDel::try_from(v)?)),
+                b"XREAD" | b"xread" => Ok(Command::XRead(XRead::try_from(v)?)),
+                b"XINFO" | b"xinfo" => Ok(Command::XInfo(XInfo::try_from(v)?)),
+                b"XGROUP" | b"xgroup" => Ok(Command::XGroup(XGroup::try_from(v)?)),
+                b"XREADGROUP" | b"xreadgroup" => Ok(Command::XReadGroup(XReadGroup::try_from(v)?)),
+                b"XACK" | b"xack" => Ok(Command::XAck(XAck::try_from(v)?)),
+                b"XPENDING" | b"xpending" => Ok(Command::XPending(XPending::try_from(v)?)),
+                b"XCLAIM" | b"xclaim" => Ok(Command::XClaim(XClaim::try_from(v)?)),
+                b"XAUTOCLAIM" | b"xautoclaim" => Ok(Command::XAutoClaim(XAutoClaim::try_from(v)?)),
+                b"PFADD" | b"pfadd" => Ok(Command::PFAdd(PFAdd::try_from(v)?)),
+                b"PFCOUNT" | b"pfcount" => Ok(Command::PFCount(PFCount::try_from(v)?)),
+                b"PFMERGE" | b"pfmerge" => Ok(Command::PFMerge(PFMerge::try_from(v)?)),
+                b"GEOADD" | b"geoadd" => Ok(Command::GeoAdd(GeoAdd::try_from(v)?)),
+                b"GEOPOS" | b"geopos" => Ok(Command::GeoPos(GeoPos::try_from(v)?)),
+                b"GEODIST" | b"geodist" => Ok(Command::GeoDist(GeoDist::try_from(v)?)),
+                b"GEOHASH" | b"geohash" => Ok(Command::GeoHash(GeoHash::try_from(v)?)),
+                b"GEOSEARCH" | b"geosearch" => Ok(Command::GeoSearch(GeoSearch::try_from(v)?)),
+                b"GEOSEARCHSTORE" | b"geosearchstore" => {
+                    Ok(Command::GeoSearchStore(GeoSearchStore::try_from(v)?))
+                }
+                b"BF.RESERVE" | b"bf.reserve" => Ok(Command::BFReserve(BFReserve::try_from(v)?)),
+                b"BF.ADD" | b"bf.add" | b"BF.MADD" | b"bf.madd" => {
+                    Ok(Command::BFAdd(BFAdd::try_from(v)?))
+                }
+                b"BF.EXISTS" | b"bf.exists" | b"BF.MEXISTS" | b"bf.mexists" => {
+                    Ok(Command::BFExists(BFExists::try_from(v)?))
+                }
+                b"CF.ADD" | b"cf.add" => Ok(Command::CFAdd(CFAdd::try_from(v)?)),
+                b"CF.DEL" | b"cf.del" => Ok(Command::CFDel(CFDel::try_from(v)?)),
+                b"CF.EXISTS" | b"cf.exists" => Ok(Command::CFExists(CFExists::try_from(v)?)),
+                b"CMS.INITBYDIM" | b"cms.initbydim" => {
+                    Ok(Command::CMSInitByDim(CMSInitByDim::try_from(v)?))
+                }
+                b"CMS.INCRBY" | b"cms.incrby" => Ok(Command::CMSIncrBy(CMSIncrBy::try_from(v)?)),
+                b"CMS.QUERY" | b"cms.query" => Ok(Command::CMSQuery(CMSQuery::try_from(v)?)),
+                b"CMS.MERGE" | b"cms.merge" => Ok(Command::CMSMerge(CMSMerge::try_from(v)?)),
+                b"TOPK.RESERVE" | b"topk.reserve" => {
+                    Ok(Command::TopKReserve(TopKReserve::try_from(v)?))
+                }
+                b"TOPK.ADD" | b"topk.add" => Ok(Command::TopKAdd(TopKAdd::try_from(v)?)),
+                b"TOPK.QUERY" | b"topk.query" => Ok(Command::TopKQuery(TopKQuery::try_from(v)?)),
+                b"TOPK.LIST" | b"topk.list" => Ok(Command::TopKList(TopKList::try_from(v)?)),
+                b"JSON.SET" | b"json.set" => Ok(Command::JsonSet(JsonSet::try_from(v)?)),
+                b"JSON.GET" | b"json.get" => Ok(Command::JsonGet(JsonGet::try_from(v)?)),
+                b"JSON.DEL" | b"json.del" | b"JSON.FORGET" | b"json.forget" => {
+                    Ok(Command::JsonDel(JsonDel::try_from(v)?))
+                }
+                b"JSON.NUMINCRBY" | b"json.numincrby" => {
+                    Ok(Command::JsonNumIncrBy(JsonNumIncrBy::try_from(v)?))
+                }
+                b"JSON.ARRAPPEND" | b"json.arrappend" => {
+                    Ok(Command::JsonArrAppend(JsonArrAppend::try_from(v)?))
+                }
+                b"JSON.ARRINSERT" | b"json.arrinsert" => {
+                    Ok(Command::JsonArrInsert(JsonArrInsert::try_from(v)?))
+                }
+                b"JSON.ARRPOP" | b"json.arrpop" => {
+                    Ok(Command::JsonArrPop(JsonArrPop::try_from(v)?))
+                }
+                b"JSON.TYPE" | b"json.type" => Ok(Command::JsonType(JsonType::try_from(v)?)),
+                b"JSON.OBJLEN" | b"json.objlen" | b"JSON.ARRLEN" | b"json.arrlen" => {
+                    Ok(Command::JsonLen(JsonLen::try_from(v)?))
+                }
+                b"JSON.OBJKEYS" | b"json.objkeys" => {
+                    Ok(Command::JsonObjKeys(JsonObjKeys::try_from(v)?))
+                }
+                b"TS.CREATE" | b"ts.create" => Ok(Command::TSCreate(TSCreate::try_from(v)?)),
+                b"TS.ADD" | b"ts.add" => Ok(Command::TSAdd(TSAdd::try_from(v)?)),
+                b"TS.MADD" | b"ts.madd" => Ok(Command::TSMAdd(TSMAdd::try_from(v)?)),
+                b"TS.INCRBY" | b"ts.incrby" | b"TS.DECRBY" | b"ts.decrby" => {
+                    Ok(Command::TSIncrBy(TSIncrBy::try_from(v)?))
+                }
+                b"TS.GET" | b"ts.get" => Ok(Command::TSGet(TSGet::try_from(v)?)),
+                b"TS.RANGE" | b"ts.range" | b"TS.REVRANGE" | b"ts.revrange" => {
+                    Ok(Command::TSRange(TSRange::try_from(v)?))
+                }
+                b"TS.MRANGE" | b"ts.mrange" | b"TS.MREVRANGE" | b"ts.mrevrange" => {
+                    Ok(Command::TSMRange(TSMRange::try_from(v)?))
+                }
+                b"TS.CREATERULE" | b"ts.createrule" => {
+                    Ok(Command::TSCreateRule(TSCreateRule::try_from(v)?))
+                }
+                b"TS.DELETERULE" | b"ts.deleterule" => {
+                    Ok(Command::TSDeleteRule(TSDeleteRule::try_from(v)?))
+                }
+                b"FT.CREATE" | b"ft.create" => Ok(Command::FTCreate(FTCreate::try_from(v)?)),
+                b"FT.SEARCH" | b"ft.search" => Ok(Command::FTSearch(FTSearch::try_from(v)?)),
+                b"FT.INFO" | b"ft.info" => Ok(Command::FTInfo(FTInfo::try_from(v)?)),
+                b"FT.DROPINDEX" | b"ft.dropindex" => {
+                    Ok(Command::FTDropIndex(FTDropIndex::try_from(v)?))
+                }
+                b"CL.THROTTLE" | b"cl.throttle" => {
+                    Ok(Command::CLThrottle(CLThrottle::try_from(v)?))
+                }
+                b"EVAL" | b"eval" | b"EVAL_RO" | b"eval_ro" => {
+                    Ok(Command::Eval(Eval::try_from(v)?))
+                }
+                b"EVALSHA" | b"evalsha" | b"EVALSHA_RO" | b"evalsha_ro" => {
+                    Ok(Command::EvalSha(EvalSha::try_from(v)?))
+                }
+                b"SCRIPT" | b"script" => Ok(Command::Script(Script::try_from(v)?)),
+                b"FUNCTION" | b"function" => Ok(Command::Function(Function::try_from(v)?)),
+                b"FCALL" | b"fcall" | b"FCALL_RO" | b"fcall_ro" => {
+                    Ok(Command::FCall(FCall::try_from(v)?))
+                }
+                b"WASM.LOAD" | b"wasm.load" => Ok(Command::WasmLoad(WasmLoad::try_from(v)?)),
+                b"WASM.CALL" | b"wasm.call" => Ok(Command::WasmCall(WasmCall::try_from(v)?)),
+                b"PUBLISH" | b"publish" => Ok(Command::Publish(Publish::try_from(v)?)),
+                b"PUBSUB" | b"pubsub" => Ok(Command::PubSub(PubSubCommand::try_from(v)?)),
                 _ => Ok(Unrecognized.into()),
             },
             _ => Err(CommandError::InvalidCommand(
@@ -0,0 +1,208 @@
+use crate::{Backend, BulkString, RespArray, RespFrame, SimpleError};
+
+use super::{
+    extract_args, parse_string, validate_min_args, CommandError, CommandExecutor, RESP_OK,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConfigKind {
+    Get(Vec<String>),
+    Set(Vec<(String, String)>),
+}
+
+/// CONFIG GET pattern [pattern ...] | CONFIG SET parameter value [parameter value ...]
+#[derive(Debug)]
+pub struct Config {
+    pub(crate) kind: ConfigKind,
+}
+
+impl CommandExecutor for Config {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        match self.kind {
+            ConfigKind::Get(patterns) => {
+                // 多个模式匹配到同一个参数时只返回一次
+                let mut params = Vec::new();
+                for pattern in patterns {
+                    for param in backend.config_get(&pattern) {
+                        if !params.contains(&param) {
+                            params.push(param);
+                        }
+                    }
+                }
+                let frames = params
+                    .into_iter()
+                    .flat_map(|(name, value)| {
+                        [
+                            BulkString::from(name).into(),
+                            BulkString::from(value).into(),
+                        ]
+                    })
+                    .collect::<Vec<RespFrame>>();
+                RespArray::new(frames).into()
+            }
+            ConfigKind::Set(params) => {
+                for (name, value) in params {
+                    if let Err(e) = backend.config_set(&name, &value) {
+                        return SimpleError::new(e.to_string()).into();
+                    }
+                }
+                RESP_OK.clone()
+            }
+        }
+    }
+}
+
+impl TryFrom<RespArray> for Config {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+
+        let kind = match sub.as_str() {
+            "get" => {
+                validate_min_args(&value, &["config", "get"], 1)?;
+                let patterns = extract_args(value, 2)?
+                    .into_iter()
+                    .map(parse_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                ConfigKind::Get(patterns)
+            }
+            "set" => {
+                validate_min_args(&value, &["config", "set"], 2)?;
+                let args = extract_args(value, 2)?
+                    .into_iter()
+                    .map(parse_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if args.len() % 2 != 0 {
+                    return Err(CommandError::InvalidArgument(
+                        "wrong number of arguments for 'config|set' command".to_string(),
+                    ));
+                }
+                let params = args
+                    .chunks(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect();
+                ConfigKind::Set(params)
+            }
+            _ => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "unknown subcommand '{}'",
+                    sub
+                )))
+            }
+        };
+        Ok(Config { kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_config_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$6\r\nCONFIG\r\n$3\r\nGET\r\n$3\r\nlua\r\n$1\r\n*\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Config = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            ConfigKind::Get(vec!["lua".to_string(), "*".to_string()])
+        );
+
+        buf.extend_from_slice(
+            b"*4\r\n$6\r\nconfig\r\n$3\r\nset\r\n$14\r\nlua-time-limit\r\n$3\r\n100\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Config = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            ConfigKind::Set(vec![("lua-time-limit".to_string(), "100".to_string())])
+        );
+
+        buf.extend_from_slice(b"*3\r\n$6\r\nconfig\r\n$3\r\nset\r\n$1\r\na\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Config::try_from(frame).is_err());
+
+        buf.extend_from_slice(b"*2\r\n$6\r\nconfig\r\n$7\r\nrewrite\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Config::try_from(frame).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_command() -> Result<()> {
+        let backend = Backend::new();
+        let set = |name: &str, value: &str| {
+            Config {
+                kind: ConfigKind::Set(vec![(name.to_string(), value.to_string())]),
+            }
+            .execute(&backend)
+        };
+        let get = |pattern: &str| {
+            Config {
+                kind: ConfigKind::Get(vec![pattern.to_string()]),
+            }
+            .execute(&backend)
+        };
+
+        assert_eq!(set("LUA-TIME-LIMIT", "100"), RESP_OK.clone());
+        let expected = RespArray::new([
+            BulkString::from("lua-time-limit").into(),
+            BulkString::from("100").into(),
+        ]);
+        assert_eq!(get("lua-*"), expected.into());
+        assert_eq!(get("nothing"), RespArray::new([]).into());
+
+        // 只修改 pubsub 的硬限制
+        let limit = "normal 0 0 0 pubsub 32mb 8mb 60";
+        assert_eq!(set("client-output-buffer-limit", limit), RESP_OK.clone());
+        assert_eq!(backend.pubsub_output_limit(), 32 * 1024 * 1024);
+        let expected = RespArray::new([
+            BulkString::from("client-output-buffer-limit").into(),
+            BulkString::from("pubsub 33554432 0 0").into(),
+        ]);
+        assert_eq!(get("client-*"), expected.into());
+        for limit in ["pubsub 1 2", "master 1 2 3", "pubsub 1x 0 0"] {
+            let ret = set("client-output-buffer-limit", limit);
+            assert!(matches!(ret, RespFrame::Error(_)), "{limit}");
+        }
+
+        assert_eq!(set("notify-keyspace-events", "KEA"), RESP_OK.clone());
+        let expected = RespArray::new([
+            BulkString::from("notify-keyspace-events").into(),
+            BulkString::from("AKE").into(),
+        ]);
+        assert_eq!(get("notify-*"), expected.into());
+        let ret = set("notify-keyspace-events", "KEw");
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.contains("notify-keyspace-events")));
+
+        assert_eq!(set("wasm-fuel-limit", "5000"), RESP_OK.clone());
+        assert_eq!(set("wasm-memory-limit", "1mb"), RESP_OK.clone());
+        let expected = RespArray::new([
+            BulkString::from("wasm-fuel-limit").into(),
+            BulkString::from("5000").into(),
+            BulkString::from("wasm-memory-limit").into(),
+            BulkString::from("1048576").into(),
+        ]);
+        assert_eq!(get("wasm-*"), expected.into());
+        let ret = set("wasm-memory-limit", "1x");
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.contains("wasm-memory-limit")));
+
+        let ret = set("lua-time-limit", "abc");
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.contains("lua-time-limit")));
+        let ret = set("maxmemory", "1");
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.contains("Unknown option")));
+
+        Ok(())
+    }
+}
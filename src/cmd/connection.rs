@@ -0,0 +1,232 @@
+use crate::{RespArray, RespFrame};
+
+use super::{
+    extract_args, next_arg, parse_bytes, parse_number, parse_string, validate_command,
+    validate_min_args, CommandError,
+};
+
+const CONNECTION_COMMANDS: [&str; 13] = [
+    "multi",
+    "exec",
+    "discard",
+    "watch",
+    "unwatch",
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ping",
+    "quit",
+    "reset",
+    "hello",
+];
+
+// RESP2 连接订阅了 channel 之后只能执行这些命令
+const SUBSCRIBED_COMMANDS: [&str; 7] = [
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ping",
+    "quit",
+    "reset",
+];
+
+/// 需要访问连接上下文的命令，由 network 层执行，不经过 Command 分发
+#[derive(Debug, PartialEq)]
+pub enum ConnectionCommand {
+    Multi,
+    Exec,
+    Discard,
+    Watch(Vec<String>),
+    Unwatch,
+    Subscribe(Vec<String>),
+    // 为空时退订所有的 channel
+    Unsubscribe(Vec<String>),
+    // glob 模式
+    Psubscribe(Vec<String>),
+    // 为空时退订所有的模式
+    Punsubscribe(Vec<String>),
+    Ping(Option<Vec<u8>>),
+    Quit,
+    Reset,
+    // 协商 RESP 协议版本
+    Hello(Option<i64>),
+}
+
+impl ConnectionCommand {
+    /// 是否是连接命令，不是时交给 Command 解析
+    pub fn matches(frame: &RespFrame) -> bool {
+        match frame {
+            RespFrame::Array(array) => {
+                command_name(array).is_some_and(|name| CONNECTION_COMMANDS.contains(&name.as_str()))
+            }
+            _ => false,
+        }
+    }
+
+    /// MULTI 之后仍然立即执行的命令，其它的连接命令由事务排队或者拒绝
+    pub fn runs_in_transaction(&self) -> bool {
+        matches!(
+            self,
+            Self::Multi | Self::Exec | Self::Discard | Self::Watch(_) | Self::Quit | Self::Reset
+        )
+    }
+
+    /// 处于订阅状态的 RESP2 连接不能执行的命令返回 Some(命令名)
+    pub fn rejected_when_subscribed(frame: &RespFrame) -> Option<String> {
+        let name = match frame {
+            RespFrame::Array(array) => command_name(array).unwrap_or_default(),
+            _ => String::new(),
+        };
+        (!SUBSCRIBED_COMMANDS.contains(&name.as_str())).then_some(name)
+    }
+}
+
+// 订阅命令的 channel 或模式列表
+fn parse_names(value: RespArray) -> Result<Vec<String>, CommandError> {
+    extract_args(value, 1)?
+        .into_iter()
+        .map(parse_string)
+        .collect()
+}
+
+fn command_name(array: &RespArray) -> Option<String> {
+    match array.first() {
+        Some(RespFrame::BulkString(name)) => {
+            Some(String::from_utf8_lossy(name).to_ascii_lowercase())
+        }
+        _ => None,
+    }
+}
+
+impl TryFrom<RespFrame> for ConnectionCommand {
+    type Error = CommandError;
+
+    fn try_from(v: RespFrame) -> Result<Self, Self::Error> {
+        match v {
+            RespFrame::Array(value) => value.try_into(),
+            _ => Err(CommandError::InvalidCommand(
+                "Command must be an Array".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ConnectionCommand {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let name = command_name(&value).unwrap_or_default();
+        match name.as_str() {
+            "multi" => validate_command(&value, &["multi"], 0).map(|_| ConnectionCommand::Multi),
+            "exec" => validate_command(&value, &["exec"], 0).map(|_| ConnectionCommand::Exec),
+            "discard" => {
+                validate_command(&value, &["discard"], 0).map(|_| ConnectionCommand::Discard)
+            }
+            "unwatch" => {
+                validate_command(&value, &["unwatch"], 0).map(|_| ConnectionCommand::Unwatch)
+            }
+            "watch" => {
+                validate_min_args(&value, &["watch"], 1)?;
+                let keys = extract_args(value, 1)?
+                    .into_iter()
+                    .map(parse_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ConnectionCommand::Watch(keys))
+            }
+            "subscribe" => {
+                validate_min_args(&value, &["subscribe"], 1)?;
+                Ok(ConnectionCommand::Subscribe(parse_names(value)?))
+            }
+            "psubscribe" => {
+                validate_min_args(&value, &["psubscribe"], 1)?;
+                Ok(ConnectionCommand::Psubscribe(parse_names(value)?))
+            }
+            "unsubscribe" => Ok(ConnectionCommand::Unsubscribe(parse_names(value)?)),
+            "punsubscribe" => Ok(ConnectionCommand::Punsubscribe(parse_names(value)?)),
+            "ping" => {
+                if value.len() > 2 {
+                    return Err(CommandError::InvalidArgument(
+                        "ping command must have at most 1 argument".to_string(),
+                    ));
+                }
+                let message = extract_args(value, 1)?.into_iter().next();
+                Ok(ConnectionCommand::Ping(
+                    message.map(parse_bytes).transpose()?,
+                ))
+            }
+            "quit" => validate_command(&value, &["quit"], 0).map(|_| ConnectionCommand::Quit),
+            "reset" => validate_command(&value, &["reset"], 0).map(|_| ConnectionCommand::Reset),
+            "hello" => {
+                // 不支持 AUTH 和 SETNAME
+                if value.len() > 2 {
+                    return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                }
+                let mut args = extract_args(value, 1)?.into_iter().peekable();
+                let protover = match args.peek() {
+                    Some(_) => Some(parse_number(next_arg(&mut args)?)?),
+                    None => None,
+                };
+                Ok(ConnectionCommand::Hello(protover))
+            }
+            _ => Err(CommandError::InvalidCommand(format!(
+                "Invalid command: {}",
+                name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_connection_command_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$5\r\nWATCH\r\n$8\r\nstock:42\r\n$8\r\nstock:43\r\n");
+
+        let frame = RespFrame::decode(&mut buf)?;
+        assert!(ConnectionCommand::matches(&frame));
+        let result: ConnectionCommand = frame.try_into()?;
+        assert_eq!(
+            result,
+            ConnectionCommand::Watch(vec!["stock:42".to_string(), "stock:43".to_string()])
+        );
+
+        buf.extend_from_slice(b"*2\r\n$4\r\nexec\r\n$1\r\nx\r\n");
+        let frame = RespFrame::decode(&mut buf)?;
+        assert!(ConnectionCommand::try_from(frame).is_err());
+
+        buf.extend_from_slice(b"*2\r\n$3\r\nget\r\n$1\r\nx\r\n");
+        let frame = RespFrame::decode(&mut buf)?;
+        assert!(!ConnectionCommand::matches(&frame));
+        assert_eq!(
+            ConnectionCommand::rejected_when_subscribed(&frame),
+            Some("get".to_string())
+        );
+
+        buf.extend_from_slice(b"*3\r\n$9\r\nSUBSCRIBE\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let frame = RespFrame::decode(&mut buf)?;
+        assert_eq!(ConnectionCommand::rejected_when_subscribed(&frame), None);
+        assert_eq!(
+            ConnectionCommand::try_from(frame)?,
+            ConnectionCommand::Subscribe(vec!["a".to_string(), "b".to_string()])
+        );
+
+        buf.extend_from_slice(b"*2\r\n$5\r\nhello\r\n$1\r\n3\r\n");
+        let frame = RespFrame::decode(&mut buf)?;
+        assert_eq!(
+            ConnectionCommand::try_from(frame)?,
+            ConnectionCommand::Hello(Some(3))
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct CFAdd {
+    pub(crate) key: String,
+    pub(crate) item: Vec<u8>,
+}
+
+impl CommandExecutor for CFAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cf_add(self.key, &self.item) {
+            Ok(()) => 1.into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CFAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cf.add"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let item = parse_bytes(next_arg(&mut args)?)?;
+
+        Ok(CFAdd { key, item })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cf_add_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nCF.ADD\r\n$2\r\ncf\r\n$1\r\na\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CFAdd = frame.try_into()?;
+        assert_eq!(result.key, "cf");
+        assert_eq!(result.item, b"a");
+
+        Ok(())
+    }
+}
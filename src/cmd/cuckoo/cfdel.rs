@@ -0,0 +1,60 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+/// 删除一次添加，同一个元素添加多次需要删除多次
+#[derive(Debug)]
+pub struct CFDel {
+    pub(crate) key: String,
+    pub(crate) item: Vec<u8>,
+}
+
+impl CommandExecutor for CFDel {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cf_del(&self.key, &self.item) {
+            Ok(deleted) => (deleted as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CFDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cf.del"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let item = parse_bytes(next_arg(&mut args)?)?;
+
+        Ok(CFDel { key, item })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cf_del_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nCF.DEL\r\n$2\r\ncf\r\n$1\r\na\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CFDel = frame.try_into()?;
+        assert_eq!(result.key, "cf");
+        assert_eq!(result.item, b"a");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame,
+};
+
+#[derive(Debug)]
+pub struct CFExists {
+    pub(crate) key: String,
+    pub(crate) item: Vec<u8>,
+}
+
+impl CommandExecutor for CFExists {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.cf_exists(&self.key, &self.item) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for CFExists {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["cf.exists"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let item = parse_bytes(next_arg(&mut args)?)?;
+
+        Ok(CFExists { key, item })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cf_exists_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$9\r\nCF.EXISTS\r\n$2\r\ncf\r\n$1\r\na\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CFExists = frame.try_into()?;
+        assert_eq!(result.key, "cf");
+        assert_eq!(result.item, b"a");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+mod cfadd;
+mod cfdel;
+mod cfexists;
+
+pub use cfadd::*;
+pub use cfdel::*;
+pub use cfexists::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::CommandExecutor, Backend, CuckooFilter, CuckooOptions, RespFrame, SimpleError,
+    };
+
+    use super::*;
+
+    fn cf_add(backend: &Backend, key: &str, item: &str) -> RespFrame {
+        CFAdd {
+            key: key.to_string(),
+            item: item.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    fn cf_del(backend: &Backend, key: &str, item: &str) -> RespFrame {
+        CFDel {
+            key: key.to_string(),
+            item: item.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    fn cf_exists(backend: &Backend, key: &str, item: &str) -> RespFrame {
+        CFExists {
+            key: key.to_string(),
+            item: item.as_bytes().to_vec(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_cf_commands() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            cf_del(&backend, "cf", "a"),
+            SimpleError::new("ERR not found").into()
+        );
+        assert_eq!(cf_exists(&backend, "cf", "a"), 0.into());
+
+        // 允许重复添加，需要删除同样的次数
+        assert_eq!(cf_add(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_add(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_add(&backend, "cf", "b"), 1.into());
+        assert_eq!(cf_exists(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_del(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_exists(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_del(&backend, "cf", "a"), 1.into());
+        assert_eq!(cf_exists(&backend, "cf", "a"), 0.into());
+        assert_eq!(cf_del(&backend, "cf", "a"), 0.into());
+        assert_eq!(cf_exists(&backend, "cf", "b"), 1.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cuckoo_filter_expansion() -> Result<()> {
+        let mut filter = CuckooFilter::new(CuckooOptions {
+            capacity: 64,
+            ..Default::default()
+        })?;
+        let items = (0..1000).map(|i| format!("item-{}", i)).collect::<Vec<_>>();
+        for item in &items {
+            filter.add(item.as_bytes())?;
+        }
+        assert!(items.iter().all(|item| filter.exists(item.as_bytes())));
+        for item in &items {
+            assert!(filter.remove(item.as_bytes()));
+        }
+
+        // 不扩容的过滤器满了之后返回错误
+        let mut filter = CuckooFilter::new(CuckooOptions {
+            capacity: 8,
+            expansion: 0,
+            ..Default::default()
+        })?;
+        let ret = (0..100).try_for_each(|i| filter.add(format!("item-{}", i).as_bytes()));
+        assert_eq!(ret.unwrap_err().to_string(), "ERR filter is full");
+
+        // 参数超过上限时返回错误，不会分配
+        let ret = CuckooFilter::new(CuckooOptions {
+            capacity: u64::MAX,
+            ..Default::default()
+        });
+        assert_eq!(
+            ret.unwrap_err().to_string(),
+            "ERR requested size is too large"
+        );
+        let ret = CuckooFilter::new(CuckooOptions {
+            bucket_size: 1000,
+            ..Default::default()
+        });
+        assert!(ret.is_err());
+
+        Ok(())
+    }
+}
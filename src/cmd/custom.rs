@@ -0,0 +1,220 @@
+use std::{fmt, sync::Arc};
+
+use crate::{Backend, CommandHandler, RespArray, RespFrame};
+
+use super::{parse_bytes, CommandError, CommandExecutor};
+
+/// 通过 Backend::register_command 注册的命令
+pub struct CustomCommand {
+    handler: Arc<dyn CommandHandler>,
+    args: Vec<Vec<u8>>,
+}
+
+impl CustomCommand {
+    /// 按 handler 声明的 arity 校验参数个数
+    pub fn new(handler: Arc<dyn CommandHandler>, value: RespArray) -> Result<Self, CommandError> {
+        let arity = handler.arity();
+        let len = value.len() as i64;
+        if (arity >= 0 && len != arity) || (arity < 0 && len < -arity) {
+            return Err(CommandError::InvalidArgument(format!(
+                "wrong number of arguments for '{}' command",
+                handler.name()
+            )));
+        }
+
+        let args = value
+            .0
+            .into_iter()
+            .skip(1)
+            .map(parse_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CustomCommand { handler, args })
+    }
+
+    pub fn handler(&self) -> &dyn CommandHandler {
+        self.handler.as_ref()
+    }
+}
+
+impl fmt::Debug for CustomCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomCommand")
+            .field("name", &self.handler.name())
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+impl CommandExecutor for CustomCommand {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.handler.execute(backend, self.args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{
+        cmd::{Command, CommandExecutor},
+        BackendError, CommandFlags, CustomType, RespDecode, SimpleError,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Counter(i64);
+
+    impl CustomType for Counter {
+        const NAME: &'static str = "counter";
+    }
+
+    // COUNTER.INCR key [n]
+    struct CounterIncr;
+
+    impl CommandHandler for CounterIncr {
+        fn name(&self) -> &str {
+            "COUNTER.INCR"
+        }
+
+        fn arity(&self) -> i64 {
+            -2
+        }
+
+        fn execute(&self, backend: &Backend, args: Vec<Vec<u8>>) -> RespFrame {
+            let key = String::from_utf8_lossy(&args[0]);
+            let n = args
+                .get(1)
+                .and_then(|n| String::from_utf8_lossy(n).parse().ok())
+                .unwrap_or(1);
+            match backend.custom_update(&key, Counter::default, |c: &mut Counter| {
+                c.0 += n;
+                c.0
+            }) {
+                Ok(value) => RespFrame::Integer(value),
+                Err(e) => SimpleError::new(e.to_string()).into(),
+            }
+        }
+    }
+
+    // COUNTER.GET key
+    struct CounterGet;
+
+    impl CommandHandler for CounterGet {
+        fn name(&self) -> &str {
+            "counter.get"
+        }
+
+        fn arity(&self) -> i64 {
+            2
+        }
+
+        fn flags(&self) -> CommandFlags {
+            CommandFlags {
+                readonly: true,
+                ..Default::default()
+            }
+        }
+
+        fn execute(&self, backend: &Backend, args: Vec<Vec<u8>>) -> RespFrame {
+            let key = String::from_utf8_lossy(&args[0]);
+            match backend.custom_get(&key, |c: &Counter| c.0) {
+                Ok(value) => RespFrame::Integer(value.unwrap_or(0)),
+                Err(e) => SimpleError::new(e.to_string()).into(),
+            }
+        }
+    }
+
+    fn parse(backend: &Backend, data: &[u8]) -> Result<Command> {
+        let mut buf = BytesMut::from(data);
+        let frame = RespFrame::decode(&mut buf)?;
+        Ok(Command::parse(frame, backend)?)
+    }
+
+    #[test]
+    fn test_register_command() -> Result<()> {
+        let backend = Backend::new();
+        assert!(backend.register_command(CounterIncr).is_ok());
+        assert!(matches!(
+            backend.register_command(CounterIncr),
+            Err(BackendError::CommandExists(_))
+        ));
+
+        // 不能覆盖内置命令
+        struct Get;
+        impl CommandHandler for Get {
+            fn name(&self) -> &str {
+                "get"
+            }
+            fn arity(&self) -> i64 {
+                2
+            }
+            fn execute(&self, _: &Backend, _: Vec<Vec<u8>>) -> RespFrame {
+                RespFrame::Integer(0)
+            }
+        }
+        assert!(matches!(
+            backend.register_command(Get),
+            Err(BackendError::CommandExists(_))
+        ));
+
+        let cmd = parse(&backend, b"*1\r\n$12\r\ncounter.incr\r\n");
+        assert!(cmd.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_command_and_type() -> Result<()> {
+        let backend = Backend::new();
+        backend.register_command(CounterIncr)?;
+        backend.register_command(CounterGet)?;
+
+        // 类型注册之前不能使用
+        let cmd = parse(&backend, b"*2\r\n$12\r\nCOUNTER.INCR\r\n$1\r\nc\r\n")?;
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR unknown type 'counter'".to_string()).into()
+        );
+        backend.register_type::<Counter>()?;
+
+        let cmd = parse(
+            &backend,
+            b"*3\r\n$12\r\nCOUNTER.INCR\r\n$1\r\nc\r\n$1\r\n5\r\n",
+        )?;
+        assert!(!cmd.is_readonly());
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(5));
+        let cmd = parse(&backend, b"*2\r\n$11\r\ncounter.get\r\n$1\r\nc\r\n")?;
+        assert!(cmd.is_readonly());
+        assert_eq!(cmd.execute(&backend), RespFrame::Integer(5));
+        assert_eq!(backend.custom_type("c"), Some("counter"));
+
+        // 类型不同时返回 WRONGTYPE
+        #[derive(Debug)]
+        struct Other;
+        impl CustomType for Other {
+            const NAME: &'static str = "other";
+        }
+        backend.register_type::<Other>()?;
+        assert!(matches!(
+            backend.custom_get("c", |_: &Other| ()),
+            Err(BackendError::WrongType)
+        ));
+
+        // DEL 可以删除自定义类型的 key
+        let del = parse(&backend, b"*2\r\n$3\r\ndel\r\n$1\r\nc\r\n")?;
+        assert_eq!(del.execute(&backend), RespFrame::Integer(1));
+        assert_eq!(backend.custom_type("c"), None);
+
+        // 内置类型的 key 不能被自定义类型覆盖
+        backend.rpush("l".to_string(), vec!["a".into()])?;
+        let cmd = parse(&backend, b"*2\r\n$12\r\nCOUNTER.INCR\r\n$1\r\nl\r\n")?;
+        assert!(
+            matches!(cmd.execute(&backend), RespFrame::Error(e) if e.0.starts_with("WRONGTYPE"))
+        );
+        assert_eq!(backend.custom_type("l"), None);
+
+        Ok(())
+    }
+}
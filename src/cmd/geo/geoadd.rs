@@ -0,0 +1,96 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError, ZAddFlags,
+};
+
+use super::parse_lonlat;
+
+#[derive(Debug)]
+pub struct GeoAdd {
+    pub(crate) key: String,
+    pub(crate) flags: ZAddFlags,
+    // (longitude, latitude, member)
+    pub(crate) items: Vec<(f64, f64, String)>,
+}
+
+impl CommandExecutor for GeoAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.geoadd(self.key, self.items, self.flags) {
+            Ok(n) => (n as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for GeoAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geoadd"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = parse_string(next_arg(&mut args)?)?;
+
+        let mut flags = ZAddFlags::default();
+        while let Some(RespFrame::BulkString(arg)) = args.peek() {
+            match arg.to_ascii_lowercase().as_slice() {
+                b"nx" => flags.nx = true,
+                b"xx" => flags.xx = true,
+                b"ch" => flags.ch = true,
+                _ => break,
+            }
+            args.next();
+        }
+        if flags.nx && flags.xx {
+            return Err(CommandError::InvalidArgument(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+
+        let mut items = Vec::new();
+        while args.peek().is_some() {
+            let (lon, lat) = parse_lonlat(&mut args)?;
+            items.push((lon, lat, parse_string(next_arg(&mut args)?)?));
+        }
+        if items.is_empty() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(GeoAdd { key, flags, items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_geoadd_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$6\r\nGEOADD\r\n$6\r\nSicily\r\n$2\r\nCH\r\n$9\r\n13.361389\r\n$9\r\n38.115556\r\n$7\r\nPalermo\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoAdd = frame.try_into()?;
+        assert_eq!(result.key, "Sicily");
+        assert!(result.flags.ch);
+        assert_eq!(
+            result.items,
+            vec![(13.361389, 38.115556, "Palermo".to_string())]
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$6\r\nGEOADD\r\n$6\r\nSicily\r\n$3\r\n181\r\n$9\r\n38.115556\r\n$7\r\nPalermo\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(GeoAdd::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
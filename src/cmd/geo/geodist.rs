@@ -0,0 +1,74 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+use super::{dist_to_frame, parse_unit};
+
+#[derive(Debug)]
+pub struct GeoDist {
+    pub(crate) key: String,
+    pub(crate) member1: String,
+    pub(crate) member2: String,
+    // 单位换算成米的倍数
+    pub(crate) unit: f64,
+}
+
+impl CommandExecutor for GeoDist {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.geodist(&self.key, &self.member1, &self.member2) {
+            Some(dist) => dist_to_frame(dist, self.unit),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for GeoDist {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geodist"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let member1 = parse_string(next_arg(&mut args)?)?;
+        let member2 = parse_string(next_arg(&mut args)?)?;
+        let unit = args.next().map(parse_unit).transpose()?.unwrap_or(1.0);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(GeoDist {
+            key,
+            member1,
+            member2,
+            unit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_geodist_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$7\r\nGEODIST\r\n$6\r\nSicily\r\n$7\r\nPalermo\r\n$7\r\nCatania\r\n$2\r\nmi\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoDist = frame.try_into()?;
+        assert_eq!(result.member1, "Palermo");
+        assert_eq!(result.member2, "Catania");
+        assert_eq!(result.unit, 1609.34);
+
+        Ok(())
+    }
+}
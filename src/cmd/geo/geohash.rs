@@ -0,0 +1,61 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, RespArray, RespFrame, RespNull,
+};
+
+#[derive(Debug)]
+pub struct GeoHash {
+    pub(crate) key: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecutor for GeoHash {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let hashes = self
+            .members
+            .iter()
+            .map(|member| match backend.geohash(&self.key, member) {
+                Some(hash) => BulkString::from(hash).into(),
+                None => RespFrame::Null(RespNull),
+            })
+            .collect::<Vec<_>>();
+        RespArray::new(hashes).into()
+    }
+}
+
+impl TryFrom<RespArray> for GeoHash {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geohash"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let members = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(GeoHash { key, members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_geohash_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$7\r\nGEOHASH\r\n$6\r\nSicily\r\n$7\r\nPalermo\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoHash = frame.try_into()?;
+        assert_eq!(result.key, "Sicily");
+        assert_eq!(result.members, vec!["Palermo"]);
+
+        Ok(())
+    }
+}
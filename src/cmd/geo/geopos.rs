@@ -0,0 +1,63 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+#[derive(Debug)]
+pub struct GeoPos {
+    pub(crate) key: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecutor for GeoPos {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let positions = self
+            .members
+            .iter()
+            .map(|member| match backend.geopos(&self.key, member) {
+                Some((lon, lat)) => RespArray::new([lon.into(), lat.into()]).into(),
+                None => RespFrame::Null(RespNull),
+            })
+            .collect::<Vec<_>>();
+        RespArray::new(positions).into()
+    }
+}
+
+impl TryFrom<RespArray> for GeoPos {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geopos"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let members = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(GeoPos { key, members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_geopos_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*4\r\n$6\r\ngeopos\r\n$6\r\nSicily\r\n$7\r\nPalermo\r\n$1\r\nx\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoPos = frame.try_into()?;
+        assert_eq!(result.key, "Sicily");
+        assert_eq!(result.members, vec!["Palermo", "x"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, GeoFrom, GeoSearchOptions, GeoShape, RespArray, RespFrame, SimpleError,
+};
+
+use super::{dist_to_frame, parse_lonlat, parse_unit};
+
+#[derive(Debug)]
+pub struct GeoSearch {
+    pub(crate) key: String,
+    pub(crate) opts: GeoSearchOptions,
+    // 返回距离时使用的单位，换算成米的倍数
+    pub(crate) unit: f64,
+    pub(crate) with_coord: bool,
+    pub(crate) with_dist: bool,
+    pub(crate) with_hash: bool,
+}
+
+impl CommandExecutor for GeoSearch {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let results = match backend.geosearch(&self.key, &self.opts) {
+            Ok(results) => results,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+
+        let with_any = self.with_coord || self.with_dist || self.with_hash;
+        let results = results
+            .into_iter()
+            .map(|r| {
+                let member = BulkString::from(r.member).into();
+                if !with_any {
+                    return member;
+                }
+                // 顺序固定为 member, dist, hash, coord
+                let mut item = vec![member];
+                if self.with_dist {
+                    item.push(dist_to_frame(r.dist, self.unit));
+                }
+                if self.with_hash {
+                    item.push((r.hash as i64).into());
+                }
+                if self.with_coord {
+                    item.push(RespArray::new([r.lon.into(), r.lat.into()]).into());
+                }
+                RespArray::new(item).into()
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(results).into()
+    }
+}
+
+impl GeoSearch {
+    /// 解析 GEOSEARCH 和 GEOSEARCHSTORE 共同的参数，store 为 true 时允许 STOREDIST，不允许 WITH*
+    pub(crate) fn parse(
+        key: String,
+        args: impl Iterator<Item = RespFrame>,
+        store: bool,
+    ) -> Result<(Self, bool), CommandError> {
+        let name = if store { "GEOSEARCHSTORE" } else { "GEOSEARCH" };
+        let syntax_error = || CommandError::InvalidArgument("syntax error".to_string());
+
+        let (mut from, mut shape, mut unit) = (None, None, 1.0);
+        let (mut desc, mut count, mut any) = (None, 0, false);
+        let (mut with_coord, mut with_dist, mut with_hash, mut store_dist) =
+            (false, false, false, false);
+
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "frommember" if from.is_none() => {
+                    from = Some(GeoFrom::Member(parse_string(next_arg(&mut args)?)?))
+                }
+                "fromlonlat" if from.is_none() => {
+                    let (lon, lat) = parse_lonlat(&mut args)?;
+                    from = Some(GeoFrom::LonLat(lon, lat));
+                }
+                "frommember" | "fromlonlat" => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+                        name
+                    )))
+                }
+                "byradius" if shape.is_none() => {
+                    let radius: f64 = parse_number(next_arg(&mut args)?)?;
+                    unit = parse_unit(next_arg(&mut args)?)?;
+                    if radius < 0.0 {
+                        return Err(CommandError::InvalidArgument(
+                            "radius cannot be negative".to_string(),
+                        ));
+                    }
+                    shape = Some(GeoShape::Radius(radius * unit));
+                }
+                "bybox" if shape.is_none() => {
+                    let width: f64 = parse_number(next_arg(&mut args)?)?;
+                    let height: f64 = parse_number(next_arg(&mut args)?)?;
+                    unit = parse_unit(next_arg(&mut args)?)?;
+                    if width < 0.0 || height < 0.0 {
+                        return Err(CommandError::InvalidArgument(
+                            "height or width cannot be negative".to_string(),
+                        ));
+                    }
+                    shape = Some(GeoShape::Box {
+                        width: width * unit,
+                        height: height * unit,
+                    });
+                }
+                "byradius" | "bybox" => {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "exactly one of BYRADIUS and BYBOX can be specified for {}",
+                        name
+                    )))
+                }
+                "asc" => desc = Some(false),
+                "desc" => desc = Some(true),
+                "count" => {
+                    let n: i64 = parse_number(next_arg(&mut args)?)?;
+                    if n <= 0 {
+                        return Err(CommandError::InvalidArgument(
+                            "COUNT must be > 0".to_string(),
+                        ));
+                    }
+                    count = n as usize;
+                    if let Some(RespFrame::BulkString(arg)) = args.peek() {
+                        if arg.eq_ignore_ascii_case(b"any") {
+                            args.next();
+                            any = true;
+                        }
+                    }
+                }
+                "withcoord" if !store => with_coord = true,
+                "withdist" if !store => with_dist = true,
+                "withhash" if !store => with_hash = true,
+                "storedist" if store => store_dist = true,
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        let from = from.ok_or_else(|| {
+            CommandError::InvalidArgument(format!(
+                "exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+                name
+            ))
+        })?;
+        let shape = shape.ok_or_else(|| {
+            CommandError::InvalidArgument(format!(
+                "exactly one of BYRADIUS and BYBOX can be specified for {}",
+                name
+            ))
+        })?;
+
+        let search = GeoSearch {
+            key,
+            opts: GeoSearchOptions {
+                from,
+                shape,
+                desc,
+                count,
+                any,
+            },
+            unit,
+            with_coord,
+            with_dist,
+            with_hash,
+        };
+        Ok((search, store_dist))
+    }
+}
+
+impl TryFrom<RespArray> for GeoSearch {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geosearch"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let (search, _) = GeoSearch::parse(key, args, false)?;
+        Ok(search)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_geosearch_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*12\r\n$9\r\nGEOSEARCH\r\n$6\r\nSicily\r\n$10\r\nFROMLONLAT\r\n$2\r\n15\r\n$2\r\n37\r\n$5\r\nBYBOX\r\n$3\r\n400\r\n$3\r\n400\r\n$2\r\nkm\r\n$4\r\nDESC\r\n$8\r\nWITHDIST\r\n$8\r\nWITHHASH\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoSearch = frame.try_into()?;
+        assert_eq!(result.key, "Sicily");
+        assert_eq!(
+            result.opts,
+            GeoSearchOptions {
+                from: GeoFrom::LonLat(15.0, 37.0),
+                shape: GeoShape::Box {
+                    width: 400_000.0,
+                    height: 400_000.0
+                },
+                desc: Some(true),
+                count: 0,
+                any: false,
+            }
+        );
+        assert_eq!(result.unit, 1000.0);
+        assert!(result.with_dist && result.with_hash && !result.with_coord);
+
+        // 没有指定 BYRADIUS 或者 BYBOX
+        buf.extend_from_slice(
+            b"*4\r\n$9\r\nGEOSEARCH\r\n$6\r\nSicily\r\n$10\r\nFROMMEMBER\r\n$7\r\nPalermo\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(GeoSearch::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
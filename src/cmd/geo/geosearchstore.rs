@@ -0,0 +1,95 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::GeoSearch;
+
+#[derive(Debug)]
+pub struct GeoSearchStore {
+    pub(crate) destination: String,
+    pub(crate) search: GeoSearch,
+    // 保存距离作为分数，否则保存 geohash
+    pub(crate) store_dist: bool,
+}
+
+impl CommandExecutor for GeoSearchStore {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let results = match backend.geosearch(&self.search.key, &self.search.opts) {
+            Ok(results) => results,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+
+        let unit = self.search.unit;
+        let zset = results
+            .into_iter()
+            .map(|r| {
+                let score = if self.store_dist {
+                    r.dist / unit
+                } else {
+                    r.hash as f64
+                };
+                (r.member, score)
+            })
+            .collect();
+        (backend.zstore(self.destination, zset, "geosearchstore") as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for GeoSearchStore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["geosearchstore"], 6)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let destination = parse_string(next_arg(&mut args)?)?;
+        let key = parse_string(next_arg(&mut args)?)?;
+        let (search, store_dist) = GeoSearch::parse(key, args, true)?;
+
+        Ok(GeoSearchStore {
+            destination,
+            search,
+            store_dist,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{GeoFrom, GeoShape, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_geosearchstore_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*11\r\n$14\r\nGEOSEARCHSTORE\r\n$3\r\ndst\r\n$6\r\nSicily\r\n$10\r\nFROMMEMBER\r\n$7\r\nPalermo\r\n$8\r\nBYRADIUS\r\n$3\r\n200\r\n$2\r\nkm\r\n$5\r\nCOUNT\r\n$1\r\n2\r\n$9\r\nSTOREDIST\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: GeoSearchStore = frame.try_into()?;
+        assert_eq!(result.destination, "dst");
+        assert_eq!(result.search.key, "Sicily");
+        assert_eq!(
+            result.search.opts.from,
+            GeoFrom::Member("Palermo".to_string())
+        );
+        assert_eq!(result.search.opts.shape, GeoShape::Radius(200_000.0));
+        assert_eq!(result.search.opts.count, 2);
+        assert!(result.store_dist);
+
+        // GEOSEARCHSTORE 不支持 WITHDIST
+        buf.extend_from_slice(
+            b"*9\r\n$14\r\nGEOSEARCHSTORE\r\n$3\r\ndst\r\n$6\r\nSicily\r\n$10\r\nFROMMEMBER\r\n$7\r\nPalermo\r\n$8\r\nBYRADIUS\r\n$3\r\n200\r\n$2\r\nkm\r\n$8\r\nWITHDIST\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(GeoSearchStore::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
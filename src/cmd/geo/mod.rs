@@ -0,0 +1,208 @@
+mod geoadd;
+mod geodist;
+mod geohash;
+mod geopos;
+mod geosearch;
+mod geosearchstore;
+
+pub use geoadd::*;
+pub use geodist::*;
+pub use geohash::*;
+pub use geopos::*;
+pub use geosearch::*;
+pub use geosearchstore::*;
+
+use crate::{BulkString, RespFrame, GEO_LAT_MAX, GEO_LAT_MIN, GEO_LONG_MAX, GEO_LONG_MIN};
+
+use super::{next_arg, parse_number, parse_string, CommandError};
+
+// 单位换算成米的倍数
+fn parse_unit(frame: RespFrame) -> Result<f64, CommandError> {
+    match parse_string(frame)?.to_ascii_lowercase().as_str() {
+        "m" => Ok(1.0),
+        "km" => Ok(1000.0),
+        "ft" => Ok(0.3048),
+        "mi" => Ok(1609.34),
+        _ => Err(CommandError::InvalidArgument(
+            "unsupported unit provided. please use M, KM, FT, MI".to_string(),
+        )),
+    }
+}
+
+// 读取 longitude latitude 并校验范围
+fn parse_lonlat(args: &mut impl Iterator<Item = RespFrame>) -> Result<(f64, f64), CommandError> {
+    let lon: f64 = parse_number(next_arg(args)?)?;
+    let lat: f64 = parse_number(next_arg(args)?)?;
+    if !(GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) || !(GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+    {
+        return Err(CommandError::InvalidArgument(format!(
+            "invalid longitude,latitude pair {:.6},{:.6}",
+            lon, lat
+        )));
+    }
+    Ok((lon, lat))
+}
+
+// 距离保留 4 位小数，以字符串返回
+fn dist_to_frame(meters: f64, unit: f64) -> RespFrame {
+    BulkString::from(format!("{:.4}", meters / unit)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::CommandExecutor, Backend, GeoFrom, GeoSearchOptions, GeoShape, RespArray, RespNull,
+        ZAddFlags,
+    };
+
+    use super::*;
+
+    // redis 文档中的例子
+    fn sicily(backend: &Backend) {
+        let cmd = GeoAdd {
+            key: "Sicily".to_string(),
+            flags: ZAddFlags::default(),
+            items: vec![
+                (13.361389, 38.115556, "Palermo".to_string()),
+                (15.087269, 37.502669, "Catania".to_string()),
+            ],
+        };
+        assert_eq!(cmd.execute(backend), 2.into());
+    }
+
+    fn search(from: GeoFrom, shape: GeoShape, desc: Option<bool>) -> GeoSearch {
+        GeoSearch {
+            key: "Sicily".to_string(),
+            opts: GeoSearchOptions {
+                from,
+                shape,
+                desc,
+                count: 0,
+                any: false,
+            },
+            unit: 1000.0,
+            with_coord: false,
+            with_dist: true,
+            with_hash: false,
+        }
+    }
+
+    #[test]
+    fn test_geoadd_geopos_geodist_geohash_commands() -> Result<()> {
+        let backend = Backend::new();
+        sicily(&backend);
+
+        let cmd = GeoDist {
+            key: "Sicily".to_string(),
+            member1: "Palermo".to_string(),
+            member2: "Catania".to_string(),
+            unit: 1000.0,
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::from("166.2742").into());
+
+        let cmd = GeoHash {
+            key: "Sicily".to_string(),
+            members: vec!["Palermo".to_string(), "nomember".to_string()],
+        };
+        let expected = RespArray::new([BulkString::from("sqc8b49rny0").into(), RespNull.into()]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let (lon, lat) = backend.geopos("Sicily", "Palermo").unwrap();
+        assert!((lon - 13.361389).abs() < 1e-5);
+        assert!((lat - 38.115556).abs() < 1e-5);
+        let cmd = GeoPos {
+            key: "Sicily".to_string(),
+            members: vec!["nomember".to_string()],
+        };
+        let expected = RespArray::new([RespFrame::Null(RespNull)]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        // 分数就是 52 bit 的 geohash
+        assert_eq!(
+            backend.zscore("Sicily", "Palermo"),
+            Some(3479099956230698.0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_geosearch_command() -> Result<()> {
+        let backend = Backend::new();
+        sicily(&backend);
+        let cmd = GeoAdd {
+            key: "Sicily".to_string(),
+            flags: ZAddFlags::default(),
+            items: vec![
+                (12.758489, 38.788135, "edge1".to_string()),
+                (17.241510, 38.788135, "edge2".to_string()),
+            ],
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        let item = |member: &str, dist: &str| {
+            RespFrame::from(RespArray::new([
+                BulkString::from(member).into(),
+                BulkString::from(dist).into(),
+            ]))
+        };
+
+        let cmd = search(
+            GeoFrom::LonLat(15.0, 37.0),
+            GeoShape::Radius(200_000.0),
+            Some(false),
+        );
+        let expected = RespArray::new([item("Catania", "56.4413"), item("Palermo", "190.4424")]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = search(
+            GeoFrom::LonLat(15.0, 37.0),
+            GeoShape::Box {
+                width: 400_000.0,
+                height: 400_000.0,
+            },
+            Some(true),
+        );
+        let expected = RespArray::new([
+            item("edge1", "279.7405"),
+            item("edge2", "279.7403"),
+            item("Palermo", "190.4424"),
+            item("Catania", "56.4413"),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let mut cmd = search(
+            GeoFrom::Member("Palermo".to_string()),
+            GeoShape::Radius(200_000.0),
+            None,
+        );
+        cmd.opts.count = 1;
+        cmd.with_dist = false;
+        let expected = RespArray::new([BulkString::from("Palermo").into()]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = search(
+            GeoFrom::Member("nomember".to_string()),
+            GeoShape::Radius(1.0),
+            None,
+        );
+        assert!(matches!(cmd.execute(&backend), RespFrame::Error(_)));
+
+        let cmd = GeoSearchStore {
+            destination: "dst".to_string(),
+            search: search(
+                GeoFrom::LonLat(15.0, 37.0),
+                GeoShape::Radius(100_000.0),
+                None,
+            ),
+            store_dist: true,
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        let score = backend.zscore("dst", "Catania").unwrap();
+        assert!((score - 56.4413).abs() < 1e-4);
+
+        Ok(())
+    }
+}
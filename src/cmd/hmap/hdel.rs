@@ -0,0 +1,54 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame,
+};
+
+/// 返回删除的字段数量
+#[derive(Debug)]
+pub struct HDel {
+    pub(crate) key: String,
+    pub(crate) fields: Vec<String>,
+}
+
+impl CommandExecutor for HDel {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        backend.hdel(&self.key, &self.fields).into()
+    }
+}
+
+impl TryFrom<RespArray> for HDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["hdel"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let fields = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(HDel { key, fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_hdel_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$4\r\nhdel\r\n$3\r\nmap\r\n$5\r\nhello\r\n$5\r\nworld\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: HDel = frame.try_into()?;
+        assert_eq!(result.key, "map");
+        assert_eq!(result.fields, vec!["hello", "world"]);
+
+        Ok(())
+    }
+}
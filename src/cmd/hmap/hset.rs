@@ -1,6 +1,6 @@
 use crate::{
     cmd::{extract_args, validate_command, CommandError, CommandExecutor},
-    RespArray, RespFrame,
+    RespArray, RespFrame, SimpleError,
 };
 
 #[derive(Debug)]
@@ -13,8 +13,9 @@ pub struct HSet {
 impl CommandExecutor for HSet {
     fn execute(self, backend: &crate::Backend) -> RespFrame {
         match backend.hset(self.key, self.field, self.value) {
-            Some(_) => (0_i64).into(),
-            None => (1_i64).into(),
+            Ok(Some(_)) => (0_i64).into(),
+            Ok(None) => (1_i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
         }
     }
 }
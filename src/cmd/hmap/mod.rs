@@ -1,8 +1,10 @@
+mod hdel;
 mod hget;
 mod hgetall;
 mod hmget;
 mod hset;
 
+pub use hdel::*;
 pub use hget::*;
 pub use hgetall::*;
 pub use hmget::*;
@@ -95,4 +97,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_hdel_command() -> Result<()> {
+        let backend = Backend::new();
+        for field in ["f1", "f2"] {
+            HSet {
+                key: "map".to_string(),
+                field: field.to_string(),
+                value: RespFrame::BulkString(b"v".into()),
+            }
+            .execute(&backend);
+        }
+
+        let cmd = HDel {
+            key: "map".to_string(),
+            fields: vec!["f1".to_string(), "nofield".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        // 最后一个字段删除之后 key 也被删除
+        let cmd = HDel {
+            key: "map".to_string(),
+            fields: vec!["f2".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        assert!(backend.hgetall("map").is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,105 @@
+mod pfadd;
+mod pfcount;
+mod pfmerge;
+
+pub use pfadd::*;
+pub use pfcount::*;
+pub use pfmerge::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::CommandExecutor, Backend, BulkString, HyperLogLog, RespFrame, SimpleError,
+        SimpleString,
+    };
+
+    use super::*;
+
+    fn pfadd(backend: &Backend, key: &str, elements: &[&str]) -> RespFrame {
+        PFAdd {
+            key: key.to_string(),
+            elements: elements.iter().map(|e| e.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    fn pfcount(backend: &Backend, keys: &[&str]) -> RespFrame {
+        PFCount {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_pfadd_pfcount_commands() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(pfadd(&backend, "hll", &[]), 1.into());
+        assert_eq!(pfadd(&backend, "hll", &[]), 0.into());
+        assert_eq!(pfadd(&backend, "hll", &["a", "b", "c"]), 1.into());
+        assert_eq!(pfadd(&backend, "hll", &["a"]), 0.into());
+        assert_eq!(pfcount(&backend, &["hll"]), 3.into());
+        assert_eq!(pfcount(&backend, &["nokey"]), 0.into());
+
+        // 保存的是 redis 格式的字符串，PFCOUNT 之后缓存了基数
+        let value = match backend.get("hll") {
+            Some(RespFrame::BulkString(s)) => s,
+            _ => unreachable!(),
+        };
+        assert_eq!(&value[..5], b"HYLL\x01");
+        assert_eq!(&value[8..16], &3u64.to_le_bytes());
+
+        backend.set("str".to_string(), BulkString::from("hello").into());
+        let expected = SimpleError::new("WRONGTYPE Key is not a valid HyperLogLog string value.");
+        assert_eq!(pfadd(&backend, "str", &["a"]), expected.clone().into());
+        assert_eq!(pfcount(&backend, &["hll", "str"]), expected.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pfmerge_command() -> Result<()> {
+        let backend = Backend::new();
+        pfadd(&backend, "h1", &["a", "b", "c"]);
+        pfadd(&backend, "h2", &["c", "d"]);
+        assert_eq!(pfcount(&backend, &["h1", "h2", "nokey"]), 4.into());
+
+        let cmd = PFMerge {
+            destination: "dst".to_string(),
+            sources: vec!["h1".to_string(), "h2".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+        assert_eq!(pfcount(&backend, &["dst"]), 4.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hll_encoding() -> Result<()> {
+        // 空的 HLL 是一个覆盖全部寄存器的 XZERO
+        let hll = HyperLogLog::new();
+        let bytes = hll.to_bytes();
+        assert_eq!(&bytes[..5], b"HYLL\x01");
+        assert_eq!(&bytes[16..], &[0x7f, 0xff]);
+        assert_eq!(HyperLogLog::from_bytes(&bytes)?, hll);
+
+        // 超过 sparse 的大小限制后转换成 dense，估算误差约为 0.81%
+        let mut hll = HyperLogLog::new();
+        for i in 0..100000 {
+            hll.add(format!("element-{}", i).as_bytes());
+        }
+        let bytes = hll.to_bytes();
+        assert_eq!(&bytes[..5], b"HYLL\x00");
+        assert_eq!(bytes.len(), 16 + 12288);
+        let mut decoded = HyperLogLog::from_bytes(&bytes)?;
+        assert_eq!(decoded.to_bytes(), bytes);
+        let count = decoded.count() as f64;
+        assert!((count - 100000.0).abs() / 100000.0 < 0.02);
+
+        assert!(HyperLogLog::from_bytes(b"HYLL\x01\x00\x00\x00").is_err());
+        assert!(HyperLogLog::from_bytes(b"not a hll string").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct PFAdd {
+    pub(crate) key: String,
+    pub(crate) elements: Vec<Vec<u8>>,
+}
+
+impl CommandExecutor for PFAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.pfadd(self.key, &self.elements) {
+            Ok(updated) => (updated as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for PFAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["pfadd"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let elements = args.map(parse_bytes).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PFAdd { key, elements })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_pfadd_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$5\r\npfadd\r\n$3\r\nhll\r\n$1\r\na\r\n$2\r\n\xff\xfe\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: PFAdd = frame.try_into()?;
+        assert_eq!(result.key, "hll");
+        assert_eq!(result.elements, vec![b"a".to_vec(), vec![0xff, 0xfe]]);
+
+        Ok(())
+    }
+}
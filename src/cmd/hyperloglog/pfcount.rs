@@ -0,0 +1,55 @@
+use crate::{
+    cmd::{extract_args, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct PFCount {
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecutor for PFCount {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.pfcount(&self.keys) {
+            Ok(count) => (count as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for PFCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["pfcount"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(parse_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PFCount { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_pfcount_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$7\r\nPFCOUNT\r\n$2\r\nh1\r\n$2\r\nh2\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: PFCount = frame.try_into()?;
+        assert_eq!(result.keys, vec!["h1", "h2"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError, SimpleString,
+};
+
+#[derive(Debug)]
+pub struct PFMerge {
+    pub(crate) destination: String,
+    pub(crate) sources: Vec<String>,
+}
+
+impl CommandExecutor for PFMerge {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.pfmerge(self.destination, &self.sources) {
+            Ok(()) => SimpleString::new("OK").into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for PFMerge {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["pfmerge"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let destination = parse_string(next_arg(&mut args)?)?;
+        let sources = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PFMerge {
+            destination,
+            sources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_pfmerge_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$7\r\npfmerge\r\n$3\r\ndst\r\n$2\r\nh1\r\n$2\r\nh2\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: PFMerge = frame.try_into()?;
+        assert_eq!(result.destination, "dst");
+        assert_eq!(result.sources, vec!["h1", "h2"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    JsonPath, RespArray, RespFrame, SimpleError,
+};
+
+use super::{parse_json, parse_path, path_reply};
+
+/// JSON.ARRAPPEND key path value [value ...]，返回追加之后数组的长度
+#[derive(Debug)]
+pub struct JsonArrAppend {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) values: Vec<serde_json::Value>,
+}
+
+impl CommandExecutor for JsonArrAppend {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_arrappend(&self.key, &self.path, &self.values) {
+            Ok(ret) => path_reply(&self.path, ret, |len| (len as i64).into()),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonArrAppend {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.arrappend"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = parse_path(next_arg(&mut args)?)?;
+        let values = args.map(parse_json).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(JsonArrAppend { key, path, values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+    use serde_json::json;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_arrappend_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$14\r\nJSON.ARRAPPEND\r\n$3\r\ndoc\r\n$5\r\n$.arr\r\n$1\r\n1\r\n$3\r\n\"a\"\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonArrAppend = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$.arr".parse()?);
+        assert_eq!(result.values, vec![json!(1), json!("a")]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    JsonPath, RespArray, RespFrame, SimpleError,
+};
+
+use super::{parse_json, parse_path, path_reply};
+
+/// JSON.ARRINSERT key path index value [value ...]，在 index 之前插入
+#[derive(Debug)]
+pub struct JsonArrInsert {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) index: i64,
+    pub(crate) values: Vec<serde_json::Value>,
+}
+
+impl CommandExecutor for JsonArrInsert {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_arrinsert(&self.key, &self.path, self.index, &self.values) {
+            Ok(ret) => path_reply(&self.path, ret, |len| (len as i64).into()),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonArrInsert {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.arrinsert"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = parse_path(next_arg(&mut args)?)?;
+        let index = parse_number(next_arg(&mut args)?)?;
+        let values = args.map(parse_json).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(JsonArrInsert {
+            key,
+            path,
+            index,
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+    use serde_json::json;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_arrinsert_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$14\r\nJSON.ARRINSERT\r\n$3\r\ndoc\r\n$5\r\n$.arr\r\n$2\r\n-1\r\n$4\r\ntrue\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonArrInsert = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.index, -1);
+        assert_eq!(result.values, vec![json!(true)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    JsonPath, RespArray, RespFrame, RespNull, SimpleError,
+};
+
+use super::{json_to_frame, parse_path, root_path};
+
+/// JSON.ARRPOP key [path [index]]，默认弹出最后一个元素
+#[derive(Debug)]
+pub struct JsonArrPop {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) index: i64,
+}
+
+impl CommandExecutor for JsonArrPop {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = match backend.json_arrpop(&self.key, &self.path, self.index) {
+            Ok(ret) => ret,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+        // 空数组弹出 nil，所以旧语法不区分类型错误
+        let to_frame = |v: Option<serde_json::Value>| match v {
+            Some(v) => json_to_frame(&v),
+            None => RespFrame::Null(RespNull),
+        };
+        if self.path.is_legacy() {
+            return match ret.into_iter().next() {
+                Some(v) => to_frame(v),
+                None => SimpleError::new(
+                    crate::BackendError::JsonPathNotFound(self.path.to_string()).to_string(),
+                )
+                .into(),
+            };
+        }
+        RespArray::new(ret.into_iter().map(to_frame).collect::<Vec<_>>()).into()
+    }
+}
+
+impl TryFrom<RespArray> for JsonArrPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.arrpop"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = args
+            .next()
+            .map(parse_path)
+            .transpose()?
+            .unwrap_or_else(root_path);
+        let index = args.next().map(parse_number).transpose()?.unwrap_or(-1);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonArrPop { key, path, index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_arrpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$11\r\nJSON.ARRPOP\r\n$3\r\ndoc\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonArrPop = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert!(result.path.is_root());
+        assert_eq!(result.index, -1);
+
+        buf.extend_from_slice(
+            b"*4\r\n$11\r\njson.arrpop\r\n$3\r\ndoc\r\n$5\r\n$.arr\r\n$1\r\n0\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: JsonArrPop = frame.try_into()?;
+        assert_eq!(result.path, "$.arr".parse()?);
+        assert_eq!(result.index, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    JsonPath, RespArray, RespFrame,
+};
+
+use super::{parse_path, root_path};
+
+/// JSON.DEL 和 JSON.FORGET，返回删除的值的数量
+#[derive(Debug)]
+pub struct JsonDel {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+}
+
+impl CommandExecutor for JsonDel {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.json_del(&self.key, &self.path) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for JsonDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let forget = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"json.forget"));
+        let name = if forget { "json.forget" } else { "json.del" };
+        validate_min_args(&value, &[name], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = args
+            .next()
+            .map(parse_path)
+            .transpose()?
+            .unwrap_or_else(root_path);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonDel { key, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_del_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$8\r\nJSON.DEL\r\n$3\r\ndoc\r\n$4\r\n$..a\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonDel = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$..a".parse()?);
+
+        buf.extend_from_slice(b"*2\r\n$11\r\njson.forget\r\n$3\r\ndoc\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: JsonDel = frame.try_into()?;
+        assert!(result.path.is_root());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    JsonPath, RespArray, RespFrame, RespNull, SimpleError,
+};
+
+use super::{json_to_frame, parse_path};
+
+/// JSON.GET key [path ...]，没有路径时返回整个文档
+#[derive(Debug)]
+pub struct JsonGet {
+    pub(crate) key: String,
+    pub(crate) paths: Vec<JsonPath>,
+}
+
+impl CommandExecutor for JsonGet {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_get(&self.key, &self.paths) {
+            Ok(Some(value)) => json_to_frame(&value),
+            Ok(None) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.get"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let mut paths = args.map(parse_path).collect::<Result<Vec<_>, _>>()?;
+        if paths.is_empty() {
+            paths.push(super::root_path());
+        }
+
+        Ok(JsonGet { key, paths })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_get_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$8\r\nJSON.GET\r\n$3\r\ndoc\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonGet = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.paths, vec![".".parse()?]);
+
+        buf.extend_from_slice(
+            b"*4\r\n$8\r\njson.get\r\n$3\r\ndoc\r\n$3\r\n$.a\r\n$7\r\n$..b[*]\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: JsonGet = frame.try_into()?;
+        assert_eq!(result.paths, vec!["$.a".parse()?, "$..b[*]".parse()?]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    JsonPath, RespArray, RespFrame, RespNull,
+};
+
+use super::{parse_path, path_reply, root_path};
+
+/// JSON.OBJLEN 和 JSON.ARRLEN 共用，array 为 true 时返回数组的长度
+#[derive(Debug)]
+pub struct JsonLen {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) array: bool,
+}
+
+impl CommandExecutor for JsonLen {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = if self.array {
+            backend.json_arrlen(&self.key, &self.path)
+        } else {
+            backend.json_objlen(&self.key, &self.path)
+        };
+        match ret {
+            Some(ret) => path_reply(&self.path, ret, |len| (len as i64).into()),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonLen {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let array = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"json.arrlen"));
+        let name = if array { "json.arrlen" } else { "json.objlen" };
+        validate_min_args(&value, &[name], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = args
+            .next()
+            .map(parse_path)
+            .transpose()?
+            .unwrap_or_else(root_path);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonLen { key, path, array })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_len_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$11\r\nJSON.ARRLEN\r\n$3\r\ndoc\r\n$5\r\n$.arr\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonLen = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$.arr".parse()?);
+        assert!(result.array);
+
+        buf.extend_from_slice(b"*2\r\n$11\r\njson.objlen\r\n$3\r\ndoc\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: JsonLen = frame.try_into()?;
+        assert!(result.path.is_root());
+        assert!(!result.array);
+
+        Ok(())
+    }
+}
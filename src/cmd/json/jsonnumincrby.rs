@@ -0,0 +1,87 @@
+use serde_json::{Number, Value};
+
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    JsonPath, RespArray, RespFrame, SimpleError,
+};
+
+use super::{json_to_frame, parse_path, path_reply};
+
+/// JSON.NUMINCRBY key path value，JSONPath 返回所有新值组成的 JSON 数组
+#[derive(Debug)]
+pub struct JsonNumIncrBy {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) increment: Number,
+}
+
+impl CommandExecutor for JsonNumIncrBy {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = match backend.json_numincrby(&self.key, &self.path, &self.increment) {
+            Ok(ret) => ret,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+        if self.path.is_legacy() {
+            return path_reply(&self.path, ret, |n| json_to_frame(&Value::Number(n)));
+        }
+        let values = ret
+            .into_iter()
+            .map(|n| n.map_or(Value::Null, Value::Number))
+            .collect();
+        json_to_frame(&Value::Array(values))
+    }
+}
+
+impl TryFrom<RespArray> for JsonNumIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["json.numincrby"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = parse_path(next_arg(&mut args)?)?;
+        let increment = parse_string(next_arg(&mut args)?)?;
+        let increment = increment.parse().map_err(|_| {
+            CommandError::InvalidArgument(format!("value is not a valid number: {}", increment))
+        })?;
+
+        Ok(JsonNumIncrBy {
+            key,
+            path,
+            increment,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_numincrby_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*4\r\n$14\r\nJSON.NUMINCRBY\r\n$3\r\ndoc\r\n$5\r\n$.a.b\r\n$3\r\n1.5\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonNumIncrBy = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$.a.b".parse()?);
+        assert_eq!(result.increment.as_f64(), Some(1.5));
+
+        buf.extend_from_slice(
+            b"*4\r\n$14\r\njson.numincrby\r\n$3\r\ndoc\r\n$2\r\n.a\r\n$1\r\nx\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(JsonNumIncrBy::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
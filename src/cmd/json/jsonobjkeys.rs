@@ -0,0 +1,73 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, JsonPath, RespArray, RespFrame, RespNull,
+};
+
+use super::{parse_path, path_reply, root_path};
+
+/// JSON.OBJKEYS key [path]，按插入的顺序返回对象的字段
+#[derive(Debug)]
+pub struct JsonObjKeys {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+}
+
+impl CommandExecutor for JsonObjKeys {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_objkeys(&self.key, &self.path) {
+            Some(ret) => path_reply(&self.path, ret, |keys| {
+                RespArray::new(
+                    keys.into_iter()
+                        .map(|k| BulkString::from(k).into())
+                        .collect::<Vec<RespFrame>>(),
+                )
+                .into()
+            }),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonObjKeys {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.objkeys"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = args
+            .next()
+            .map(parse_path)
+            .transpose()?
+            .unwrap_or_else(root_path);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonObjKeys { key, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_objkeys_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$12\r\nJSON.OBJKEYS\r\n$3\r\ndoc\r\n$4\r\n$..a\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonObjKeys = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$..a".parse()?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor,
+        RESP_OK,
+    },
+    JsonPath, JsonSetFlags, RespArray, RespFrame, RespNull, SimpleError,
+};
+
+use super::{parse_json, parse_path};
+
+/// JSON.SET key path value [NX | XX]
+#[derive(Debug)]
+pub struct JsonSet {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+    pub(crate) value: serde_json::Value,
+    pub(crate) flags: JsonSetFlags,
+}
+
+impl CommandExecutor for JsonSet {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_set(self.key, &self.path, self.value, self.flags) {
+            Ok(true) => RESP_OK.clone(),
+            Ok(false) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonSet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.set"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = parse_path(next_arg(&mut args)?)?;
+        let value = parse_json(next_arg(&mut args)?)?;
+        let mut flags = JsonSetFlags::default();
+        for arg in args {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "nx" => flags.nx = true,
+                "xx" => flags.xx = true,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+        if flags.nx && flags.xx {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonSet {
+            key,
+            path,
+            value,
+            flags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+    use serde_json::json;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_set_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$8\r\nJSON.SET\r\n$3\r\ndoc\r\n$8\r\n$.a.b[0]\r\n$7\r\n{\"c\":1}\r\n$2\r\nNX\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonSet = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$.a.b[0]".parse()?);
+        assert_eq!(result.value, json!({"c": 1}));
+        assert!(result.flags.nx);
+
+        buf.extend_from_slice(b"*4\r\n$8\r\njson.set\r\n$3\r\ndoc\r\n$1\r\n$\r\n$4\r\n{a:1\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(JsonSet::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
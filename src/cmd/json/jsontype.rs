@@ -0,0 +1,66 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, JsonPath, RespArray, RespFrame, RespNull,
+};
+
+use super::{parse_path, path_reply, root_path};
+
+/// JSON.TYPE key [path]，key 不存在时返回 nil
+#[derive(Debug)]
+pub struct JsonType {
+    pub(crate) key: String,
+    pub(crate) path: JsonPath,
+}
+
+impl CommandExecutor for JsonType {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.json_type(&self.key, &self.path) {
+            Some(ret) => path_reply(&self.path, ret, |t| BulkString::from(t).into()),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for JsonType {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["json.type"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let path = args
+            .next()
+            .map(parse_path)
+            .transpose()?
+            .unwrap_or_else(root_path);
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(JsonType { key, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_json_type_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$9\r\nJSON.TYPE\r\n$3\r\ndoc\r\n$3\r\n$.a\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: JsonType = frame.try_into()?;
+        assert_eq!(result.key, "doc");
+        assert_eq!(result.path, "$.a".parse()?);
+
+        Ok(())
+    }
+}
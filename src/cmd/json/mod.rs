@@ -0,0 +1,433 @@
+mod jsonarrappend;
+mod jsonarrinsert;
+mod jsonarrpop;
+mod jsondel;
+mod jsonget;
+mod jsonlen;
+mod jsonnumincrby;
+mod jsonobjkeys;
+mod jsonset;
+mod jsontype;
+
+pub use jsonarrappend::*;
+pub use jsonarrinsert::*;
+pub use jsonarrpop::*;
+pub use jsondel::*;
+pub use jsonget::*;
+pub use jsonlen::*;
+pub use jsonnumincrby::*;
+pub use jsonobjkeys::*;
+pub use jsonset::*;
+pub use jsontype::*;
+
+use serde_json::Value;
+
+use crate::{BackendError, BulkString, JsonPath, RespArray, RespFrame, RespNull, SimpleError};
+
+use super::{parse_bytes, parse_string, CommandError};
+
+fn parse_path(frame: RespFrame) -> Result<JsonPath, CommandError> {
+    parse_string(frame)?
+        .parse()
+        .map_err(|e: BackendError| CommandError::InvalidArgument(e.to_string()))
+}
+
+fn parse_json(frame: RespFrame) -> Result<Value, CommandError> {
+    serde_json::from_slice(&parse_bytes(frame)?)
+        .map_err(|e| CommandError::InvalidArgument(format!("invalid JSON value: {}", e)))
+}
+
+// 省略路径时使用旧语法的根路径
+fn root_path() -> JsonPath {
+    ".".parse().expect("root path is valid")
+}
+
+// JSON 值序列化之后作为 BulkString 返回
+fn json_to_frame(value: &Value) -> RespFrame {
+    BulkString::from(value.to_string()).into()
+}
+
+// 旧语法只返回第一个匹配的结果，类型不匹配时返回错误
+// JSONPath 返回所有匹配的结果，类型不匹配的位置返回 nil
+fn path_reply<T>(path: &JsonPath, ret: Vec<Option<T>>, f: impl Fn(T) -> RespFrame) -> RespFrame {
+    if path.is_legacy() {
+        let err = match ret.into_iter().next() {
+            Some(Some(v)) => return f(v),
+            Some(None) => BackendError::JsonWrongType,
+            None => BackendError::JsonPathNotFound(path.to_string()),
+        };
+        return SimpleError::new(err.to_string()).into();
+    }
+
+    RespArray::new(
+        ret.into_iter()
+            .map(|v| v.map_or(RespFrame::Null(RespNull), &f))
+            .collect::<Vec<_>>(),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use crate::{cmd::CommandExecutor, Backend, JsonSetFlags, SimpleString};
+
+    use super::*;
+
+    fn json_set(backend: &Backend, key: &str, path: &str, value: Value) -> Result<RespFrame> {
+        Ok(JsonSet {
+            key: key.to_string(),
+            path: path.parse()?,
+            value,
+            flags: JsonSetFlags::default(),
+        }
+        .execute(backend))
+    }
+
+    fn json_get(backend: &Backend, key: &str, paths: &[&str]) -> Result<RespFrame> {
+        Ok(JsonGet {
+            key: key.to_string(),
+            paths: paths.iter().map(|p| p.parse()).collect::<Result<_, _>>()?,
+        }
+        .execute(backend))
+    }
+
+    fn bulk(s: &str) -> RespFrame {
+        BulkString::from(s).into()
+    }
+
+    fn profile() -> Value {
+        json!({
+            "name": "alice",
+            "age": 30,
+            "address": {"city": "Beijing", "zip": "100000"},
+            "tags": ["a", "b", "c", "d"],
+            "friends": [{"name": "bob", "age": 28}, {"name": "carol", "age": 31.5}]
+        })
+    }
+
+    #[test]
+    fn test_json_set_get_commands() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(json_get(&backend, "user", &[])?, RespFrame::Null(RespNull));
+        assert_eq!(
+            json_set(&backend, "user", "$.name", json!("x"))?,
+            SimpleError::new("ERR new objects must be created at the root").into()
+        );
+        assert_eq!(
+            json_set(&backend, "user", "$", profile())?,
+            SimpleString::new("OK").into()
+        );
+
+        // 旧语法返回值本身，JSONPath 返回数组
+        assert_eq!(
+            json_get(&backend, "user", &[".address.city"])?,
+            bulk("\"Beijing\"")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["address.city"])?,
+            bulk("\"Beijing\"")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$.address.city"])?,
+            bulk("[\"Beijing\"]")
+        );
+        assert_eq!(json_get(&backend, "user", &["$.nokey"])?, bulk("[]"));
+        assert_eq!(
+            json_get(&backend, "user", &[".nokey"])?,
+            SimpleError::new("ERR Path '.nokey' does not exist").into()
+        );
+
+        // 通配符、切片、下标和递归查找
+        assert_eq!(
+            json_get(&backend, "user", &["$.friends[*].name"])?,
+            bulk("[\"bob\",\"carol\"]")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$.tags[1:3]"])?,
+            bulk("[\"b\",\"c\"]")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$.tags[::2]"])?,
+            bulk("[\"a\",\"c\"]")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$.tags[-1,0]"])?,
+            bulk("[\"d\",\"a\"]")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$..age"])?,
+            bulk("[30,28,31.5]")
+        );
+        assert_eq!(
+            json_get(&backend, "user", &["$.friends[0]['name']", "$.age"])?,
+            bulk("{\"$.friends[0]['name']\":[\"bob\"],\"$.age\":[30]}")
+        );
+
+        // 修改已有的值，或者在父对象中添加新的字段
+        json_set(&backend, "user", "$.friends[*].age", json!(0))?;
+        json_set(&backend, "user", "$.address.country", json!("CN"))?;
+        assert_eq!(
+            json_get(&backend, "user", &["$..age", "$.address"])?,
+            bulk("{\"$..age\":[30,0,0],\"$.address\":[{\"city\":\"Beijing\",\"zip\":\"100000\",\"country\":\"CN\"}]}")
+        );
+        assert_eq!(
+            json_set(&backend, "user", "$.nokey.city", json!(1))?,
+            RespFrame::Null(RespNull)
+        );
+
+        // NX 只在路径不存在时设置，XX 只在路径存在时设置
+        let cmd = JsonSet {
+            key: "user".to_string(),
+            path: "$.name".parse()?,
+            value: json!("bob"),
+            flags: JsonSetFlags {
+                nx: true,
+                xx: false,
+            },
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+        let cmd = JsonSet {
+            key: "user".to_string(),
+            path: "$.email".parse()?,
+            value: json!("a@b.c"),
+            flags: JsonSetFlags {
+                nx: false,
+                xx: true,
+            },
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_del_command() -> Result<()> {
+        let backend = Backend::new();
+        json_set(&backend, "user", "$", profile())?;
+
+        let cmd = JsonDel {
+            key: "user".to_string(),
+            path: "$.tags[0,2]".parse()?,
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+        assert_eq!(
+            json_get(&backend, "user", &[".tags"])?,
+            bulk("[\"b\",\"d\"]")
+        );
+
+        let cmd = JsonDel {
+            key: "user".to_string(),
+            path: "$..age".parse()?,
+        };
+        assert_eq!(cmd.execute(&backend), 3.into());
+        assert_eq!(
+            json_get(&backend, "user", &["$.friends"])?,
+            bulk("[[{\"name\":\"bob\"},{\"name\":\"carol\"}]]")
+        );
+
+        let cmd = JsonDel {
+            key: "user".to_string(),
+            path: root_path(),
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        assert_eq!(json_get(&backend, "user", &[])?, RespFrame::Null(RespNull));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_numincrby_command() -> Result<()> {
+        let backend = Backend::new();
+        json_set(&backend, "user", "$", profile())?;
+
+        let incr = |path: &str, n: &str| -> Result<RespFrame> {
+            Ok(JsonNumIncrBy {
+                key: "user".to_string(),
+                path: path.parse()?,
+                increment: n.parse()?,
+            }
+            .execute(&backend))
+        };
+        assert_eq!(incr(".age", "2")?, bulk("32"));
+        assert_eq!(incr("$..age", "1")?, bulk("[33,29,32.5]"));
+        assert_eq!(incr("$.age", "0.5")?, bulk("[33.5]"));
+        assert_eq!(incr("$.name", "1")?, bulk("[null]"));
+        assert_eq!(
+            incr(".name", "1")?,
+            SimpleError::new("WRONGTYPE wrong type of path value").into()
+        );
+
+        let cmd = JsonNumIncrBy {
+            key: "nokey".to_string(),
+            path: "$.a".parse()?,
+            increment: 1.into(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR could not perform this operation on a key that doesn't exist")
+                .into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_array_commands() -> Result<()> {
+        let backend = Backend::new();
+        json_set(&backend, "user", "$", profile())?;
+
+        let cmd = JsonArrAppend {
+            key: "user".to_string(),
+            path: "$.tags".parse()?,
+            values: vec![json!("e"), json!(1)],
+        };
+        assert_eq!(cmd.execute(&backend), RespArray::new([6.into()]).into());
+
+        let cmd = JsonArrAppend {
+            key: "user".to_string(),
+            path: "$.*".parse()?,
+            values: vec![json!(null)],
+        };
+        let ret = cmd.execute(&backend);
+        assert_eq!(
+            ret,
+            RespArray::new([
+                RespFrame::Null(RespNull),
+                RespFrame::Null(RespNull),
+                RespFrame::Null(RespNull),
+                7.into(),
+                3.into()
+            ])
+            .into()
+        );
+
+        let cmd = JsonArrInsert {
+            key: "user".to_string(),
+            path: ".tags".parse()?,
+            index: 0,
+            values: vec![json!("z")],
+        };
+        assert_eq!(cmd.execute(&backend), 8.into());
+        let cmd = JsonArrInsert {
+            key: "user".to_string(),
+            path: ".tags".parse()?,
+            index: 100,
+            values: vec![json!("z")],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR index out of bounds").into()
+        );
+
+        let cmd = JsonArrPop {
+            key: "user".to_string(),
+            path: ".tags".parse()?,
+            index: 0,
+        };
+        assert_eq!(cmd.execute(&backend), bulk("\"z\""));
+        let cmd = JsonArrPop {
+            key: "user".to_string(),
+            path: "$.tags".parse()?,
+            index: -1,
+        };
+        assert_eq!(cmd.execute(&backend), RespArray::new([bulk("null")]).into());
+        assert_eq!(
+            json_get(&backend, "user", &[".tags"])?,
+            bulk("[\"a\",\"b\",\"c\",\"d\",\"e\",1]")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_introspection_commands() -> Result<()> {
+        let backend = Backend::new();
+        json_set(&backend, "user", "$", profile())?;
+
+        let cmd = JsonType {
+            key: "user".to_string(),
+            path: root_path(),
+        };
+        assert_eq!(cmd.execute(&backend), bulk("object"));
+        let cmd = JsonType {
+            key: "user".to_string(),
+            path: "$..age".parse()?,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([bulk("integer"), bulk("integer"), bulk("number")]).into()
+        );
+        let cmd = JsonType {
+            key: "nokey".to_string(),
+            path: root_path(),
+        };
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+
+        let cmd = JsonLen {
+            key: "user".to_string(),
+            path: "$.*".parse()?,
+            array: false,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([
+                RespFrame::Null(RespNull),
+                RespFrame::Null(RespNull),
+                2.into(),
+                RespFrame::Null(RespNull),
+                RespFrame::Null(RespNull)
+            ])
+            .into()
+        );
+        let cmd = JsonLen {
+            key: "user".to_string(),
+            path: ".tags".parse()?,
+            array: true,
+        };
+        assert_eq!(cmd.execute(&backend), 4.into());
+        let cmd = JsonLen {
+            key: "user".to_string(),
+            path: ".name".parse()?,
+            array: true,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("WRONGTYPE wrong type of path value").into()
+        );
+
+        let cmd = JsonObjKeys {
+            key: "user".to_string(),
+            path: ".address".parse()?,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([bulk("city"), bulk("zip")]).into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_path_syntax() -> Result<()> {
+        for path in [
+            "$",
+            ".",
+            "$.a.b",
+            "a.b",
+            "$['a'][\"b\"]",
+            "$..*",
+            "$[1:-1:2]",
+            "$[ 0 , 1 ]",
+        ] {
+            assert!(path.parse::<JsonPath>().is_ok(), "{}", path);
+        }
+        for path in ["$.", "$[", "$[a]", "$['a'", "$x"] {
+            assert!(path.parse::<JsonPath>().is_err(), "{}", path);
+        }
+
+        Ok(())
+    }
+}
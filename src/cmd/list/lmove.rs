@@ -0,0 +1,67 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    BulkString, ListDirection, RespArray, RespFrame, RespNull, SimpleError,
+};
+
+use super::parse_direction;
+
+#[derive(Debug)]
+pub struct LMove {
+    pub(crate) source: String,
+    pub(crate) destination: String,
+    pub(crate) from: ListDirection,
+    pub(crate) to: ListDirection,
+}
+
+impl CommandExecutor for LMove {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.lmove(&self.source, self.destination, self.from, self.to) {
+            Ok(Some(value)) => BulkString::from(value).into(),
+            Ok(None) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for LMove {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lmove"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LMove {
+            source: parse_string(next_arg(&mut args)?)?,
+            destination: parse_string(next_arg(&mut args)?)?,
+            from: parse_direction(next_arg(&mut args)?)?,
+            to: parse_direction(next_arg(&mut args)?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_lmove_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$5\r\nlmove\r\n$3\r\nsrc\r\n$3\r\ndst\r\n$4\r\nLEFT\r\n$5\r\nright\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: LMove = frame.try_into()?;
+        assert_eq!(result.source, "src");
+        assert_eq!(result.destination, "dst");
+        assert_eq!(result.from, ListDirection::Left);
+        assert_eq!(result.to, ListDirection::Right);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, ListDirection, RespArray, RespFrame, RespNull,
+};
+
+use super::parse_direction;
+
+#[derive(Debug)]
+pub struct LMPop {
+    pub(crate) keys: Vec<String>,
+    pub(crate) direction: ListDirection,
+    pub(crate) count: usize,
+}
+
+impl CommandExecutor for LMPop {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        // 返回第一个非空列表的 key 和弹出的元素
+        for key in self.keys {
+            if let Some(values) = backend.lpop_count(&key, self.direction, self.count) {
+                let values = values
+                    .into_iter()
+                    .map(|v| BulkString::from(v).into())
+                    .collect::<Vec<RespFrame>>();
+                return RespArray::new([
+                    BulkString::from(key).into(),
+                    RespArray::new(values).into(),
+                ])
+                .into();
+            }
+        }
+
+        RespFrame::Null(RespNull)
+    }
+}
+
+impl TryFrom<RespArray> for LMPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["lmpop"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let numkeys: usize = parse_number(next_arg(&mut args)?)?;
+        if numkeys == 0 {
+            return Err(CommandError::InvalidArgument(
+                "numkeys should be greater than 0".to_string(),
+            ));
+        }
+
+        let keys = (0..numkeys)
+            .map(|_| parse_string(next_arg(&mut args)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let direction = parse_direction(next_arg(&mut args)?)?;
+
+        let mut count = 1;
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "count" => count = parse_number(next_arg(&mut args)?)?,
+                _ => {
+                    return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                }
+            }
+        }
+        if count == 0 {
+            return Err(CommandError::InvalidArgument(
+                "count should be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(LMPop {
+            keys,
+            direction,
+            count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_lmpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*7\r\n$5\r\nlmpop\r\n$1\r\n2\r\n$2\r\nl1\r\n$2\r\nl2\r\n$5\r\nRIGHT\r\n$5\r\nCOUNT\r\n$1\r\n3\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: LMPop = frame.try_into()?;
+        assert_eq!(result.keys, vec!["l1", "l2"]);
+        assert_eq!(result.direction, ListDirection::Right);
+        assert_eq!(result.count, 3);
+
+        Ok(())
+    }
+}
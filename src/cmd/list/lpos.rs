@@ -0,0 +1,103 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, RespNull,
+};
+
+#[derive(Debug)]
+pub struct LPos {
+    pub(crate) key: String,
+    pub(crate) element: String,
+    pub(crate) rank: i64,
+    // None 表示没有 COUNT 参数，只返回一个下标
+    pub(crate) count: Option<usize>,
+    pub(crate) maxlen: usize,
+}
+
+impl CommandExecutor for LPos {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let count = self.count.unwrap_or(1);
+        let indexes = backend.lpos(&self.key, &self.element, self.rank, count, self.maxlen);
+
+        match self.count {
+            Some(_) => {
+                let indexes = indexes
+                    .into_iter()
+                    .map(|i| (i as i64).into())
+                    .collect::<Vec<RespFrame>>();
+                RespArray::new(indexes).into()
+            }
+            None => match indexes.first() {
+                Some(&i) => (i as i64).into(),
+                None => RespFrame::Null(RespNull),
+            },
+        }
+    }
+}
+
+impl TryFrom<RespArray> for LPos {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["lpos"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let element = parse_string(next_arg(&mut args)?)?;
+
+        let (mut rank, mut count, mut maxlen) = (1, None, 0);
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "rank" => rank = parse_number(next_arg(&mut args)?)?,
+                "count" => count = Some(parse_number(next_arg(&mut args)?)?),
+                "maxlen" => maxlen = parse_number(next_arg(&mut args)?)?,
+                _ => {
+                    return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                }
+            }
+        }
+        if rank == 0 {
+            return Err(CommandError::InvalidArgument(
+                "RANK can't be zero".to_string(),
+            ));
+        }
+
+        Ok(LPos {
+            key,
+            element,
+            rank,
+            count,
+            maxlen,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_lpos_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*7\r\n$4\r\nlpos\r\n$6\r\nmylist\r\n$1\r\na\r\n$4\r\nRANK\r\n$2\r\n-1\r\n$5\r\nCOUNT\r\n$1\r\n0\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: LPos = frame.try_into()?;
+        assert_eq!(result.key, "mylist");
+        assert_eq!(result.element, "a");
+        assert_eq!(result.rank, -1);
+        assert_eq!(result.count, Some(0));
+        assert_eq!(result.maxlen, 0);
+
+        Ok(())
+    }
+}
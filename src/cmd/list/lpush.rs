@@ -0,0 +1,56 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct LPush {
+    pub(crate) key: String,
+    pub(crate) values: Vec<String>,
+}
+
+impl CommandExecutor for LPush {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.lpush(self.key, self.values) {
+            Ok(n) => (n as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for LPush {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["lpush"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let values = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LPush { key, values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_lpush_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$5\r\nlpush\r\n$6\r\nmylist\r\n$1\r\na\r\n$1\r\nb\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: LPush = frame.try_into()?;
+        assert_eq!(result.key, "mylist");
+        assert_eq!(result.values, vec!["a", "b"]);
+
+        Ok(())
+    }
+}
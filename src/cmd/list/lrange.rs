@@ -0,0 +1,64 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    BulkString, RespArray, RespFrame,
+};
+
+#[derive(Debug)]
+pub struct LRange {
+    pub(crate) key: String,
+    pub(crate) start: i64,
+    pub(crate) stop: i64,
+}
+
+impl CommandExecutor for LRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let values = backend
+            .lrange(&self.key, self.start, self.stop)
+            .into_iter()
+            .map(|v| BulkString::from(v).into())
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(values).into()
+    }
+}
+
+impl TryFrom<RespArray> for LRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["lrange"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(LRange {
+            key: parse_string(next_arg(&mut args)?)?,
+            start: parse_number(next_arg(&mut args)?)?,
+            stop: parse_number(next_arg(&mut args)?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_lrange_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$6\r\nlrange\r\n$6\r\nmylist\r\n$1\r\n0\r\n$2\r\n-1\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: LRange = frame.try_into()?;
+        assert_eq!(result.key, "mylist");
+        assert_eq!(result.start, 0);
+        assert_eq!(result.stop, -1);
+
+        Ok(())
+    }
+}
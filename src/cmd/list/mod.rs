@@ -0,0 +1,222 @@
+mod lmove;
+mod lmpop;
+mod lpos;
+mod lpush;
+mod lrange;
+mod rpush;
+
+pub use lmove::*;
+pub use lmpop::*;
+pub use lpos::*;
+pub use lpush::*;
+pub use lrange::*;
+pub use rpush::*;
+
+use crate::{ListDirection, RespFrame};
+
+use super::{parse_string, CommandError};
+
+// 解析 LEFT | RIGHT 参数
+fn parse_direction(frame: RespFrame) -> Result<ListDirection, CommandError> {
+    match parse_string(frame)?.to_ascii_lowercase().as_str() {
+        "left" => Ok(ListDirection::Left),
+        "right" => Ok(ListDirection::Right),
+        _ => Err(CommandError::InvalidArgument("syntax error".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, BulkString, RespArray, RespFrame, RespNull};
+
+    use super::*;
+
+    fn lrange(backend: &Backend, key: &str) -> RespFrame {
+        LRange {
+            key: key.to_string(),
+            start: 0,
+            stop: -1,
+        }
+        .execute(backend)
+    }
+
+    fn array(values: &[&str]) -> RespFrame {
+        RespArray::new(
+            values
+                .iter()
+                .map(|&v| BulkString::from(v).into())
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_push_range_commands() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = RPush {
+            key: "mylist".to_string(),
+            values: vec!["b".to_string(), "c".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        let cmd = LPush {
+            key: "mylist".to_string(),
+            values: vec!["a".to_string(), "z".to_string()],
+        };
+        assert_eq!(cmd.execute(&backend), 4.into());
+        assert_eq!(lrange(&backend, "mylist"), array(&["z", "a", "b", "c"]));
+
+        let cmd = LRange {
+            key: "mylist".to_string(),
+            start: -3,
+            stop: 1,
+        };
+        assert_eq!(cmd.execute(&backend), array(&["a"]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lmove_command() -> Result<()> {
+        let backend = Backend::new();
+        backend.rpush(
+            "src".to_string(),
+            vec!["one".to_string(), "two".to_string(), "three".to_string()],
+        )?;
+
+        // 同一个列表，相当于旋转
+        let cmd = LMove {
+            source: "src".to_string(),
+            destination: "src".to_string(),
+            from: ListDirection::Left,
+            to: ListDirection::Right,
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::from("one").into());
+        assert_eq!(lrange(&backend, "src"), array(&["two", "three", "one"]));
+
+        let cmd = LMove {
+            source: "src".to_string(),
+            destination: "dst".to_string(),
+            from: ListDirection::Right,
+            to: ListDirection::Left,
+        };
+        assert_eq!(cmd.execute(&backend), BulkString::from("one").into());
+        assert_eq!(lrange(&backend, "src"), array(&["two", "three"]));
+        assert_eq!(lrange(&backend, "dst"), array(&["one"]));
+
+        let cmd = LMove {
+            source: "nokey".to_string(),
+            destination: "dst".to_string(),
+            from: ListDirection::Right,
+            to: ListDirection::Left,
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        // destination 是其它类型时不弹出元素
+        backend.set("str".to_string(), BulkString::from("v").into());
+        let cmd = LMove {
+            source: "src".to_string(),
+            destination: "str".to_string(),
+            from: ListDirection::Left,
+            to: ListDirection::Left,
+        };
+        assert!(
+            matches!(cmd.execute(&backend), RespFrame::Error(e) if e.0.starts_with("WRONGTYPE"))
+        );
+        assert_eq!(lrange(&backend, "src"), array(&["two", "three"]));
+
+        // 弹出最后一个元素之后删除 source，不同 shard 和同一个 shard 的 key 都能移动
+        for i in 0..64 {
+            let cmd = LMove {
+                source: if i % 2 == 0 { "src" } else { "dst" }.to_string(),
+                destination: if i % 2 == 0 { "dst" } else { "src" }.to_string(),
+                from: ListDirection::Left,
+                to: ListDirection::Right,
+            };
+            assert!(matches!(cmd.execute(&backend), RespFrame::BulkString(_)));
+        }
+        for i in 0..64 {
+            let cmd = LMove {
+                source: "src".to_string(),
+                destination: format!("dst{}", i),
+                from: ListDirection::Left,
+                to: ListDirection::Right,
+            };
+            cmd.execute(&backend);
+        }
+        assert_eq!(backend.key_type("src"), None);
+        assert_eq!(backend.key_type("dst0"), Some("list"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lmpop_command() -> Result<()> {
+        let backend = Backend::new();
+        backend.rpush(
+            "l2".to_string(),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        )?;
+
+        let cmd = LMPop {
+            keys: vec!["l1".to_string(), "l2".to_string()],
+            direction: ListDirection::Right,
+            count: 2,
+        };
+        let expected = RespArray::new([BulkString::from("l2").into(), array(&["c", "b"])]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = LMPop {
+            keys: vec!["l2".to_string()],
+            direction: ListDirection::Left,
+            count: 10,
+        };
+        let expected = RespArray::new([BulkString::from("l2").into(), array(&["a"])]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        // 列表为空之后 key 被删除
+        assert!(backend.list.get("l2").is_none());
+        let cmd = LMPop {
+            keys: vec!["l1".to_string(), "l2".to_string()],
+            direction: ListDirection::Left,
+            count: 1,
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lpos_command() -> Result<()> {
+        let backend = Backend::new();
+        let values = ["a", "b", "c", "1", "2", "3", "c", "c"];
+        backend.rpush(
+            "mylist".to_string(),
+            values.iter().map(|v| v.to_string()).collect(),
+        )?;
+
+        let lpos = |rank, count, maxlen| LPos {
+            key: "mylist".to_string(),
+            element: "c".to_string(),
+            rank,
+            count,
+            maxlen,
+        };
+
+        assert_eq!(lpos(1, None, 0).execute(&backend), 2.into());
+        assert_eq!(lpos(-1, None, 0).execute(&backend), 7.into());
+        assert_eq!(lpos(2, None, 0).execute(&backend), 6.into());
+
+        let expected = RespArray::new([2.into(), 6.into(), 7.into()]);
+        assert_eq!(lpos(1, Some(0), 0).execute(&backend), expected.into());
+
+        let expected = RespArray::new([7.into(), 6.into()]);
+        assert_eq!(lpos(-1, Some(2), 0).execute(&backend), expected.into());
+
+        assert_eq!(lpos(1, None, 2).execute(&backend), RespNull.into());
+
+        Ok(())
+    }
+}
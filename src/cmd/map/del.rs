@@ -0,0 +1,54 @@
+use crate::{
+    cmd::{extract_args, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame,
+};
+
+/// 删除任意类型的 key，返回删除的 key 的数量
+#[derive(Debug)]
+pub struct Del {
+    pub(crate) keys: Vec<String>,
+}
+
+impl CommandExecutor for Del {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let count = self.keys.iter().filter(|key| backend.del(key)).count();
+        (count as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for Del {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["del"], 1)?;
+
+        let keys = extract_args(value, 1)?
+            .into_iter()
+            .map(parse_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Del { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_del_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$3\r\ndel\r\n$5\r\nhello\r\n$5\r\nworld\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: Del = frame.try_into()?;
+        assert_eq!(result.keys, vec!["hello", "world"]);
+
+        Ok(())
+    }
+}
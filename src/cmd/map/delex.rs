@@ -0,0 +1,59 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SetCondition,
+};
+
+use super::parse_condition;
+
+/// DELEX key [IFEQ value | IFNE value]，用于安全地释放锁，返回删除的 key 的数量
+#[derive(Debug)]
+pub struct DelEx {
+    pub(crate) key: String,
+    pub(crate) condition: Option<SetCondition>,
+}
+
+impl CommandExecutor for DelEx {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.delex(&self.key, self.condition.as_ref()) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for DelEx {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["delex"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let condition = parse_condition(args)?;
+        Ok(DelEx { key, condition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_delex_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$5\r\nDELEX\r\n$4\r\nlock\r\n$4\r\nIFNE\r\n$5\r\ntoken\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: DelEx = frame.try_into()?;
+        assert_eq!(result.key, "lock");
+        assert_eq!(
+            result.condition,
+            Some(SetCondition::IfNe(RespFrame::BulkString(b"token".into())))
+        );
+
+        Ok(())
+    }
+}
@@ -1,16 +1,47 @@
+mod del;
+mod delex;
 mod get;
+mod object;
 mod set;
 
+pub use del::*;
+pub use delex::*;
 pub use get::*;
+pub use object::*;
 pub use set::*;
 
+use crate::{RespFrame, SetCondition};
+
+use super::{parse_string, CommandError};
+
+// 解析可选的 IFEQ value | IFNE value 参数
+fn parse_condition(
+    mut args: impl Iterator<Item = RespFrame>,
+) -> Result<Option<SetCondition>, CommandError> {
+    let Some(arg) = args.next() else {
+        return Ok(None);
+    };
+    let condition = match (
+        parse_string(arg)?.to_ascii_lowercase().as_str(),
+        args.next(),
+    ) {
+        ("ifeq", Some(value)) => SetCondition::IfEq(value),
+        ("ifne", Some(value)) => SetCondition::IfNe(value),
+        _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+    };
+    if args.next().is_some() {
+        return Err(CommandError::InvalidArgument("syntax error".to_string()));
+    }
+    Ok(Some(condition))
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
     use crate::{
         cmd::{CommandExecutor, RESP_OK},
-        Backend, RespFrame,
+        Backend, RespFrame, RespNull,
     };
 
     use super::*;
@@ -20,6 +51,7 @@ mod tests {
         let cmd = Set {
             key: "hello".to_string(),
             value: RespFrame::BulkString(b"world".into()),
+            condition: None,
         };
         let result = cmd.execute(&backend);
         assert_eq!(result, RESP_OK.clone());
@@ -32,4 +64,128 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_del_command() -> Result<()> {
+        let backend = Backend::new();
+        backend.set("hello".to_string(), RespFrame::BulkString(b"world".into()));
+        backend.hset(
+            "map".to_string(),
+            "field".to_string(),
+            RespFrame::BulkString(b"value".into()),
+        )?;
+        backend.sadd("set".to_string(), "member".to_string())?;
+
+        let cmd = Del {
+            keys: vec![
+                "hello".to_string(),
+                "map".to_string(),
+                "set".to_string(),
+                "nokey".to_string(),
+            ],
+        };
+        assert_eq!(cmd.execute(&backend), 3.into());
+        assert!(backend.get("hello").is_none());
+        assert!(backend.hgetall("map").is_none());
+
+        Ok(())
+    }
+
+    fn bulk(s: &str) -> RespFrame {
+        RespFrame::BulkString(s.into())
+    }
+
+    #[test]
+    fn test_set_condition_command() -> Result<()> {
+        let backend = Backend::new();
+        let set = |value: &str, condition| Set {
+            key: "lock".to_string(),
+            value: bulk(value),
+            condition: Some(condition),
+        };
+
+        // key 不存在时 IFEQ 失败，IFNE 成功
+        let cmd = set("a", SetCondition::IfEq(bulk("a")));
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+        assert!(backend.get("lock").is_none());
+        let cmd = set("a", SetCondition::IfNe(bulk("a")));
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+
+        let cmd = set("b", SetCondition::IfEq(bulk("x")));
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+        let cmd = set("b", SetCondition::IfEq(bulk("a")));
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        let cmd = set("c", SetCondition::IfNe(bulk("b")));
+        assert_eq!(cmd.execute(&backend), RespFrame::Null(RespNull));
+        assert_eq!(backend.get("lock"), Some(bulk("b")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delex_command() -> Result<()> {
+        let backend = Backend::new();
+        backend.set("lock".to_string(), bulk("token1"));
+
+        let cmd = DelEx {
+            key: "lock".to_string(),
+            condition: Some(SetCondition::IfEq(bulk("token2"))),
+        };
+        assert_eq!(cmd.execute(&backend), 0.into());
+        assert!(backend.get("lock").is_some());
+
+        let cmd = DelEx {
+            key: "lock".to_string(),
+            condition: Some(SetCondition::IfEq(bulk("token1"))),
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        assert!(backend.get("lock").is_none());
+
+        backend.set("lock".to_string(), bulk("token1"));
+        let cmd = DelEx {
+            key: "lock".to_string(),
+            condition: None,
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        let cmd = DelEx {
+            key: "lock".to_string(),
+            condition: None,
+        };
+        assert_eq!(cmd.execute(&backend), 0.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_object_version_command() -> Result<()> {
+        let backend = Backend::new();
+        let version = |backend: &Backend| {
+            ObjectVersion {
+                key: "counter".to_string(),
+            }
+            .execute(backend)
+        };
+        assert_eq!(version(&backend), RespFrame::Null(RespNull));
+
+        backend.set("counter".to_string(), bulk("1"));
+        let RespFrame::Integer(v1) = version(&backend) else {
+            panic!("expected integer");
+        };
+        backend.set("other".to_string(), bulk("1"));
+        backend.set("counter".to_string(), bulk("2"));
+        let RespFrame::Integer(v2) = version(&backend) else {
+            panic!("expected integer");
+        };
+        assert!(v2 > v1);
+
+        backend.del("counter");
+        assert_eq!(version(&backend), RespFrame::Null(RespNull));
+        backend.set("counter".to_string(), bulk("3"));
+        let RespFrame::Integer(v3) = version(&backend) else {
+            panic!("expected integer");
+        };
+        assert!(v3 > v2);
+
+        Ok(())
+    }
 }
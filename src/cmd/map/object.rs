@@ -0,0 +1,58 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+/// OBJECT VERSION key，返回字符串 key 的版本号，每次写入之后都会变大
+#[derive(Debug)]
+pub struct ObjectVersion {
+    pub(crate) key: String,
+}
+
+impl CommandExecutor for ObjectVersion {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.object_version(&self.key) {
+            Some(version) => (version as i64).into(),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ObjectVersion {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["object", "version"], 1)?;
+
+        let mut args = extract_args(value, 2)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        Ok(ObjectVersion { key })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_object_version_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nOBJECT\r\n$7\r\nversion\r\n$4\r\nlock\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ObjectVersion = frame.try_into()?;
+        assert_eq!(result.key, "lock");
+
+        buf.extend_from_slice(b"*3\r\n$6\r\nobject\r\n$8\r\nencoding\r\n$4\r\nlock\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(ObjectVersion::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
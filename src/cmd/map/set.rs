@@ -1,31 +1,45 @@
 use crate::{
-    cmd::{extract_args, validate_command, CommandError, CommandExecutor, RESP_OK},
-    RespArray, RespFrame,
+    cmd::{extract_args, validate_min_args, CommandError, CommandExecutor, RESP_OK},
+    RespArray, RespFrame, RespNull, SetCondition, SimpleError,
 };
 
+use super::parse_condition;
+
+/// SET key value [IFEQ old | IFNE old]，条件不满足时返回 nil
 #[derive(Debug)]
 pub struct Set {
     pub(crate) key: String,
     pub(crate) value: RespFrame,
+    pub(crate) condition: Option<SetCondition>,
 }
 
 impl CommandExecutor for Set {
     fn execute(self, backend: &crate::Backend) -> RespFrame {
-        backend.set(self.key, self.value);
-        RESP_OK.clone()
+        match self.condition {
+            Some(condition) => match backend.set_if(self.key, self.value, &condition) {
+                Ok(true) => RESP_OK.clone(),
+                Ok(false) => RespFrame::Null(RespNull),
+                Err(e) => SimpleError::new(e.to_string()).into(),
+            },
+            None => {
+                backend.set(self.key, self.value);
+                RESP_OK.clone()
+            }
+        }
     }
 }
 
 impl TryFrom<RespArray> for Set {
     type Error = CommandError;
     fn try_from(value: RespArray) -> Result<Self, Self::Error> {
-        validate_command(&value, &["set"], 2)?;
+        validate_min_args(&value, &["set"], 2)?;
 
         let mut args = extract_args(value, 1)?.into_iter();
         match (args.next(), args.next()) {
             (Some(RespFrame::BulkString(key)), Some(value)) => Ok(Set {
                 key: String::from_utf8(key.0)?,
                 value,
+                condition: parse_condition(args)?,
             }),
             _ => Err(CommandError::InvalidArgument(
                 "Invalid key or value".to_string(),
@@ -53,6 +67,21 @@ mod tests {
         let result: Set = frame.try_into()?;
         assert_eq!(result.key, "hello");
         assert_eq!(result.value, RespFrame::BulkString(b"world".into()));
+        assert_eq!(result.condition, None);
+
+        buf.extend_from_slice(
+            b"*5\r\n$3\r\nSET\r\n$4\r\nlock\r\n$2\r\nv2\r\n$4\r\nIFEQ\r\n$2\r\nv1\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Set = frame.try_into()?;
+        assert_eq!(
+            result.condition,
+            Some(SetCondition::IfEq(RespFrame::BulkString(b"v1".into())))
+        );
+
+        buf.extend_from_slice(b"*4\r\n$3\r\nset\r\n$4\r\nlock\r\n$2\r\nv2\r\n$4\r\nIFNE\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Set::try_from(frame).is_err());
 
         Ok(())
     }
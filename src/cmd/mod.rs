@@ -1,23 +1,73 @@
+mod bloom;
+mod cms;
 mod command;
+mod config;
+mod connection;
+mod cuckoo;
+mod custom;
 mod echo;
+mod geo;
 mod hmap;
+mod hyperloglog;
+mod json;
+mod list;
 mod map;
+mod pubsub;
+mod scripting;
+mod search;
 mod set;
+mod stream;
+mod throttle;
+mod timeseries;
+mod topk;
 mod unrecognized;
+mod wasm;
+mod zset;
 
 use crate::{Backend, RespArray, RespError, RespFrame, SimpleString};
 use anyhow::Result;
 use enum_dispatch::enum_dispatch;
 use lazy_static::lazy_static;
+use std::time::Duration;
 use thiserror::Error;
 
 pub use {
-    command::Command,
+    bloom::{BFAdd, BFExists, BFReserve},
+    cms::{CMSIncrBy, CMSInitByDim, CMSMerge, CMSQuery},
+    command::{Command, LockMode},
+    config::{Config, ConfigKind},
+    connection::ConnectionCommand,
+    cuckoo::{CFAdd, CFDel, CFExists},
+    custom::CustomCommand,
     echo::Echo,
-    hmap::{HGet, HGetAll, HMGet, HSet},
-    map::{Get, Set},
+    geo::{GeoAdd, GeoDist, GeoHash, GeoPos, GeoSearch, GeoSearchStore},
+    hmap::{HDel, HGet, HGetAll, HMGet, HSet},
+    hyperloglog::{PFAdd, PFCount, PFMerge},
+    json::{
+        JsonArrAppend, JsonArrInsert, JsonArrPop, JsonDel, JsonGet, JsonLen, JsonNumIncrBy,
+        JsonObjKeys, JsonSet, JsonType,
+    },
+    list::{LMPop, LMove, LPos, LPush, LRange, RPush},
+    map::{Del, DelEx, Get, ObjectVersion, Set},
+    pubsub::{PubSubCommand, PubSubKind, Publish},
+    scripting::{Eval, EvalSha, FCall, Function, FunctionKind, Script, ScriptKind},
+    search::{FTCreate, FTDropIndex, FTInfo, FTSearch},
     set::{SAdd, SIsmember},
+    stream::{
+        XAck, XAdd, XAutoClaim, XClaim, XDel, XGroup, XGroupKind, XInfo, XInfoKind, XLen, XPending,
+        XPendingRange, XRange, XRead, XReadGroup, XReadId, XTrim,
+    },
+    throttle::CLThrottle,
+    timeseries::{
+        TSAdd, TSCreate, TSCreateRule, TSDeleteRule, TSGet, TSIncrBy, TSMAdd, TSMRange, TSRange,
+    },
+    topk::{TopKAdd, TopKList, TopKQuery, TopKReserve},
     unrecognized::Unrecognized,
+    wasm::{WasmCall, WasmLoad},
+    zset::{
+        BZMPop, BZPop, ZAdd, ZCard, ZCount, ZIncrBy, ZInterCard, ZMPop, ZMScore, ZPop, ZRange,
+        ZRangeStore, ZRank, ZRem, ZRemRange, ZScore, ZSetOp,
+    },
 };
 
 // you could also use once_cell instead of lazy_static
@@ -43,6 +93,17 @@ pub trait CommandExecutor {
     fn execute(self, backend: &Backend) -> RespFrame;
 }
 
+/// 阻塞命令，没有数据时由 network 层在 keys 上等待其它连接写入后重试
+pub trait BlockingCommandExecutor: Send + Sync {
+    fn keys(&self) -> &[String];
+    // None 表示一直阻塞
+    fn timeout(&self) -> Option<Duration>;
+    // 阻塞之前调用一次，例如 XREAD 需要把 "$" 解析成当前最后一个 ID
+    fn prepare(&mut self, _backend: &Backend) {}
+    // 有数据时返回结果，没有数据时返回 None 继续等待
+    fn try_execute(&self, backend: &Backend) -> Option<RespFrame>;
+}
+
 pub fn validate_command(
     value: &RespArray,
     names: &[&'static str],
@@ -78,6 +139,100 @@ pub fn validate_command(
     Ok(())
 }
 
+// 变长参数的命令，校验至少有 min_args 个参数
+fn validate_min_args(
+    value: &RespArray,
+    names: &[&'static str],
+    min_args: usize,
+) -> Result<(), CommandError> {
+    validate_command(value, names, usize::MAX)?;
+    if value.len() < min_args + names.len() {
+        return Err(CommandError::InvalidArgument(format!(
+            "{} command must have at least {} arguments",
+            names.join(" "),
+            min_args
+        )));
+    }
+
+    Ok(())
+}
+
 fn extract_args(value: RespArray, start: usize) -> Result<Vec<RespFrame>, CommandError> {
     Ok(value.0.into_iter().skip(start).collect::<Vec<RespFrame>>())
 }
+
+// 解析 numkeys [key ...] [arg ...]
+fn parse_keys_and_args(
+    mut args: impl Iterator<Item = RespFrame>,
+) -> Result<(Vec<String>, Vec<Vec<u8>>), CommandError> {
+    let numkeys: i64 = parse_number(next_arg(&mut args)?)?;
+    if numkeys < 0 {
+        return Err(CommandError::InvalidArgument(
+            "Number of keys can't be negative".to_string(),
+        ));
+    }
+    let args = args.collect::<Vec<_>>();
+    if numkeys as usize > args.len() {
+        return Err(CommandError::InvalidArgument(
+            "Number of keys can't be greater than number of args".to_string(),
+        ));
+    }
+
+    let mut args = args.into_iter();
+    let keys = args
+        .by_ref()
+        .take(numkeys as usize)
+        .map(parse_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    let args = args.map(parse_bytes).collect::<Result<Vec<_>, _>>()?;
+    Ok((keys, args))
+}
+
+// 取出下一个参数，参数不够时返回语法错误
+fn next_arg(args: &mut impl Iterator<Item = RespFrame>) -> Result<RespFrame, CommandError> {
+    args.next()
+        .ok_or_else(|| CommandError::InvalidArgument("syntax error".to_string()))
+}
+
+// 从 BulkString 参数中解析出 String
+fn parse_string(frame: RespFrame) -> Result<String, CommandError> {
+    match frame {
+        RespFrame::BulkString(s) => Ok(String::from_utf8(s.0)?),
+        _ => Err(CommandError::InvalidArgument(
+            "Argument must be a BulkString".to_string(),
+        )),
+    }
+}
+
+// 从 BulkString 参数中取出原始字节，用于不要求是 utf8 的元素
+fn parse_bytes(frame: RespFrame) -> Result<Vec<u8>, CommandError> {
+    match frame {
+        RespFrame::BulkString(s) => Ok(s.0),
+        _ => Err(CommandError::InvalidArgument(
+            "Argument must be a BulkString".to_string(),
+        )),
+    }
+}
+
+// 从 BulkString 参数中解析出数字，例如 count, index 等
+fn parse_number<T: std::str::FromStr>(frame: RespFrame) -> Result<T, CommandError> {
+    let s = parse_string(frame)?;
+    s.parse()
+        .map_err(|_| CommandError::InvalidArgument(format!("value is not a valid number: {}", s)))
+}
+
+// 阻塞命令的超时时间，单位是秒，0 表示一直阻塞
+fn parse_timeout(frame: RespFrame) -> Result<Option<Duration>, CommandError> {
+    let timeout: f64 = parse_number(frame)?;
+    if !timeout.is_finite() || timeout < 0.0 {
+        return Err(CommandError::InvalidArgument(
+            "timeout is negative or out of range".to_string(),
+        ));
+    }
+
+    if timeout == 0.0 {
+        Ok(None)
+    } else {
+        Ok(Some(Duration::from_secs_f64(timeout)))
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    cmd::{
+        extract_args, parse_string, validate_command, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, RespArray, RespFrame,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PubSubKind {
+    Channels(Option<String>),
+    NumSub(Vec<String>),
+    NumPat,
+    // 只是占位：没有实现 SSUBSCRIBE 等分片订阅命令，总是返回空
+    ShardChannels(Option<String>),
+}
+
+/// PUBSUB CHANNELS [pattern] | NUMSUB [channel ...] | NUMPAT | SHARDCHANNELS [pattern]
+/// SHARDCHANNELS 只为兼容客户端，不支持分片订阅，总是返回空数组
+#[derive(Debug)]
+pub struct PubSubCommand {
+    pub(crate) kind: PubSubKind,
+}
+
+impl CommandExecutor for PubSubCommand {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match self.kind {
+            PubSubKind::Channels(pattern) => {
+                let channels = backend
+                    .pubsub_channels(pattern.as_deref())
+                    .into_iter()
+                    .map(|channel| BulkString::from(channel).into())
+                    .collect::<Vec<RespFrame>>();
+                RespArray::new(channels).into()
+            }
+            PubSubKind::NumSub(channels) => {
+                let mut frames = Vec::with_capacity(channels.len() * 2);
+                for channel in channels {
+                    let count = backend.pubsub_numsub(&channel) as i64;
+                    frames.push(BulkString::from(channel).into());
+                    frames.push(count.into());
+                }
+                RespArray::new(frames).into()
+            }
+            PubSubKind::NumPat => (backend.pubsub_numpat() as i64).into(),
+            PubSubKind::ShardChannels(_) => RespArray::new([]).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for PubSubCommand {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+
+        let kind = match sub.as_str() {
+            "channels" | "shardchannels" => {
+                if value.len() > 3 {
+                    return Err(CommandError::InvalidArgument(format!(
+                        "pubsub {} command must have at most 1 argument",
+                        sub
+                    )));
+                }
+                let pattern = extract_args(value, 2)?
+                    .into_iter()
+                    .next()
+                    .map(parse_string)
+                    .transpose()?;
+                match sub.as_str() {
+                    "channels" => PubSubKind::Channels(pattern),
+                    _ => PubSubKind::ShardChannels(pattern),
+                }
+            }
+            "numsub" => {
+                validate_min_args(&value, &["pubsub", "numsub"], 0)?;
+                let channels = extract_args(value, 2)?
+                    .into_iter()
+                    .map(parse_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                PubSubKind::NumSub(channels)
+            }
+            "numpat" => {
+                validate_command(&value, &["pubsub", "numpat"], 0)?;
+                PubSubKind::NumPat
+            }
+            _ => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "unknown subcommand '{}'",
+                    sub
+                )))
+            }
+        };
+        Ok(PubSubCommand { kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_pubsub_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nPUBSUB\r\n$8\r\nCHANNELS\r\n$6\r\nnews.*\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: PubSubCommand = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            PubSubKind::Channels(Some("news.*".to_string()))
+        );
+
+        buf.extend_from_slice(b"*4\r\n$6\r\npubsub\r\n$6\r\nnumsub\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: PubSubCommand = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            PubSubKind::NumSub(vec!["a".to_string(), "b".to_string()])
+        );
+
+        buf.extend_from_slice(b"*2\r\n$6\r\npubsub\r\n$6\r\nnumpat\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: PubSubCommand = frame.try_into()?;
+        assert_eq!(result.kind, PubSubKind::NumPat);
+
+        buf.extend_from_slice(b"*2\r\n$6\r\npubsub\r\n$4\r\nhelp\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(PubSubCommand::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
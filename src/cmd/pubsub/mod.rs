@@ -0,0 +1,99 @@
+mod introspect;
+mod publish;
+
+pub use introspect::*;
+pub use publish::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::CommandExecutor, Backend, BulkString, PubSubMessage, RespArray, RespFrame, Subscriber,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_publish() -> Result<()> {
+        let backend = Backend::new();
+        let publish = || Publish {
+            channel: "news".to_string(),
+            message: b"hello".to_vec(),
+        };
+        assert_eq!(publish().execute(&backend), RespFrame::Integer(0));
+
+        // 已经断开的订阅者不计算在内
+        let (tx, mut rx) = Subscriber::new();
+        let (closed, _) = Subscriber::new();
+        backend.subscribe("news".to_string(), 1, tx);
+        backend.subscribe("news".to_string(), 2, closed);
+        assert_eq!(publish().execute(&backend), RespFrame::Integer(1));
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(
+            message,
+            PubSubMessage::Message {
+                channel: "news".to_string(),
+                payload: b"hello".to_vec(),
+            }
+        );
+        let expected: Vec<RespFrame> = vec![
+            BulkString::from("message").into(),
+            BulkString::from("news").into(),
+            BulkString::from("hello").into(),
+        ];
+        assert_eq!(message.into_frames(), expected);
+
+        backend.unsubscribe("news", 1);
+        backend.unsubscribe("news", 2);
+        assert_eq!(publish().execute(&backend), RespFrame::Integer(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pubsub_introspection() -> Result<()> {
+        let backend = Backend::new();
+        let (tx, _rx) = Subscriber::new();
+        backend.subscribe("news.tech".to_string(), 1, tx.clone());
+        backend.subscribe("news.sport".to_string(), 1, tx.clone());
+        backend.subscribe("news.tech".to_string(), 2, tx.clone());
+        backend.subscribe("alerts".to_string(), 1, tx.clone());
+        backend.psubscribe("news.*".to_string(), 1, tx.clone());
+        backend.psubscribe("news.*".to_string(), 2, tx.clone());
+        backend.psubscribe("alerts.*".to_string(), 2, tx);
+
+        let channels = |pattern: Option<&str>| PubSubCommand {
+            kind: PubSubKind::Channels(pattern.map(str::to_string)),
+        };
+        let expected = RespArray::new([
+            BulkString::from("alerts").into(),
+            BulkString::from("news.sport").into(),
+            BulkString::from("news.tech").into(),
+        ]);
+        assert_eq!(channels(None).execute(&backend), expected.into());
+        let expected = RespArray::new([BulkString::from("news.tech").into()]);
+        assert_eq!(channels(Some("news.t*")).execute(&backend), expected.into());
+
+        // 模式订阅不计入 NUMSUB
+        let numsub = PubSubCommand {
+            kind: PubSubKind::NumSub(vec!["news.tech".to_string(), "none".to_string()]),
+        };
+        let expected = RespArray::new([
+            BulkString::from("news.tech").into(),
+            RespFrame::Integer(2),
+            BulkString::from("none").into(),
+            RespFrame::Integer(0),
+        ]);
+        assert_eq!(numsub.execute(&backend), expected.into());
+
+        // 多个连接订阅同一个模式只算一次
+        let numpat = PubSubCommand {
+            kind: PubSubKind::NumPat,
+        };
+        assert_eq!(numpat.execute(&backend), RespFrame::Integer(2));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    Backend, RespArray, RespFrame,
+};
+
+/// PUBLISH channel message，返回收到消息的订阅者数量
+#[derive(Debug)]
+pub struct Publish {
+    pub(crate) channel: String,
+    pub(crate) message: Vec<u8>,
+}
+
+impl CommandExecutor for Publish {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        (backend.publish(&self.channel, self.message) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for Publish {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["publish"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let channel = parse_string(next_arg(&mut args)?)?;
+        let message = parse_bytes(next_arg(&mut args)?)?;
+        Ok(Publish { channel, message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_publish_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$7\r\nPUBLISH\r\n$4\r\nnews\r\n$5\r\nhello\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Publish = frame.try_into()?;
+        assert_eq!(result.channel, "news");
+        assert_eq!(result.message, b"hello".to_vec());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_keys_and_args, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame,
+};
+
+use super::lua::eval_script;
+
+/// EVAL script numkeys [key ...] [arg ...]，EVAL_RO 中不能调用写命令
+#[derive(Debug)]
+pub struct Eval {
+    pub(crate) script: String,
+    pub(crate) keys: Vec<String>,
+    pub(crate) args: Vec<Vec<u8>>,
+    pub(crate) readonly: bool,
+}
+
+impl CommandExecutor for Eval {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        // 和 Redis 一样，EVAL 执行过的脚本也可以用 EVALSHA 调用
+        backend.script_load(self.script.clone());
+        eval_script(backend, &self.script, self.keys, self.args, self.readonly)
+    }
+}
+
+impl TryFrom<RespArray> for Eval {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let readonly = match value.first() {
+            Some(RespFrame::BulkString(cmd)) => cmd.eq_ignore_ascii_case(b"eval_ro"),
+            _ => false,
+        };
+        let name = if readonly { "eval_ro" } else { "eval" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let script = parse_string(next_arg(&mut args)?)?;
+        let (keys, args) = parse_keys_and_args(args)?;
+        Ok(Eval {
+            script,
+            keys,
+            args,
+            readonly,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_eval_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*5\r\n$4\r\nEVAL\r\n$24\r\nreturn {KEYS[1],ARGV[1]}\r\n$1\r\n1\r\n$3\r\nkey\r\n$3\r\narg\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Eval = frame.try_into()?;
+        assert_eq!(result.script, "return {KEYS[1],ARGV[1]}");
+        assert_eq!(result.keys, vec!["key".to_string()]);
+        assert_eq!(result.args, vec![b"arg".to_vec()]);
+        assert!(!result.readonly);
+
+        buf.extend_from_slice(b"*4\r\n$7\r\neval_ro\r\n$8\r\nreturn 1\r\n$1\r\n2\r\n$3\r\nkey\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(Eval::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
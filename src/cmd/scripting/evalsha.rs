@@ -0,0 +1,77 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_keys_and_args, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::lua::eval_script;
+
+/// EVALSHA sha1 numkeys [key ...] [arg ...]，执行 SCRIPT LOAD 或者 EVAL 缓存的脚本
+#[derive(Debug)]
+pub struct EvalSha {
+    pub(crate) sha: String,
+    pub(crate) keys: Vec<String>,
+    pub(crate) args: Vec<Vec<u8>>,
+    pub(crate) readonly: bool,
+}
+
+impl CommandExecutor for EvalSha {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.script_get(&self.sha) {
+            Some(script) => eval_script(backend, &script, self.keys, self.args, self.readonly),
+            None => {
+                SimpleError::new("NOSCRIPT No matching script. Please use EVAL.".to_string()).into()
+            }
+        }
+    }
+}
+
+impl TryFrom<RespArray> for EvalSha {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let readonly = match value.first() {
+            Some(RespFrame::BulkString(cmd)) => cmd.eq_ignore_ascii_case(b"evalsha_ro"),
+            _ => false,
+        };
+        let name = if readonly { "evalsha_ro" } else { "evalsha" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let sha = parse_string(next_arg(&mut args)?)?;
+        let (keys, args) = parse_keys_and_args(args)?;
+        Ok(EvalSha {
+            sha,
+            keys,
+            args,
+            readonly,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_evalsha_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$10\r\nEVALSHA_RO\r\n$40\r\ne0e1f9fabfc9d4800c877a703b823ac0578ff8db\r\n$1\r\n0\r\n$3\r\narg\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: EvalSha = frame.try_into()?;
+        assert_eq!(result.sha, "e0e1f9fabfc9d4800c877a703b823ac0578ff8db");
+        assert!(result.keys.is_empty());
+        assert_eq!(result.args, vec![b"arg".to_vec()]);
+        assert!(result.readonly);
+
+        Ok(())
+    }
+}
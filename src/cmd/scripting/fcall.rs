@@ -0,0 +1,88 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_keys_and_args, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::lua::call_function;
+
+/// FCALL function numkeys [key ...] [arg ...]，FCALL_RO 只能调用声明了 no-writes 的函数
+#[derive(Debug)]
+pub struct FCall {
+    pub(crate) function: String,
+    pub(crate) keys: Vec<String>,
+    pub(crate) args: Vec<Vec<u8>>,
+    pub(crate) readonly: bool,
+}
+
+impl CommandExecutor for FCall {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let (library, info) = match backend.function_get(&self.function) {
+            Some(found) => found,
+            None => return SimpleError::new("ERR Function not found".to_string()).into(),
+        };
+        if self.readonly && !info.no_writes() {
+            return SimpleError::new(
+                "ERR Can not execute a script with write flag using *_ro command.".to_string(),
+            )
+            .into();
+        }
+        // 声明了 no-writes 的函数用 FCALL 调用时也不能执行写命令
+        let readonly = self.readonly || info.no_writes();
+        call_function(
+            backend, &library, &info.name, self.keys, self.args, readonly,
+        )
+    }
+}
+
+impl TryFrom<RespArray> for FCall {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let readonly = match value.first() {
+            Some(RespFrame::BulkString(cmd)) => cmd.eq_ignore_ascii_case(b"fcall_ro"),
+            _ => false,
+        };
+        let name = if readonly { "fcall_ro" } else { "fcall" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let function = parse_string(next_arg(&mut args)?)?;
+        let (keys, args) = parse_keys_and_args(args)?;
+        Ok(FCall {
+            function,
+            keys,
+            args,
+            readonly,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_fcall_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$8\r\nFCALL_RO\r\n$5\r\nmyget\r\n$1\r\n1\r\n$3\r\nkey\r\n$3\r\narg\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FCall = frame.try_into()?;
+        assert_eq!(result.function, "myget");
+        assert_eq!(result.keys, vec!["key".to_string()]);
+        assert_eq!(result.args, vec![b"arg".to_vec()]);
+        assert!(result.readonly);
+
+        Ok(())
+    }
+}
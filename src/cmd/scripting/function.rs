@@ -0,0 +1,259 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_command, validate_min_args,
+        CommandError, CommandExecutor, RESP_OK,
+    },
+    decode_function_dump, Backend, BackendError, BulkString, Library, RespArray, RespFrame,
+    RespNull, RestorePolicy, SimpleError,
+};
+
+use super::lua::load_library;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FunctionKind {
+    Load {
+        code: String,
+        replace: bool,
+    },
+    List {
+        withcode: bool,
+    },
+    Delete(String),
+    Flush,
+    Dump,
+    Restore {
+        payload: Vec<u8>,
+        policy: RestorePolicy,
+    },
+}
+
+/// FUNCTION LOAD | LIST | DELETE | FLUSH | DUMP | RESTORE
+#[derive(Debug)]
+pub struct Function {
+    pub(crate) kind: FunctionKind,
+}
+
+impl Function {
+    /// LOAD 和 RESTORE 需要执行库代码
+    pub(crate) fn runs_code(&self) -> bool {
+        matches!(
+            self.kind,
+            FunctionKind::Load { .. } | FunctionKind::Restore { .. }
+        )
+    }
+}
+
+impl CommandExecutor for Function {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = match self.kind {
+            FunctionKind::Load { code, replace } => match load_library(backend, &code) {
+                Ok(library) => backend
+                    .function_load(library, replace)
+                    .map(|name| BulkString::from(name).into()),
+                Err(e) => return SimpleError::new(e).into(),
+            },
+            FunctionKind::List { withcode } => {
+                let libraries = backend
+                    .function_list()
+                    .into_iter()
+                    .map(|library| library_frame(library, withcode))
+                    .collect::<Vec<_>>();
+                Ok(RespArray::new(libraries).into())
+            }
+            FunctionKind::Delete(name) => backend.function_delete(&name).map(|_| RESP_OK.clone()),
+            FunctionKind::Flush => {
+                backend.function_flush();
+                Ok(RESP_OK.clone())
+            }
+            FunctionKind::Dump => Ok(BulkString::new(backend.function_dump()).into()),
+            FunctionKind::Restore { payload, policy } => {
+                match restore_libraries(backend, &payload) {
+                    Ok(libraries) => backend
+                        .function_restore(libraries, policy)
+                        .map(|_| RESP_OK.clone()),
+                    Err(e) => return SimpleError::new(e).into(),
+                }
+            }
+        };
+        ret.unwrap_or_else(|e: BackendError| SimpleError::new(e.to_string()).into())
+    }
+}
+
+// 重新执行每个库的代码得到注册的函数
+fn restore_libraries(backend: &Backend, payload: &[u8]) -> Result<Vec<Library>, String> {
+    decode_function_dump(payload)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|code| load_library(backend, code))
+        .collect()
+}
+
+fn library_frame(library: Library, withcode: bool) -> RespFrame {
+    let functions = library
+        .functions
+        .into_iter()
+        .map(|f| {
+            let description = match f.description {
+                Some(description) => BulkString::from(description).into(),
+                None => RespFrame::Null(RespNull),
+            };
+            let flags = f
+                .flags
+                .into_iter()
+                .map(|flag| BulkString::from(flag).into())
+                .collect::<Vec<RespFrame>>();
+            RespArray::new([
+                BulkString::from("name").into(),
+                BulkString::from(f.name).into(),
+                BulkString::from("description").into(),
+                description,
+                BulkString::from("flags").into(),
+                RespArray::new(flags).into(),
+            ])
+            .into()
+        })
+        .collect::<Vec<RespFrame>>();
+
+    let mut frames: Vec<RespFrame> = vec![
+        BulkString::from("library_name").into(),
+        BulkString::from(library.name).into(),
+        BulkString::from("engine").into(),
+        BulkString::from("LUA").into(),
+        BulkString::from("functions").into(),
+        RespArray::new(functions).into(),
+    ];
+    if withcode {
+        frames.push(BulkString::from("library_code").into());
+        frames.push(BulkString::from(library.code).into());
+    }
+    RespArray::new(frames).into()
+}
+
+impl TryFrom<RespArray> for Function {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+        let syntax_error = || CommandError::InvalidArgument("syntax error".to_string());
+
+        let kind = match sub.as_str() {
+            "load" => {
+                validate_min_args(&value, &["function", "load"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let mut replace = false;
+                let mut code = parse_string(next_arg(&mut args)?)?;
+                if code.eq_ignore_ascii_case("replace") {
+                    replace = true;
+                    code = parse_string(next_arg(&mut args)?)?;
+                }
+                if args.next().is_some() {
+                    return Err(syntax_error());
+                }
+                FunctionKind::Load { code, replace }
+            }
+            "list" => {
+                validate_min_args(&value, &["function", "list"], 0)?;
+                let mut withcode = false;
+                for arg in extract_args(value, 2)? {
+                    match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                        "withcode" => withcode = true,
+                        _ => return Err(syntax_error()),
+                    }
+                }
+                FunctionKind::List { withcode }
+            }
+            "delete" => {
+                validate_command(&value, &["function", "delete"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                FunctionKind::Delete(parse_string(next_arg(&mut args)?)?)
+            }
+            "flush" => {
+                // ASYNC 和 SYNC 参数只影响释放内存的方式，这里直接忽略
+                validate_min_args(&value, &["function", "flush"], 0)?;
+                if value.len() > 3 {
+                    return Err(syntax_error());
+                }
+                FunctionKind::Flush
+            }
+            "dump" => {
+                validate_command(&value, &["function", "dump"], 0)?;
+                FunctionKind::Dump
+            }
+            "restore" => {
+                validate_min_args(&value, &["function", "restore"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let payload = parse_bytes(next_arg(&mut args)?)?;
+                let policy = match args.next() {
+                    None => RestorePolicy::Append,
+                    Some(policy) => match parse_string(policy)?.to_ascii_lowercase().as_str() {
+                        "append" => RestorePolicy::Append,
+                        "replace" => RestorePolicy::Replace,
+                        "flush" => RestorePolicy::Flush,
+                        _ => return Err(syntax_error()),
+                    },
+                };
+                if args.next().is_some() {
+                    return Err(syntax_error());
+                }
+                FunctionKind::Restore { payload, policy }
+            }
+            _ => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "unknown subcommand '{}'",
+                    sub
+                )))
+            }
+        };
+        Ok(Function { kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_function_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*4\r\n$8\r\nFUNCTION\r\n$4\r\nLOAD\r\n$7\r\nREPLACE\r\n$14\r\n#!lua name=lib\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Function = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            FunctionKind::Load {
+                code: "#!lua name=lib".to_string(),
+                replace: true
+            }
+        );
+
+        buf.extend_from_slice(b"*3\r\n$8\r\nfunction\r\n$4\r\nlist\r\n$8\r\nwithcode\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Function = frame.try_into()?;
+        assert_eq!(result.kind, FunctionKind::List { withcode: true });
+
+        buf.extend_from_slice(
+            b"*4\r\n$8\r\nfunction\r\n$7\r\nrestore\r\n$3\r\nabc\r\n$5\r\nFLUSH\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Function = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            FunctionKind::Restore {
+                payload: b"abc".to_vec(),
+                policy: RestorePolicy::Flush
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use mlua::{Function, HookTriggers, Lua, LuaOptions, StdLib, Table, Value, Variadic};
+use tracing::info;
+
+use crate::{
+    cmd::{Command, ConnectionCommand},
+    sha1_hex, Backend, BulkString, FunctionInfo, Library, RespArray, RespFrame, RespNull,
+    SimpleError, SimpleString, FUNCTION_FLAGS,
+};
+
+// 每执行这么多条指令检查一次脚本是否被 SCRIPT KILL 终止
+const KILL_CHECK_INTERVAL: u32 = 1000;
+// 加载函数库时执行库代码的时间上限
+const LOAD_TIMEOUT: Duration = Duration::from_millis(500);
+// 注册的函数保存在 Lua 注册表的这个表中
+const FUNCTIONS_REGISTRY: &str = "functions";
+
+/// 返回给客户端的错误信息，redis.call 的错误原样返回，不加前缀
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct ScriptError(String);
+
+fn script_err(msg: impl Into<String>) -> mlua::Error {
+    mlua::Error::external(ScriptError(msg.into()))
+}
+
+/// 执行脚本，readonly 为 true 时脚本中不能调用写命令
+pub(crate) fn eval_script(
+    backend: &Backend,
+    source: &str,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+    readonly: bool,
+) -> RespFrame {
+    backend.script_begin();
+    let ret = run(backend, source, keys, args, readonly);
+    backend.script_end();
+    ret.unwrap_or_else(|e| SimpleError::new(error_message(&e)).into())
+}
+
+fn run(
+    backend: &Backend,
+    source: &str,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+    readonly: bool,
+) -> mlua::Result<RespFrame> {
+    let lua = new_lua(backend)?;
+    set_call(&lua, backend, readonly)?;
+    let globals = lua.globals();
+    globals.set("KEYS", lua.create_sequence_from(keys)?)?;
+    globals.set("ARGV", args_table(&lua, &args)?)?;
+
+    let value: Value = lua.load(source).set_name("@user_script").eval()?;
+    Ok(lua_to_frame(value))
+}
+
+/// 执行库代码，返回库名和库中注册的函数，库代码中不能调用 redis.call
+pub(crate) fn load_library(backend: &Backend, code: &str) -> Result<Library, String> {
+    let (name, body) = parse_library_header(code)?;
+    let lua = new_lua(backend).map_err(|e| error_message(&e))?;
+    let started = Instant::now();
+    let triggers = HookTriggers::new().every_nth_instruction(KILL_CHECK_INTERVAL);
+    lua.set_hook(triggers, move |_, _| {
+        if started.elapsed() > LOAD_TIMEOUT {
+            return Err(script_err("ERR FUNCTION LOAD timeout"));
+        }
+        Ok(())
+    });
+
+    let registered = set_register_function(&lua).map_err(|e| error_message(&e))?;
+    lua.load(body)
+        .set_name("@user_function")
+        .exec()
+        .map_err(|e| error_message(&e))?;
+    let functions = registered.lock().unwrap().clone();
+    if functions.is_empty() {
+        return Err("ERR No functions registered".to_string());
+    }
+    // 解释器保存在库中供 FCALL 复用，加载完成后不能再注册函数
+    lua.remove_hook();
+    let redis: Table = lua.globals().get("redis").map_err(|e| error_message(&e))?;
+    redis
+        .set("register_function", Value::Nil)
+        .map_err(|e| error_message(&e))?;
+    drop(redis);
+    Ok(Library {
+        name,
+        code: code.to_string(),
+        functions,
+        lua: Arc::new(Mutex::new(lua)),
+    })
+}
+
+/// 调用库中注册的函数，函数的参数是 keys 和 args 两个表
+pub(crate) fn call_function(
+    backend: &Backend,
+    library: &Library,
+    function: &str,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+    readonly: bool,
+) -> RespFrame {
+    backend.script_begin();
+    let ret = run_function(backend, library, function, keys, args, readonly);
+    backend.script_end();
+    ret.unwrap_or_else(|e| SimpleError::new(error_message(&e)).into())
+}
+
+fn run_function(
+    backend: &Backend,
+    library: &Library,
+    function: &str,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+    readonly: bool,
+) -> mlua::Result<RespFrame> {
+    let lua = library.lua.lock().unwrap();
+    set_call(&lua, backend, readonly)?;
+    set_kill_hook(&lua, backend);
+    let ret = call_registered(&lua, function, keys, args);
+    // 解释器会一直保存在库中，调用结束后去掉引用 backend 的回调，避免循环引用
+    lua.remove_hook();
+    let redis: Table = lua.globals().get("redis")?;
+    redis.set("call", Value::Nil)?;
+    redis.set("pcall", Value::Nil)?;
+    ret
+}
+
+fn call_registered(
+    lua: &Lua,
+    function: &str,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+) -> mlua::Result<RespFrame> {
+    let functions: Table = lua.named_registry_value(FUNCTIONS_REGISTRY)?;
+    let callback: Function = functions.get(function)?;
+    let keys = lua.create_sequence_from(keys)?;
+    let value: Value = callback.call((keys, args_table(lua, &args)?))?;
+    Ok(lua_to_frame(value))
+}
+
+// 第一行是 #!lua name=<library>，返回库名和去掉第一行之后的代码，保留换行让错误信息中的行号不变
+fn parse_library_header(code: &str) -> Result<(String, String), String> {
+    let (header, rest) = code.split_once('\n').unwrap_or((code, ""));
+    let header = header
+        .strip_prefix("#!")
+        .ok_or_else(|| "ERR Missing library metadata".to_string())?;
+    let mut parts = header.split_whitespace();
+    let engine = parts.next().unwrap_or_default();
+    if !engine.eq_ignore_ascii_case("lua") {
+        return Err(format!("ERR Engine '{}' not found", engine));
+    }
+
+    let mut name = None;
+    for part in parts {
+        match part.split_once('=') {
+            Some(("name", value)) => name = Some(value.to_string()),
+            _ => return Err(format!("ERR Invalid metadata value given: {}", part)),
+        }
+    }
+    let name = name.ok_or_else(|| "ERR Library name was not given".to_string())?;
+    if !valid_name(&name) {
+        return Err("ERR Library names can only contain letters, numbers, or underscores(_) and must be at least one character long".to_string());
+    }
+    Ok((name, format!("\n{}", rest)))
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn args_table<'lua>(lua: &'lua Lua, args: &[Vec<u8>]) -> mlua::Result<Table<'lua>> {
+    let args = args
+        .iter()
+        .map(|arg| lua.create_string(arg))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    lua.create_sequence_from(args)
+}
+
+// 和 Redis 一样只加载 base、table、string、math 库，EVAL 每次执行都使用新的解释器
+fn new_lua(backend: &Backend) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(
+        StdLib::TABLE | StdLib::STRING | StdLib::MATH,
+        LuaOptions::default(),
+    )?;
+    let globals = lua.globals();
+    for name in ["loadfile", "dofile"] {
+        globals.set(name, Value::Nil)?;
+    }
+
+    let redis = lua.create_table()?;
+    let error_reply = lua.create_function(|lua, msg: String| reply_table(lua, "err", msg))?;
+    redis.set("error_reply", error_reply)?;
+    let status_reply = lua.create_function(|lua, msg: String| reply_table(lua, "ok", msg))?;
+    redis.set("status_reply", status_reply)?;
+    let sha1hex = lua.create_function(|_, s: mlua::String| Ok(sha1_hex(s.to_str()?)))?;
+    redis.set("sha1hex", sha1hex)?;
+    let log = lua.create_function(|_, (_level, msg): (i64, String)| {
+        info!("Script log: {}", msg);
+        Ok(())
+    })?;
+    redis.set("log", log)?;
+    for (i, level) in ["LOG_DEBUG", "LOG_VERBOSE", "LOG_NOTICE", "LOG_WARNING"]
+        .iter()
+        .enumerate()
+    {
+        redis.set(*level, i)?;
+    }
+    globals.set("redis", redis)?;
+
+    set_kill_hook(&lua, backend);
+    drop(globals);
+    Ok(lua)
+}
+
+// 定期检查脚本是否被 SCRIPT KILL 终止
+fn set_kill_hook(lua: &Lua, backend: &Backend) {
+    let b = backend.clone();
+    let triggers = HookTriggers::new().every_nth_instruction(KILL_CHECK_INTERVAL);
+    lua.set_hook(triggers, move |_, _| {
+        if b.script_killed() {
+            return Err(script_err("ERR Script killed by user with SCRIPT KILL..."));
+        }
+        Ok(())
+    });
+}
+
+// 添加 redis.call 和 redis.pcall
+fn set_call(lua: &Lua, backend: &Backend, readonly: bool) -> mlua::Result<()> {
+    let redis: Table = lua.globals().get("redis")?;
+    let b = backend.clone();
+    let call = lua.create_function(move |lua, args: Variadic<Value>| {
+        match redis_call(&b, &args, readonly) {
+            RespFrame::Error(e) => Err(script_err(e.0)),
+            frame => frame_to_lua(lua, frame),
+        }
+    })?;
+    redis.set("call", call)?;
+    let b = backend.clone();
+    let pcall = lua.create_function(move |lua, args: Variadic<Value>| {
+        frame_to_lua(lua, redis_call(&b, &args, readonly))
+    })?;
+    redis.set("pcall", pcall)
+}
+
+// 添加 redis.register_function，回调保存在注册表中，返回注册的函数信息
+fn set_register_function(lua: &Lua) -> mlua::Result<Arc<Mutex<Vec<FunctionInfo>>>> {
+    lua.set_named_registry_value(FUNCTIONS_REGISTRY, lua.create_table()?)?;
+    let registered = Arc::new(Mutex::new(Vec::<FunctionInfo>::new()));
+    let r = registered.clone();
+    let register = lua.create_function(move |lua, args: Variadic<Value>| {
+        let (info, callback) = parse_register_args(&args)?;
+        let mut registered = r.lock().unwrap();
+        if registered.iter().any(|f| f.name == info.name) {
+            return Err(script_err("ERR Function already exists in the library"));
+        }
+        let functions: Table = lua.named_registry_value(FUNCTIONS_REGISTRY)?;
+        functions.set(info.name.as_str(), callback)?;
+        registered.push(info);
+        Ok(())
+    })?;
+    let redis: Table = lua.globals().get("redis")?;
+    redis.set("register_function", register)?;
+    Ok(registered)
+}
+
+// redis.register_function(name, callback) 或者
+// redis.register_function{function_name=..., callback=..., flags={...}, description=...}
+fn parse_register_args<'lua>(args: &[Value<'lua>]) -> mlua::Result<(FunctionInfo, Function<'lua>)> {
+    let (name, callback, flags, description) = match args {
+        [Value::String(name), Value::Function(callback)] => {
+            (name.to_str()?.to_string(), callback.clone(), vec![], None)
+        }
+        [Value::Table(table)] => {
+            let name: Option<String> = table.get("function_name")?;
+            let name = name.ok_or_else(|| {
+                script_err("ERR redis.register_function must get a function name argument")
+            })?;
+            let callback: Option<Function> = table.get("callback")?;
+            let callback = callback.ok_or_else(|| {
+                script_err("ERR redis.register_function must get a callback argument")
+            })?;
+            let flags = match table.get::<_, Option<Table>>("flags")? {
+                Some(flags) => flags
+                    .sequence_values::<String>()
+                    .collect::<mlua::Result<Vec<_>>>()?,
+                None => vec![],
+            };
+            (name, callback, flags, table.get("description")?)
+        }
+        _ => {
+            return Err(script_err(
+                "ERR wrong number of arguments to redis.register_function",
+            ))
+        }
+    };
+
+    if !valid_name(&name) {
+        return Err(script_err("ERR Function names can only contain letters, numbers, or underscores(_) and must be at least one character long"));
+    }
+    if flags
+        .iter()
+        .any(|flag| !FUNCTION_FLAGS.contains(&flag.as_str()))
+    {
+        return Err(script_err("ERR unknown flag given"));
+    }
+    let info = FunctionInfo {
+        name,
+        description,
+        flags,
+    };
+    Ok((info, callback))
+}
+
+fn reply_table<'lua>(lua: &'lua Lua, field: &str, msg: String) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set(field, msg)?;
+    Ok(table)
+}
+
+fn script_error(msg: &str) -> RespFrame {
+    SimpleError::new(msg.to_string()).into()
+}
+
+// redis.call 和 redis.pcall 共用，错误以 RespFrame::Error 返回
+fn redis_call(backend: &Backend, args: &[Value], readonly: bool) -> RespFrame {
+    if args.is_empty() {
+        return script_error("ERR Please specify at least one argument for this redis lib call");
+    }
+    let mut frames = Vec::with_capacity(args.len());
+    for arg in args {
+        let arg = match arg {
+            Value::String(s) => s.as_bytes().to_vec(),
+            Value::Integer(n) => n.to_string().into_bytes(),
+            Value::Number(n) => n.to_string().into_bytes(),
+            _ => {
+                return script_error(
+                    "ERR Lua redis lib command arguments must be strings or integers",
+                )
+            }
+        };
+        frames.push(BulkString::new(arg).into());
+    }
+
+    let frame: RespFrame = RespArray::new(frames).into();
+    if ConnectionCommand::matches(&frame) {
+        return script_error("ERR This Redis command is not allowed from script");
+    }
+    let cmd = match Command::parse(frame, backend) {
+        Ok(Command::Unrecognized(_)) => {
+            return script_error("ERR Unknown Redis command called from script")
+        }
+        Ok(
+            Command::Eval(_)
+            | Command::EvalSha(_)
+            | Command::Script(_)
+            | Command::Function(_)
+            | Command::FCall(_)
+            | Command::WasmCall(_),
+        ) => return script_error("ERR This Redis command is not allowed from script"),
+        Ok(Command::Custom(cmd)) if cmd.handler().flags().noscript => {
+            return script_error("ERR This Redis command is not allowed from script")
+        }
+        Ok(cmd) => cmd,
+        Err(e) => return SimpleError::new(format!("ERR {}", e)).into(),
+    };
+    if !cmd.is_readonly() {
+        if readonly {
+            return script_error("ERR Write commands are not allowed from read-only scripts.");
+        }
+        backend.script_wrote();
+    }
+    cmd.execute_now(backend)
+}
+
+/// Redis 的返回值转换成 Lua 的值，和 RESP2 的规则一样，nil 转换成 false
+fn frame_to_lua(lua: &Lua, frame: RespFrame) -> mlua::Result<Value<'_>> {
+    let value = match frame {
+        RespFrame::SimpleString(s) => Value::Table(reply_table(lua, "ok", s.0)?),
+        RespFrame::Error(e) => Value::Table(reply_table(lua, "err", e.0)?),
+        RespFrame::Integer(n) => Value::Integer(n),
+        RespFrame::BulkString(s) => Value::String(lua.create_string(&s.0)?),
+        RespFrame::Null(_) => Value::Boolean(false),
+        RespFrame::Boolean(b) => Value::Integer(b as i64),
+        RespFrame::Double(d) => Value::String(lua.create_string(d.to_string())?),
+        RespFrame::Array(array) => frames_to_lua(lua, array.0)?,
+        RespFrame::Set(set) => frames_to_lua(lua, set.0)?,
+        RespFrame::Push(push) => frames_to_lua(lua, push.0)?,
+        RespFrame::Map(map) => {
+            let frames = map
+                .0
+                .into_iter()
+                .flat_map(|(k, v)| [BulkString::from(k).into(), v])
+                .collect();
+            frames_to_lua(lua, frames)?
+        }
+    };
+    Ok(value)
+}
+
+fn frames_to_lua(lua: &Lua, frames: Vec<RespFrame>) -> mlua::Result<Value<'_>> {
+    let values = frames
+        .into_iter()
+        .map(|frame| frame_to_lua(lua, frame))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    Ok(Value::Table(lua.create_sequence_from(values)?))
+}
+
+/// 脚本的返回值转换成 Redis 的返回值，数字截断成整数，表遇到第一个 nil 时结束
+fn lua_to_frame(value: Value) -> RespFrame {
+    match value {
+        Value::Boolean(true) => RespFrame::Integer(1),
+        Value::Integer(n) => RespFrame::Integer(n),
+        Value::Number(n) => RespFrame::Integer(n as i64),
+        Value::String(s) => BulkString::new(s.as_bytes()).into(),
+        Value::Table(table) => {
+            if let Ok(Value::String(err)) = table.raw_get("err") {
+                return SimpleError::new(err.to_string_lossy().to_string()).into();
+            }
+            if let Ok(Value::String(ok)) = table.raw_get("ok") {
+                return SimpleString::new(ok.to_string_lossy().to_string()).into();
+            }
+            let frames = table
+                .sequence_values::<Value>()
+                .map_while(Result::ok)
+                .map(lua_to_frame)
+                .collect::<Vec<_>>();
+            RespArray::new(frames).into()
+        }
+        _ => RespFrame::Null(RespNull),
+    }
+}
+
+fn error_message(err: &mlua::Error) -> String {
+    match err {
+        mlua::Error::SyntaxError { message, .. } => {
+            format!("ERR Error compiling script: {}", message)
+        }
+        mlua::Error::CallbackError { cause, .. } => error_message(cause),
+        mlua::Error::ExternalError(e) => match e.downcast_ref::<ScriptError>() {
+            Some(e) => e.0.clone(),
+            None => format!("ERR Error running script: {}", e),
+        },
+        mlua::Error::RuntimeError(msg) => format!("ERR Error running script: {}", msg),
+        e => format!("ERR Error running script: {}", e),
+    }
+}
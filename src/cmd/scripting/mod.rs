@@ -0,0 +1,442 @@
+mod eval;
+mod evalsha;
+mod fcall;
+mod function;
+mod lua;
+mod script;
+
+pub use eval::*;
+pub use evalsha::*;
+pub use fcall::*;
+pub use function::*;
+pub use script::*;
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use anyhow::Result;
+
+    use crate::{
+        cmd::{CommandExecutor, RESP_OK},
+        Backend, BulkString, RespArray, RespFrame, RespNull, RestorePolicy, SimpleError,
+        SimpleString,
+    };
+
+    use super::*;
+
+    fn eval(backend: &Backend, script: &str, keys: &[&str], args: &[&str]) -> RespFrame {
+        Eval {
+            script: script.to_string(),
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            args: args.iter().map(|a| a.as_bytes().to_vec()).collect(),
+            readonly: false,
+        }
+        .execute(backend)
+    }
+
+    fn error(msg: &str) -> RespFrame {
+        SimpleError::new(msg.to_string()).into()
+    }
+
+    fn function(backend: &Backend, kind: FunctionKind) -> RespFrame {
+        Function { kind }.execute(backend)
+    }
+
+    fn fcall(backend: &Backend, name: &str, keys: &[&str], readonly: bool) -> RespFrame {
+        FCall {
+            function: name.to_string(),
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            args: vec![],
+            readonly,
+        }
+        .execute(backend)
+    }
+
+    const LIBRARY: &str = "#!lua name=mylib
+redis.register_function('myset', function(keys, args) return redis.call('set', keys[1], 'v') end)
+redis.register_function{
+    function_name = 'myget',
+    callback = function(keys, args) return redis.call('get', keys[1]) end,
+    flags = {'no-writes'},
+}";
+
+    fn load(code: &str, replace: bool) -> FunctionKind {
+        FunctionKind::Load {
+            code: code.to_string(),
+            replace,
+        }
+    }
+
+    #[test]
+    fn test_function_load_and_fcall() -> Result<()> {
+        let backend = Backend::new();
+
+        assert_eq!(
+            function(&backend, load(LIBRARY, false)),
+            BulkString::from("mylib").into()
+        );
+        assert_eq!(
+            function(&backend, load(LIBRARY, false)),
+            error("ERR Library 'mylib' already exists")
+        );
+        assert_eq!(
+            function(&backend, load(LIBRARY, true)),
+            BulkString::from("mylib").into()
+        );
+        // 函数名在所有库中唯一
+        let other = "#!lua name=other\nredis.register_function('myget', function() end)";
+        assert_eq!(
+            function(&backend, load(other, false)),
+            error("ERR Function myget already exists")
+        );
+        assert_eq!(
+            function(&backend, load("return 1", false)),
+            error("ERR Missing library metadata")
+        );
+        assert_eq!(
+            function(&backend, load("#!lua name=empty\nlocal a = 1", false)),
+            error("ERR No functions registered")
+        );
+        // 库代码中不能调用 redis.call
+        let ret = function(&backend, load("#!lua name=bad\nredis.call('ping')", false));
+        assert!(matches!(ret, RespFrame::Error(_)));
+
+        assert_eq!(fcall(&backend, "myset", &["k"], false), RESP_OK.clone());
+        assert_eq!(
+            fcall(&backend, "myget", &["k"], true),
+            BulkString::from("v").into()
+        );
+        assert_eq!(
+            fcall(&backend, "myset", &["k"], true),
+            error("ERR Can not execute a script with write flag using *_ro command.")
+        );
+        assert_eq!(
+            fcall(&backend, "nosuch", &[], false),
+            error("ERR Function not found")
+        );
+
+        let list = function(&backend, FunctionKind::List { withcode: true });
+        let RespFrame::Array(libraries) = list else {
+            unreachable!()
+        };
+        let RespFrame::Array(library) = &libraries[0] else {
+            unreachable!()
+        };
+        assert_eq!(library[1], BulkString::from("mylib").into());
+        assert_eq!(library[7], BulkString::from(LIBRARY).into());
+        let RespFrame::Array(functions) = &library[5] else {
+            unreachable!()
+        };
+        assert_eq!(
+            functions[0],
+            RespArray::new([
+                BulkString::from("name").into(),
+                BulkString::from("myset").into(),
+                BulkString::from("description").into(),
+                RespFrame::Null(RespNull),
+                BulkString::from("flags").into(),
+                RespArray::new([]).into(),
+            ])
+            .into()
+        );
+
+        assert_eq!(
+            function(&backend, FunctionKind::Delete("mylib".to_string())),
+            RESP_OK.clone()
+        );
+        assert_eq!(
+            function(&backend, FunctionKind::Delete("mylib".to_string())),
+            error("ERR Library not found")
+        );
+        assert_eq!(
+            fcall(&backend, "myget", &["k"], false),
+            error("ERR Function not found")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fcall_reuses_library() -> Result<()> {
+        let backend = Backend::new();
+        let code = "#!lua name=counter
+local loads = 0
+loads = loads + 1
+local calls = 0
+redis.register_function('count', function() calls = calls + 1 return {loads, calls} end)
+redis.register_function('register', function() redis.register_function('x', function() end) end)
+redis.register_function('spin', function() while true do end end)";
+        let counts = |loads, calls| {
+            RespFrame::from(RespArray::new([
+                RespFrame::Integer(loads),
+                RespFrame::Integer(calls),
+            ]))
+        };
+
+        function(&backend, load(code, false));
+        // 库代码只在加载时执行一次
+        assert_eq!(fcall(&backend, "count", &[], false), counts(1, 1));
+        assert_eq!(fcall(&backend, "count", &[], false), counts(1, 2));
+        function(&backend, load(code, true));
+        assert_eq!(fcall(&backend, "count", &[], false), counts(1, 1));
+        // 加载完成后不能再注册函数
+        assert!(matches!(
+            fcall(&backend, "register", &[], false),
+            RespFrame::Error(_)
+        ));
+
+        backend.set_lua_time_limit(Duration::from_millis(10));
+        let runaway = thread::spawn({
+            let backend = backend.clone();
+            move || fcall(&backend, "spin", &[], false)
+        });
+        while !backend.script_busy() {
+            thread::sleep(Duration::from_millis(5));
+        }
+        Script {
+            kind: ScriptKind::Kill,
+        }
+        .execute(&backend);
+        assert_eq!(
+            runaway.join().unwrap(),
+            error("ERR Script killed by user with SCRIPT KILL...")
+        );
+        assert_eq!(fcall(&backend, "count", &[], false), counts(1, 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_function_dump_and_restore() -> Result<()> {
+        let backend = Backend::new();
+        function(&backend, load(LIBRARY, false));
+        let RespFrame::BulkString(payload) = function(&backend, FunctionKind::Dump) else {
+            unreachable!()
+        };
+        let restore = |policy| FunctionKind::Restore {
+            payload: payload.0.clone(),
+            policy,
+        };
+
+        assert_eq!(
+            function(&backend, restore(RestorePolicy::Append)),
+            error("ERR Library 'mylib' already exists")
+        );
+        assert_eq!(
+            function(&backend, restore(RestorePolicy::Replace)),
+            RESP_OK.clone()
+        );
+
+        assert_eq!(function(&backend, FunctionKind::Flush), RESP_OK.clone());
+        assert_eq!(
+            fcall(&backend, "myset", &["k"], false),
+            error("ERR Function not found")
+        );
+        assert_eq!(
+            function(&backend, restore(RestorePolicy::Append)),
+            RESP_OK.clone()
+        );
+        assert_eq!(fcall(&backend, "myset", &["k"], false), RESP_OK.clone());
+
+        let mut corrupted = payload.0.clone();
+        corrupted[6] ^= 1;
+        let ret = function(
+            &backend,
+            FunctionKind::Restore {
+                payload: corrupted,
+                policy: RestorePolicy::Flush,
+            },
+        );
+        assert_eq!(ret, error("ERR DUMP payload version or checksum are wrong"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_type_conversion() -> Result<()> {
+        let backend = Backend::new();
+
+        let ret = eval(
+            &backend,
+            "return {KEYS[1], ARGV[1], 3.7, true, false}",
+            &["k"],
+            &["v"],
+        );
+        let expected = RespArray::new([
+            BulkString::from("k").into(),
+            BulkString::from("v").into(),
+            RespFrame::Integer(3),
+            RespFrame::Integer(1),
+            RespFrame::Null(RespNull),
+        ]);
+        assert_eq!(ret, expected.into());
+
+        // 表遇到第一个 nil 时结束
+        let ret = eval(&backend, "return {1, 2, nil, 4}", &[], &[]);
+        let expected = RespArray::new([RespFrame::Integer(1), RespFrame::Integer(2)]);
+        assert_eq!(ret, expected.into());
+
+        let ret = eval(&backend, "return redis.status_reply('PONG')", &[], &[]);
+        assert_eq!(ret, SimpleString::new("PONG".to_string()).into());
+        let ret = eval(&backend, "return {err = 'MYERR oops'}", &[], &[]);
+        assert_eq!(ret, error("MYERR oops"));
+        assert_eq!(eval(&backend, "return nil", &[], &[]), RespNull.into());
+
+        let ret = eval(&backend, "return redis.sha1hex('')", &[], &[]);
+        assert_eq!(
+            ret,
+            BulkString::from("da39a3ee5e6b4b0d3255bfef95601890afd80709").into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_redis_call() -> Result<()> {
+        let backend = Backend::new();
+
+        let script = "redis.call('SET', KEYS[1], ARGV[1]); return redis.call('get', KEYS[1])";
+        let ret = eval(&backend, script, &["name"], &["kaka"]);
+        assert_eq!(ret, BulkString::from("kaka").into());
+
+        // 不存在的 key 转换成 false，数字参数转换成字符串
+        let script =
+            "return {redis.call('get', 'nokey') == false, redis.call('zadd', 'z', 1, 'a')}";
+        let expected = RespArray::new([RespFrame::Integer(1), RespFrame::Integer(1)]);
+        assert_eq!(eval(&backend, script, &[], &[]), expected.into());
+
+        // 状态回复转换成带 ok 字段的表
+        let ret = eval(&backend, "return redis.call('set', 'a', 'b').ok", &[], &[]);
+        assert_eq!(ret, BulkString::from("OK").into());
+
+        // redis.call 的错误终止脚本，redis.pcall 返回带 err 字段的表
+        let ret = eval(&backend, "redis.call('nosuchcmd'); return 1", &[], &[]);
+        assert_eq!(ret, error("ERR Unknown Redis command called from script"));
+        let script = "local r = redis.pcall('get'); return type(r) == 'table' and r.err ~= nil";
+        assert_eq!(eval(&backend, script, &[], &[]), RespFrame::Integer(1));
+        let ret = eval(&backend, "return redis.call('multi')", &[], &[]);
+        assert_eq!(
+            ret,
+            error("ERR This Redis command is not allowed from script")
+        );
+        let ret = eval(
+            &backend,
+            "return redis.call('eval', 'return 1', 0)",
+            &[],
+            &[],
+        );
+        assert_eq!(
+            ret,
+            error("ERR This Redis command is not allowed from script")
+        );
+
+        let ret = eval(&backend, "return +", &[], &[]);
+        assert!(
+            matches!(ret, RespFrame::Error(e) if e.0.starts_with("ERR Error compiling script"))
+        );
+        let ret = eval(&backend, "error('boom')", &[], &[]);
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.contains("boom")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_ro_and_evalsha() -> Result<()> {
+        let backend = Backend::new();
+        backend.set("k".to_string(), BulkString::from("v").into());
+
+        let eval_ro = |script: &str| {
+            Eval {
+                script: script.to_string(),
+                keys: vec![],
+                args: vec![],
+                readonly: true,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(
+            eval_ro("return redis.call('get', 'k')"),
+            BulkString::from("v").into()
+        );
+        assert_eq!(
+            eval_ro("return redis.call('set', 'k', 'x')"),
+            error("ERR Write commands are not allowed from read-only scripts.")
+        );
+
+        let script = "return redis.call('get', KEYS[1])";
+        let sha = match (Script {
+            kind: ScriptKind::Load(script.to_string()),
+        })
+        .execute(&backend)
+        {
+            RespFrame::BulkString(sha) => String::from_utf8(sha.0)?,
+            _ => unreachable!(),
+        };
+        let evalsha = |sha: &str| {
+            EvalSha {
+                sha: sha.to_string(),
+                keys: vec!["k".to_string()],
+                args: vec![],
+                readonly: false,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(evalsha(&sha.to_uppercase()), BulkString::from("v").into());
+
+        let exists = Script {
+            kind: ScriptKind::Exists(vec![sha.clone(), "nosha".to_string()]),
+        };
+        let expected = RespArray::new([RespFrame::Integer(1), RespFrame::Integer(0)]);
+        assert_eq!(exists.execute(&backend), expected.into());
+
+        let flush = Script {
+            kind: ScriptKind::Flush,
+        };
+        assert_eq!(flush.execute(&backend), RESP_OK.clone());
+        assert_eq!(
+            evalsha(&sha),
+            error("NOSCRIPT No matching script. Please use EVAL.")
+        );
+
+        // EVAL 执行过的脚本也会被缓存
+        eval(&backend, script, &["k"], &[]);
+        assert_eq!(evalsha(&sha), BulkString::from("v").into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_kill() -> Result<()> {
+        let backend = Backend::new();
+        let kill = || {
+            Script {
+                kind: ScriptKind::Kill,
+            }
+            .execute(&backend)
+        };
+        assert_eq!(kill(), error("NOTBUSY No scripts in execution right now."));
+
+        backend.set_lua_time_limit(Duration::from_millis(10));
+        let runaway = thread::spawn({
+            let backend = backend.clone();
+            move || eval(&backend, "while true do end", &[], &[])
+        });
+        while !backend.script_busy() {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(kill(), RESP_OK.clone());
+        assert_eq!(
+            runaway.join().unwrap(),
+            error("ERR Script killed by user with SCRIPT KILL...")
+        );
+
+        // 执行过写命令的脚本不能被终止
+        backend.script_begin();
+        backend.script_wrote();
+        assert!(matches!(kill(), RespFrame::Error(e) if e.0.starts_with("UNKILLABLE")));
+        backend.script_end();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_command, validate_min_args, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptKind {
+    Load(String),
+    Exists(Vec<String>),
+    Flush,
+    Kill,
+}
+
+/// SCRIPT LOAD | EXISTS | FLUSH | KILL，不需要等待正在执行的脚本
+#[derive(Debug)]
+pub struct Script {
+    pub(crate) kind: ScriptKind,
+}
+
+impl CommandExecutor for Script {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match self.kind {
+            ScriptKind::Load(script) => RespFrame::BulkString(backend.script_load(script).into()),
+            ScriptKind::Exists(shas) => {
+                let exists = shas
+                    .iter()
+                    .map(|sha| RespFrame::Integer(backend.script_exists(sha) as i64))
+                    .collect::<Vec<_>>();
+                RespArray::new(exists).into()
+            }
+            ScriptKind::Flush => {
+                backend.script_flush();
+                RESP_OK.clone()
+            }
+            ScriptKind::Kill => match backend.script_kill() {
+                Ok(()) => RESP_OK.clone(),
+                Err(e) => SimpleError::new(e.to_string()).into(),
+            },
+        }
+    }
+}
+
+impl TryFrom<RespArray> for Script {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+
+        let kind = match sub.as_str() {
+            "load" => {
+                validate_command(&value, &["script", "load"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                ScriptKind::Load(parse_string(next_arg(&mut args)?)?)
+            }
+            "exists" => {
+                validate_min_args(&value, &["script", "exists"], 1)?;
+                let shas = extract_args(value, 2)?
+                    .into_iter()
+                    .map(parse_string)
+                    .collect::<Result<Vec<_>, _>>()?;
+                ScriptKind::Exists(shas)
+            }
+            "flush" => {
+                // ASYNC 和 SYNC 参数只影响释放内存的方式，这里直接忽略
+                validate_min_args(&value, &["script", "flush"], 0)?;
+                if value.len() > 3 {
+                    return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                }
+                ScriptKind::Flush
+            }
+            "kill" => {
+                validate_command(&value, &["script", "kill"], 0)?;
+                ScriptKind::Kill
+            }
+            _ => {
+                return Err(CommandError::InvalidArgument(format!(
+                    "unknown subcommand '{}'",
+                    sub
+                )))
+            }
+        };
+        Ok(Script { kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_script_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nSCRIPT\r\n$4\r\nLOAD\r\n$8\r\nreturn 1\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Script = frame.try_into()?;
+        assert_eq!(result.kind, ScriptKind::Load("return 1".to_string()));
+
+        buf.extend_from_slice(b"*4\r\n$6\r\nscript\r\n$6\r\nexists\r\n$1\r\na\r\n$1\r\nb\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Script = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            ScriptKind::Exists(vec!["a".to_string(), "b".to_string()])
+        );
+
+        buf.extend_from_slice(b"*2\r\n$6\r\nscript\r\n$4\r\nkill\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: Script = frame.try_into()?;
+        assert_eq!(result.kind, ScriptKind::Kill);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    DistanceMetric, FieldSchema, FieldType, IndexDefinition, RespArray, RespFrame, SimpleError,
+    VectorAlgorithm, VectorParams,
+};
+
+/// FT.CREATE index [ON HASH] [PREFIX count prefix ...]
+///   SCHEMA field TEXT|NUMERIC|TAG [SEPARATOR sep] [SORTABLE] | VECTOR FLAT|HNSW count attrs ...
+#[derive(Debug)]
+pub struct FTCreate {
+    pub(crate) index: String,
+    pub(crate) definition: IndexDefinition,
+}
+
+impl CommandExecutor for FTCreate {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ft_create(self.index, self.definition) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+fn syntax_error() -> CommandError {
+    CommandError::InvalidArgument("syntax error".to_string())
+}
+
+// VECTOR FLAT|HNSW count TYPE FLOAT32 DIM dim DISTANCE_METRIC L2|IP|COSINE [M m] ...
+fn parse_vector_params(
+    algorithm: &str,
+    attrs: Vec<(String, String)>,
+) -> Result<VectorParams, CommandError> {
+    let mut algorithm = match algorithm.to_ascii_lowercase().as_str() {
+        "flat" => VectorAlgorithm::Flat,
+        "hnsw" => VectorAlgorithm::default(),
+        _ => return Err(syntax_error()),
+    };
+    let (mut dim, mut metric) = (None, None);
+    let number = |v: &str| v.parse::<usize>().map_err(|_| syntax_error());
+    for (name, value) in attrs {
+        match (name.as_str(), &mut algorithm) {
+            ("type", _) if value.eq_ignore_ascii_case("float32") => {}
+            ("type", _) => {
+                return Err(CommandError::InvalidArgument(
+                    "only FLOAT32 vectors are supported".to_string(),
+                ))
+            }
+            ("dim", _) => dim = Some(number(&value)?).filter(|d| *d > 0),
+            ("distance_metric", _) => {
+                metric = Some(match value.to_ascii_lowercase().as_str() {
+                    "l2" => DistanceMetric::L2,
+                    "ip" => DistanceMetric::IP,
+                    "cosine" => DistanceMetric::Cosine,
+                    _ => return Err(syntax_error()),
+                })
+            }
+            // FLAT 的参数只影响内存分配，直接忽略
+            ("initial_cap" | "block_size", _) => {
+                number(&value)?;
+            }
+            ("m", VectorAlgorithm::Hnsw { m, .. }) => *m = number(&value)?,
+            (
+                "ef_construction",
+                VectorAlgorithm::Hnsw {
+                    ef_construction, ..
+                },
+            ) => *ef_construction = number(&value)?,
+            ("ef_runtime", VectorAlgorithm::Hnsw { ef_runtime, .. }) => {
+                *ef_runtime = number(&value)?
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    match (dim, metric) {
+        (Some(dim), Some(metric)) => Ok(VectorParams {
+            algorithm,
+            dim,
+            metric,
+        }),
+        _ => Err(CommandError::InvalidArgument(
+            "vector fields require TYPE, DIM and DISTANCE_METRIC".to_string(),
+        )),
+    }
+}
+
+impl TryFrom<RespArray> for FTCreate {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ft.create"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let index = parse_string(next_arg(&mut args)?)?;
+        let mut definition = IndexDefinition::default();
+        loop {
+            match parse_string(next_arg(&mut args)?)?
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "on" => {
+                    if !parse_string(next_arg(&mut args)?)?.eq_ignore_ascii_case("hash") {
+                        return Err(CommandError::InvalidArgument(
+                            "only HASH indexes are supported".to_string(),
+                        ));
+                    }
+                }
+                "prefix" => {
+                    let count: usize = parse_number(next_arg(&mut args)?)?;
+                    for _ in 0..count {
+                        definition
+                            .prefixes
+                            .push(parse_string(next_arg(&mut args)?)?);
+                    }
+                }
+                "schema" => break,
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        let mut args = args.map(parse_string).peekable();
+        while let Some(name) = args.next() {
+            let name = name?;
+            let field_type = match args
+                .next()
+                .ok_or_else(syntax_error)??
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "text" => FieldType::Text,
+                "numeric" => FieldType::Numeric,
+                "tag" => FieldType::Tag { separator: ',' },
+                "vector" => {
+                    let algorithm = args.next().ok_or_else(syntax_error)??;
+                    let count: usize = args
+                        .next()
+                        .ok_or_else(syntax_error)??
+                        .parse()
+                        .map_err(|_| syntax_error())?;
+                    if !count.is_multiple_of(2) {
+                        return Err(syntax_error());
+                    }
+                    let mut attrs = Vec::with_capacity(count / 2);
+                    for _ in 0..count / 2 {
+                        let name = args.next().ok_or_else(syntax_error)??;
+                        let value = args.next().ok_or_else(syntax_error)??;
+                        attrs.push((name.to_ascii_lowercase(), value));
+                    }
+                    FieldType::Vector(parse_vector_params(&algorithm, attrs)?)
+                }
+                _ => return Err(syntax_error()),
+            };
+            let mut field = FieldSchema { name, field_type };
+            // 字段的选项，SORTABLE 和 NOSTEM 不影响索引的结构，直接忽略
+            while let Some(Ok(opt)) = args.peek() {
+                match opt.to_ascii_lowercase().as_str() {
+                    "sortable" | "nostem" => {
+                        args.next();
+                    }
+                    "separator" => {
+                        args.next();
+                        let sep = args.next().ok_or_else(syntax_error)??;
+                        let mut chars = sep.chars();
+                        match (&mut field.field_type, chars.next(), chars.next()) {
+                            (FieldType::Tag { separator }, Some(c), None) => *separator = c,
+                            _ => return Err(syntax_error()),
+                        }
+                    }
+                    _ => break,
+                }
+            }
+            definition.fields.push(field);
+        }
+        if definition.fields.is_empty() {
+            return Err(syntax_error());
+        }
+
+        Ok(FTCreate { index, definition })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ft_create_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*15\r\n$9\r\nFT.CREATE\r\n$3\r\nidx\r\n$2\r\nON\r\n$4\r\nHASH\r\n$6\r\nPREFIX\r\n$1\r\n1\r\n$8\r\nproduct:\r\n$6\r\nSCHEMA\r\n$4\r\nname\r\n$4\r\nTEXT\r\n$5\r\nprice\r\n$7\r\nNUMERIC\r\n$8\r\nSORTABLE\r\n$4\r\ntags\r\n$3\r\nTAG\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: FTCreate = frame.try_into()?;
+        assert_eq!(result.index, "idx");
+        assert_eq!(result.definition.prefixes, vec!["product:"]);
+        assert_eq!(
+            result.definition.fields,
+            vec![
+                FieldSchema {
+                    name: "name".to_string(),
+                    field_type: FieldType::Text
+                },
+                FieldSchema {
+                    name: "price".to_string(),
+                    field_type: FieldType::Numeric
+                },
+                FieldSchema {
+                    name: "tags".to_string(),
+                    field_type: FieldType::Tag { separator: ',' }
+                },
+            ]
+        );
+
+        buf.extend_from_slice(b"*7\r\n$9\r\nft.create\r\n$3\r\nidx\r\n$6\r\nSCHEMA\r\n$4\r\ntags\r\n$3\r\nTAG\r\n$9\r\nSEPARATOR\r\n$1\r\n;\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FTCreate = frame.try_into()?;
+        assert!(result.definition.prefixes.is_empty());
+        assert_eq!(
+            result.definition.fields[0].field_type,
+            FieldType::Tag { separator: ';' }
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$9\r\nft.create\r\n$3\r\nidx\r\n$6\r\nSCHEMA\r\n$4\r\nname\r\n$4\r\nBLOB\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(FTCreate::try_from(frame).is_err());
+
+        buf.extend_from_slice(b"*15\r\n$9\r\nft.create\r\n$3\r\nidx\r\n$6\r\nSCHEMA\r\n$3\r\nvec\r\n$6\r\nVECTOR\r\n$4\r\nHNSW\r\n$1\r\n8\r\n$4\r\nTYPE\r\n$7\r\nFLOAT32\r\n$3\r\nDIM\r\n$1\r\n4\r\n$15\r\nDISTANCE_METRIC\r\n$6\r\nCOSINE\r\n$1\r\nM\r\n$1\r\n8\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FTCreate = frame.try_into()?;
+        assert_eq!(
+            result.definition.fields[0].field_type,
+            FieldType::Vector(VectorParams {
+                algorithm: VectorAlgorithm::Hnsw {
+                    m: 8,
+                    ef_construction: 200,
+                    ef_runtime: 10
+                },
+                dim: 4,
+                metric: DistanceMetric::Cosine,
+            })
+        );
+
+        // 缺少 DISTANCE_METRIC
+        buf.extend_from_slice(b"*11\r\n$9\r\nft.create\r\n$3\r\nidx\r\n$6\r\nSCHEMA\r\n$3\r\nvec\r\n$6\r\nVECTOR\r\n$4\r\nFLAT\r\n$1\r\n4\r\n$4\r\nTYPE\r\n$7\r\nFLOAT32\r\n$3\r\nDIM\r\n$1\r\n4\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(FTCreate::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
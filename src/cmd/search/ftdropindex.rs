@@ -0,0 +1,69 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor,
+        RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+/// FT.DROPINDEX index [DD]，DD 表示同时删除索引中的 hash
+#[derive(Debug)]
+pub struct FTDropIndex {
+    pub(crate) index: String,
+    pub(crate) delete_docs: bool,
+}
+
+impl CommandExecutor for FTDropIndex {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ft_dropindex(&self.index, self.delete_docs) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for FTDropIndex {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ft.dropindex"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let index = parse_string(next_arg(&mut args)?)?;
+        let delete_docs = match (args.next(), args.next()) {
+            (None, None) => false,
+            (Some(RespFrame::BulkString(arg)), None) if arg.eq_ignore_ascii_case(b"dd") => true,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+
+        Ok(FTDropIndex { index, delete_docs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ft_dropindex_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$12\r\nFT.DROPINDEX\r\n$3\r\nidx\r\n$2\r\nDD\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: FTDropIndex = frame.try_into()?;
+        assert_eq!(result.index, "idx");
+        assert!(result.delete_docs);
+
+        buf.extend_from_slice(b"*2\r\n$12\r\nft.dropindex\r\n$3\r\nidx\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FTDropIndex = frame.try_into()?;
+        assert!(!result.delete_docs);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    BulkString, DistanceMetric, FieldType, RespArray, RespFrame, SimpleError, VectorAlgorithm,
+};
+
+/// 返回索引的定义和统计信息
+#[derive(Debug)]
+pub struct FTInfo {
+    pub(crate) index: String,
+}
+
+impl CommandExecutor for FTInfo {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let info = match backend.ft_info(&self.index) {
+            Ok(info) => info,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+
+        let prefixes = info
+            .definition
+            .prefixes
+            .into_iter()
+            .map(|p| BulkString::from(p).into())
+            .collect::<Vec<RespFrame>>();
+        let definition = RespArray::new([
+            BulkString::from("key_type").into(),
+            BulkString::from("HASH").into(),
+            BulkString::from("prefixes").into(),
+            RespArray::new(prefixes).into(),
+        ]);
+        let attributes = info
+            .definition
+            .fields
+            .into_iter()
+            .map(|f| {
+                let mut attr: Vec<RespFrame> = vec![
+                    BulkString::from("identifier").into(),
+                    BulkString::from(f.name.as_str()).into(),
+                    BulkString::from("attribute").into(),
+                    BulkString::from(f.name).into(),
+                    BulkString::from("type").into(),
+                ];
+                match f.field_type {
+                    FieldType::Text => attr.push(BulkString::from("TEXT").into()),
+                    FieldType::Numeric => attr.push(BulkString::from("NUMERIC").into()),
+                    FieldType::Tag { separator } => {
+                        attr.push(BulkString::from("TAG").into());
+                        attr.push(BulkString::from("SEPARATOR").into());
+                        attr.push(BulkString::from(separator.to_string()).into());
+                    }
+                    FieldType::Vector(params) => {
+                        let algorithm = match params.algorithm {
+                            VectorAlgorithm::Flat => "FLAT",
+                            VectorAlgorithm::Hnsw { .. } => "HNSW",
+                        };
+                        let metric = match params.metric {
+                            DistanceMetric::L2 => "L2",
+                            DistanceMetric::IP => "IP",
+                            DistanceMetric::Cosine => "COSINE",
+                        };
+                        attr.extend([
+                            BulkString::from("VECTOR").into(),
+                            BulkString::from("algorithm").into(),
+                            BulkString::from(algorithm).into(),
+                            BulkString::from("data_type").into(),
+                            BulkString::from("FLOAT32").into(),
+                            BulkString::from("dim").into(),
+                            (params.dim as i64).into(),
+                            BulkString::from("distance_metric").into(),
+                            BulkString::from(metric).into(),
+                        ]);
+                    }
+                }
+                RespArray::new(attr).into()
+            })
+            .collect::<Vec<RespFrame>>();
+
+        RespArray::new([
+            BulkString::from("index_name").into(),
+            BulkString::from(self.index).into(),
+            BulkString::from("index_definition").into(),
+            definition.into(),
+            BulkString::from("attributes").into(),
+            RespArray::new(attributes).into(),
+            BulkString::from("num_docs").into(),
+            (info.num_docs as i64).into(),
+            BulkString::from("num_terms").into(),
+            (info.num_terms as i64).into(),
+            BulkString::from("num_records").into(),
+            (info.num_records as i64).into(),
+        ])
+        .into()
+    }
+}
+
+impl TryFrom<RespArray> for FTInfo {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["ft.info"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let index = parse_string(next_arg(&mut args)?)?;
+
+        Ok(FTInfo { index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ft_info_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$7\r\nFT.INFO\r\n$3\r\nidx\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: FTInfo = frame.try_into()?;
+        assert_eq!(result.index, "idx");
+
+        Ok(())
+    }
+}
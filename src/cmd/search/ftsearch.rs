@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_number, parse_string, validate_min_args,
+        CommandError, CommandExecutor,
+    },
+    BulkString, KnnQuery, Query, RespArray, RespFrame, SearchOptions, SimpleError,
+};
+
+/// FT.SEARCH index query [NOCONTENT] [RETURN count field ...]
+///   [SORTBY field [ASC|DESC]] [LIMIT offset num] [PARAMS count name value ...] [DIALECT n]
+/// query 可以带上 `=>[KNN k @field $param [AS score]]` 在过滤之后的文档中做向量搜索
+#[derive(Debug)]
+pub struct FTSearch {
+    pub(crate) index: String,
+    pub(crate) query: Query,
+    pub(crate) opts: SearchOptions,
+}
+
+impl CommandExecutor for FTSearch {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let result = match backend.ft_search(&self.index, &self.query, &self.opts) {
+            Ok(result) => result,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+
+        let mut ret: Vec<RespFrame> = vec![(result.total as i64).into()];
+        for doc in result.docs {
+            ret.push(BulkString::from(doc.key).into());
+            if let Some(fields) = doc.fields {
+                let fields = fields
+                    .into_iter()
+                    .flat_map(|(name, value)| [BulkString::from(name).into(), value])
+                    .collect::<Vec<RespFrame>>();
+                ret.push(RespArray::new(fields).into());
+            }
+        }
+        RespArray::new(ret).into()
+    }
+}
+
+// `[KNN k @field $param [AS score]]`，k 和向量都可以引用 PARAMS 中的参数
+fn parse_knn(expr: &str, params: &HashMap<String, Vec<u8>>) -> Result<KnnQuery, CommandError> {
+    let invalid = || CommandError::InvalidArgument(format!("invalid KNN query: {}", expr));
+    let param = |s: &str| {
+        s.strip_prefix('$')
+            .and_then(|name| params.get(name))
+            .ok_or_else(|| CommandError::InvalidArgument(format!("No such parameter `{}`", s)))
+    };
+
+    let inner = expr
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(invalid)?;
+    let tokens = inner.split_whitespace().collect::<Vec<_>>();
+    let [knn, k, field, vector, rest @ ..] = &tokens[..] else {
+        return Err(invalid());
+    };
+    if !knn.eq_ignore_ascii_case("knn") {
+        return Err(invalid());
+    }
+    let k = match k.strip_prefix('$') {
+        Some(_) => String::from_utf8_lossy(param(k)?).parse(),
+        None => k.parse(),
+    }
+    .map_err(|_| invalid())?;
+    let field = field.strip_prefix('@').ok_or_else(invalid)?.to_string();
+    let blob = param(vector)?.clone();
+    let score_field = match rest {
+        [] => format!("__{}_score", field),
+        [alias, name] if alias.eq_ignore_ascii_case("as") => name.to_string(),
+        _ => return Err(invalid()),
+    };
+
+    Ok(KnnQuery {
+        k,
+        field,
+        blob,
+        score_field,
+    })
+}
+
+impl TryFrom<RespArray> for FTSearch {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ft.search"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let index = parse_string(next_arg(&mut args)?)?;
+        let query = parse_string(next_arg(&mut args)?)?;
+        let mut opts = SearchOptions::default();
+        let mut params = HashMap::new();
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "nocontent" => opts.no_content = true,
+                "return" => {
+                    let count: usize = parse_number(next_arg(&mut args)?)?;
+                    let mut fields = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        fields.push(parse_string(next_arg(&mut args)?)?);
+                    }
+                    // RETURN 0 和 NOCONTENT 一样只返回 key
+                    if fields.is_empty() {
+                        opts.no_content = true;
+                    }
+                    opts.return_fields = Some(fields);
+                }
+                "sortby" => {
+                    let field = parse_string(next_arg(&mut args)?)?;
+                    let order = match args.peek() {
+                        Some(RespFrame::BulkString(s)) if s.eq_ignore_ascii_case(b"asc") => {
+                            Some(false)
+                        }
+                        Some(RespFrame::BulkString(s)) if s.eq_ignore_ascii_case(b"desc") => {
+                            Some(true)
+                        }
+                        _ => None,
+                    };
+                    if order.is_some() {
+                        args.next();
+                    }
+                    opts.sort_by = Some((field, order.unwrap_or(false)));
+                }
+                "limit" => {
+                    opts.offset = parse_number(next_arg(&mut args)?)?;
+                    opts.num = parse_number(next_arg(&mut args)?)?;
+                }
+                "params" => {
+                    let count: usize = parse_number(next_arg(&mut args)?)?;
+                    if !count.is_multiple_of(2) {
+                        return Err(CommandError::InvalidArgument(
+                            "PARAMS requires name value pairs".to_string(),
+                        ));
+                    }
+                    for _ in 0..count / 2 {
+                        let name = parse_string(next_arg(&mut args)?)?;
+                        params.insert(name, parse_bytes(next_arg(&mut args)?)?);
+                    }
+                }
+                // 只支持一种查询语法，DIALECT 直接忽略
+                "dialect" => {
+                    parse_number::<u32>(next_arg(&mut args)?)?;
+                }
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+
+        // PARAMS 在 query 之后，所以最后再解析 query
+        let (filter, knn) = match query.split_once("=>") {
+            Some((filter, knn)) => (filter, Some(parse_knn(knn, &params)?)),
+            None => (query.as_str(), None),
+        };
+        let query = filter
+            .parse::<Query>()
+            .map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+        opts.knn = knn;
+
+        Ok(FTSearch { index, query, opts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ft_search_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*12\r\n$9\r\nFT.SEARCH\r\n$3\r\nidx\r\n$38\r\nphone* @price:[(100 +inf] -@tag:{used}\r\n$6\r\nRETURN\r\n$1\r\n1\r\n$4\r\nname\r\n$6\r\nSORTBY\r\n$5\r\nprice\r\n$4\r\nDESC\r\n$5\r\nLIMIT\r\n$1\r\n0\r\n$1\r\n5\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: FTSearch = frame.try_into()?;
+        assert_eq!(result.index, "idx");
+        assert_eq!(
+            result.query,
+            Query::And(vec![
+                Query::Term {
+                    field: None,
+                    term: "phone".to_string(),
+                    prefix: true
+                },
+                Query::Numeric {
+                    field: "price".to_string(),
+                    min: Bound::Excluded(100.0),
+                    max: Bound::Unbounded
+                },
+                Query::Not(Box::new(Query::Tag {
+                    field: "tag".to_string(),
+                    tags: vec!["used".to_string()]
+                })),
+            ])
+        );
+        assert_eq!(result.opts.return_fields, Some(vec!["name".to_string()]));
+        assert_eq!(result.opts.sort_by, Some(("price".to_string(), true)));
+        assert_eq!((result.opts.offset, result.opts.num), (0, 5));
+
+        buf.extend_from_slice(b"*4\r\n$9\r\nft.search\r\n$3\r\nidx\r\n$18\r\n@title:(a|b c) | d\r\n$9\r\nNOCONTENT\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FTSearch = frame.try_into()?;
+        let term = |field: Option<&str>, term: &str| Query::Term {
+            field: field.map(|f| f.to_string()),
+            term: term.to_string(),
+            prefix: false,
+        };
+        assert_eq!(
+            result.query,
+            Query::Or(vec![
+                Query::Or(vec![
+                    term(Some("title"), "a"),
+                    Query::And(vec![term(Some("title"), "b"), term(Some("title"), "c")]),
+                ]),
+                term(None, "d"),
+            ])
+        );
+        assert!(result.opts.no_content);
+
+        buf.extend_from_slice(b"*3\r\n$9\r\nft.search\r\n$3\r\nidx\r\n$11\r\n@price:[1 2\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(FTSearch::try_from(frame).is_err());
+
+        let blob = [1.0f32, 0.5]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let query = "(@tag:{a})=>[KNN $k @vec $blob AS dist]";
+        let mut data = format!(
+            "*11\r\n$9\r\nFT.SEARCH\r\n$3\r\nidx\r\n${}\r\n{}\r\n$6\r\nPARAMS\r\n$1\r\n4\r\n$1\r\nk\r\n$1\r\n3\r\n$4\r\nblob\r\n$8\r\n",
+            query.len(),
+            query
+        )
+        .into_bytes();
+        data.extend_from_slice(&blob);
+        data.extend_from_slice(b"\r\n$7\r\nDIALECT\r\n$1\r\n2\r\n");
+        buf.extend_from_slice(&data);
+        let frame = RespArray::decode(&mut buf)?;
+        let result: FTSearch = frame.try_into()?;
+        assert_eq!(
+            result.query,
+            Query::Tag {
+                field: "tag".to_string(),
+                tags: vec!["a".to_string()]
+            }
+        );
+        assert_eq!(
+            result.opts.knn,
+            Some(KnnQuery {
+                k: 3,
+                field: "vec".to_string(),
+                blob,
+                score_field: "dist".to_string(),
+            })
+        );
+
+        // 引用了不存在的参数
+        buf.extend_from_slice(
+            b"*3\r\n$9\r\nft.search\r\n$3\r\nidx\r\n$25\r\n*=>[KNN 10 @vec $nothing]\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(FTSearch::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
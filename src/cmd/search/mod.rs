@@ -0,0 +1,459 @@
+mod ftcreate;
+mod ftdropindex;
+mod ftinfo;
+mod ftsearch;
+
+pub use ftcreate::*;
+pub use ftdropindex::*;
+pub use ftinfo::*;
+pub use ftsearch::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::{CommandExecutor, RESP_OK},
+        Backend, BulkString, DistanceMetric, FieldSchema, FieldType, IndexDefinition, KnnQuery,
+        RespArray, RespFrame, SearchOptions, SimpleError, VectorAlgorithm, VectorParams,
+    };
+
+    use super::*;
+
+    fn hset(backend: &Backend, key: &str, fields: &[(&str, &str)]) {
+        for (field, value) in fields {
+            backend
+                .hset(
+                    key.to_string(),
+                    field.to_string(),
+                    BulkString::from(*value).into(),
+                )
+                .unwrap();
+        }
+    }
+
+    fn search(backend: &Backend, query: &str, opts: SearchOptions) -> RespFrame {
+        FTSearch {
+            index: "idx".to_string(),
+            query: query.parse().unwrap(),
+            opts,
+        }
+        .execute(backend)
+    }
+
+    fn keys(total: i64, keys: &[&str]) -> RespFrame {
+        let mut ret: Vec<RespFrame> = vec![total.into()];
+        ret.extend(keys.iter().map(|k| BulkString::from(*k).into()));
+        RespArray::new(ret).into()
+    }
+
+    fn no_content() -> SearchOptions {
+        SearchOptions {
+            no_content: true,
+            ..Default::default()
+        }
+    }
+
+    fn create_index(backend: &Backend) -> RespFrame {
+        let field = |name: &str, field_type| FieldSchema {
+            name: name.to_string(),
+            field_type,
+        };
+        FTCreate {
+            index: "idx".to_string(),
+            definition: IndexDefinition {
+                prefixes: vec!["product:".to_string()],
+                fields: vec![
+                    field("name", FieldType::Text),
+                    field("price", FieldType::Numeric),
+                    field("tags", FieldType::Tag { separator: ',' }),
+                ],
+            },
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_ft_search_commands() -> Result<()> {
+        let backend = Backend::new();
+        // 创建索引之前写入的 hash 也会被索引
+        hset(
+            &backend,
+            "product:1",
+            &[
+                ("name", "Red Phone"),
+                ("price", "199"),
+                ("tags", "sale, new"),
+            ],
+        );
+        hset(&backend, "other:1", &[("name", "Red Phone")]);
+
+        assert_eq!(create_index(&backend), RESP_OK.clone());
+        assert_eq!(
+            create_index(&backend),
+            SimpleError::new("Index already exists".to_string()).into()
+        );
+
+        hset(
+            &backend,
+            "product:2",
+            &[
+                ("name", "Blue Phone Case"),
+                ("price", "25"),
+                ("tags", "new"),
+            ],
+        );
+        hset(
+            &backend,
+            "product:3",
+            &[
+                ("name", "Red Headphones"),
+                ("price", "99.5"),
+                ("tags", "used"),
+            ],
+        );
+
+        assert_eq!(
+            search(&backend, "*", no_content()),
+            keys(3, &["product:1", "product:2", "product:3"])
+        );
+        assert_eq!(
+            search(&backend, "red", no_content()),
+            keys(2, &["product:1", "product:3"])
+        );
+        assert_eq!(
+            search(&backend, "@name:phone*", no_content()),
+            keys(2, &["product:1", "product:2"])
+        );
+        assert_eq!(
+            search(&backend, "@price:[(25 199]", no_content()),
+            keys(2, &["product:1", "product:3"])
+        );
+        assert_eq!(
+            search(&backend, "@tags:{NEW} -red", no_content()),
+            keys(1, &["product:2"])
+        );
+        assert_eq!(
+            search(&backend, "@tags:{used} | @price:[-inf 30]", no_content()),
+            keys(2, &["product:2", "product:3"])
+        );
+        assert_eq!(
+            search(&backend, "@price:[10 1]", no_content()),
+            keys(0, &[])
+        );
+
+        // SORTBY + LIMIT + RETURN
+        let opts = SearchOptions {
+            offset: 1,
+            num: 2,
+            sort_by: Some(("price".to_string(), true)),
+            return_fields: Some(vec!["price".to_string(), "nofield".to_string()]),
+            ..Default::default()
+        };
+        let expected = RespArray::new([
+            3.into(),
+            BulkString::from("product:3").into(),
+            RespArray::new([
+                BulkString::from("price").into(),
+                BulkString::from("99.5").into(),
+            ])
+            .into(),
+            BulkString::from("product:2").into(),
+            RespArray::new([
+                BulkString::from("price").into(),
+                BulkString::from("25").into(),
+            ])
+            .into(),
+        ]);
+        assert_eq!(search(&backend, "*", opts), expected.into());
+
+        let ret = search(&backend, "@nofield:red", no_content());
+        assert_eq!(
+            ret,
+            SimpleError::new("Unknown field `nofield`".to_string()).into()
+        );
+        let ret = search(&backend, "@price:red", no_content());
+        assert_eq!(
+            ret,
+            SimpleError::new("Field `price` does not support this query".to_string()).into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ft_index_maintenance() -> Result<()> {
+        let backend = Backend::new();
+        create_index(&backend);
+        hset(
+            &backend,
+            "product:1",
+            &[("name", "Red Phone"), ("price", "199")],
+        );
+        hset(
+            &backend,
+            "product:2",
+            &[("name", "Blue Phone"), ("price", "25")],
+        );
+
+        // 修改字段之后旧的词不再命中
+        hset(&backend, "product:1", &[("name", "Green Phone")]);
+        assert_eq!(search(&backend, "red", no_content()), keys(0, &[]));
+        assert_eq!(
+            search(&backend, "green", no_content()),
+            keys(1, &["product:1"])
+        );
+
+        backend.hdel("product:1", &["price".to_string()]);
+        assert_eq!(
+            search(&backend, "@price:[0 +inf]", no_content()),
+            keys(1, &["product:2"])
+        );
+
+        backend.del("product:2");
+        assert_eq!(
+            search(&backend, "phone", no_content()),
+            keys(1, &["product:1"])
+        );
+
+        let info = FTInfo {
+            index: "idx".to_string(),
+        }
+        .execute(&backend);
+        let RespFrame::Array(info) = info else {
+            panic!("FT.INFO should return an array");
+        };
+        assert_eq!(info[6], BulkString::from("num_docs").into());
+        assert_eq!(info[7], 1.into());
+
+        let cmd = FTDropIndex {
+            index: "idx".to_string(),
+            delete_docs: true,
+        };
+        assert_eq!(cmd.execute(&backend), RESP_OK.clone());
+        assert!(backend.hgetall("product:1").is_none());
+        assert_eq!(
+            search(&backend, "*", no_content()),
+            SimpleError::new("Unknown index name".to_string()).into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ft_create_concurrent_hset() -> Result<()> {
+        // 创建索引的同时写入 hash，所有 key 最终都要出现在索引中
+        for _ in 0..20 {
+            let backend = Backend::new();
+            let writer = {
+                let backend = backend.clone();
+                std::thread::spawn(move || {
+                    for i in 0..200 {
+                        hset(&backend, &format!("product:{}", i), &[("name", "phone")]);
+                    }
+                })
+            };
+            assert_eq!(create_index(&backend), RESP_OK.clone());
+            writer.join().unwrap();
+
+            let ret = search(&backend, "phone", no_content());
+            let RespFrame::Array(ret) = ret else {
+                panic!("FT.SEARCH should return an array");
+            };
+            assert_eq!(ret[0], 200.into());
+        }
+
+        Ok(())
+    }
+
+    fn blob(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn hset_vector(backend: &Backend, key: &str, vector: &[f32]) {
+        backend
+            .hset(
+                key.to_string(),
+                "vec".to_string(),
+                BulkString::new(blob(vector)).into(),
+            )
+            .unwrap();
+    }
+
+    fn create_vector_index(backend: &Backend, algorithm: VectorAlgorithm, metric: DistanceMetric) {
+        let fields = vec![
+            FieldSchema {
+                name: "vec".to_string(),
+                field_type: FieldType::Vector(VectorParams {
+                    algorithm,
+                    dim: 2,
+                    metric,
+                }),
+            },
+            FieldSchema {
+                name: "color".to_string(),
+                field_type: FieldType::Tag { separator: ',' },
+            },
+        ];
+        let cmd = FTCreate {
+            index: "idx".to_string(),
+            definition: IndexDefinition {
+                prefixes: vec![],
+                fields,
+            },
+        };
+        assert_eq!(cmd.execute(backend), RESP_OK.clone());
+    }
+
+    fn knn(k: usize, vector: &[f32]) -> SearchOptions {
+        SearchOptions {
+            return_fields: Some(vec!["__vec_score".to_string()]),
+            knn: Some(KnnQuery {
+                k,
+                field: "vec".to_string(),
+                blob: blob(vector),
+                score_field: "__vec_score".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn scored(hits: &[(&str, &str)]) -> RespFrame {
+        let mut ret: Vec<RespFrame> = vec![(hits.len() as i64).into()];
+        for (key, score) in hits {
+            ret.push(BulkString::from(*key).into());
+            ret.push(
+                RespArray::new([
+                    BulkString::from("__vec_score").into(),
+                    BulkString::from(*score).into(),
+                ])
+                .into(),
+            );
+        }
+        RespArray::new(ret).into()
+    }
+
+    #[test]
+    fn test_ft_search_knn_commands() -> Result<()> {
+        let hnsw = VectorAlgorithm::Hnsw {
+            m: 4,
+            ef_construction: 20,
+            ef_runtime: 10,
+        };
+        for algorithm in [VectorAlgorithm::Flat, hnsw] {
+            let backend = Backend::new();
+            create_vector_index(&backend, algorithm, DistanceMetric::L2);
+            hset_vector(&backend, "a", &[0.0, 0.0]);
+            hset_vector(&backend, "b", &[1.0, 0.0]);
+            hset_vector(&backend, "c", &[3.0, 4.0]);
+            hset(&backend, "b", &[("color", "red")]);
+            hset(&backend, "c", &[("color", "red")]);
+
+            assert_eq!(
+                search(&backend, "*", knn(2, &[0.0, 0.0])),
+                scored(&[("a", "0"), ("b", "1")])
+            );
+            // 先过滤再查找最近的向量
+            assert_eq!(
+                search(&backend, "@color:{red}", knn(5, &[0.0, 0.0])),
+                scored(&[("b", "1"), ("c", "25")])
+            );
+
+            // 更新和删除向量
+            hset_vector(&backend, "b", &[10.0, 10.0]);
+            backend.del("a");
+            assert_eq!(
+                search(&backend, "*", knn(1, &[0.0, 0.0])),
+                scored(&[("c", "25")])
+            );
+
+            // 向量的长度和 DIM 不一致
+            let ret = search(&backend, "*", knn(1, &[0.0, 0.0, 0.0]));
+            assert!(matches!(ret, RespFrame::Error(_)));
+        }
+
+        let backend = Backend::new();
+        create_vector_index(&backend, VectorAlgorithm::Flat, DistanceMetric::Cosine);
+        hset_vector(&backend, "a", &[1.0, 0.0]);
+        hset_vector(&backend, "b", &[0.0, 2.0]);
+        assert_eq!(
+            search(&backend, "*", knn(2, &[0.0, 1.0])),
+            scored(&[("b", "0"), ("a", "1")])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ft_search_hnsw_recall() -> Result<()> {
+        let backend = Backend::new();
+        let hnsw = VectorAlgorithm::Hnsw {
+            m: 16,
+            ef_construction: 200,
+            ef_runtime: 50,
+        };
+        create_vector_index(&backend, hnsw, DistanceMetric::IP);
+        let vectors = (0..300)
+            .map(|i| {
+                let angle = i as f32 * 0.021;
+                [angle.cos(), angle.sin()]
+            })
+            .collect::<Vec<_>>();
+        for (i, v) in vectors.iter().enumerate() {
+            hset_vector(&backend, &format!("v{}", i), v);
+        }
+        // 删除时修复邻居，删除一半之后仍然能找到剩下的节点
+        for i in (0..300).step_by(2) {
+            backend.del(&format!("v{}", i));
+        }
+
+        for i in (1..300).step_by(10) {
+            let ret = search(&backend, "*", knn(1, &vectors[i]));
+            let RespFrame::Array(ret) = ret else {
+                panic!("FT.SEARCH should return an array");
+            };
+            assert_eq!(ret[1], BulkString::from(format!("v{}", i)).into());
+        }
+
+        // 包括入口在内的节点全部删除之后，新插入的节点复用删除的位置
+        for i in (1..299).step_by(2) {
+            backend.del(&format!("v{}", i));
+        }
+        let ret = search(&backend, "*", knn(1, &vectors[0]));
+        let RespFrame::Array(ret) = ret else {
+            panic!("FT.SEARCH should return an array");
+        };
+        assert_eq!(ret[1], BulkString::from("v299").into());
+        for (i, v) in vectors.iter().enumerate().take(100) {
+            hset_vector(&backend, &format!("v{}", i), v);
+        }
+        for i in (0..100).step_by(7) {
+            let ret = search(&backend, "*", knn(1, &vectors[i]));
+            let RespFrame::Array(ret) = ret else {
+                panic!("FT.SEARCH should return an array");
+            };
+            assert_eq!(ret[1], BulkString::from(format!("v{}", i)).into());
+        }
+
+        // 离查询最近的节点被删除之后仍然返回 k 个结果
+        let backend = Backend::new();
+        let hnsw = VectorAlgorithm::Hnsw {
+            m: 4,
+            ef_construction: 20,
+            ef_runtime: 1,
+        };
+        create_vector_index(&backend, hnsw, DistanceMetric::L2);
+        for i in 0..20 {
+            hset_vector(&backend, &format!("v{}", i), &[i as f32, 0.0]);
+        }
+        for i in 0..5 {
+            backend.del(&format!("v{}", i));
+        }
+        assert_eq!(
+            search(&backend, "*", knn(3, &[0.0, 0.0])),
+            scored(&[("v5", "25"), ("v6", "36"), ("v7", "49")])
+        );
+
+        Ok(())
+    }
+}
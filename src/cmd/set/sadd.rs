@@ -1,6 +1,6 @@
 use crate::{
     cmd::{extract_args, validate_command, CommandError, CommandExecutor},
-    RespArray, RespFrame,
+    RespArray, RespFrame, SimpleError,
 };
 
 #[derive(Debug)]
@@ -11,7 +11,10 @@ pub struct SAdd {
 
 impl CommandExecutor for SAdd {
     fn execute(self, backend: &crate::Backend) -> RespFrame {
-        (backend.sadd(self.key, self.member) as i64).into()
+        match backend.sadd(self.key, self.member) {
+            Ok(n) => (n as i64).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
     }
 }
 
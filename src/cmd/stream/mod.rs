@@ -0,0 +1,670 @@
+mod xack;
+mod xadd;
+mod xautoclaim;
+mod xclaim;
+mod xdel;
+mod xgroup;
+mod xinfo;
+mod xlen;
+mod xpending;
+mod xrange;
+mod xread;
+mod xreadgroup;
+mod xtrim;
+
+pub use xack::*;
+pub use xadd::*;
+pub use xautoclaim::*;
+pub use xclaim::*;
+pub use xdel::*;
+pub use xgroup::*;
+pub use xinfo::*;
+pub use xlen::*;
+pub use xpending::*;
+pub use xrange::*;
+pub use xread::*;
+pub use xreadgroup::*;
+pub use xtrim::*;
+
+use std::ops::Bound;
+
+use crate::{
+    BulkString, GroupStreamEntry, RespArray, RespFrame, RespNull, StreamEntry, StreamId,
+    StreamTrim, TrimStrategy,
+};
+
+use super::{next_arg, parse_number, parse_string, CommandError};
+
+// 解析 <ms>-<seq>，只有 <ms> 时序列号使用 default_seq
+fn parse_stream_id(s: &str, default_seq: u64) -> Result<StreamId, CommandError> {
+    let invalid = || {
+        CommandError::InvalidArgument(
+            "Invalid stream ID specified as stream command argument".to_string(),
+        )
+    };
+    let (ms, seq) = match s.split_once('-') {
+        Some((ms, seq)) => (ms, seq.parse().map_err(|_| invalid())?),
+        None => (s, default_seq),
+    };
+    Ok(StreamId::new(ms.parse().map_err(|_| invalid())?, seq))
+}
+
+// 消费者组的 ID，"$" 表示 stream 当前最后一个 ID，返回 None
+fn parse_group_id(s: &str) -> Result<Option<StreamId>, CommandError> {
+    match s {
+        "$" => Ok(None),
+        _ => Ok(Some(parse_stream_id(s, 0)?)),
+    }
+}
+
+// 范围查询的起点，"-" 表示最小的 ID，"(" 开头表示不包含
+fn parse_range_start(frame: RespFrame) -> Result<Bound<StreamId>, CommandError> {
+    let s = parse_string(frame)?;
+    match s.as_str() {
+        "-" => Ok(Bound::Unbounded),
+        _ => match s.strip_prefix('(') {
+            Some(id) => Ok(Bound::Excluded(parse_stream_id(id, 0)?)),
+            None => Ok(Bound::Included(parse_stream_id(&s, 0)?)),
+        },
+    }
+}
+
+// 范围查询的终点，"+" 表示最大的 ID，"(" 开头表示不包含
+fn parse_range_end(frame: RespFrame) -> Result<Bound<StreamId>, CommandError> {
+    let s = parse_string(frame)?;
+    match s.as_str() {
+        "+" => Ok(Bound::Unbounded),
+        _ => match s.strip_prefix('(') {
+            Some(id) => Ok(Bound::Excluded(parse_stream_id(id, u64::MAX)?)),
+            None => Ok(Bound::Included(parse_stream_id(&s, u64::MAX)?)),
+        },
+    }
+}
+
+// 解析 <MAXLEN | MINID> [= | ~] threshold [LIMIT count]，strategy 是已经读取的 MAXLEN 或 MINID
+fn parse_trim(
+    strategy: &str,
+    args: &mut std::iter::Peekable<impl Iterator<Item = RespFrame>>,
+) -> Result<StreamTrim, CommandError> {
+    let mut threshold = parse_string(next_arg(args)?)?;
+    let mut approximate = false;
+    if threshold == "~" || threshold == "=" {
+        approximate = threshold == "~";
+        threshold = parse_string(next_arg(args)?)?;
+    }
+
+    let strategy = match strategy {
+        "maxlen" => TrimStrategy::MaxLen(threshold.parse().map_err(|_| {
+            CommandError::InvalidArgument("value is not an integer or out of range".to_string())
+        })?),
+        _ => TrimStrategy::MinId(parse_stream_id(&threshold, 0)?),
+    };
+
+    let mut limit = 0;
+    if let Some(RespFrame::BulkString(arg)) = args.peek() {
+        if arg.eq_ignore_ascii_case(b"limit") {
+            args.next();
+            if !approximate {
+                return Err(CommandError::InvalidArgument(
+                    "syntax error, LIMIT cannot be used without the special ~ option".to_string(),
+                ));
+            }
+            limit = parse_number(next_arg(args)?)?;
+        }
+    }
+
+    Ok(StreamTrim {
+        strategy,
+        approximate,
+        limit,
+    })
+}
+
+fn id_to_frame(id: StreamId) -> RespFrame {
+    BulkString::from(id.to_string()).into()
+}
+
+// [id, [field, value, ...]]
+fn entry_to_frame((id, fields): StreamEntry) -> RespFrame {
+    let fields = fields
+        .into_iter()
+        .flat_map(|(k, v)| [BulkString::from(k).into(), BulkString::from(v).into()])
+        .collect::<Vec<RespFrame>>();
+    RespArray::new([id_to_frame(id), RespArray::new(fields).into()]).into()
+}
+
+fn entries_to_frame(entries: Vec<StreamEntry>) -> RespFrame {
+    let entries = entries
+        .into_iter()
+        .map(entry_to_frame)
+        .collect::<Vec<RespFrame>>();
+    RespArray::new(entries).into()
+}
+
+// XREADGROUP 读取历史消息时，已经被删除的消息返回 [id, nil]
+fn pending_entries_to_frame(entries: Vec<GroupStreamEntry>) -> RespFrame {
+    let entries = entries
+        .into_iter()
+        .map(|(id, fields)| match fields {
+            Some(fields) => entry_to_frame((id, fields)),
+            None => RespArray::new([id_to_frame(id), RespNull.into()]).into(),
+        })
+        .collect::<Vec<RespFrame>>();
+    RespArray::new(entries).into()
+}
+
+// XCLAIM 和 XAUTOCLAIM 指定 JUSTID 时只返回 ID
+fn claimed_to_frame(entries: Vec<StreamEntry>, justid: bool) -> RespFrame {
+    if justid {
+        let ids = entries
+            .into_iter()
+            .map(|(id, _)| id_to_frame(id))
+            .collect::<Vec<_>>();
+        RespArray::new(ids).into()
+    } else {
+        entries_to_frame(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, SimpleError, SimpleString, XAddId, XClaimOptions};
+
+    use super::*;
+
+    fn xadd(backend: &Backend, id: XAddId, trim: Option<StreamTrim>) -> RespFrame {
+        XAdd {
+            key: "s".to_string(),
+            nomkstream: false,
+            trim,
+            id,
+            fields: vec![("f".to_string(), "v".to_string())],
+        }
+        .execute(backend)
+    }
+
+    fn id_frame(ms: u64, seq: u64) -> RespFrame {
+        BulkString::from(StreamId::new(ms, seq).to_string()).into()
+    }
+
+    #[test]
+    fn test_xadd_command() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            xadd(&backend, XAddId::Explicit(StreamId::new(1, 1)), None),
+            id_frame(1, 1)
+        );
+        assert_eq!(xadd(&backend, XAddId::AutoSeq(1), None), id_frame(1, 2));
+        assert_eq!(xadd(&backend, XAddId::AutoSeq(5), None), id_frame(5, 0));
+
+        // ID 必须递增
+        let ret = xadd(&backend, XAddId::Explicit(StreamId::new(5, 0)), None);
+        assert!(matches!(ret, RespFrame::Error(_)));
+
+        // 0-0 不允许，也不会留下空的 stream
+        let cmd = XAdd {
+            key: "empty".to_string(),
+            nomkstream: false,
+            trim: None,
+            id: XAddId::Explicit(StreamId::default()),
+            fields: vec![("f".to_string(), "v".to_string())],
+        };
+        assert!(matches!(cmd.execute(&backend), RespFrame::Error(_)));
+        assert!(backend.stream.get("empty").is_none());
+
+        let cmd = XAdd {
+            key: "nokey".to_string(),
+            nomkstream: true,
+            trim: None,
+            id: XAddId::Auto,
+            fields: vec![("f".to_string(), "v".to_string())],
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        // 添加的同时裁剪
+        let trim = StreamTrim {
+            strategy: TrimStrategy::MaxLen(2),
+            approximate: false,
+            limit: 0,
+        };
+        assert_eq!(
+            xadd(&backend, XAddId::AutoSeq(6), Some(trim)),
+            id_frame(6, 0)
+        );
+        assert_eq!(
+            XLen {
+                key: "s".to_string()
+            }
+            .execute(&backend),
+            2.into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xrange_xdel_xtrim_commands() -> Result<()> {
+        let backend = Backend::new();
+        for ms in 1..=5 {
+            xadd(&backend, XAddId::Explicit(StreamId::new(ms, 0)), None);
+        }
+
+        let xrange = |start, end, count, rev| {
+            XRange {
+                key: "s".to_string(),
+                start,
+                end,
+                count,
+                rev,
+            }
+            .execute(&backend)
+        };
+        let ids = |frame: RespFrame| match frame {
+            RespFrame::Array(entries) => entries
+                .iter()
+                .map(|e| match e {
+                    RespFrame::Array(e) => e[0].clone(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+
+        let ret = xrange(
+            Bound::Excluded(StreamId::new(1, 0)),
+            Bound::Unbounded,
+            2,
+            false,
+        );
+        assert_eq!(ids(ret), vec![id_frame(2, 0), id_frame(3, 0)]);
+        let ret = xrange(Bound::Unbounded, Bound::Unbounded, 2, true);
+        assert_eq!(ids(ret), vec![id_frame(5, 0), id_frame(4, 0)]);
+        // start > end 时返回空数组
+        let ret = xrange(
+            Bound::Included(StreamId::new(3, 0)),
+            Bound::Included(StreamId::new(2, 0)),
+            usize::MAX,
+            false,
+        );
+        assert_eq!(ret, RespArray::new(Vec::<RespFrame>::new()).into());
+
+        let cmd = XDel {
+            key: "s".to_string(),
+            ids: vec![StreamId::new(2, 0), StreamId::new(9, 0)],
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        let cmd = XTrim {
+            key: "s".to_string(),
+            trim: StreamTrim {
+                strategy: TrimStrategy::MinId(StreamId::new(4, 0)),
+                approximate: false,
+                limit: 0,
+            },
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+        let ret = xrange(Bound::Unbounded, Bound::Unbounded, usize::MAX, false);
+        assert_eq!(ids(ret), vec![id_frame(4, 0), id_frame(5, 0)]);
+
+        let info = backend.xinfo("s").unwrap();
+        assert_eq!(info.length, 2);
+        assert_eq!(info.entries_added, 5);
+        assert_eq!(info.max_deleted_entry_id, StreamId::new(2, 0));
+        assert_eq!(info.last_generated_id, StreamId::new(5, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xread_command() -> Result<()> {
+        let backend = Backend::new();
+        for ms in 1..=3 {
+            xadd(&backend, XAddId::Explicit(StreamId::new(ms, 0)), None);
+        }
+
+        let cmd = XRead {
+            count: usize::MAX,
+            block: None,
+            keys: vec!["s".to_string(), "nokey".to_string()],
+            ids: vec![
+                XReadId::After(StreamId::new(2, 0)),
+                XReadId::After(StreamId::default()),
+            ],
+        };
+        let entry = entry_to_frame((
+            StreamId::new(3, 0),
+            vec![("f".to_string(), "v".to_string())],
+        ));
+        let expected = RespArray::new([RespFrame::from(RespArray::new([
+            BulkString::from("s").into(),
+            RespArray::new([entry.clone()]).into(),
+        ]))]);
+        assert_eq!(cmd.execute(&backend), expected.clone().into());
+
+        let cmd = XRead {
+            count: usize::MAX,
+            block: None,
+            keys: vec!["s".to_string()],
+            ids: vec![XReadId::Last],
+        };
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = XRead {
+            count: usize::MAX,
+            block: None,
+            keys: vec!["s".to_string()],
+            ids: vec![XReadId::After(StreamId::new(3, 0))],
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xinfo_command() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = XInfo {
+            kind: XInfoKind::Stream {
+                key: "s".to_string(),
+            },
+        };
+        assert!(matches!(cmd.execute(&backend), RespFrame::Error(_)));
+
+        xadd(&backend, XAddId::Explicit(StreamId::new(1, 0)), None);
+        let cmd = XInfo {
+            kind: XInfoKind::Stream {
+                key: "s".to_string(),
+            },
+        };
+        let ret = match cmd.execute(&backend) {
+            RespFrame::Map(map) => map,
+            _ => unreachable!(),
+        };
+        assert_eq!(ret.get("length"), Some(&1.into()));
+        assert_eq!(ret.get("last-generated-id"), Some(&id_frame(1, 0)));
+
+        Ok(())
+    }
+
+    fn xreadgroup(backend: &Backend, consumer: &str, id: Option<StreamId>) -> RespFrame {
+        XReadGroup {
+            group: "g".to_string(),
+            consumer: consumer.to_string(),
+            count: usize::MAX,
+            block: None,
+            noack: false,
+            keys: vec!["s".to_string()],
+            ids: vec![id],
+        }
+        .execute(backend)
+    }
+
+    // 取出 XREADGROUP 返回的消息 ID
+    fn read_ids(frame: RespFrame) -> Vec<RespFrame> {
+        let streams = match frame {
+            RespFrame::Array(streams) => streams,
+            _ => return vec![],
+        };
+        match &streams[0] {
+            RespFrame::Array(stream) => match &stream[1] {
+                RespFrame::Array(entries) => entries
+                    .iter()
+                    .map(|e| match e {
+                        RespFrame::Array(e) => e[0].clone(),
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_xgroup_command() -> Result<()> {
+        let backend = Backend::new();
+        let create = |mkstream| XGroup {
+            kind: XGroupKind::Create {
+                key: "s".to_string(),
+                group: "g".to_string(),
+                id: None,
+                mkstream,
+                entries_read: None,
+            },
+        };
+        assert!(matches!(
+            create(false).execute(&backend),
+            RespFrame::Error(_)
+        ));
+        assert_eq!(
+            create(true).execute(&backend),
+            SimpleString::new("OK").into()
+        );
+        let ret = create(true).execute(&backend);
+        assert_eq!(
+            ret,
+            SimpleError::new("BUSYGROUP Consumer Group name already exists").into()
+        );
+
+        let cmd = XGroup {
+            kind: XGroupKind::CreateConsumer {
+                key: "s".to_string(),
+                group: "g".to_string(),
+                consumer: "c".to_string(),
+            },
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        let cmd = XGroup {
+            kind: XGroupKind::CreateConsumer {
+                key: "s".to_string(),
+                group: "nogroup".to_string(),
+                consumer: "c".to_string(),
+            },
+        };
+        let ret = cmd.execute(&backend);
+        assert_eq!(
+            ret,
+            SimpleError::new("NOGROUP No such key 's' or consumer group 'nogroup'").into()
+        );
+
+        let cmd = XGroup {
+            kind: XGroupKind::Destroy {
+                key: "s".to_string(),
+                group: "g".to_string(),
+            },
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+        assert_eq!(backend.xinfo("s").unwrap().groups, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xreadgroup_xack_xpending_commands() -> Result<()> {
+        let backend = Backend::new();
+        for ms in 1..=3 {
+            xadd(&backend, XAddId::Explicit(StreamId::new(ms, 0)), None);
+        }
+        backend.xgroup_create(
+            "s".to_string(),
+            "g".to_string(),
+            Some(StreamId::default()),
+            false,
+            None,
+        )?;
+
+        // ">" 读取新消息，同一个组里的消息只会投递一次
+        let ret = xreadgroup(&backend, "c1", None);
+        assert_eq!(
+            read_ids(ret),
+            vec![id_frame(1, 0), id_frame(2, 0), id_frame(3, 0)]
+        );
+        assert_eq!(xreadgroup(&backend, "c2", None), RespNull.into());
+
+        let cmd = XAck {
+            key: "s".to_string(),
+            group: "g".to_string(),
+            ids: vec![StreamId::new(1, 0), StreamId::new(9, 0)],
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        // 读取自己 PEL 中的历史消息，被删除的消息内容为 nil
+        backend.xdel("s", &[StreamId::new(3, 0)]);
+        let ret = xreadgroup(&backend, "c1", Some(StreamId::default()));
+        let expected = RespArray::new([RespFrame::from(RespArray::new([
+            BulkString::from("s").into(),
+            RespArray::new([
+                entry_to_frame((
+                    StreamId::new(2, 0),
+                    vec![("f".to_string(), "v".to_string())],
+                )),
+                RespArray::new([id_frame(3, 0), RespNull.into()]).into(),
+            ])
+            .into(),
+        ]))]);
+        assert_eq!(ret, expected.into());
+        // 其它 consumer 的历史消息为空，但是仍然返回这个 key
+        let ret = xreadgroup(&backend, "c2", Some(StreamId::default()));
+        let expected = RespArray::new([RespFrame::from(RespArray::new([
+            BulkString::from("s").into(),
+            RespArray::new(Vec::<RespFrame>::new()).into(),
+        ]))]);
+        assert_eq!(ret, expected.into());
+
+        let cmd = XPending {
+            key: "s".to_string(),
+            group: "g".to_string(),
+            range: None,
+        };
+        let expected = RespArray::new([
+            2.into(),
+            id_frame(2, 0),
+            id_frame(3, 0),
+            RespArray::new([RespFrame::from(RespArray::new([
+                BulkString::from("c1").into(),
+                BulkString::from("2").into(),
+            ]))])
+            .into(),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let pending = backend.xpending(
+            "s",
+            "g",
+            Bound::Unbounded,
+            Bound::Unbounded,
+            10,
+            Some("c1"),
+            0,
+        )?;
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].id, StreamId::new(2, 0));
+        // 读取历史消息也算一次投递
+        assert_eq!(pending[0].delivery_count, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_xclaim_xautoclaim_commands() -> Result<()> {
+        let backend = Backend::new();
+        for ms in 1..=3 {
+            xadd(&backend, XAddId::Explicit(StreamId::new(ms, 0)), None);
+        }
+        backend.xgroup_create(
+            "s".to_string(),
+            "g".to_string(),
+            Some(StreamId::default()),
+            false,
+            None,
+        )?;
+        xreadgroup(&backend, "c1", None);
+
+        // 空闲时间不够时不会认领
+        let xclaim = |min_idle, opts| XClaim {
+            key: "s".to_string(),
+            group: "g".to_string(),
+            consumer: "c2".to_string(),
+            min_idle,
+            ids: vec![StreamId::new(1, 0)],
+            opts,
+        };
+        let ret = xclaim(60_000, XClaimOptions::default()).execute(&backend);
+        assert_eq!(ret, RespArray::new(Vec::<RespFrame>::new()).into());
+
+        let opts = XClaimOptions {
+            justid: true,
+            ..Default::default()
+        };
+        let ret = xclaim(0, opts).execute(&backend);
+        assert_eq!(ret, RespArray::new([id_frame(1, 0)]).into());
+        let summary = backend.xpending_summary("s", "g")?;
+        assert_eq!(
+            summary.consumers,
+            vec![("c1".to_string(), 2), ("c2".to_string(), 1)]
+        );
+
+        // 被删除的消息从 PEL 中删除，并在第三个元素中返回
+        backend.xdel("s", &[StreamId::new(2, 0)]);
+        let cmd = XAutoClaim {
+            key: "s".to_string(),
+            group: "g".to_string(),
+            consumer: "c3".to_string(),
+            min_idle: 0,
+            start: StreamId::default(),
+            count: 1,
+            justid: true,
+        };
+        // 返回下一次扫描的起点
+        let expected = RespArray::new([
+            id_frame(2, 0),
+            RespArray::new([id_frame(1, 0)]).into(),
+            RespArray::new(Vec::<RespFrame>::new()).into(),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = XAutoClaim {
+            key: "s".to_string(),
+            group: "g".to_string(),
+            consumer: "c3".to_string(),
+            min_idle: 0,
+            start: StreamId::new(2, 0),
+            count: 10,
+            justid: true,
+        };
+        let expected = RespArray::new([
+            id_frame(0, 0),
+            RespArray::new([id_frame(3, 0)]).into(),
+            RespArray::new([id_frame(2, 0)]).into(),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let consumers = backend.xinfo_consumers("s", "g")?;
+        let pending = consumers
+            .iter()
+            .map(|c| (c.name.as_str(), c.pending))
+            .collect::<Vec<_>>();
+        assert_eq!(pending, vec![("c1", 0), ("c2", 0), ("c3", 2)]);
+
+        let groups = backend.xinfo_groups("s").unwrap();
+        assert_eq!(groups[0].pending, 2);
+        assert_eq!(groups[0].last_delivered_id, StreamId::new(3, 0));
+        assert_eq!(groups[0].entries_read, Some(3));
+        assert_eq!(groups[0].lag, 0);
+
+        let cmd = XGroup {
+            kind: XGroupKind::DelConsumer {
+                key: "s".to_string(),
+                group: "g".to_string(),
+                consumer: "c3".to_string(),
+            },
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+        assert_eq!(backend.xpending_summary("s", "g")?.count, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, StreamId,
+};
+
+use super::parse_stream_id;
+
+#[derive(Debug)]
+pub struct XAck {
+    pub(crate) key: String,
+    pub(crate) group: String,
+    pub(crate) ids: Vec<StreamId>,
+}
+
+impl CommandExecutor for XAck {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.xack(&self.key, &self.group, &self.ids) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for XAck {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xack"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let group = parse_string(next_arg(&mut args)?)?;
+        let ids = args
+            .map(|id| parse_stream_id(&parse_string(id)?, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(XAck { key, group, ids })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xack_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$4\r\nxack\r\n$1\r\ns\r\n$1\r\ng\r\n$3\r\n1-1\r\n$1\r\n2\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XAck = frame.try_into()?;
+        assert_eq!(result.key, "s");
+        assert_eq!(result.group, "g");
+        assert_eq!(result.ids, vec![StreamId::new(1, 1), StreamId::new(2, 0)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, RespArray, RespFrame, RespNull, SimpleError, StreamTrim, XAddId,
+};
+
+use super::{parse_stream_id, parse_trim};
+
+#[derive(Debug)]
+pub struct XAdd {
+    pub(crate) key: String,
+    pub(crate) nomkstream: bool,
+    pub(crate) trim: Option<StreamTrim>,
+    pub(crate) id: XAddId,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+impl CommandExecutor for XAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.xadd(self.key, self.id, self.fields, self.nomkstream, self.trim) {
+            Ok(Some(id)) => BulkString::from(id.to_string()).into(),
+            Ok(None) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for XAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xadd"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = parse_string(next_arg(&mut args)?)?;
+
+        let (mut nomkstream, mut trim) = (false, None);
+        let id = loop {
+            let arg = parse_string(next_arg(&mut args)?)?;
+            match arg.to_ascii_lowercase().as_str() {
+                "nomkstream" => nomkstream = true,
+                strategy @ ("maxlen" | "minid") => trim = Some(parse_trim(strategy, &mut args)?),
+                "*" => break XAddId::Auto,
+                _ => match arg.strip_suffix("-*") {
+                    Some(ms) => {
+                        let ms = ms.parse().map_err(|_| {
+                            CommandError::InvalidArgument(
+                                "Invalid stream ID specified as stream command argument"
+                                    .to_string(),
+                            )
+                        })?;
+                        break XAddId::AutoSeq(ms);
+                    }
+                    None => break XAddId::Explicit(parse_stream_id(&arg, 0)?),
+                },
+            }
+        };
+
+        let mut fields = Vec::new();
+        while let Some(field) = args.next() {
+            let field = parse_string(field)?;
+            let value = parse_string(next_arg(&mut args)?)?;
+            fields.push((field, value));
+        }
+        if fields.is_empty() {
+            return Err(CommandError::InvalidArgument(
+                "wrong number of arguments for 'xadd' command".to_string(),
+            ));
+        }
+
+        Ok(XAdd {
+            key,
+            nomkstream,
+            trim,
+            id,
+            fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{RespDecode, TrimStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_xadd_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*11\r\n$4\r\nxadd\r\n$6\r\nstream\r\n$10\r\nNOMKSTREAM\r\n$6\r\nMAXLEN\r\n$1\r\n~\r\n$4\r\n1000\r\n$5\r\nLIMIT\r\n$2\r\n10\r\n$1\r\n*\r\n$4\r\nname\r\n$4\r\nkaka\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XAdd = frame.try_into()?;
+        assert_eq!(result.key, "stream");
+        assert!(result.nomkstream);
+        assert_eq!(
+            result.trim,
+            Some(StreamTrim {
+                strategy: TrimStrategy::MaxLen(1000),
+                approximate: true,
+                limit: 10,
+            })
+        );
+        assert_eq!(result.id, XAddId::Auto);
+        assert_eq!(
+            result.fields,
+            vec![("name".to_string(), "kaka".to_string())]
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$4\r\nxadd\r\n$6\r\nstream\r\n$3\r\n5-*\r\n$1\r\na\r\n$1\r\n1\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XAdd = frame.try_into()?;
+        assert_eq!(result.id, XAddId::AutoSeq(5));
+
+        Ok(())
+    }
+}
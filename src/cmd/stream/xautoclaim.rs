@@ -0,0 +1,109 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError, StreamId,
+};
+
+use super::{claimed_to_frame, id_to_frame, parse_stream_id};
+
+#[derive(Debug)]
+pub struct XAutoClaim {
+    pub(crate) key: String,
+    pub(crate) group: String,
+    pub(crate) consumer: String,
+    pub(crate) min_idle: u64,
+    pub(crate) start: StreamId,
+    pub(crate) count: usize,
+    pub(crate) justid: bool,
+}
+
+impl CommandExecutor for XAutoClaim {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.xautoclaim(
+            &self.key,
+            &self.group,
+            &self.consumer,
+            self.min_idle,
+            self.start,
+            self.count,
+            self.justid,
+        ) {
+            // [下一次扫描的起点, 认领的消息, 已经被删除的消息 ID]
+            Ok((next, claimed, deleted)) => RespArray::new([
+                id_to_frame(next),
+                claimed_to_frame(claimed, self.justid),
+                RespArray::new(deleted.into_iter().map(id_to_frame).collect::<Vec<_>>()).into(),
+            ])
+            .into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for XAutoClaim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xautoclaim"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let group = parse_string(next_arg(&mut args)?)?;
+        let consumer = parse_string(next_arg(&mut args)?)?;
+        let min_idle = parse_number(next_arg(&mut args)?)?;
+        let start = parse_stream_id(&parse_string(next_arg(&mut args)?)?, 0)?;
+
+        let (mut count, mut justid) = (100, false);
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "count" => count = parse_number(next_arg(&mut args)?)?,
+                "justid" => justid = true,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+        if count == 0 {
+            return Err(CommandError::InvalidArgument(
+                "COUNT must be > 0".to_string(),
+            ));
+        }
+
+        Ok(XAutoClaim {
+            key,
+            group,
+            consumer,
+            min_idle,
+            start,
+            count,
+            justid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xautoclaim_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*8\r\n$10\r\nxautoclaim\r\n$1\r\ns\r\n$1\r\ng\r\n$1\r\nc\r\n$1\r\n0\r\n$3\r\n0-0\r\n$5\r\nCOUNT\r\n$2\r\n10\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XAutoClaim = frame.try_into()?;
+        assert_eq!(result.consumer, "c");
+        assert_eq!(result.start, StreamId::default());
+        assert_eq!(result.count, 10);
+        assert!(!result.justid);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError, StreamId, XClaimOptions,
+};
+
+use super::{claimed_to_frame, parse_stream_id};
+
+#[derive(Debug)]
+pub struct XClaim {
+    pub(crate) key: String,
+    pub(crate) group: String,
+    pub(crate) consumer: String,
+    pub(crate) min_idle: u64,
+    pub(crate) ids: Vec<StreamId>,
+    pub(crate) opts: XClaimOptions,
+}
+
+impl CommandExecutor for XClaim {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.xclaim(
+            &self.key,
+            &self.group,
+            &self.consumer,
+            self.min_idle,
+            &self.ids,
+            self.opts,
+        ) {
+            Ok(entries) => claimed_to_frame(entries, self.opts.justid),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for XClaim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xclaim"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let group = parse_string(next_arg(&mut args)?)?;
+        let consumer = parse_string(next_arg(&mut args)?)?;
+        let min_idle = parse_number(next_arg(&mut args)?)?;
+
+        // 先是 ID 列表，第一个不是 ID 的参数开始是可选参数
+        let (mut ids, mut opts) = (Vec::new(), XClaimOptions::default());
+        while let Some(arg) = args.next() {
+            let arg = parse_string(arg)?;
+            match arg.to_ascii_lowercase().as_str() {
+                "idle" => opts.idle = Some(parse_number(next_arg(&mut args)?)?),
+                "time" => opts.time = Some(parse_number(next_arg(&mut args)?)?),
+                "retrycount" => opts.retry_count = Some(parse_number(next_arg(&mut args)?)?),
+                "force" => opts.force = true,
+                "justid" => opts.justid = true,
+                "lastid" => {
+                    opts.last_id = Some(parse_stream_id(&parse_string(next_arg(&mut args)?)?, 0)?)
+                }
+                _ if opts == XClaimOptions::default() => ids.push(parse_stream_id(&arg, 0)?),
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+        if ids.is_empty() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(XClaim {
+            key,
+            group,
+            consumer,
+            min_idle,
+            ids,
+            opts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xclaim_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*10\r\n$6\r\nxclaim\r\n$1\r\ns\r\n$1\r\ng\r\n$1\r\nc\r\n$4\r\n3600\r\n$3\r\n1-0\r\n$3\r\n2-0\r\n$10\r\nRETRYCOUNT\r\n$1\r\n5\r\n$6\r\nJUSTID\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XClaim = frame.try_into()?;
+        assert_eq!(result.consumer, "c");
+        assert_eq!(result.min_idle, 3600);
+        assert_eq!(result.ids, vec![StreamId::new(1, 0), StreamId::new(2, 0)]);
+        assert_eq!(
+            result.opts,
+            XClaimOptions {
+                retry_count: Some(5),
+                justid: true,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+}
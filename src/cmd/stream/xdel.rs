@@ -0,0 +1,57 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, StreamId,
+};
+
+use super::parse_stream_id;
+
+#[derive(Debug)]
+pub struct XDel {
+    pub(crate) key: String,
+    pub(crate) ids: Vec<StreamId>,
+}
+
+impl CommandExecutor for XDel {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.xdel(&self.key, &self.ids) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for XDel {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xdel"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let ids = args
+            .map(|id| parse_stream_id(&parse_string(id)?, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(XDel { key, ids })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xdel_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$4\r\nxdel\r\n$6\r\nstream\r\n$3\r\n1-1\r\n$1\r\n2\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XDel = frame.try_into()?;
+        assert_eq!(result.key, "stream");
+        assert_eq!(result.ids, vec![StreamId::new(1, 1), StreamId::new(2, 0)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_command, validate_min_args,
+        CommandError, CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError, SimpleString, StreamId,
+};
+
+use super::parse_group_id;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum XGroupKind {
+    // id 为 None 表示 "$"
+    Create {
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    },
+    SetId {
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    },
+    Destroy {
+        key: String,
+        group: String,
+    },
+    CreateConsumer {
+        key: String,
+        group: String,
+        consumer: String,
+    },
+    DelConsumer {
+        key: String,
+        group: String,
+        consumer: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct XGroup {
+    pub(crate) kind: XGroupKind,
+}
+
+impl CommandExecutor for XGroup {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = match self.kind {
+            XGroupKind::Create {
+                key,
+                group,
+                id,
+                mkstream,
+                entries_read,
+            } => backend
+                .xgroup_create(key, group, id, mkstream, entries_read)
+                .map(|_| SimpleString::new("OK").into()),
+            XGroupKind::SetId {
+                key,
+                group,
+                id,
+                entries_read,
+            } => backend
+                .xgroup_setid(&key, &group, id, entries_read)
+                .map(|_| SimpleString::new("OK").into()),
+            XGroupKind::Destroy { key, group } => backend
+                .xgroup_destroy(&key, &group)
+                .map(|ret| (ret as i64).into()),
+            XGroupKind::CreateConsumer {
+                key,
+                group,
+                consumer,
+            } => backend
+                .xgroup_createconsumer(&key, &group, &consumer)
+                .map(|ret| (ret as i64).into()),
+            XGroupKind::DelConsumer {
+                key,
+                group,
+                consumer,
+            } => backend
+                .xgroup_delconsumer(&key, &group, &consumer)
+                .map(|ret| (ret as i64).into()),
+        };
+        ret.unwrap_or_else(|e| SimpleError::new(e.to_string()).into())
+    }
+}
+
+// 解析可选的 ENTRIESREAD 参数
+fn parse_entries_read(
+    args: &mut impl Iterator<Item = RespFrame>,
+) -> Result<Option<u64>, CommandError> {
+    match args.next().map(parse_string).transpose()? {
+        Some(arg) if arg.eq_ignore_ascii_case("entriesread") => {
+            Ok(Some(parse_number(next_arg(args)?)?))
+        }
+        None => Ok(None),
+        _ => Err(CommandError::InvalidArgument("syntax error".to_string())),
+    }
+}
+
+impl TryFrom<RespArray> for XGroup {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+
+        let kind = match sub.as_str() {
+            "create" => {
+                validate_min_args(&value, &["xgroup", "create"], 3)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                let group = parse_string(next_arg(&mut args)?)?;
+                let id = parse_group_id(&parse_string(next_arg(&mut args)?)?)?;
+
+                let (mut mkstream, mut entries_read) = (false, None);
+                while let Some(arg) = args.next() {
+                    match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                        "mkstream" => mkstream = true,
+                        "entriesread" => entries_read = Some(parse_number(next_arg(&mut args)?)?),
+                        _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+                    }
+                }
+                XGroupKind::Create {
+                    key,
+                    group,
+                    id,
+                    mkstream,
+                    entries_read,
+                }
+            }
+            "setid" => {
+                validate_min_args(&value, &["xgroup", "setid"], 3)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                let group = parse_string(next_arg(&mut args)?)?;
+                let id = parse_group_id(&parse_string(next_arg(&mut args)?)?)?;
+                let entries_read = parse_entries_read(&mut args)?;
+                if args.next().is_some() {
+                    return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                }
+                XGroupKind::SetId {
+                    key,
+                    group,
+                    id,
+                    entries_read,
+                }
+            }
+            "destroy" => {
+                validate_command(&value, &["xgroup", "destroy"], 2)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                XGroupKind::Destroy {
+                    key: parse_string(next_arg(&mut args)?)?,
+                    group: parse_string(next_arg(&mut args)?)?,
+                }
+            }
+            name @ ("createconsumer" | "delconsumer") => {
+                let names = if name == "createconsumer" {
+                    ["xgroup", "createconsumer"]
+                } else {
+                    ["xgroup", "delconsumer"]
+                };
+                validate_command(&value, &names, 3)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                let group = parse_string(next_arg(&mut args)?)?;
+                let consumer = parse_string(next_arg(&mut args)?)?;
+                if name == "createconsumer" {
+                    XGroupKind::CreateConsumer {
+                        key,
+                        group,
+                        consumer,
+                    }
+                } else {
+                    XGroupKind::DelConsumer {
+                        key,
+                        group,
+                        consumer,
+                    }
+                }
+            }
+            _ => {
+                return Err(CommandError::InvalidCommand(format!(
+                    "unknown subcommand '{}' for 'xgroup' command",
+                    sub
+                )))
+            }
+        };
+
+        Ok(XGroup { kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xgroup_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$6\r\nXGROUP\r\n$6\r\nCREATE\r\n$1\r\ns\r\n$1\r\ng\r\n$1\r\n$\r\n$8\r\nMKSTREAM\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XGroup = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            XGroupKind::Create {
+                key: "s".to_string(),
+                group: "g".to_string(),
+                id: None,
+                mkstream: true,
+                entries_read: None,
+            }
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$6\r\nxgroup\r\n$11\r\ndelconsumer\r\n$1\r\ns\r\n$1\r\ng\r\n$1\r\nc\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XGroup = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            XGroupKind::DelConsumer {
+                key: "s".to_string(),
+                group: "g".to_string(),
+                consumer: "c".to_string(),
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    BackendError, BulkString, RespArray, RespFrame, RespMap, RespNull, SimpleError,
+};
+
+use super::{entry_to_frame, id_to_frame};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum XInfoKind {
+    Stream { key: String },
+    Groups { key: String },
+    Consumers { key: String, group: String },
+}
+
+#[derive(Debug)]
+pub struct XInfo {
+    pub(crate) kind: XInfoKind,
+}
+
+impl CommandExecutor for XInfo {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match self.kind {
+            XInfoKind::Stream { key } => {
+                let info = match backend.xinfo(&key) {
+                    Some(info) => info,
+                    None => return SimpleError::new(BackendError::NoSuchKey.to_string()).into(),
+                };
+
+                let entry_frame = |entry| match entry {
+                    Some(entry) => entry_to_frame(entry),
+                    None => RespFrame::Null(RespNull),
+                };
+                let first_id = info.first_entry.as_ref().map(|e| e.0).unwrap_or_default();
+
+                let mut map = RespMap::new();
+                map.insert("length".to_string(), (info.length as i64).into());
+                map.insert(
+                    "last-generated-id".to_string(),
+                    id_to_frame(info.last_generated_id),
+                );
+                map.insert(
+                    "max-deleted-entry-id".to_string(),
+                    id_to_frame(info.max_deleted_entry_id),
+                );
+                map.insert(
+                    "entries-added".to_string(),
+                    (info.entries_added as i64).into(),
+                );
+                map.insert("recorded-first-entry-id".to_string(), id_to_frame(first_id));
+                map.insert("groups".to_string(), (info.groups as i64).into());
+                map.insert("first-entry".to_string(), entry_frame(info.first_entry));
+                map.insert("last-entry".to_string(), entry_frame(info.last_entry));
+                map.into()
+            }
+            XInfoKind::Groups { key } => {
+                let groups = match backend.xinfo_groups(&key) {
+                    Some(groups) => groups,
+                    None => return SimpleError::new(BackendError::NoSuchKey.to_string()).into(),
+                };
+                let groups = groups
+                    .into_iter()
+                    .map(|g| {
+                        let mut map = RespMap::new();
+                        map.insert("name".to_string(), BulkString::from(g.name).into());
+                        map.insert("consumers".to_string(), (g.consumers as i64).into());
+                        map.insert("pending".to_string(), (g.pending as i64).into());
+                        map.insert(
+                            "last-delivered-id".to_string(),
+                            id_to_frame(g.last_delivered_id),
+                        );
+                        map.insert(
+                            "entries-read".to_string(),
+                            g.entries_read
+                                .map_or(RespNull.into(), |n| (n as i64).into()),
+                        );
+                        map.insert("lag".to_string(), (g.lag as i64).into());
+                        map.into()
+                    })
+                    .collect::<Vec<RespFrame>>();
+                RespArray::new(groups).into()
+            }
+            XInfoKind::Consumers { key, group } => {
+                let consumers = match backend.xinfo_consumers(&key, &group) {
+                    Ok(consumers) => consumers,
+                    Err(e) => return SimpleError::new(e.to_string()).into(),
+                };
+                let consumers = consumers
+                    .into_iter()
+                    .map(|c| {
+                        let mut map = RespMap::new();
+                        map.insert("name".to_string(), BulkString::from(c.name).into());
+                        map.insert("pending".to_string(), (c.pending as i64).into());
+                        map.insert("idle".to_string(), (c.idle as i64).into());
+                        // 从未读到过消息时返回 -1
+                        map.insert(
+                            "inactive".to_string(),
+                            c.inactive.map_or(-1, |t| t as i64).into(),
+                        );
+                        map.into()
+                    })
+                    .collect::<Vec<RespFrame>>();
+                RespArray::new(consumers).into()
+            }
+        }
+    }
+}
+
+impl TryFrom<RespArray> for XInfo {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let sub = match value.get(1) {
+            Some(RespFrame::BulkString(sub)) => String::from_utf8_lossy(sub).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+
+        match sub.as_str() {
+            "stream" => {
+                validate_command(&value, &["xinfo", "stream"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                Ok(XInfo {
+                    kind: XInfoKind::Stream { key },
+                })
+            }
+            "groups" => {
+                validate_command(&value, &["xinfo", "groups"], 1)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                Ok(XInfo {
+                    kind: XInfoKind::Groups { key },
+                })
+            }
+            "consumers" => {
+                validate_command(&value, &["xinfo", "consumers"], 2)?;
+                let mut args = extract_args(value, 2)?.into_iter();
+                let key = parse_string(next_arg(&mut args)?)?;
+                let group = parse_string(next_arg(&mut args)?)?;
+                Ok(XInfo {
+                    kind: XInfoKind::Consumers { key, group },
+                })
+            }
+            _ => Err(CommandError::InvalidCommand(format!(
+                "unknown subcommand '{}' for 'xinfo' command",
+                sub
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xinfo_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$5\r\nXINFO\r\n$6\r\nSTREAM\r\n$6\r\nstream\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XInfo = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            XInfoKind::Stream {
+                key: "stream".to_string()
+            }
+        );
+
+        buf.extend_from_slice(b"*4\r\n$5\r\nxinfo\r\n$9\r\nconsumers\r\n$1\r\ns\r\n$1\r\ng\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XInfo = frame.try_into()?;
+        assert_eq!(
+            result.kind,
+            XInfoKind::Consumers {
+                key: "s".to_string(),
+                group: "g".to_string()
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::Bound;
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, RespArray, RespFrame, RespNull, SimpleError, StreamId,
+};
+
+use super::{id_to_frame, parse_range_end, parse_range_start};
+
+/// XPENDING 的扩展形式：[IDLE min-idle] start end count [consumer]
+#[derive(Debug, PartialEq, Eq)]
+pub struct XPendingRange {
+    pub(crate) min_idle: u64,
+    pub(crate) start: Bound<StreamId>,
+    pub(crate) end: Bound<StreamId>,
+    pub(crate) count: usize,
+    pub(crate) consumer: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct XPending {
+    pub(crate) key: String,
+    pub(crate) group: String,
+    // None 表示简略形式
+    pub(crate) range: Option<XPendingRange>,
+}
+
+impl CommandExecutor for XPending {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let range = match self.range {
+            Some(range) => range,
+            None => {
+                let summary = match backend.xpending_summary(&self.key, &self.group) {
+                    Ok(summary) => summary,
+                    Err(e) => return SimpleError::new(e.to_string()).into(),
+                };
+                let (min, max) = match summary.min_max {
+                    Some((min, max)) => (id_to_frame(min), id_to_frame(max)),
+                    None => (RespNull.into(), RespNull.into()),
+                };
+                // 每个 consumer 的未确认数量以字符串返回，和 redis 保持一致
+                let consumers = summary
+                    .consumers
+                    .into_iter()
+                    .map(|(name, count)| {
+                        RespArray::new([
+                            BulkString::from(name).into(),
+                            BulkString::from(count.to_string()).into(),
+                        ])
+                        .into()
+                    })
+                    .collect::<Vec<RespFrame>>();
+                return RespArray::new([
+                    (summary.count as i64).into(),
+                    min,
+                    max,
+                    RespArray::new(consumers).into(),
+                ])
+                .into();
+            }
+        };
+
+        let pending = match backend.xpending(
+            &self.key,
+            &self.group,
+            range.start,
+            range.end,
+            range.count,
+            range.consumer.as_deref(),
+            range.min_idle,
+        ) {
+            Ok(pending) => pending,
+            Err(e) => return SimpleError::new(e.to_string()).into(),
+        };
+        let pending = pending
+            .into_iter()
+            .map(|p| {
+                RespArray::new([
+                    id_to_frame(p.id),
+                    BulkString::from(p.consumer).into(),
+                    (p.idle as i64).into(),
+                    (p.delivery_count as i64).into(),
+                ])
+                .into()
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(pending).into()
+    }
+}
+
+impl TryFrom<RespArray> for XPending {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xpending"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let group = parse_string(next_arg(&mut args)?)?;
+        if args.peek().is_none() {
+            return Ok(XPending {
+                key,
+                group,
+                range: None,
+            });
+        }
+
+        let mut min_idle = 0;
+        if let Some(RespFrame::BulkString(arg)) = args.peek() {
+            if arg.eq_ignore_ascii_case(b"idle") {
+                args.next();
+                min_idle = parse_number(next_arg(&mut args)?)?;
+            }
+        }
+        let start = parse_range_start(next_arg(&mut args)?)?;
+        let end = parse_range_end(next_arg(&mut args)?)?;
+        let count = parse_number(next_arg(&mut args)?)?;
+        let consumer = args.next().map(parse_string).transpose()?;
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(XPending {
+            key,
+            group,
+            range: Some(XPendingRange {
+                min_idle,
+                start,
+                end,
+                count,
+                consumer,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xpending_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$8\r\nxpending\r\n$1\r\ns\r\n$1\r\ng\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XPending = frame.try_into()?;
+        assert_eq!(result.range, None);
+
+        buf.extend_from_slice(
+            b"*9\r\n$8\r\nxpending\r\n$1\r\ns\r\n$1\r\ng\r\n$4\r\nIDLE\r\n$4\r\n1000\r\n$1\r\n-\r\n$1\r\n+\r\n$2\r\n10\r\n$1\r\nc\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XPending = frame.try_into()?;
+        assert_eq!(
+            result.range,
+            Some(XPendingRange {
+                min_idle: 1000,
+                start: Bound::Unbounded,
+                end: Bound::Unbounded,
+                count: 10,
+                consumer: Some("c".to_string()),
+            })
+        );
+
+        Ok(())
+    }
+}
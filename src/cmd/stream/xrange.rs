@@ -0,0 +1,96 @@
+use std::ops::Bound;
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, StreamId,
+};
+
+use super::{entries_to_frame, parse_range_end, parse_range_start};
+
+/// XRANGE 和 XREVRANGE 共用，XREVRANGE 的参数顺序是 end start
+#[derive(Debug)]
+pub struct XRange {
+    pub(crate) key: String,
+    pub(crate) start: Bound<StreamId>,
+    pub(crate) end: Bound<StreamId>,
+    pub(crate) count: usize,
+    pub(crate) rev: bool,
+}
+
+impl CommandExecutor for XRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let entries = backend.xrange(&self.key, self.start, self.end, self.count, self.rev);
+        entries_to_frame(entries)
+    }
+}
+
+impl TryFrom<RespArray> for XRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"xrevrange"));
+        let name = if rev { "xrevrange" } else { "xrange" };
+        validate_min_args(&value, &[name], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let (start, end) = if rev {
+            let end = parse_range_end(next_arg(&mut args)?)?;
+            (parse_range_start(next_arg(&mut args)?)?, end)
+        } else {
+            let start = parse_range_start(next_arg(&mut args)?)?;
+            (start, parse_range_end(next_arg(&mut args)?)?)
+        };
+
+        let count = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("count") => parse_number(next_arg(&mut args)?)?,
+            None => usize::MAX,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+
+        Ok(XRange {
+            key,
+            start,
+            end,
+            count,
+            rev,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xrange_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$9\r\nxrevrange\r\n$6\r\nstream\r\n$1\r\n+\r\n$4\r\n(1-5\r\n$5\r\nCOUNT\r\n$1\r\n2\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XRange = frame.try_into()?;
+        assert_eq!(result.key, "stream");
+        assert_eq!(result.start, Bound::Excluded(StreamId::new(1, 5)));
+        assert_eq!(result.end, Bound::Unbounded);
+        assert_eq!(result.count, 2);
+        assert!(result.rev);
+
+        buf.extend_from_slice(b"*4\r\n$6\r\nxrange\r\n$6\r\nstream\r\n$1\r\n1\r\n$1\r\n2\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XRange = frame.try_into()?;
+        assert_eq!(result.start, Bound::Included(StreamId::new(1, 0)));
+        assert_eq!(result.end, Bound::Included(StreamId::new(2, u64::MAX)));
+
+        Ok(())
+    }
+}
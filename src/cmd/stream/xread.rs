@@ -0,0 +1,172 @@
+use std::{ops::Bound, time::Duration};
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args,
+        BlockingCommandExecutor, CommandError, CommandExecutor,
+    },
+    Backend, BulkString, RespArray, RespFrame, RespNull, StreamId,
+};
+
+use super::{entries_to_frame, parse_stream_id};
+
+/// XREAD 的起始 ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XReadId {
+    // "$"，只读取阻塞之后的新消息
+    New,
+    // "+"，读取最后一条消息
+    Last,
+    // 读取大于这个 ID 的消息
+    After(StreamId),
+}
+
+#[derive(Debug)]
+pub struct XRead {
+    pub(crate) count: usize,
+    // None 表示没有 BLOCK 参数，Some(None) 表示一直阻塞
+    pub(crate) block: Option<Option<Duration>>,
+    pub(crate) keys: Vec<String>,
+    pub(crate) ids: Vec<XReadId>,
+}
+
+impl BlockingCommandExecutor for XRead {
+    fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.block.flatten()
+    }
+
+    // "$" 需要在阻塞之前解析成当前最后一个 ID
+    fn prepare(&mut self, backend: &Backend) {
+        for (key, id) in self.keys.iter().zip(self.ids.iter_mut()) {
+            if *id == XReadId::New {
+                *id = XReadId::After(backend.xlast_id(key).unwrap_or_default());
+            }
+        }
+    }
+
+    // 返回 [[key, [entry, ...]], ...]，所有 key 都没有新消息时返回 None
+    fn try_execute(&self, backend: &Backend) -> Option<RespFrame> {
+        let mut ret = Vec::new();
+        for (key, id) in self.keys.iter().zip(self.ids.iter()) {
+            let entries = match id {
+                XReadId::New => continue,
+                XReadId::Last => backend.xrange(key, Bound::Unbounded, Bound::Unbounded, 1, true),
+                XReadId::After(id) => backend.xrange(
+                    key,
+                    Bound::Excluded(*id),
+                    Bound::Unbounded,
+                    self.count,
+                    false,
+                ),
+            };
+            if !entries.is_empty() {
+                let key = BulkString::from(key.as_str()).into();
+                ret.push(RespArray::new([key, entries_to_frame(entries)]).into());
+            }
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(RespArray::new(ret).into())
+        }
+    }
+}
+
+// 没有 BLOCK 参数或者在事务中执行时不会阻塞
+impl CommandExecutor for XRead {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_execute(backend)
+            .unwrap_or(RespFrame::Null(RespNull))
+    }
+}
+
+impl TryFrom<RespArray> for XRead {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xread"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let (mut count, mut block) = (usize::MAX, None);
+        loop {
+            match parse_string(next_arg(&mut args)?)?
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "count" => count = parse_number(next_arg(&mut args)?)?,
+                "block" => {
+                    let ms: u64 = parse_number(next_arg(&mut args)?)?;
+                    block = Some((ms > 0).then(|| Duration::from_millis(ms)));
+                }
+                "streams" => break,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+
+        // STREAMS 后面是 key1 key2 ... id1 id2 ...
+        let args = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(CommandError::InvalidArgument(
+                "Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified"
+                    .to_string(),
+            ));
+        }
+        let (keys, ids) = args.split_at(args.len() / 2);
+        let ids = ids
+            .iter()
+            .map(|id| match id.as_str() {
+                "$" => Ok(XReadId::New),
+                "+" => Ok(XReadId::Last),
+                _ => Ok(XReadId::After(parse_stream_id(id, 0)?)),
+            })
+            .collect::<Result<Vec<_>, CommandError>>()?;
+
+        Ok(XRead {
+            count,
+            block,
+            keys: keys.to_vec(),
+            ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xread_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*10\r\n$5\r\nxread\r\n$5\r\nCOUNT\r\n$1\r\n2\r\n$5\r\nBLOCK\r\n$3\r\n100\r\n$7\r\nSTREAMS\r\n$2\r\ns1\r\n$2\r\ns2\r\n$1\r\n$\r\n$3\r\n1-1\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XRead = frame.try_into()?;
+        assert_eq!(result.count, 2);
+        assert_eq!(result.block, Some(Some(Duration::from_millis(100))));
+        assert_eq!(result.keys, vec!["s1", "s2"]);
+        assert_eq!(
+            result.ids,
+            vec![XReadId::New, XReadId::After(StreamId::new(1, 1))]
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$5\r\nxread\r\n$7\r\nSTREAMS\r\n$2\r\ns1\r\n$2\r\ns2\r\n$1\r\n0\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(XRead::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
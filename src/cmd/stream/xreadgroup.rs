@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args,
+        BlockingCommandExecutor, CommandError, CommandExecutor,
+    },
+    Backend, BulkString, RespArray, RespFrame, RespNull, SimpleError, StreamId,
+};
+
+use super::{parse_stream_id, pending_entries_to_frame};
+
+#[derive(Debug)]
+pub struct XReadGroup {
+    pub(crate) group: String,
+    pub(crate) consumer: String,
+    pub(crate) count: usize,
+    // None 表示没有 BLOCK 参数，Some(None) 表示一直阻塞
+    pub(crate) block: Option<Option<Duration>>,
+    pub(crate) noack: bool,
+    pub(crate) keys: Vec<String>,
+    // None 表示 ">"，读取从未投递过的新消息，否则读取自己 PEL 中的历史消息
+    pub(crate) ids: Vec<Option<StreamId>>,
+}
+
+impl XReadGroup {
+    /// 只有全部是 ">" 时才会阻塞，读取历史消息总是立即返回
+    pub fn is_blocking(&self) -> bool {
+        self.block.is_some() && self.ids.iter().all(Option::is_none)
+    }
+}
+
+impl BlockingCommandExecutor for XReadGroup {
+    fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.block.flatten()
+    }
+
+    fn try_execute(&self, backend: &Backend) -> Option<RespFrame> {
+        let mut ret = Vec::new();
+        for (key, id) in self.keys.iter().zip(self.ids.iter()) {
+            let entries = match backend.xreadgroup(
+                key,
+                &self.group,
+                &self.consumer,
+                *id,
+                self.count,
+                self.noack,
+            ) {
+                Ok(entries) => entries,
+                Err(e) => return Some(SimpleError::new(e.to_string()).into()),
+            };
+            // 历史消息即使为空也要返回这个 key
+            if id.is_some() || !entries.is_empty() {
+                let key = BulkString::from(key.as_str()).into();
+                ret.push(RespArray::new([key, pending_entries_to_frame(entries)]).into());
+            }
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(RespArray::new(ret).into())
+        }
+    }
+}
+
+impl CommandExecutor for XReadGroup {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_execute(backend)
+            .unwrap_or(RespFrame::Null(RespNull))
+    }
+}
+
+impl TryFrom<RespArray> for XReadGroup {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xreadgroup"], 6)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        if !parse_string(next_arg(&mut args)?)?.eq_ignore_ascii_case("group") {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+        let group = parse_string(next_arg(&mut args)?)?;
+        let consumer = parse_string(next_arg(&mut args)?)?;
+
+        let (mut count, mut block, mut noack) = (usize::MAX, None, false);
+        loop {
+            match parse_string(next_arg(&mut args)?)?
+                .to_ascii_lowercase()
+                .as_str()
+            {
+                "count" => count = parse_number(next_arg(&mut args)?)?,
+                "block" => {
+                    let ms: u64 = parse_number(next_arg(&mut args)?)?;
+                    block = Some((ms > 0).then(|| Duration::from_millis(ms)));
+                }
+                "noack" => noack = true,
+                "streams" => break,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+
+        let args = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+        if args.is_empty() || args.len() % 2 != 0 {
+            return Err(CommandError::InvalidArgument(
+                "Unbalanced 'xreadgroup' list of streams: for each stream key an ID or '>' must be specified"
+                    .to_string(),
+            ));
+        }
+        let (keys, ids) = args.split_at(args.len() / 2);
+        let ids = ids
+            .iter()
+            .map(|id| match id.as_str() {
+                ">" => Ok(None),
+                _ => Ok(Some(parse_stream_id(id, 0)?)),
+            })
+            .collect::<Result<Vec<_>, CommandError>>()?;
+
+        Ok(XReadGroup {
+            group,
+            consumer,
+            count,
+            block,
+            noack,
+            keys: keys.to_vec(),
+            ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_xreadgroup_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*11\r\n$10\r\nXREADGROUP\r\n$5\r\nGROUP\r\n$1\r\ng\r\n$1\r\nc\r\n$5\r\nBLOCK\r\n$1\r\n0\r\n$5\r\nNOACK\r\n$7\r\nSTREAMS\r\n$2\r\ns1\r\n$2\r\ns2\r\n$1\r\n>\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(XReadGroup::try_from(frame).is_err());
+
+        buf.extend_from_slice(
+            b"*12\r\n$10\r\nXREADGROUP\r\n$5\r\nGROUP\r\n$1\r\ng\r\n$1\r\nc\r\n$5\r\nBLOCK\r\n$1\r\n0\r\n$5\r\nNOACK\r\n$7\r\nSTREAMS\r\n$2\r\ns1\r\n$2\r\ns2\r\n$1\r\n>\r\n$1\r\n0\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: XReadGroup = frame.try_into()?;
+        assert_eq!(result.group, "g");
+        assert_eq!(result.consumer, "c");
+        assert_eq!(result.block, Some(None));
+        assert!(result.noack);
+        assert_eq!(result.keys, vec!["s1", "s2"]);
+        assert_eq!(result.ids, vec![None, Some(StreamId::default())]);
+        assert!(!result.is_blocking());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, StreamTrim,
+};
+
+use super::parse_trim;
+
+#[derive(Debug)]
+pub struct XTrim {
+    pub(crate) key: String,
+    pub(crate) trim: StreamTrim,
+}
+
+impl CommandExecutor for XTrim {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.xtrim(&self.key, &self.trim) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for XTrim {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["xtrim"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let strategy = parse_string(next_arg(&mut args)?)?.to_ascii_lowercase();
+        if strategy != "maxlen" && strategy != "minid" {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+        let trim = parse_trim(&strategy, &mut args)?;
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(XTrim { key, trim })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{RespDecode, StreamId, TrimStrategy};
+
+    use super::*;
+
+    #[test]
+    fn test_xtrim_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$5\r\nxtrim\r\n$6\r\nstream\r\n$5\r\nMINID\r\n$1\r\n=\r\n$3\r\n5-1\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: XTrim = frame.try_into()?;
+        assert_eq!(result.key, "stream");
+        assert_eq!(
+            result.trim,
+            StreamTrim {
+                strategy: TrimStrategy::MinId(StreamId::new(5, 1)),
+                approximate: false,
+                limit: 0,
+            }
+        );
+
+        buf.extend_from_slice(b"*6\r\n$5\r\nxtrim\r\n$6\r\nstream\r\n$6\r\nMAXLEN\r\n$1\r\n1\r\n$5\r\nLIMIT\r\n$1\r\n1\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(XTrim::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError, ThrottleParams,
+};
+
+/// CL.THROTTLE key max_burst count period [quantity]
+/// 返回 [是否被限流, 上限, 剩余数量, 多少秒之后重试, 多少秒之后完全恢复]
+#[derive(Debug)]
+pub struct CLThrottle {
+    pub(crate) key: String,
+    pub(crate) params: ThrottleParams,
+}
+
+impl CommandExecutor for CLThrottle {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.cl_throttle(self.key, self.params) {
+            Ok(ret) => RespArray::new([
+                (ret.limited as i64).into(),
+                ret.limit.into(),
+                ret.remaining.into(),
+                ret.retry_after.into(),
+                ret.reset_after.into(),
+            ])
+            .into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for CLThrottle {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["cl.throttle"], 4)?;
+        if value.len() > 6 {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let max_burst: i64 = parse_number(next_arg(&mut args)?)?;
+        let count: i64 = parse_number(next_arg(&mut args)?)?;
+        let period: i64 = parse_number(next_arg(&mut args)?)?;
+        let quantity: i64 = match args.next() {
+            Some(arg) => parse_number(arg)?,
+            None => 1,
+        };
+        if max_burst < 0 || count < 1 || period < 1 || quantity < 0 {
+            return Err(CommandError::InvalidArgument(
+                "max_burst and quantity must be non-negative, count and period must be positive"
+                    .to_string(),
+            ));
+        }
+
+        Ok(CLThrottle {
+            key,
+            params: ThrottleParams {
+                max_burst,
+                count,
+                period,
+                quantity,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_cl_throttle_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$11\r\nCL.THROTTLE\r\n$9\r\nuser:1234\r\n$2\r\n15\r\n$2\r\n30\r\n$2\r\n60\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: CLThrottle = frame.try_into()?;
+        assert_eq!(result.key, "user:1234");
+        assert_eq!(
+            result.params,
+            ThrottleParams {
+                max_burst: 15,
+                count: 30,
+                period: 60,
+                quantity: 1
+            }
+        );
+
+        buf.extend_from_slice(b"*6\r\n$11\r\ncl.throttle\r\n$9\r\nuser:1234\r\n$2\r\n15\r\n$1\r\n0\r\n$2\r\n60\r\n$1\r\n1\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(CLThrottle::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+mod clthrottle;
+
+pub use clthrottle::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, BulkString, RespArray, RespFrame, ThrottleParams};
+
+    use super::*;
+
+    fn throttle(backend: &Backend, quantity: i64) -> RespFrame {
+        CLThrottle {
+            key: "user:1".to_string(),
+            params: ThrottleParams {
+                max_burst: 2,
+                count: 1,
+                period: 60,
+                quantity,
+            },
+        }
+        .execute(backend)
+    }
+
+    fn reply(values: [i64; 5]) -> RespFrame {
+        RespArray::new(values.map(RespFrame::from)).into()
+    }
+
+    #[test]
+    fn test_cl_throttle_command() -> Result<()> {
+        let backend = Backend::new();
+        // 允许突发 2 个请求，加上当前的请求一共 3 个
+        assert_eq!(throttle(&backend, 1), reply([0, 3, 2, -1, 60]));
+        assert_eq!(throttle(&backend, 1), reply([0, 3, 1, -1, 120]));
+        assert_eq!(throttle(&backend, 1), reply([0, 3, 0, -1, 180]));
+        assert_eq!(throttle(&backend, 1), reply([1, 3, 0, 60, 180]));
+        // quantity 为 0 时只查询状态
+        assert_eq!(throttle(&backend, 0), reply([0, 3, 0, -1, 180]));
+        // 超过突发上限的请求永远不会被允许
+        let RespFrame::Array(ret) = throttle(&backend, 4) else {
+            panic!("CL.THROTTLE should return an array");
+        };
+        assert_eq!(ret[0], 1.into());
+        assert_eq!(ret[3], (-1).into());
+
+        // 状态保存在会过期的 key 中
+        assert!(backend.expires.contains_key("user:1"));
+        backend.expire_at("user:1".to_string(), 0);
+        // 不访问 key 也会被主动过期删除
+        for _ in 0..backend.expires.shards().len() {
+            backend.expire_sweep();
+        }
+        assert!(!backend.map.contains_key("user:1"));
+        assert_eq!(throttle(&backend, 1), reply([0, 3, 2, -1, 60]));
+
+        // 参数很大时不会溢出
+        let ret = CLThrottle {
+            key: "user:2".to_string(),
+            params: ThrottleParams {
+                max_burst: i64::MAX,
+                count: 1,
+                period: i64::MAX,
+                quantity: i64::MAX,
+            },
+        }
+        .execute(&backend);
+        assert!(matches!(ret, RespFrame::Array(_)));
+
+        backend.set("user:1".to_string(), BulkString::from("abc").into());
+        assert!(matches!(throttle(&backend, 1), RespFrame::Error(_)));
+
+        Ok(())
+    }
+}
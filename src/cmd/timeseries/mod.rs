@@ -0,0 +1,517 @@
+mod tsadd;
+mod tscreate;
+mod tscreaterule;
+mod tsdeleterule;
+mod tsget;
+mod tsincrby;
+mod tsmadd;
+mod tsmrange;
+mod tsrange;
+
+pub use tsadd::*;
+pub use tscreate::*;
+pub use tscreaterule::*;
+pub use tsdeleterule::*;
+pub use tsget::*;
+pub use tsincrby::*;
+pub use tsmadd::*;
+pub use tsmrange::*;
+pub use tsrange::*;
+
+use crate::{
+    DuplicatePolicy, RespArray, RespFrame, TsAggregation, TsCreateOptions, TsRangeOptions,
+};
+
+use super::{next_arg, parse_number, parse_string, CommandError};
+
+// 数据点的时间戳，"*" 表示使用当前时间
+fn parse_timestamp(frame: RespFrame) -> Result<Option<i64>, CommandError> {
+    let s = parse_string(frame)?;
+    if s == "*" {
+        return Ok(None);
+    }
+    let ts: i64 = s
+        .parse()
+        .map_err(|_| CommandError::InvalidArgument("TSDB: invalid timestamp".to_string()))?;
+    if ts < 0 {
+        return Err(CommandError::InvalidArgument(
+            "TSDB: invalid timestamp".to_string(),
+        ));
+    }
+    Ok(Some(ts))
+}
+
+fn parse_value(frame: RespFrame) -> Result<f64, CommandError> {
+    let value: f64 = parse_number(frame)?;
+    if value.is_nan() {
+        return Err(CommandError::InvalidArgument(
+            "TSDB: invalid value".to_string(),
+        ));
+    }
+    Ok(value)
+}
+
+// TS.RANGE 的 fromTimestamp 和 toTimestamp，"-" 和 "+" 表示最小和最大的时间戳
+fn parse_range_bound(frame: RespFrame) -> Result<i64, CommandError> {
+    match parse_string(frame)?.as_str() {
+        "-" => Ok(0),
+        "+" => Ok(i64::MAX),
+        s => s
+            .parse()
+            .map_err(|_| CommandError::InvalidArgument("TSDB: invalid timestamp".to_string())),
+    }
+}
+
+fn parse_duplicate_policy(frame: RespFrame) -> Result<DuplicatePolicy, CommandError> {
+    match parse_string(frame)?.to_ascii_lowercase().as_str() {
+        "block" => Ok(DuplicatePolicy::Block),
+        "first" => Ok(DuplicatePolicy::First),
+        "last" => Ok(DuplicatePolicy::Last),
+        "min" => Ok(DuplicatePolicy::Min),
+        "max" => Ok(DuplicatePolicy::Max),
+        "sum" => Ok(DuplicatePolicy::Sum),
+        _ => Err(CommandError::InvalidArgument(
+            "TSDB: Unknown DUPLICATE_POLICY".to_string(),
+        )),
+    }
+}
+
+// AGGREGATION aggregator bucketDuration
+fn parse_aggregation(
+    args: &mut impl Iterator<Item = RespFrame>,
+) -> Result<(TsAggregation, i64), CommandError> {
+    let aggregation = match parse_string(next_arg(args)?)?.to_ascii_lowercase().as_str() {
+        "avg" => TsAggregation::Avg,
+        "sum" => TsAggregation::Sum,
+        "min" => TsAggregation::Min,
+        "max" => TsAggregation::Max,
+        "count" => TsAggregation::Count,
+        "first" => TsAggregation::First,
+        "last" => TsAggregation::Last,
+        _ => {
+            return Err(CommandError::InvalidArgument(
+                "TSDB: Unknown aggregation type".to_string(),
+            ))
+        }
+    };
+    let bucket: i64 = parse_number(next_arg(args)?)?;
+    if bucket <= 0 {
+        return Err(CommandError::InvalidArgument(
+            "TSDB: bucketDuration must be greater than zero".to_string(),
+        ));
+    }
+    Ok((aggregation, bucket))
+}
+
+// TS.CREATE 和 TS.ADD 共用的 RETENTION、DUPLICATE_POLICY 和 LABELS 参数
+// 能识别时返回 true，LABELS 会读取剩下所有的参数
+fn parse_create_option(
+    name: &str,
+    args: &mut impl Iterator<Item = RespFrame>,
+    opts: &mut TsCreateOptions,
+) -> Result<bool, CommandError> {
+    match name {
+        "retention" => opts.retention = parse_number(next_arg(args)?)?,
+        "duplicate_policy" => opts.duplicate_policy = parse_duplicate_policy(next_arg(args)?)?,
+        "labels" => {
+            while let Some(label) = args.next() {
+                let label = parse_string(label)?;
+                let value = parse_string(next_arg(args)?)?;
+                opts.labels.push((label, value));
+            }
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+// TS.RANGE 和 TS.MRANGE 共用的 COUNT 和 AGGREGATION 参数
+fn parse_range_option(
+    name: &str,
+    args: &mut impl Iterator<Item = RespFrame>,
+    opts: &mut TsRangeOptions,
+) -> Result<bool, CommandError> {
+    match name {
+        "count" => opts.count = Some(parse_number(next_arg(args)?)?),
+        "aggregation" => opts.aggregation = Some(parse_aggregation(args)?),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn sample_to_frame((ts, value): (i64, f64)) -> RespFrame {
+    RespArray::new([ts.into(), value.into()]).into()
+}
+
+fn samples_to_frame(samples: Vec<(i64, f64)>) -> RespFrame {
+    RespArray::new(samples.into_iter().map(sample_to_frame).collect::<Vec<_>>()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Backend, BulkString, SimpleError, SimpleString, TsFilter};
+
+    use super::*;
+
+    fn ts_add(backend: &Backend, key: &str, ts: i64, value: f64) -> RespFrame {
+        TSAdd {
+            key: key.to_string(),
+            timestamp: Some(ts),
+            value,
+            opts: TsCreateOptions::default(),
+            on_duplicate: None,
+        }
+        .execute(backend)
+    }
+
+    fn ts_range(backend: &Backend, key: &str, opts: TsRangeOptions) -> RespFrame {
+        TSRange {
+            key: key.to_string(),
+            from: 0,
+            to: i64::MAX,
+            opts,
+        }
+        .execute(backend)
+    }
+
+    fn samples(samples: &[(i64, f64)]) -> RespFrame {
+        samples_to_frame(samples.to_vec())
+    }
+
+    #[test]
+    fn test_ts_add_range_commands() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = TSCreate {
+            key: "temp".to_string(),
+            opts: TsCreateOptions {
+                duplicate_policy: DuplicatePolicy::Max,
+                ..Default::default()
+            },
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+        let cmd = TSCreate {
+            key: "temp".to_string(),
+            opts: TsCreateOptions::default(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR TSDB: key already exists").into()
+        );
+
+        assert_eq!(ts_add(&backend, "temp", 1000, 20.0), 1000.into());
+        assert_eq!(ts_add(&backend, "temp", 3000, 22.0), 3000.into());
+        // 乱序写入和重复的时间戳
+        assert_eq!(ts_add(&backend, "temp", 2000, 21.0), 2000.into());
+        assert_eq!(ts_add(&backend, "temp", 2000, 19.0), 2000.into());
+        assert_eq!(ts_add(&backend, "temp", 2000, 25.0), 2000.into());
+        assert_eq!(
+            ts_range(&backend, "temp", TsRangeOptions::default()),
+            samples(&[(1000, 20.0), (2000, 25.0), (3000, 22.0)])
+        );
+
+        let cmd = TSAdd {
+            key: "temp".to_string(),
+            timestamp: Some(3000),
+            value: 1.0,
+            opts: TsCreateOptions::default(),
+            on_duplicate: Some(DuplicatePolicy::Sum),
+        };
+        cmd.execute(&backend);
+        let cmd = TSGet {
+            key: "temp".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), sample_to_frame((3000, 23.0)));
+
+        // 默认的 DUPLICATE_POLICY 是 BLOCK
+        ts_add(&backend, "other", 1000, 1.0);
+        assert_eq!(
+            ts_add(&backend, "other", 1000, 2.0),
+            SimpleError::new("ERR TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode").into()
+        );
+
+        let cmd = TSRange {
+            key: "temp".to_string(),
+            from: 1500,
+            to: 3000,
+            opts: TsRangeOptions {
+                rev: true,
+                count: Some(1),
+                ..Default::default()
+            },
+        };
+        assert_eq!(cmd.execute(&backend), samples(&[(3000, 23.0)]));
+
+        let cmd = TSMAdd {
+            samples: vec![
+                ("temp".to_string(), Some(4000), 30.0),
+                ("nokey".to_string(), Some(4000), 30.0),
+            ],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([
+                4000.into(),
+                SimpleError::new("ERR TSDB: the key does not exist").into()
+            ])
+            .into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_incrby_command() -> Result<()> {
+        let backend = Backend::new();
+        let incr = |ts: i64, value: f64| {
+            TSIncrBy {
+                key: "counter".to_string(),
+                value,
+                timestamp: Some(ts),
+                opts: TsCreateOptions::default(),
+            }
+            .execute(&backend)
+        };
+        assert_eq!(incr(1000, 5.0), 1000.into());
+        assert_eq!(incr(1000, 2.0), 1000.into());
+        assert_eq!(incr(2000, -3.0), 2000.into());
+        assert_eq!(
+            incr(1500, 1.0),
+            SimpleError::new(
+                "ERR TSDB: timestamp must be equal to or higher than the maximum existing timestamp"
+            )
+            .into()
+        );
+        assert_eq!(
+            ts_range(&backend, "counter", TsRangeOptions::default()),
+            samples(&[(1000, 7.0), (2000, 4.0)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_aggregation_and_retention() -> Result<()> {
+        let backend = Backend::new();
+        for (i, v) in [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0].into_iter().enumerate() {
+            ts_add(&backend, "cpu", i as i64 * 10, v);
+        }
+        let agg = |aggregation: TsAggregation| {
+            ts_range(
+                &backend,
+                "cpu",
+                TsRangeOptions {
+                    aggregation: Some((aggregation, 30)),
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(
+            agg(TsAggregation::Avg),
+            samples(&[(0, 2.0), (30, 5.0), (60, 7.0)])
+        );
+        assert_eq!(
+            agg(TsAggregation::Sum),
+            samples(&[(0, 6.0), (30, 15.0), (60, 7.0)])
+        );
+        assert_eq!(
+            agg(TsAggregation::Max),
+            samples(&[(0, 3.0), (30, 6.0), (60, 7.0)])
+        );
+        assert_eq!(
+            agg(TsAggregation::Count),
+            samples(&[(0, 3.0), (30, 3.0), (60, 1.0)])
+        );
+        assert_eq!(
+            agg(TsAggregation::First),
+            samples(&[(0, 1.0), (30, 4.0), (60, 7.0)])
+        );
+
+        // 只保留最后 25ms 的数据，更早的数据不能写入
+        let cmd = TSCreate {
+            key: "short".to_string(),
+            opts: TsCreateOptions {
+                retention: 25,
+                ..Default::default()
+            },
+        };
+        cmd.execute(&backend);
+        for ts in [0, 10, 20, 30, 40, 50] {
+            ts_add(&backend, "short", ts, ts as f64);
+        }
+        assert_eq!(
+            ts_range(&backend, "short", TsRangeOptions::default()),
+            samples(&[(30, 30.0), (40, 40.0), (50, 50.0)])
+        );
+        assert_eq!(
+            ts_add(&backend, "short", 10, 1.0),
+            SimpleError::new("ERR TSDB: Timestamp is older than retention").into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_compaction_rule() -> Result<()> {
+        let backend = Backend::new();
+        for key in ["raw", "avg"] {
+            TSCreate {
+                key: key.to_string(),
+                opts: TsCreateOptions::default(),
+            }
+            .execute(&backend);
+        }
+        let cmd = TSCreateRule {
+            source: "raw".to_string(),
+            destination: "avg".to_string(),
+            aggregation: TsAggregation::Avg,
+            bucket: 100,
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+        let cmd = TSCreateRule {
+            source: "other".to_string(),
+            destination: "avg".to_string(),
+            aggregation: TsAggregation::Avg,
+            bucket: 100,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR TSDB: the key does not exist").into()
+        );
+
+        for (ts, v) in [(0, 1.0), (50, 3.0), (120, 10.0), (180, 20.0), (210, 5.0)] {
+            ts_add(&backend, "raw", ts, v);
+        }
+        // 最后一个桶还没有结束
+        assert_eq!(
+            ts_range(&backend, "avg", TsRangeOptions::default()),
+            samples(&[(0, 2.0), (100, 15.0)])
+        );
+
+        let cmd = TSDeleteRule {
+            source: "raw".to_string(),
+            destination: "avg".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+        ts_add(&backend, "raw", 500, 1.0);
+        assert_eq!(
+            ts_range(&backend, "avg", TsRangeOptions::default()),
+            samples(&[(0, 2.0), (100, 15.0)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_mrange_command() -> Result<()> {
+        let backend = Backend::new();
+        let labels = [
+            ("cpu:1", "cpu", "host1"),
+            ("cpu:2", "cpu", "host2"),
+            ("mem:1", "mem", "host1"),
+        ];
+        for (key, metric, host) in labels {
+            let cmd = TSCreate {
+                key: key.to_string(),
+                opts: TsCreateOptions {
+                    labels: vec![
+                        ("metric".to_string(), metric.to_string()),
+                        ("host".to_string(), host.to_string()),
+                    ],
+                    ..Default::default()
+                },
+            };
+            cmd.execute(&backend);
+            ts_add(&backend, key, 1000, 1.0);
+        }
+
+        let cmd = TSMRange {
+            from: 0,
+            to: i64::MAX,
+            opts: TsRangeOptions::default(),
+            with_labels: false,
+            filters: vec![
+                TsFilter::Eq("metric".to_string(), vec!["cpu".to_string()]),
+                TsFilter::Ne("host".to_string(), vec!["host2".to_string()]),
+            ],
+        };
+        let series = RespArray::new([
+            BulkString::from("cpu:1").into(),
+            RespArray::new(Vec::<RespFrame>::new()).into(),
+            samples(&[(1000, 1.0)]),
+        ]);
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([series.into()]).into()
+        );
+
+        let cmd = TSMRange {
+            from: 0,
+            to: i64::MAX,
+            opts: TsRangeOptions::default(),
+            with_labels: true,
+            filters: vec![TsFilter::Eq(
+                "host".to_string(),
+                vec!["host1".to_string(), "host3".to_string()],
+            )],
+        };
+        let ret = match cmd.execute(&backend) {
+            RespFrame::Array(ret) => ret,
+            _ => unreachable!(),
+        };
+        assert_eq!(ret.len(), 2);
+        let labels = RespArray::new([
+            RespArray::new([
+                BulkString::from("metric").into(),
+                BulkString::from("mem").into(),
+            ])
+            .into(),
+            RespArray::new([
+                BulkString::from("host").into(),
+                BulkString::from("host1").into(),
+            ])
+            .into(),
+        ]);
+        assert_eq!(
+            ret[1],
+            RespArray::new([
+                BulkString::from("mem:1").into(),
+                labels.into(),
+                samples(&[(1000, 1.0)])
+            ])
+            .into()
+        );
+
+        let cmd = TSGet {
+            key: "nokey".to_string(),
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            SimpleError::new("ERR TSDB: the key does not exist").into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_compression() -> Result<()> {
+        // 间隔固定、值变化不大的数据每个点只需要很少的字节
+        let backend = Backend::new();
+        for i in 0..1000 {
+            let value = 20.0 + (i % 10) as f64 * 0.5;
+            ts_add(&backend, "metric", 1_700_000_000_000 + i * 1000, value);
+        }
+        let series = backend.timeseries.get("metric").unwrap();
+        assert_eq!(series.len(), 1000);
+        assert!(
+            series.memory_usage() < 1000 * 4,
+            "{}",
+            series.memory_usage()
+        );
+        let all = series.range(0, i64::MAX);
+        assert_eq!(all[999], (1_700_000_000_000 + 999_000, 24.5));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    DuplicatePolicy, RespArray, RespFrame, SimpleError, TsCreateOptions,
+};
+
+use super::{parse_create_option, parse_duplicate_policy, parse_timestamp, parse_value};
+
+/// TS.ADD key timestamp value [RETENTION ms] [ON_DUPLICATE policy] [LABELS label value ...]
+/// key 不存在时使用这些参数创建
+#[derive(Debug)]
+pub struct TSAdd {
+    pub(crate) key: String,
+    // None 表示 "*"
+    pub(crate) timestamp: Option<i64>,
+    pub(crate) value: f64,
+    pub(crate) opts: TsCreateOptions,
+    pub(crate) on_duplicate: Option<DuplicatePolicy>,
+}
+
+impl CommandExecutor for TSAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_add(
+            self.key,
+            self.timestamp,
+            self.value,
+            Some(self.opts),
+            self.on_duplicate,
+        ) {
+            Ok(ts) => ts.into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ts.add"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let timestamp = parse_timestamp(next_arg(&mut args)?)?;
+        let value = parse_value(next_arg(&mut args)?)?;
+        let mut opts = TsCreateOptions::default();
+        let mut on_duplicate = None;
+        while let Some(arg) = args.next() {
+            let name = parse_string(arg)?.to_ascii_lowercase();
+            if name == "on_duplicate" {
+                on_duplicate = Some(parse_duplicate_policy(next_arg(&mut args)?)?);
+            } else if !parse_create_option(&name, &mut args, &mut opts)? {
+                return Err(CommandError::InvalidArgument("syntax error".to_string()));
+            }
+        }
+
+        Ok(TSAdd {
+            key,
+            timestamp,
+            value,
+            opts,
+            on_duplicate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_add_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*6\r\n$6\r\nTS.ADD\r\n$4\r\ntemp\r\n$1\r\n*\r\n$4\r\n20.5\r\n$12\r\nON_DUPLICATE\r\n$3\r\nsum\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSAdd = frame.try_into()?;
+        assert_eq!(result.key, "temp");
+        assert_eq!(result.timestamp, None);
+        assert_eq!(result.value, 20.5);
+        assert_eq!(result.on_duplicate, Some(DuplicatePolicy::Sum));
+
+        buf.extend_from_slice(b"*4\r\n$6\r\nts.add\r\n$4\r\ntemp\r\n$4\r\n1000\r\n$3\r\nnan\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSAdd::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
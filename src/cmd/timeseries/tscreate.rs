@@ -0,0 +1,79 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor,
+        RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError, TsCreateOptions,
+};
+
+use super::parse_create_option;
+
+/// TS.CREATE key [RETENTION ms] [DUPLICATE_POLICY policy] [LABELS label value ...]
+#[derive(Debug)]
+pub struct TSCreate {
+    pub(crate) key: String,
+    pub(crate) opts: TsCreateOptions,
+}
+
+impl CommandExecutor for TSCreate {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_create(self.key, self.opts) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSCreate {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ts.create"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let mut opts = TsCreateOptions::default();
+        while let Some(arg) = args.next() {
+            let name = parse_string(arg)?.to_ascii_lowercase();
+            if !parse_create_option(&name, &mut args, &mut opts)? {
+                return Err(CommandError::InvalidArgument("syntax error".to_string()));
+            }
+        }
+
+        Ok(TSCreate { key, opts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{DuplicatePolicy, RespDecode};
+
+    use super::*;
+
+    #[test]
+    fn test_ts_create_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*9\r\n$9\r\nTS.CREATE\r\n$4\r\ntemp\r\n$9\r\nRETENTION\r\n$5\r\n60000\r\n$16\r\nDUPLICATE_POLICY\r\n$3\r\nMAX\r\n$6\r\nLABELS\r\n$4\r\nroom\r\n$7\r\nkitchen\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSCreate = frame.try_into()?;
+        assert_eq!(result.key, "temp");
+        assert_eq!(result.opts.retention, 60000);
+        assert_eq!(result.opts.duplicate_policy, DuplicatePolicy::Max);
+        assert_eq!(
+            result.opts.labels,
+            vec![("room".to_string(), "kitchen".to_string())]
+        );
+
+        buf.extend_from_slice(
+            b"*4\r\n$9\r\nts.create\r\n$4\r\ntemp\r\n$6\r\nLABELS\r\n$4\r\nroom\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSCreate::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor,
+        RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError, TsAggregation,
+};
+
+use super::parse_aggregation;
+
+/// TS.CREATERULE sourceKey destKey AGGREGATION aggregator bucketDuration
+#[derive(Debug)]
+pub struct TSCreateRule {
+    pub(crate) source: String,
+    pub(crate) destination: String,
+    pub(crate) aggregation: TsAggregation,
+    pub(crate) bucket: i64,
+}
+
+impl CommandExecutor for TSCreateRule {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_createrule(
+            &self.source,
+            self.destination,
+            self.aggregation,
+            self.bucket,
+        ) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSCreateRule {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["ts.createrule"], 5)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let source = parse_string(next_arg(&mut args)?)?;
+        let destination = parse_string(next_arg(&mut args)?)?;
+        if !parse_string(next_arg(&mut args)?)?.eq_ignore_ascii_case("aggregation") {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+        let (aggregation, bucket) = parse_aggregation(&mut args)?;
+
+        Ok(TSCreateRule {
+            source,
+            destination,
+            aggregation,
+            bucket,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_createrule_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*6\r\n$13\r\nTS.CREATERULE\r\n$3\r\nraw\r\n$3\r\navg\r\n$11\r\nAGGREGATION\r\n$3\r\nAVG\r\n$5\r\n60000\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSCreateRule = frame.try_into()?;
+        assert_eq!(result.source, "raw");
+        assert_eq!(result.destination, "avg");
+        assert_eq!(result.aggregation, TsAggregation::Avg);
+        assert_eq!(result.bucket, 60000);
+
+        buf.extend_from_slice(b"*6\r\n$13\r\nts.createrule\r\n$3\r\nraw\r\n$3\r\navg\r\n$11\r\nAGGREGATION\r\n$3\r\navg\r\n$1\r\n0\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSCreateRule::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor,
+        RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+#[derive(Debug)]
+pub struct TSDeleteRule {
+    pub(crate) source: String,
+    pub(crate) destination: String,
+}
+
+impl CommandExecutor for TSDeleteRule {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_deleterule(&self.source, &self.destination) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSDeleteRule {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["ts.deleterule"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let source = parse_string(next_arg(&mut args)?)?;
+        let destination = parse_string(next_arg(&mut args)?)?;
+
+        Ok(TSDeleteRule {
+            source,
+            destination,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_deleterule_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$13\r\nTS.DELETERULE\r\n$3\r\nraw\r\n$3\r\navg\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSDeleteRule = frame.try_into()?;
+        assert_eq!(result.source, "raw");
+        assert_eq!(result.destination, "avg");
+
+        Ok(())
+    }
+}
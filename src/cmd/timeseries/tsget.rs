@@ -0,0 +1,57 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::sample_to_frame;
+
+/// 返回最后一个数据点，时间序列为空时返回空数组
+#[derive(Debug)]
+pub struct TSGet {
+    pub(crate) key: String,
+}
+
+impl CommandExecutor for TSGet {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_get(&self.key) {
+            Ok(Some(sample)) => sample_to_frame(sample),
+            Ok(None) => RespArray::new(Vec::<RespFrame>::new()).into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSGet {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["ts.get"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+
+        Ok(TSGet { key })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_get_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$6\r\nTS.GET\r\n$4\r\ntemp\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSGet = frame.try_into()?;
+        assert_eq!(result.key, "temp");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError, TsCreateOptions,
+};
+
+use super::{parse_create_option, parse_timestamp, parse_value};
+
+/// TS.INCRBY 和 TS.DECRBY 共用，DECRBY 解析时把 value 取反
+/// TS.INCRBY key value [TIMESTAMP timestamp] [RETENTION ms] [LABELS label value ...]
+#[derive(Debug)]
+pub struct TSIncrBy {
+    pub(crate) key: String,
+    pub(crate) value: f64,
+    pub(crate) timestamp: Option<i64>,
+    pub(crate) opts: TsCreateOptions,
+}
+
+impl CommandExecutor for TSIncrBy {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_incrby(self.key, self.timestamp, self.value, self.opts) {
+            Ok(ts) => ts.into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let decr = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"ts.decrby"));
+        let name = if decr { "ts.decrby" } else { "ts.incrby" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let mut value = parse_value(next_arg(&mut args)?)?;
+        if decr {
+            value = -value;
+        }
+        let mut timestamp = None;
+        let mut opts = TsCreateOptions::default();
+        while let Some(arg) = args.next() {
+            let name = parse_string(arg)?.to_ascii_lowercase();
+            if name == "timestamp" {
+                timestamp = parse_timestamp(next_arg(&mut args)?)?;
+            } else if !parse_create_option(&name, &mut args, &mut opts)? {
+                return Err(CommandError::InvalidArgument("syntax error".to_string()));
+            }
+        }
+
+        Ok(TSIncrBy {
+            key,
+            value,
+            timestamp,
+            opts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_incrby_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*5\r\n$9\r\nTS.INCRBY\r\n$7\r\ncounter\r\n$1\r\n3\r\n$9\r\nTIMESTAMP\r\n$4\r\n1000\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSIncrBy = frame.try_into()?;
+        assert_eq!(result.key, "counter");
+        assert_eq!(result.value, 3.0);
+        assert_eq!(result.timestamp, Some(1000));
+
+        buf.extend_from_slice(b"*3\r\n$9\r\nts.decrby\r\n$7\r\ncounter\r\n$3\r\n1.5\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: TSIncrBy = frame.try_into()?;
+        assert_eq!(result.value, -1.5);
+        assert_eq!(result.timestamp, None);
+
+        Ok(())
+    }
+}
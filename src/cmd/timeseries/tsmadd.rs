@@ -0,0 +1,79 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::{parse_timestamp, parse_value};
+
+/// TS.MADD key timestamp value [key timestamp value ...]，key 必须已经存在
+#[derive(Debug)]
+pub struct TSMAdd {
+    pub(crate) samples: Vec<(String, Option<i64>, f64)>,
+}
+
+impl CommandExecutor for TSMAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = self
+            .samples
+            .into_iter()
+            .map(
+                |(key, ts, value)| match backend.ts_add(key, ts, value, None, None) {
+                    Ok(ts) => ts.into(),
+                    Err(e) => SimpleError::new(e.to_string()).into(),
+                },
+            )
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(ret).into()
+    }
+}
+
+impl TryFrom<RespArray> for TSMAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["ts.madd"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let mut samples = Vec::new();
+        while let Some(key) = args.next() {
+            let key = parse_string(key)?;
+            let ts = parse_timestamp(next_arg(&mut args)?)?;
+            let value = parse_value(next_arg(&mut args)?)?;
+            samples.push((key, ts, value));
+        }
+
+        Ok(TSMAdd { samples })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_madd_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*7\r\n$7\r\nTS.MADD\r\n$1\r\na\r\n$4\r\n1000\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n*\r\n$1\r\n2\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSMAdd = frame.try_into()?;
+        assert_eq!(
+            result.samples,
+            vec![
+                ("a".to_string(), Some(1000), 1.0),
+                ("b".to_string(), None, 2.0)
+            ]
+        );
+
+        buf.extend_from_slice(b"*3\r\n$7\r\nts.madd\r\n$1\r\na\r\n$4\r\n1000\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSMAdd::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, RespArray, RespFrame, TsFilter, TsRangeOptions,
+};
+
+use super::{parse_range_bound, parse_range_option, samples_to_frame};
+
+/// TS.MRANGE 和 TS.MREVRANGE 共用
+/// TS.MRANGE fromTimestamp toTimestamp [WITHLABELS] [COUNT count]
+///   [AGGREGATION aggregator bucketDuration] FILTER filterExpr ...
+#[derive(Debug)]
+pub struct TSMRange {
+    pub(crate) from: i64,
+    pub(crate) to: i64,
+    pub(crate) opts: TsRangeOptions,
+    pub(crate) with_labels: bool,
+    pub(crate) filters: Vec<TsFilter>,
+}
+
+impl CommandExecutor for TSMRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let ret = backend
+            .ts_mrange(self.from, self.to, &self.filters, &self.opts)
+            .into_iter()
+            .map(|(key, labels, samples)| {
+                let labels = if self.with_labels {
+                    labels
+                        .into_iter()
+                        .map(|(l, v)| {
+                            RespArray::new([BulkString::from(l).into(), BulkString::from(v).into()])
+                                .into()
+                        })
+                        .collect::<Vec<RespFrame>>()
+                } else {
+                    Vec::new()
+                };
+                RespArray::new([
+                    BulkString::from(key).into(),
+                    RespArray::new(labels).into(),
+                    samples_to_frame(samples),
+                ])
+                .into()
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(ret).into()
+    }
+}
+
+// label=value label!=value label= label!= label=(v1,v2) label!=(v1,v2)
+fn parse_filter(s: &str) -> Result<TsFilter, CommandError> {
+    let invalid = || CommandError::InvalidArgument(format!("TSDB: failed parsing filter {}", s));
+    let (label, values, eq) = match s.split_once("!=") {
+        Some((label, values)) => (label, values, false),
+        None => {
+            let (label, values) = s.split_once('=').ok_or_else(invalid)?;
+            (label, values, true)
+        }
+    };
+    if label.is_empty() {
+        return Err(invalid());
+    }
+    let values = match values.strip_prefix('(') {
+        Some(list) => list
+            .strip_suffix(')')
+            .ok_or_else(invalid)?
+            .split(',')
+            .map(|v| v.to_string())
+            .collect(),
+        None => vec![values.to_string()],
+    };
+
+    if eq {
+        Ok(TsFilter::Eq(label.to_string(), values))
+    } else {
+        Ok(TsFilter::Ne(label.to_string(), values))
+    }
+}
+
+impl TryFrom<RespArray> for TSMRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"ts.mrevrange"));
+        let name = if rev { "ts.mrevrange" } else { "ts.mrange" };
+        validate_min_args(&value, &[name], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let from = parse_range_bound(next_arg(&mut args)?)?;
+        let to = parse_range_bound(next_arg(&mut args)?)?;
+        let mut opts = TsRangeOptions {
+            rev,
+            ..Default::default()
+        };
+        let mut with_labels = false;
+        let mut filters = Vec::new();
+        while let Some(arg) = args.next() {
+            let name = parse_string(arg)?.to_ascii_lowercase();
+            match name.as_str() {
+                "withlabels" => with_labels = true,
+                // FILTER 必须是最后一个参数
+                "filter" => {
+                    for arg in args.by_ref() {
+                        filters.push(parse_filter(&parse_string(arg)?)?);
+                    }
+                }
+                _ => {
+                    if !parse_range_option(&name, &mut args, &mut opts)? {
+                        return Err(CommandError::InvalidArgument("syntax error".to_string()));
+                    }
+                }
+            }
+        }
+        // 至少需要一个 label=value 的条件，否则会匹配所有没有这个 label 的时间序列
+        let has_matcher = filters
+            .iter()
+            .any(|f| matches!(f, TsFilter::Eq(_, values) if values.iter().any(|v| !v.is_empty())));
+        if !has_matcher {
+            return Err(CommandError::InvalidArgument(
+                "TSDB: please provide at least one matcher".to_string(),
+            ));
+        }
+
+        Ok(TSMRange {
+            from,
+            to,
+            opts,
+            with_labels,
+            filters,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_ts_mrange_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*8\r\n$9\r\nTS.MRANGE\r\n$1\r\n-\r\n$1\r\n+\r\n$10\r\nWITHLABELS\r\n$6\r\nFILTER\r\n$10\r\nmetric=cpu\r\n$16\r\nhost!=(web,db,c)\r\n$5\r\nzone=\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSMRange = frame.try_into()?;
+        assert!(result.with_labels);
+        assert!(!result.opts.rev);
+        assert_eq!(
+            result.filters,
+            vec![
+                TsFilter::Eq("metric".to_string(), vec!["cpu".to_string()]),
+                TsFilter::Ne(
+                    "host".to_string(),
+                    vec!["web".to_string(), "db".to_string(), "c".to_string()]
+                ),
+                TsFilter::Eq("zone".to_string(), vec!["".to_string()]),
+            ]
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$12\r\nts.mrevrange\r\n$1\r\n-\r\n$1\r\n+\r\n$6\r\nFILTER\r\n$5\r\nzone=\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSMRange::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
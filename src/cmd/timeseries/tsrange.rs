@@ -0,0 +1,95 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, SimpleError, TsRangeOptions,
+};
+
+use super::{parse_range_bound, parse_range_option, samples_to_frame};
+
+/// TS.RANGE 和 TS.REVRANGE 共用
+/// TS.RANGE key fromTimestamp toTimestamp [COUNT count] [AGGREGATION aggregator bucketDuration]
+#[derive(Debug)]
+pub struct TSRange {
+    pub(crate) key: String,
+    pub(crate) from: i64,
+    pub(crate) to: i64,
+    pub(crate) opts: TsRangeOptions,
+}
+
+impl CommandExecutor for TSRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.ts_range(&self.key, self.from, self.to, &self.opts) {
+            Ok(samples) => samples_to_frame(samples),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TSRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"ts.revrange"));
+        let name = if rev { "ts.revrange" } else { "ts.range" };
+        validate_min_args(&value, &[name], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let from = parse_range_bound(next_arg(&mut args)?)?;
+        let to = parse_range_bound(next_arg(&mut args)?)?;
+        let mut opts = TsRangeOptions {
+            rev,
+            ..Default::default()
+        };
+        while let Some(arg) = args.next() {
+            let name = parse_string(arg)?.to_ascii_lowercase();
+            if !parse_range_option(&name, &mut args, &mut opts)? {
+                return Err(CommandError::InvalidArgument("syntax error".to_string()));
+            }
+        }
+
+        Ok(TSRange {
+            key,
+            from,
+            to,
+            opts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{RespDecode, TsAggregation};
+
+    use super::*;
+
+    #[test]
+    fn test_ts_range_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*9\r\n$8\r\nTS.RANGE\r\n$4\r\ntemp\r\n$1\r\n-\r\n$1\r\n+\r\n$5\r\nCOUNT\r\n$2\r\n10\r\n$11\r\nAGGREGATION\r\n$3\r\navg\r\n$4\r\n1000\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TSRange = frame.try_into()?;
+        assert_eq!(result.key, "temp");
+        assert_eq!((result.from, result.to), (0, i64::MAX));
+        assert_eq!(result.opts.count, Some(10));
+        assert_eq!(result.opts.aggregation, Some((TsAggregation::Avg, 1000)));
+        assert!(!result.opts.rev);
+
+        buf.extend_from_slice(
+            b"*4\r\n$11\r\nts.revrange\r\n$4\r\ntemp\r\n$3\r\n100\r\n$3\r\n200\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: TSRange = frame.try_into()?;
+        assert_eq!((result.from, result.to), (100, 200));
+        assert!(result.opts.rev);
+
+        buf.extend_from_slice(b"*7\r\n$8\r\nts.range\r\n$4\r\ntemp\r\n$1\r\n-\r\n$1\r\n+\r\n$11\r\nAGGREGATION\r\n$6\r\nmedian\r\n$2\r\n10\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TSRange::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+mod topkadd;
+mod topklist;
+mod topkquery;
+mod topkreserve;
+
+pub use topkadd::*;
+pub use topklist::*;
+pub use topkquery::*;
+pub use topkreserve::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::CommandExecutor, Backend, BulkString, RespArray, RespFrame, RespNull, SimpleError,
+        SimpleString,
+    };
+
+    use super::*;
+
+    fn topk_add(backend: &Backend, key: &str, items: &[&str]) -> RespFrame {
+        TopKAdd {
+            key: key.to_string(),
+            items: items.iter().map(|i| i.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_topk_commands() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(
+            topk_add(&backend, "topk", &["a"]),
+            SimpleError::new("TopK: key does not exist").into()
+        );
+
+        let cmd = TopKReserve {
+            key: "topk".to_string(),
+            k: 2,
+            width: 50,
+            depth: 4,
+            decay: 0.9,
+        };
+        assert_eq!(cmd.execute(&backend), SimpleString::new("OK").into());
+
+        assert_eq!(
+            topk_add(&backend, "topk", &["a", "a", "a", "b", "b"]),
+            RespArray::new([
+                RespNull.into(),
+                RespNull.into(),
+                RespNull.into(),
+                RespNull.into(),
+                RespNull.into()
+            ])
+            .into()
+        );
+        // c 的计数超过列表中最小的 b 时，b 被挤出
+        assert_eq!(
+            topk_add(&backend, "topk", &["c", "c", "c"]),
+            RespArray::new([
+                RespNull.into(),
+                RespNull.into(),
+                BulkString::from("b").into()
+            ])
+            .into()
+        );
+
+        let cmd = TopKQuery {
+            key: "topk".to_string(),
+            items: vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([1.into(), 0.into(), 1.into()]).into()
+        );
+
+        let cmd = TopKList {
+            key: "topk".to_string(),
+            with_count: true,
+        };
+        assert_eq!(
+            cmd.execute(&backend),
+            RespArray::new([
+                BulkString::from("a").into(),
+                3.into(),
+                BulkString::from("c").into(),
+                3.into()
+            ])
+            .into()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, RespArray, RespFrame, RespNull, SimpleError,
+};
+
+/// 返回每个元素添加后被挤出 Top-K 列表的元素，没有则为 nil
+#[derive(Debug)]
+pub struct TopKAdd {
+    pub(crate) key: String,
+    pub(crate) items: Vec<Vec<u8>>,
+}
+
+impl CommandExecutor for TopKAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.topk_add(&self.key, &self.items) {
+            Ok(expelled) => RespArray::new(
+                expelled
+                    .into_iter()
+                    .map(|item| match item {
+                        Some(item) => BulkString::new(item).into(),
+                        None => RespNull.into(),
+                    })
+                    .collect::<Vec<RespFrame>>(),
+            )
+            .into(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TopKAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["topk.add"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let items = args.map(parse_bytes).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TopKAdd { key, items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_topk_add_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$8\r\nTOPK.ADD\r\n$4\r\ntopk\r\n$1\r\na\r\n$1\r\nb\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TopKAdd = frame.try_into()?;
+        assert_eq!(result.key, "topk");
+        assert_eq!(result.items, vec![b"a".to_vec(), b"b".to_vec()]);
+
+        Ok(())
+    }
+}
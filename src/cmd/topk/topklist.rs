@@ -0,0 +1,78 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    BulkString, RespArray, RespFrame, SimpleError,
+};
+
+/// TOPK.LIST key [WITHCOUNT]，按计数从大到小返回
+#[derive(Debug)]
+pub struct TopKList {
+    pub(crate) key: String,
+    pub(crate) with_count: bool,
+}
+
+impl CommandExecutor for TopKList {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.topk_list(&self.key) {
+            Ok(items) => {
+                let mut ret: Vec<RespFrame> = Vec::with_capacity(items.len() * 2);
+                for (item, count) in items {
+                    ret.push(BulkString::new(item).into());
+                    if self.with_count {
+                        ret.push((count as i64).into());
+                    }
+                }
+                RespArray::new(ret).into()
+            }
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TopKList {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["topk.list"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let with_count = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("withcount") => true,
+            None => false,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(TopKList { key, with_count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_topk_list_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$9\r\nTOPK.LIST\r\n$4\r\ntopk\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TopKList = frame.try_into()?;
+        assert_eq!(result.key, "topk");
+        assert!(!result.with_count);
+
+        buf.extend_from_slice(b"*3\r\n$9\r\ntopk.list\r\n$4\r\ntopk\r\n$9\r\nWITHCOUNT\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: TopKList = frame.try_into()?;
+        assert!(result.with_count);
+
+        Ok(())
+    }
+}
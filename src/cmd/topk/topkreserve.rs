@@ -0,0 +1,118 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError, TOPK_MAX_BUCKETS, TOPK_MAX_K,
+};
+
+// 没有指定 width depth decay 时的默认值，和 RedisBloom 一致
+const TOPK_DEFAULT_WIDTH: usize = 8;
+const TOPK_DEFAULT_DEPTH: usize = 7;
+const TOPK_DEFAULT_DECAY: f64 = 0.9;
+
+/// TOPK.RESERVE key topk [width depth decay]
+#[derive(Debug)]
+pub struct TopKReserve {
+    pub(crate) key: String,
+    pub(crate) k: usize,
+    pub(crate) width: usize,
+    pub(crate) depth: usize,
+    pub(crate) decay: f64,
+}
+
+impl CommandExecutor for TopKReserve {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.topk_reserve(self.key, self.k, self.width, self.depth, self.decay) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for TopKReserve {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["topk.reserve"], 2)?;
+        if value.len() != 3 && value.len() != 6 {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let k: usize = parse_number(next_arg(&mut args)?)?;
+        let (width, depth, decay) = match args.next() {
+            Some(width) => (
+                parse_number(width)?,
+                parse_number(next_arg(&mut args)?)?,
+                parse_number(next_arg(&mut args)?)?,
+            ),
+            None => (TOPK_DEFAULT_WIDTH, TOPK_DEFAULT_DEPTH, TOPK_DEFAULT_DECAY),
+        };
+        let size = width.saturating_mul(depth);
+        if k == 0 || width == 0 || depth == 0 || k > TOPK_MAX_K || size > TOPK_MAX_BUCKETS {
+            return Err(CommandError::InvalidArgument(
+                "TopK: invalid k, width or depth".to_string(),
+            ));
+        }
+        if !(decay > 0.0 && decay <= 1.0) {
+            return Err(CommandError::InvalidArgument(
+                "TopK: decay must be between 0 and 1".to_string(),
+            ));
+        }
+
+        Ok(TopKReserve {
+            key,
+            k,
+            width,
+            depth,
+            decay,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_topk_reserve_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$12\r\nTOPK.RESERVE\r\n$4\r\ntopk\r\n$2\r\n10\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: TopKReserve = frame.try_into()?;
+        assert_eq!(result.key, "topk");
+        assert_eq!(result.k, 10);
+        assert_eq!(result.width, 8);
+        assert_eq!(result.depth, 7);
+        assert_eq!(result.decay, 0.9);
+
+        buf.extend_from_slice(b"*6\r\n$12\r\ntopk.reserve\r\n$4\r\ntopk\r\n$1\r\n3\r\n$2\r\n50\r\n$1\r\n4\r\n$3\r\n0.5\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: TopKReserve = frame.try_into()?;
+        assert_eq!((result.k, result.width, result.depth), (3, 50, 4));
+        assert_eq!(result.decay, 0.5);
+
+        buf.extend_from_slice(
+            b"*4\r\n$12\r\ntopk.reserve\r\n$4\r\ntopk\r\n$1\r\n3\r\n$2\r\n50\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TopKReserve::try_from(frame).is_err());
+
+        buf.extend_from_slice(b"*6\r\n$12\r\ntopk.reserve\r\n$4\r\ntopk\r\n$1\r\n3\r\n$20\r\n18446744073709551615\r\n$1\r\n2\r\n$3\r\n0.5\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TopKReserve::try_from(frame).is_err());
+        buf.extend_from_slice(b"*3\r\n$12\r\ntopk.reserve\r\n$4\r\ntopk\r\n$10\r\n1000000000\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(TopKReserve::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
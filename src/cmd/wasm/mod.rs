@@ -0,0 +1,177 @@
+mod runtime;
+mod wasmcall;
+mod wasmload;
+
+pub use wasmcall::*;
+pub use wasmload::*;
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        cmd::{CommandExecutor, RESP_OK},
+        Backend, BulkString, RespFrame, SimpleError, WasmLimits,
+    };
+
+    use super::*;
+
+    // 把 ARGV[0] 追加到 KEYS[0] 的值后面，返回新的值
+    const APPEND: &str = r#"
+        (module
+            (import "redis" "key" (func $key (param i32 i32 i32) (result i32)))
+            (import "redis" "arg" (func $arg (param i32 i32 i32) (result i32)))
+            (import "redis" "get" (func $get (param i32 i32 i32 i32) (result i32)))
+            (import "redis" "set" (func $set (param i32 i32 i32 i32)))
+            (import "redis" "reply" (func $reply (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "call") (result i64)
+                (local $klen i32) (local $vlen i32) (local $alen i32)
+                (local.set $klen (call $key (i32.const 0) (i32.const 0) (i32.const 256)))
+                (local.set $vlen (call $get (i32.const 0) (local.get $klen) (i32.const 256) (i32.const 1024)))
+                (if (i32.lt_s (local.get $vlen) (i32.const 0)) (then (local.set $vlen (i32.const 0))))
+                (local.set $alen
+                    (call $arg (i32.const 0) (i32.add (i32.const 256) (local.get $vlen)) (i32.const 1024)))
+                (call $set (i32.const 0) (local.get $klen)
+                    (i32.const 256) (i32.add (local.get $vlen) (local.get $alen)))
+                (call $reply (i32.const 256) (i32.add (local.get $vlen) (local.get $alen)))
+                (i64.const 0)))
+    "#;
+
+    fn load(backend: &Backend, name: &str, wat: &str) -> Result<RespFrame> {
+        let load = WasmLoad {
+            name: name.to_string(),
+            module: wat::parse_str(wat)?,
+            fuel: None,
+            memory: None,
+        };
+        Ok(load.execute(backend))
+    }
+
+    fn call(backend: &Backend, name: &str, keys: &[&str], args: &[&str]) -> RespFrame {
+        WasmCall {
+            name: name.to_string(),
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            args: args.iter().map(|a| a.as_bytes().to_vec()).collect(),
+        }
+        .execute(backend)
+    }
+
+    #[test]
+    fn test_wasm_load_and_call() -> Result<()> {
+        let backend = Backend::new();
+        assert_eq!(load(&backend, "append", APPEND)?, RESP_OK.clone());
+
+        let ret = call(&backend, "append", &["greeting"], &["hello"]);
+        assert_eq!(ret, BulkString::from("hello").into());
+        let ret = call(&backend, "append", &["greeting"], &[" world"]);
+        assert_eq!(ret, BulkString::from("hello world").into());
+        assert_eq!(
+            backend.get("greeting"),
+            Some(BulkString::from("hello world").into())
+        );
+
+        // 没有调用 reply 时返回 call 的返回值
+        let answer = r#"(module (memory (export "memory") 1)
+            (func (export "call") (result i64) (i64.const 42)))"#;
+        load(&backend, "answer", answer)?;
+        assert_eq!(call(&backend, "answer", &[], &[]), RespFrame::Integer(42));
+
+        assert_eq!(
+            call(&backend, "nosuch", &[], &[]),
+            SimpleError::new("ERR no such WASM module".to_string()).into()
+        );
+        let ret = WasmLoad {
+            name: "bad".to_string(),
+            module: b"not wasm".to_vec(),
+            fuel: None,
+            memory: None,
+        }
+        .execute(&backend);
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.starts_with("ERR Invalid WASM module")));
+        let ret = load(&backend, "noentry", r#"(module (func (export "run")))"#)?;
+        assert_eq!(
+            ret,
+            SimpleError::new(
+                "ERR Invalid WASM module: missing exported function 'call'".to_string()
+            )
+            .into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wasm_limits() -> Result<()> {
+        let backend = Backend::new();
+        backend.set_wasm_limits(WasmLimits {
+            fuel: 100_000,
+            memory: 2 * 65536,
+        });
+
+        let forever = r#"(module (memory (export "memory") 1)
+            (func (export "call") (result i64) (loop $l (br $l)) (i64.const 0)))"#;
+        load(&backend, "forever", forever)?;
+        assert_eq!(
+            call(&backend, "forever", &[], &[]),
+            SimpleError::new("ERR WASM execution exceeded the fuel limit".to_string()).into()
+        );
+
+        // 超过内存限制时 memory.grow 返回 -1
+        let grow = r#"(module (memory (export "memory") 1)
+            (func (export "call") (result i64)
+                (i64.extend_i32_s (memory.grow (i32.const 4)))))"#;
+        load(&backend, "grow", grow)?;
+        assert_eq!(call(&backend, "grow", &[], &[]), RespFrame::Integer(-1));
+
+        // 初始内存超过限制时不能实例化
+        let big = r#"(module (memory (export "memory") 4)
+            (func (export "call") (result i64) (i64.const 0)))"#;
+        load(&backend, "big", big)?;
+        assert!(matches!(
+            call(&backend, "big", &[], &[]),
+            RespFrame::Error(_)
+        ));
+
+        // 访问越界的内存时返回错误
+        let oob = r#"(module
+            (import "redis" "reply" (func $reply (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "call") (result i64)
+                (call $reply (i32.const 65530) (i32.const 100)) (i64.const 0)))"#;
+        load(&backend, "oob", oob)?;
+        assert!(matches!(
+            call(&backend, "oob", &[], &[]),
+            RespFrame::Error(_)
+        ));
+
+        // WASM.LOAD 指定的限制只作用于这个模块，其它模块使用默认值
+        let load = WasmLoad {
+            name: "roomy".to_string(),
+            module: wat::parse_str(grow)?,
+            fuel: None,
+            memory: Some(8 * 65536),
+        };
+        assert_eq!(load.execute(&backend), RESP_OK.clone());
+        assert_eq!(call(&backend, "roomy", &[], &[]), RespFrame::Integer(1));
+        assert_eq!(call(&backend, "grow", &[], &[]), RespFrame::Integer(-1));
+
+        let load = WasmLoad {
+            name: "forever".to_string(),
+            module: wat::parse_str(forever)?,
+            fuel: Some(1_000),
+            memory: None,
+        };
+        assert_eq!(load.execute(&backend), RESP_OK.clone());
+        assert_eq!(
+            call(&backend, "forever", &[], &[]),
+            SimpleError::new("ERR WASM execution exceeded the fuel limit".to_string()).into()
+        );
+
+        // 修改默认值之后，没有指定限制的模块使用新的默认值
+        backend.config_set("wasm-memory-limit", "8mb")?;
+        assert_eq!(call(&backend, "grow", &[], &[]), RespFrame::Integer(1));
+
+        Ok(())
+    }
+}
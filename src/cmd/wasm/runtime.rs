@@ -0,0 +1,170 @@
+use wasmi::{
+    core::TrapCode, Caller, Error, Extern, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+use crate::{Backend, BulkString, RespFrame, SimpleError, WasmLimits};
+
+// 宿主函数都在 redis 模块下，模块需要导出 memory 和入口函数 call: () -> i64
+const HOST_MODULE: &str = "redis";
+
+struct HostState {
+    backend: Backend,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+    // 调用过 reply 时返回 bulk string，否则返回 call 的返回值
+    reply: Option<Vec<u8>>,
+    limits: StoreLimits,
+}
+
+/// 每次调用都创建新的实例，fuel 用完或者内存超过限制时返回错误
+pub(crate) fn call_module(
+    backend: &Backend,
+    module: &Module,
+    limits: WasmLimits,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+) -> RespFrame {
+    run(backend, module, limits, keys, args)
+        .unwrap_or_else(|e| SimpleError::new(error_message(&e)).into())
+}
+
+fn run(
+    backend: &Backend,
+    module: &Module,
+    limits: WasmLimits,
+    keys: Vec<String>,
+    args: Vec<Vec<u8>>,
+) -> Result<RespFrame, Error> {
+    let state = HostState {
+        backend: backend.clone(),
+        keys,
+        args,
+        reply: None,
+        limits: StoreLimitsBuilder::new().memory_size(limits.memory).build(),
+    };
+    let mut store = Store::new(backend.wasm_engine(), state);
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(limits.fuel)?;
+
+    let linker = new_linker(backend)?;
+    let instance = linker.instantiate(&mut store, module)?.start(&mut store)?;
+    let call = instance.get_typed_func::<(), i64>(&store, "call")?;
+    let ret = call.call(&mut store, ())?;
+    let frame = match store.data_mut().reply.take() {
+        Some(reply) => BulkString::new(reply).into(),
+        None => RespFrame::Integer(ret),
+    };
+    Ok(frame)
+}
+
+// 宿主函数只能读写字符串类型的 key，复制到模块内存时最多写入 cap 个字节，返回完整的长度，不存在时返回 -1
+fn new_linker(backend: &Backend) -> Result<Linker<HostState>, Error> {
+    let mut linker = Linker::new(backend.wasm_engine());
+    linker.func_wrap(
+        HOST_MODULE,
+        "key",
+        |mut caller: Caller<'_, HostState>, index: i32, ptr: i32, cap: i32| {
+            let key = caller.data().keys.get(index as usize).cloned();
+            match key {
+                Some(key) => write_bytes(&mut caller, ptr, cap, key.as_bytes()),
+                None => Ok(-1),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "arg",
+        |mut caller: Caller<'_, HostState>, index: i32, ptr: i32, cap: i32| {
+            let arg = caller.data().args.get(index as usize).cloned();
+            match arg {
+                Some(arg) => write_bytes(&mut caller, ptr, cap, &arg),
+                None => Ok(-1),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "get",
+        |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32, ptr: i32, cap: i32| {
+            let key = read_key(&caller, key_ptr, key_len)?;
+            match caller.data().backend.get(&key) {
+                Some(RespFrame::BulkString(value)) => write_bytes(&mut caller, ptr, cap, &value),
+                _ => Ok(-1),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "set",
+        |caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32, ptr: i32, len: i32| {
+            let key = read_key(&caller, key_ptr, key_len)?;
+            let value = read_bytes(&caller, ptr, len)?;
+            caller
+                .data()
+                .backend
+                .set(key, BulkString::new(value).into());
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "del",
+        |caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32| {
+            let key = read_key(&caller, key_ptr, key_len)?;
+            Ok(caller.data().backend.del(&key) as i32)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "reply",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            let reply = read_bytes(&caller, ptr, len)?;
+            caller.data_mut().reply = Some(reply);
+            Ok(())
+        },
+    )?;
+    Ok(linker)
+}
+
+fn memory(caller: &Caller<'_, HostState>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("module must export its memory as 'memory'"))
+}
+
+fn read_bytes(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Result<Vec<u8>, Error> {
+    let memory = memory(caller)?;
+    let start = ptr as u32 as usize;
+    let end = start.saturating_add(len as u32 as usize);
+    memory
+        .data(caller)
+        .get(start..end)
+        .map(|bytes| bytes.to_vec())
+        .ok_or_else(|| Error::from(TrapCode::MemoryOutOfBounds))
+}
+
+fn read_key(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String, Error> {
+    String::from_utf8(read_bytes(caller, ptr, len)?)
+        .map_err(|_| Error::new("key is not valid UTF-8"))
+}
+
+fn write_bytes(
+    caller: &mut Caller<'_, HostState>,
+    ptr: i32,
+    cap: i32,
+    data: &[u8],
+) -> Result<i32, Error> {
+    let memory = memory(caller)?;
+    let n = data.len().min(cap.max(0) as usize);
+    memory.write(caller, ptr as u32 as usize, &data[..n])?;
+    Ok(data.len() as i32)
+}
+
+fn error_message(err: &Error) -> String {
+    match err.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => "ERR WASM execution exceeded the fuel limit".to_string(),
+        _ => format!("ERR Error running WASM module: {}", err),
+    }
+}
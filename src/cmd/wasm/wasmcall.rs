@@ -0,0 +1,65 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_keys_and_args, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+use super::runtime::call_module;
+
+/// WASM.CALL name numkeys [key ...] [arg ...]，调用模块导出的 call 函数
+#[derive(Debug)]
+pub struct WasmCall {
+    pub(crate) name: String,
+    pub(crate) keys: Vec<String>,
+    pub(crate) args: Vec<Vec<u8>>,
+}
+
+impl CommandExecutor for WasmCall {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.wasm_module(&self.name) {
+            Some((module, limits)) => call_module(backend, &module, limits, self.keys, self.args),
+            None => SimpleError::new("ERR no such WASM module".to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for WasmCall {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["wasm.call"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let name = parse_string(next_arg(&mut args)?)?;
+        let (keys, args) = parse_keys_and_args(args)?;
+        Ok(WasmCall { name, keys, args })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_wasm_call_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$9\r\nwasm.call\r\n$3\r\nadd\r\n$1\r\n1\r\n$3\r\nkey\r\n$3\r\narg\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: WasmCall = frame.try_into()?;
+        assert_eq!(result.name, "add");
+        assert_eq!(result.keys, vec!["key".to_string()]);
+        assert_eq!(result.args, vec![b"arg".to_vec()]);
+
+        Ok(())
+    }
+}
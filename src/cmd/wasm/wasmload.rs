@@ -0,0 +1,87 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_bytes, parse_number, parse_string, validate_min_args,
+        CommandError, CommandExecutor, RESP_OK,
+    },
+    RespArray, RespFrame, SimpleError,
+};
+
+/// WASM.LOAD name module [FUEL fuel] [MEMORY bytes]，编译并保存模块，同名的模块会被替换
+/// 没有指定 FUEL 和 MEMORY 时使用 CONFIG 中的 wasm-fuel-limit 和 wasm-memory-limit
+#[derive(Debug)]
+pub struct WasmLoad {
+    pub(crate) name: String,
+    pub(crate) module: Vec<u8>,
+    pub(crate) fuel: Option<u64>,
+    pub(crate) memory: Option<usize>,
+}
+
+impl CommandExecutor for WasmLoad {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.wasm_load(self.name, &self.module, self.fuel, self.memory) {
+            Ok(()) => RESP_OK.clone(),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for WasmLoad {
+    type Error = CommandError;
+
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["wasm.load"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let name = parse_string(next_arg(&mut args)?)?;
+        let module = parse_bytes(next_arg(&mut args)?)?;
+        let (mut fuel, mut memory) = (None, None);
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "fuel" => fuel = Some(parse_number(next_arg(&mut args)?)?),
+                "memory" => memory = Some(parse_number(next_arg(&mut args)?)?),
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+        Ok(WasmLoad {
+            name,
+            module,
+            fuel,
+            memory,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_wasm_load_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$9\r\nWASM.LOAD\r\n$3\r\nadd\r\n$8\r\n\0asm\x01\0\0\0\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+        let result: WasmLoad = frame.try_into()?;
+        assert_eq!(result.name, "add");
+        assert_eq!(result.module, b"\0asm\x01\0\0\0".to_vec());
+        assert_eq!((result.fuel, result.memory), (None, None));
+
+        buf.extend_from_slice(b"*7\r\n$9\r\nwasm.load\r\n$3\r\nadd\r\n$8\r\n\0asm\x01\0\0\0\r\n$4\r\nFUEL\r\n$4\r\n1000\r\n$6\r\nmemory\r\n$6\r\n131072\r\n");
+        let frame = RespArray::decode(&mut buf)?;
+        let result: WasmLoad = frame.try_into()?;
+        assert_eq!((result.fuel, result.memory), (Some(1000), Some(131072)));
+
+        buf.extend_from_slice(
+            b"*4\r\n$9\r\nwasm.load\r\n$3\r\nadd\r\n$8\r\n\0asm\x01\0\0\0\r\n$4\r\nfuel\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(WasmLoad::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
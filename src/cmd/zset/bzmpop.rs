@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_timeout, validate_min_args, BlockingCommandExecutor,
+        CommandError, CommandExecutor,
+    },
+    Backend, RespArray, RespFrame, RespNull,
+};
+
+use super::ZMPop;
+
+#[derive(Debug)]
+pub struct BZMPop {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) zmpop: ZMPop,
+}
+
+impl BlockingCommandExecutor for BZMPop {
+    fn keys(&self) -> &[String] {
+        &self.zmpop.keys
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn try_execute(&self, backend: &Backend) -> Option<RespFrame> {
+        self.zmpop.pop(backend)
+    }
+}
+
+// 不经过 network 层执行时（例如事务中）不会阻塞
+impl CommandExecutor for BZMPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_execute(backend)
+            .unwrap_or(RespFrame::Null(RespNull))
+    }
+}
+
+impl TryFrom<RespArray> for BZMPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["bzmpop"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let timeout = parse_timeout(next_arg(&mut args)?)?;
+        let zmpop = ZMPop::parse(&mut args)?;
+
+        Ok(BZMPop { timeout, zmpop })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_bzmpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*5\r\n$6\r\nbzmpop\r\n$1\r\n0\r\n$1\r\n1\r\n$2\r\nz1\r\n$3\r\nmax\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: BZMPop = frame.try_into()?;
+        assert_eq!(result.timeout, None);
+        assert_eq!(result.zmpop.keys, vec!["z1"]);
+        assert!(result.zmpop.max);
+        assert_eq!(result.zmpop.count, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use crate::{
+    cmd::{
+        extract_args, parse_string, parse_timeout, validate_min_args, BlockingCommandExecutor,
+        CommandError, CommandExecutor,
+    },
+    Backend, BulkString, RespArray, RespFrame, RespNull,
+};
+
+/// BZPOPMIN 和 BZPOPMAX 共用
+#[derive(Debug)]
+pub struct BZPop {
+    pub(crate) keys: Vec<String>,
+    pub(crate) max: bool,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl BlockingCommandExecutor for BZPop {
+    fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    // 返回 [key, member, score]
+    fn try_execute(&self, backend: &Backend) -> Option<RespFrame> {
+        self.keys.iter().find_map(|key| {
+            let (member, score) = backend.zpop(key, self.max, 1).pop()?;
+            let ret = RespArray::new([
+                BulkString::from(key.as_str()).into(),
+                BulkString::from(member).into(),
+                score.into(),
+            ]);
+            Some(ret.into())
+        })
+    }
+}
+
+// 不经过 network 层执行时（例如事务中）不会阻塞
+impl CommandExecutor for BZPop {
+    fn execute(self, backend: &Backend) -> RespFrame {
+        self.try_execute(backend)
+            .unwrap_or(RespFrame::Null(RespNull))
+    }
+}
+
+impl TryFrom<RespArray> for BZPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let max = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"bzpopmax"));
+        let name = if max { "bzpopmax" } else { "bzpopmin" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?;
+        // 最后一个参数是超时时间，前面的都是 key
+        let timeout = match args.pop() {
+            Some(timeout) => parse_timeout(timeout)?,
+            None => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        let keys = args
+            .into_iter()
+            .map(parse_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BZPop { keys, max, timeout })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_bzpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$8\r\nbzpopmin\r\n$2\r\nz1\r\n$2\r\nz2\r\n$3\r\n1.5\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: BZPop = frame.try_into()?;
+        assert_eq!(result.keys, vec!["z1", "z2"]);
+        assert!(!result.max);
+        assert_eq!(result.timeout, Some(Duration::from_millis(1500)));
+
+        Ok(())
+    }
+}
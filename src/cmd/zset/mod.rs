@@ -0,0 +1,522 @@
+mod bzmpop;
+mod bzpop;
+mod zadd;
+mod zcard;
+mod zcount;
+mod zincrby;
+mod zintercard;
+mod zmpop;
+mod zmscore;
+mod zpop;
+mod zrange;
+mod zrangestore;
+mod zrank;
+mod zrem;
+mod zremrange;
+mod zscore;
+mod zsetop;
+
+pub use bzmpop::*;
+pub use bzpop::*;
+pub use zadd::*;
+pub use zcard::*;
+pub use zcount::*;
+pub use zincrby::*;
+pub use zintercard::*;
+pub use zmpop::*;
+pub use zmscore::*;
+pub use zpop::*;
+pub use zrange::*;
+pub use zrangestore::*;
+pub use zrank::*;
+pub use zrem::*;
+pub use zremrange::*;
+pub use zscore::*;
+pub use zsetop::*;
+
+use std::ops::Bound;
+
+use crate::{BulkString, RespArray, RespFrame, ZRangeBy};
+
+use super::{next_arg, parse_number, parse_string, CommandError};
+
+// 解析 numkeys key [key ...]
+fn parse_numkeys(args: &mut impl Iterator<Item = RespFrame>) -> Result<Vec<String>, CommandError> {
+    let numkeys: usize = parse_number(next_arg(args)?)?;
+    if numkeys == 0 {
+        return Err(CommandError::InvalidArgument(
+            "numkeys should be greater than 0".to_string(),
+        ));
+    }
+
+    (0..numkeys)
+        .map(|_| parse_string(next_arg(args)?))
+        .collect::<Result<Vec<_>, _>>()
+}
+
+// 解析分数，支持 inf、+inf、-inf，不允许 NaN
+fn parse_score(frame: RespFrame) -> Result<f64, CommandError> {
+    let s = parse_string(frame)?;
+    match s.parse::<f64>() {
+        Ok(score) if !score.is_nan() => Ok(score),
+        _ => Err(CommandError::InvalidArgument(
+            "value is not a valid float".to_string(),
+        )),
+    }
+}
+
+// 解析分数区间，"(1.5" 表示不包含 1.5
+fn parse_score_bound(frame: RespFrame) -> Result<Bound<f64>, CommandError> {
+    let s = parse_string(frame)?;
+    let (exclusive, score) = match s.strip_prefix('(') {
+        Some(score) => (true, score),
+        None => (false, s.as_str()),
+    };
+
+    match score.parse::<f64>() {
+        Ok(score) if !score.is_nan() && exclusive => Ok(Bound::Excluded(score)),
+        Ok(score) if !score.is_nan() => Ok(Bound::Included(score)),
+        _ => Err(CommandError::InvalidArgument(
+            "min or max is not a float".to_string(),
+        )),
+    }
+}
+
+// 解析字典序区间，"-" 和 "+" 表示无穷小和无穷大，"[a" 包含 a，"(a" 不包含 a
+// "+" 作为下界或者 "-" 作为上界时区间为空，用两端都不包含空字符串的区间表示
+fn parse_lex_range(min: RespFrame, max: RespFrame) -> Result<ZRangeBy, CommandError> {
+    let min = parse_lex_bound(min, true)?;
+    let max = parse_lex_bound(max, false)?;
+    match (min, max) {
+        (Some(min), Some(max)) => Ok(ZRangeBy::Lex(min, max)),
+        _ => Ok(ZRangeBy::Lex(
+            Bound::Excluded(String::new()),
+            Bound::Excluded(String::new()),
+        )),
+    }
+}
+
+// 返回 None 表示这一端排除了所有元素
+fn parse_lex_bound(frame: RespFrame, min: bool) -> Result<Option<Bound<String>>, CommandError> {
+    let s = parse_string(frame)?;
+    match s.as_str() {
+        "-" => Ok(min.then_some(Bound::Unbounded)),
+        "+" => Ok((!min).then_some(Bound::Unbounded)),
+        _ if s.starts_with('[') => Ok(Some(Bound::Included(s[1..].to_string()))),
+        _ if s.starts_with('(') => Ok(Some(Bound::Excluded(s[1..].to_string()))),
+        _ => Err(CommandError::InvalidArgument(
+            "min or max not valid string range item".to_string(),
+        )),
+    }
+}
+
+// LIMIT offset count，count 为负数时返回 offset 之后的全部元素
+fn apply_limit(items: Vec<(String, f64)>, limit: Option<(i64, i64)>) -> Vec<(String, f64)> {
+    match limit {
+        Some((offset, _)) if offset < 0 => vec![],
+        Some((offset, count)) => {
+            let count = if count < 0 {
+                usize::MAX
+            } else {
+                count as usize
+            };
+            items
+                .into_iter()
+                .skip(offset as usize)
+                .take(count)
+                .collect()
+        }
+        None => items,
+    }
+}
+
+// WITHSCORES 时返回 member score 交替的数组，score 用 RESP3 的 Double 表示
+fn scored_members_to_frame(items: Vec<(String, f64)>, with_scores: bool) -> RespFrame {
+    let frames = items
+        .into_iter()
+        .flat_map(|(member, score)| {
+            let member = BulkString::from(member).into();
+            if with_scores {
+                vec![member, score.into()]
+            } else {
+                vec![member]
+            }
+        })
+        .collect::<Vec<RespFrame>>();
+    RespArray::new(frames).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{cmd::CommandExecutor, Aggregate, Backend, RespNull, ZAddFlags};
+
+    use super::*;
+
+    fn zadd(backend: &Backend, key: &str, members: &[(f64, &str)]) {
+        let members = members.iter().map(|&(s, m)| (s, m.to_string())).collect();
+        backend
+            .zadd(key.to_string(), members, ZAddFlags::default())
+            .unwrap();
+    }
+
+    fn members(values: &[&str]) -> RespFrame {
+        RespArray::new(
+            values
+                .iter()
+                .map(|&v| BulkString::from(v).into())
+                .collect::<Vec<RespFrame>>(),
+        )
+        .into()
+    }
+
+    #[test]
+    fn test_zadd_zscore_zincrby_commands() -> Result<()> {
+        let backend = Backend::new();
+        let cmd = ZAdd {
+            key: "zset".to_string(),
+            flags: ZAddFlags::default(),
+            incr: false,
+            members: vec![(1.0, "one".to_string()), (2.0, "two".to_string())],
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        // GT 只在新分数更大时更新，CH 返回更新的数量
+        let cmd = ZAdd {
+            key: "zset".to_string(),
+            flags: ZAddFlags {
+                gt: true,
+                ch: true,
+                ..Default::default()
+            },
+            incr: false,
+            members: vec![(0.5, "one".to_string()), (3.0, "two".to_string())],
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        let cmd = ZMScore {
+            key: "zset".to_string(),
+            members: vec!["one".to_string(), "two".to_string(), "none".to_string()],
+        };
+        let expected = RespArray::new([1.0.into(), 3.0.into(), RespNull.into()]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        // XX 不会添加新成员，INCR 被阻止时返回 nil
+        let cmd = ZAdd {
+            key: "zset".to_string(),
+            flags: ZAddFlags {
+                xx: true,
+                ..Default::default()
+            },
+            incr: true,
+            members: vec![(1.0, "three".to_string())],
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        let cmd = ZIncrBy {
+            key: "zset".to_string(),
+            increment: 2.5,
+            member: "one".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), 3.5.into());
+
+        let cmd = ZIncrBy {
+            key: "zset".to_string(),
+            increment: f64::INFINITY,
+            member: "inf".to_string(),
+        };
+        cmd.execute(&backend);
+        let cmd = ZIncrBy {
+            key: "zset".to_string(),
+            increment: f64::NEG_INFINITY,
+            member: "inf".to_string(),
+        };
+        assert!(matches!(cmd.execute(&backend), RespFrame::Error(_)));
+
+        let cmd = ZScore {
+            key: "zset".to_string(),
+            member: "two".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), 3.0.into());
+
+        let cmd = ZCard {
+            key: "zset".to_string(),
+        };
+        assert_eq!(cmd.execute(&backend), 3.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrank_zrem_commands() -> Result<()> {
+        let backend = Backend::new();
+        zadd(&backend, "zset", &[(1.0, "a"), (2.0, "b"), (3.0, "c")]);
+
+        let cmd = ZRank {
+            key: "zset".to_string(),
+            member: "b".to_string(),
+            rev: false,
+            with_score: false,
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        let cmd = ZRank {
+            key: "zset".to_string(),
+            member: "a".to_string(),
+            rev: true,
+            with_score: true,
+        };
+        let expected = RespArray::new([2.into(), 1.0.into()]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = ZRem {
+            key: "zset".to_string(),
+            members: vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string(),
+            ],
+        };
+        assert_eq!(cmd.execute(&backend), 3.into());
+        assert!(backend.zset.get("zset").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zsetop_commands() -> Result<()> {
+        let backend = Backend::new();
+        zadd(&backend, "z1", &[(1.0, "a"), (2.0, "b"), (3.0, "c")]);
+        zadd(&backend, "z2", &[(10.0, "b"), (20.0, "c"), (30.0, "d")]);
+
+        let zsetop = |kind, destination: Option<&str>, weights: Vec<f64>, aggregate| ZSetOp {
+            kind,
+            destination: destination.map(|d| d.to_string()),
+            keys: vec!["z1".to_string(), "z2".to_string()],
+            weights,
+            aggregate,
+            with_scores: true,
+        };
+
+        let ret = zsetop(ZSetOpKind::Union, None, vec![2.0, 1.0], Aggregate::Sum).execute(&backend);
+        let expected = RespArray::new([
+            BulkString::from("a").into(),
+            2.0.into(),
+            BulkString::from("b").into(),
+            14.0.into(),
+            BulkString::from("c").into(),
+            26.0.into(),
+            BulkString::from("d").into(),
+            30.0.into(),
+        ]);
+        assert_eq!(ret, expected.into());
+
+        let ret = zsetop(ZSetOpKind::Inter, Some("dst"), vec![], Aggregate::Max).execute(&backend);
+        assert_eq!(ret, 2.into());
+        assert_eq!(backend.zscore("dst", "c"), Some(20.0));
+
+        let ret = zsetop(ZSetOpKind::Diff, None, vec![], Aggregate::Sum).execute(&backend);
+        let expected = RespArray::new([BulkString::from("a").into(), 1.0.into()]);
+        assert_eq!(ret, expected.into());
+
+        let cmd = ZInterCard {
+            keys: vec!["z1".to_string(), "z2".to_string()],
+            limit: 1,
+        };
+        assert_eq!(cmd.execute(&backend), 1.into());
+
+        // 结果为空时删除 destination
+        let cmd = ZSetOp {
+            kind: ZSetOpKind::Inter,
+            destination: Some("dst".to_string()),
+            keys: vec!["z1".to_string(), "nokey".to_string()],
+            weights: vec![],
+            aggregate: Aggregate::Sum,
+            with_scores: false,
+        };
+        assert_eq!(cmd.execute(&backend), 0.into());
+        assert!(backend.zset.get("dst").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrangestore_zremrange_zcount_commands() -> Result<()> {
+        let backend = Backend::new();
+        zadd(
+            &backend,
+            "zset",
+            &[(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d")],
+        );
+
+        let cmd = ZRangeStore {
+            destination: "dst".to_string(),
+            source: "zset".to_string(),
+            options: ZRangeOptions {
+                by: ZRangeBy::Score(Bound::Included(2.0), Bound::Unbounded),
+                rev: false,
+                limit: Some((0, 2)),
+                with_scores: false,
+            },
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+        assert_eq!(backend.zcard("dst"), 2);
+
+        let cmd = ZCount {
+            key: "zset".to_string(),
+            by: ZRangeBy::Score(Bound::Excluded(1.0), Bound::Included(3.0)),
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        let cmd = ZRemRange {
+            key: "zset".to_string(),
+            by: ZRangeBy::Index(-2, -1),
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        zadd(&backend, "lex", &[(0.0, "a"), (0.0, "b"), (0.0, "c")]);
+        let cmd = ZCount {
+            key: "lex".to_string(),
+            by: ZRangeBy::Lex(Bound::Excluded("a".to_string()), Bound::Unbounded),
+        };
+        assert_eq!(cmd.execute(&backend), 2.into());
+
+        let cmd = ZRemRange {
+            key: "lex".to_string(),
+            by: ZRangeBy::Lex(Bound::Unbounded, Bound::Unbounded),
+        };
+        assert_eq!(cmd.execute(&backend), 3.into());
+        assert!(backend.zset.get("lex").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zpop_commands() -> Result<()> {
+        let backend = Backend::new();
+        zadd(&backend, "z2", &[(1.0, "a"), (2.0, "b"), (3.0, "c")]);
+
+        let cmd = ZPop {
+            key: "z2".to_string(),
+            max: true,
+            count: 2,
+        };
+        let expected = RespArray::new([
+            BulkString::from("c").into(),
+            3.0.into(),
+            BulkString::from("b").into(),
+            2.0.into(),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+
+        let cmd = ZMPop {
+            keys: vec!["z1".to_string(), "z2".to_string()],
+            max: false,
+            count: 10,
+        };
+        let expected = RespArray::new([
+            BulkString::from("z2").into(),
+            RespArray::new([RespArray::new([BulkString::from("a").into(), 1.0.into()]).into()])
+                .into(),
+        ]);
+        assert_eq!(cmd.execute(&backend), expected.into());
+        assert!(backend.zset.get("z2").is_none());
+
+        // 不经过 network 层执行时不会阻塞
+        let cmd = BZPop {
+            keys: vec!["z2".to_string()],
+            max: false,
+            timeout: None,
+        };
+        assert_eq!(cmd.execute(&backend), RespNull.into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zrange_command() -> Result<()> {
+        let backend = Backend::new();
+        zadd(
+            &backend,
+            "zset",
+            &[(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d")],
+        );
+
+        let zrange = |by, rev, limit, with_scores| {
+            ZRange {
+                key: "zset".to_string(),
+                options: ZRangeOptions {
+                    by,
+                    rev,
+                    limit,
+                    with_scores,
+                },
+            }
+            .execute(&backend)
+        };
+
+        let ret = zrange(ZRangeBy::Index(0, -1), false, None, false);
+        assert_eq!(ret, members(&["a", "b", "c", "d"]));
+
+        let ret = zrange(ZRangeBy::Index(0, 1), true, None, true);
+        let expected = RespArray::new([
+            BulkString::from("d").into(),
+            4.0.into(),
+            BulkString::from("c").into(),
+            3.0.into(),
+        ]);
+        assert_eq!(ret, expected.into());
+
+        let by = ZRangeBy::Score(Bound::Excluded(1.0), Bound::Unbounded);
+        let ret = zrange(by, true, Some((1, 1)), false);
+        assert_eq!(ret, members(&["c"]));
+
+        // 开闭区间的边界，以及 min 大于 max 的空区间
+        let by = ZRangeBy::Score(Bound::Excluded(1.0), Bound::Included(3.0));
+        assert_eq!(zrange(by, false, None, false), members(&["b", "c"]));
+        let by = ZRangeBy::Score(Bound::Included(2.0), Bound::Excluded(4.0));
+        assert_eq!(zrange(by, true, None, false), members(&["c", "b"]));
+        let by = ZRangeBy::Score(Bound::Excluded(2.0), Bound::Excluded(2.0));
+        assert_eq!(zrange(by, false, None, false), members(&[]));
+        let by = ZRangeBy::Score(Bound::Included(3.0), Bound::Included(2.0));
+        assert_eq!(zrange(by, false, None, false), members(&[]));
+        let by = ZRangeBy::Score(Bound::Excluded(f64::INFINITY), Bound::Unbounded);
+        assert_eq!(zrange(by, false, None, false), members(&[]));
+
+        zadd(
+            &backend,
+            "lex",
+            &[(0.0, "a"), (0.0, "b"), (0.0, "c"), (0.0, "d")],
+        );
+        let ret = ZRange {
+            key: "lex".to_string(),
+            options: ZRangeOptions {
+                by: ZRangeBy::Lex(
+                    Bound::Included("b".to_string()),
+                    Bound::Excluded("d".to_string()),
+                ),
+                rev: false,
+                limit: None,
+                with_scores: false,
+            },
+        }
+        .execute(&backend);
+        assert_eq!(ret, members(&["b", "c"]));
+
+        let ret = ZRange {
+            key: "lex".to_string(),
+            options: ZRangeOptions {
+                by: ZRangeBy::Lex(Bound::Excluded("a".to_string()), Bound::Unbounded),
+                rev: true,
+                limit: None,
+                with_scores: false,
+            },
+        }
+        .execute(&backend);
+        assert_eq!(ret, members(&["d", "c", "b"]));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull, SimpleError, ZAddFlags,
+};
+
+use super::parse_score;
+
+#[derive(Debug)]
+pub struct ZAdd {
+    pub(crate) key: String,
+    pub(crate) flags: ZAddFlags,
+    pub(crate) incr: bool,
+    pub(crate) members: Vec<(f64, String)>,
+}
+
+impl CommandExecutor for ZAdd {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        if !self.incr {
+            return match backend.zadd(self.key, self.members, self.flags) {
+                Ok(count) => (count as i64).into(),
+                Err(e) => SimpleError::new(e.to_string()).into(),
+            };
+        }
+
+        // INCR 模式下只有一个 score member，在 TryFrom 中已经校验过
+        let (increment, member) = self.members.into_iter().next().unwrap_or_default();
+        match backend.zincrby(self.key, increment, member, self.flags) {
+            Ok(Some(score)) => score.into(),
+            Ok(None) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZAdd {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zadd"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter().peekable();
+        let key = parse_string(next_arg(&mut args)?)?;
+
+        let mut flags = ZAddFlags::default();
+        let mut incr = false;
+        while let Some(RespFrame::BulkString(arg)) = args.peek() {
+            match arg.to_ascii_lowercase().as_slice() {
+                b"nx" => flags.nx = true,
+                b"xx" => flags.xx = true,
+                b"gt" => flags.gt = true,
+                b"lt" => flags.lt = true,
+                b"ch" => flags.ch = true,
+                b"incr" => incr = true,
+                _ => break,
+            }
+            args.next();
+        }
+
+        if flags.nx && flags.xx {
+            return Err(CommandError::InvalidArgument(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if (flags.gt && flags.lt) || (flags.nx && (flags.gt || flags.lt)) {
+            return Err(CommandError::InvalidArgument(
+                "GT, LT, and/or NX options at the same time are not compatible".to_string(),
+            ));
+        }
+
+        let mut members = Vec::new();
+        while let Some(score) = args.next() {
+            let score = parse_score(score)?;
+            let member = parse_string(next_arg(&mut args)?)?;
+            members.push((score, member));
+        }
+        if members.is_empty() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+        if incr && members.len() != 1 {
+            return Err(CommandError::InvalidArgument(
+                "INCR option supports a single increment-element pair".to_string(),
+            ));
+        }
+
+        Ok(ZAdd {
+            key,
+            flags,
+            incr,
+            members,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zadd_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*8\r\n$4\r\nzadd\r\n$4\r\nzset\r\n$2\r\nXX\r\n$2\r\nCH\r\n$1\r\n1\r\n$3\r\none\r\n$4\r\n-inf\r\n$3\r\ntwo\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZAdd = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert!(result.flags.xx && result.flags.ch);
+        assert!(!result.incr);
+        assert_eq!(
+            result.members,
+            vec![
+                (1.0, "one".to_string()),
+                (f64::NEG_INFINITY, "two".to_string())
+            ]
+        );
+
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$4\r\nzadd\r\n$4\r\nzset\r\n$2\r\nnx\r\n$2\r\ngt\r\n$1\r\n1\r\n$3\r\none\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        assert!(ZAdd::try_from(frame).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame,
+};
+
+#[derive(Debug)]
+pub struct ZCard {
+    pub(crate) key: String,
+}
+
+impl CommandExecutor for ZCard {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.zcard(&self.key) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zcard"], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZCard {
+            key: parse_string(next_arg(&mut args)?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zcard_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*2\r\n$5\r\nzcard\r\n$4\r\nzset\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZCard = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+
+        Ok(())
+    }
+}
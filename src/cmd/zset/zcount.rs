@@ -0,0 +1,68 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame, ZRangeBy,
+};
+
+use super::{parse_lex_range, parse_score_bound};
+
+/// ZCOUNT 和 ZLEXCOUNT 共用
+#[derive(Debug)]
+pub struct ZCount {
+    pub(crate) key: String,
+    pub(crate) by: ZRangeBy,
+}
+
+impl CommandExecutor for ZCount {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.zcount(&self.key, &self.by) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZCount {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let lex = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"zlexcount"));
+        let name = if lex { "zlexcount" } else { "zcount" };
+        validate_command(&value, &[name], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let (min, max) = (next_arg(&mut args)?, next_arg(&mut args)?);
+        let by = if lex {
+            parse_lex_range(min, max)?
+        } else {
+            ZRangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?)
+        };
+
+        Ok(ZCount { key, by })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zcount_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$9\r\nzlexcount\r\n$4\r\nzset\r\n$1\r\n-\r\n$2\r\n(c\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZCount = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(
+            result.by,
+            ZRangeBy::Lex(Bound::Unbounded, Bound::Excluded("c".to_string()))
+        );
+
+        Ok(())
+    }
+}
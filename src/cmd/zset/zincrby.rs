@@ -0,0 +1,63 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull, SimpleError, ZAddFlags,
+};
+
+use super::parse_score;
+
+#[derive(Debug)]
+pub struct ZIncrBy {
+    pub(crate) key: String,
+    pub(crate) increment: f64,
+    pub(crate) member: String,
+}
+
+impl CommandExecutor for ZIncrBy {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let flags = ZAddFlags::default();
+        match backend.zincrby(self.key, self.increment, self.member, flags) {
+            Ok(Some(score)) => score.into(),
+            Ok(None) => RespFrame::Null(RespNull),
+            Err(e) => SimpleError::new(e.to_string()).into(),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZIncrBy {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zincrby"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZIncrBy {
+            key: parse_string(next_arg(&mut args)?)?,
+            increment: parse_score(next_arg(&mut args)?)?,
+            member: parse_string(next_arg(&mut args)?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zincrby_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$7\r\nzincrby\r\n$4\r\nzset\r\n$3\r\n2.5\r\n$3\r\none\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZIncrBy = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(result.increment, 2.5);
+        assert_eq!(result.member, "one");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    Aggregate, RespArray, RespFrame,
+};
+
+use super::parse_numkeys;
+
+#[derive(Debug)]
+pub struct ZInterCard {
+    pub(crate) keys: Vec<String>,
+    // 0 表示不限制
+    pub(crate) limit: usize,
+}
+
+impl CommandExecutor for ZInterCard {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let len = backend.zinter(&self.keys, &[], Aggregate::Sum).len();
+        let len = if self.limit == 0 {
+            len
+        } else {
+            len.min(self.limit)
+        };
+        (len as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZInterCard {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zintercard"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let keys = parse_numkeys(&mut args)?;
+        let limit = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("limit") => parse_number(next_arg(&mut args)?)?,
+            None => 0,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+
+        Ok(ZInterCard { keys, limit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zintercard_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$10\r\nzintercard\r\n$1\r\n2\r\n$2\r\nz1\r\n$2\r\nz2\r\n$5\r\nLIMIT\r\n$1\r\n1\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZInterCard = frame.try_into()?;
+        assert_eq!(result.keys, vec!["z1", "z2"]);
+        assert_eq!(result.limit, 1);
+
+        Ok(())
+    }
+}
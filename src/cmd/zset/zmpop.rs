@@ -0,0 +1,106 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    BulkString, RespArray, RespFrame, RespNull,
+};
+
+use super::parse_numkeys;
+
+#[derive(Debug)]
+pub struct ZMPop {
+    pub(crate) keys: Vec<String>,
+    pub(crate) max: bool,
+    pub(crate) count: usize,
+}
+
+impl ZMPop {
+    /// 从第一个非空的有序集合中弹出元素，返回 [key, [[member, score], ...]]
+    pub(crate) fn pop(&self, backend: &crate::Backend) -> Option<RespFrame> {
+        for key in self.keys.iter() {
+            let items = backend.zpop(key, self.max, self.count);
+            if items.is_empty() {
+                continue;
+            }
+
+            let items = items
+                .into_iter()
+                .map(|(member, score)| {
+                    RespArray::new([BulkString::from(member).into(), score.into()]).into()
+                })
+                .collect::<Vec<RespFrame>>();
+            let ret = RespArray::new([
+                BulkString::from(key.as_str()).into(),
+                RespArray::new(items).into(),
+            ]);
+            return Some(ret.into());
+        }
+
+        None
+    }
+
+    /// 解析 numkeys key [key ...] <MIN | MAX> [COUNT count]
+    pub(crate) fn parse(args: &mut impl Iterator<Item = RespFrame>) -> Result<Self, CommandError> {
+        let keys = parse_numkeys(args)?;
+        let max = match parse_string(next_arg(args)?)?.to_ascii_lowercase().as_str() {
+            "min" => false,
+            "max" => true,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+
+        let count = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("count") => parse_number(next_arg(args)?)?,
+            None => 1,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        if count == 0 || args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(ZMPop { keys, max, count })
+    }
+}
+
+impl CommandExecutor for ZMPop {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        self.pop(backend).unwrap_or(RespFrame::Null(RespNull))
+    }
+}
+
+impl TryFrom<RespArray> for ZMPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zmpop"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        ZMPop::parse(&mut args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zmpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*7\r\n$5\r\nzmpop\r\n$1\r\n2\r\n$2\r\nz1\r\n$2\r\nz2\r\n$3\r\nMIN\r\n$5\r\nCOUNT\r\n$2\r\n10\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZMPop = frame.try_into()?;
+        assert_eq!(result.keys, vec!["z1", "z2"]);
+        assert!(!result.max);
+        assert_eq!(result.count, 10);
+
+        Ok(())
+    }
+}
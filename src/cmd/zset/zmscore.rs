@@ -0,0 +1,61 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+#[derive(Debug)]
+pub struct ZMScore {
+    pub(crate) key: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecutor for ZMScore {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let scores = self
+            .members
+            .iter()
+            .map(|member| match backend.zscore(&self.key, member) {
+                Some(score) => score.into(),
+                None => RespFrame::Null(RespNull),
+            })
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(scores).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZMScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zmscore"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let members = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ZMScore { key, members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zmscore_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$7\r\nzmscore\r\n$4\r\nzset\r\n$3\r\none\r\n$3\r\ntwo\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZMScore = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(result.members, vec!["one", "two"]);
+
+        Ok(())
+    }
+}
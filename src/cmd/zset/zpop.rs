@@ -0,0 +1,70 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame,
+};
+
+use super::scored_members_to_frame;
+
+/// ZPOPMIN 和 ZPOPMAX 共用
+#[derive(Debug)]
+pub struct ZPop {
+    pub(crate) key: String,
+    pub(crate) max: bool,
+    pub(crate) count: usize,
+}
+
+impl CommandExecutor for ZPop {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let items = backend.zpop(&self.key, self.max, self.count);
+        scored_members_to_frame(items, true)
+    }
+}
+
+impl TryFrom<RespArray> for ZPop {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let max = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"zpopmax"));
+        let name = if max { "zpopmax" } else { "zpopmin" };
+        validate_min_args(&value, &[name], 1)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let count = match args.next() {
+            Some(count) => parse_number(count)?,
+            None => 1,
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(ZPop { key, max, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zpop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$7\r\nZPOPMAX\r\n$4\r\nzset\r\n$1\r\n2\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZPop = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert!(result.max);
+        assert_eq!(result.count, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_min_args, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, ZRangeBy,
+};
+
+use super::{apply_limit, parse_lex_range, parse_score_bound, scored_members_to_frame};
+
+#[derive(Debug)]
+pub struct ZRange {
+    pub(crate) key: String,
+    pub(crate) options: ZRangeOptions,
+}
+
+/// ZRANGE 和 ZRANGESTORE 共用的范围参数
+#[derive(Debug, PartialEq)]
+pub struct ZRangeOptions {
+    pub(crate) by: ZRangeBy,
+    pub(crate) rev: bool,
+    pub(crate) limit: Option<(i64, i64)>,
+    pub(crate) with_scores: bool,
+}
+
+impl CommandExecutor for ZRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let items = backend.zrange(&self.key, &self.options.by, self.options.rev);
+        let items = apply_limit(items, self.options.limit);
+        scored_members_to_frame(items, self.options.with_scores)
+    }
+}
+
+impl ZRangeOptions {
+    /// 解析 start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]
+    pub(crate) fn parse(
+        start: RespFrame,
+        stop: RespFrame,
+        args: impl Iterator<Item = RespFrame>,
+    ) -> Result<Self, CommandError> {
+        let mut args = args.into_iter();
+        let (mut by_score, mut by_lex, mut rev, mut limit, mut with_scores) =
+            (false, false, false, None, false);
+        while let Some(arg) = args.next() {
+            match parse_string(arg)?.to_ascii_lowercase().as_str() {
+                "byscore" => by_score = true,
+                "bylex" => by_lex = true,
+                "rev" => rev = true,
+                "withscores" => with_scores = true,
+                "limit" => {
+                    let offset = parse_number(next_arg(&mut args)?)?;
+                    let count = parse_number(next_arg(&mut args)?)?;
+                    limit = Some((offset, count));
+                }
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+
+        if by_score && by_lex {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(CommandError::InvalidArgument(
+                "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+                    .to_string(),
+            ));
+        }
+        if with_scores && by_lex {
+            return Err(CommandError::InvalidArgument(
+                "syntax error, WITHSCORES not supported in combination with BYLEX".to_string(),
+            ));
+        }
+
+        // REV 模式下 BYSCORE 和 BYLEX 的参数顺序是 max min
+        let (min, max) = if rev && (by_score || by_lex) {
+            (stop, start)
+        } else {
+            (start, stop)
+        };
+        let by = if by_score {
+            ZRangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?)
+        } else if by_lex {
+            parse_lex_range(min, max)?
+        } else {
+            ZRangeBy::Index(parse_number(min)?, parse_number(max)?)
+        };
+
+        Ok(ZRangeOptions {
+            by,
+            rev,
+            limit,
+            with_scores,
+        })
+    }
+}
+
+impl TryFrom<RespArray> for ZRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zrange"], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let start = next_arg(&mut args)?;
+        let stop = next_arg(&mut args)?;
+        let options = ZRangeOptions::parse(start, stop, args)?;
+
+        Ok(ZRange { key, options })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zrange_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*10\r\n$6\r\nzrange\r\n$4\r\nzset\r\n$4\r\n+inf\r\n$2\r\n(1\r\n$7\r\nBYSCORE\r\n$3\r\nREV\r\n$5\r\nLIMIT\r\n$1\r\n0\r\n$1\r\n2\r\n$10\r\nWITHSCORES\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZRange = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(
+            result.options,
+            ZRangeOptions {
+                by: ZRangeBy::Score(Bound::Excluded(1.0), Bound::Included(f64::INFINITY)),
+                rev: true,
+                limit: Some((0, 2)),
+                with_scores: true,
+            }
+        );
+
+        buf.extend_from_slice(
+            b"*5\r\n$6\r\nzrange\r\n$4\r\nzset\r\n$1\r\n-\r\n$2\r\n[c\r\n$5\r\nBYLEX\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: ZRange = frame.try_into()?;
+        assert_eq!(
+            result.options.by,
+            ZRangeBy::Lex(Bound::Unbounded, Bound::Included("c".to_string()))
+        );
+
+        Ok(())
+    }
+}
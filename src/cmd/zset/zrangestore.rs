@@ -0,0 +1,72 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame,
+};
+
+use super::{apply_limit, ZRangeOptions};
+
+#[derive(Debug)]
+pub struct ZRangeStore {
+    pub(crate) destination: String,
+    pub(crate) source: String,
+    pub(crate) options: ZRangeOptions,
+}
+
+impl CommandExecutor for ZRangeStore {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let items = backend.zrange(&self.source, &self.options.by, self.options.rev);
+        let items = apply_limit(items, self.options.limit);
+        (backend.zstore(self.destination, items.into_iter().collect(), "zrangestore") as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZRangeStore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zrangestore"], 4)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let destination = parse_string(next_arg(&mut args)?)?;
+        let source = parse_string(next_arg(&mut args)?)?;
+        let start = next_arg(&mut args)?;
+        let stop = next_arg(&mut args)?;
+        let options = ZRangeOptions::parse(start, stop, args)?;
+        if options.with_scores {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(ZRangeStore {
+            destination,
+            source,
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::{RespDecode, ZRangeBy};
+
+    use super::*;
+
+    #[test]
+    fn test_zrangestore_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*6\r\n$11\r\nzrangestore\r\n$3\r\ndst\r\n$3\r\nsrc\r\n$1\r\n0\r\n$2\r\n-1\r\n$3\r\nREV\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZRangeStore = frame.try_into()?;
+        assert_eq!(result.destination, "dst");
+        assert_eq!(result.source, "src");
+        assert_eq!(result.options.by, ZRangeBy::Index(0, -1));
+        assert!(result.options.rev);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+/// ZRANK 和 ZREVRANK 共用，rev 为 true 时按分数从大到小排名
+#[derive(Debug)]
+pub struct ZRank {
+    pub(crate) key: String,
+    pub(crate) member: String,
+    pub(crate) rev: bool,
+    pub(crate) with_score: bool,
+}
+
+impl CommandExecutor for ZRank {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.zrank(&self.key, &self.member, self.rev) {
+            Some((rank, score)) if self.with_score => {
+                RespArray::new([(rank as i64).into(), score.into()]).into()
+            }
+            Some((rank, _)) => (rank as i64).into(),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZRank {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let rev = matches!(value.first(), Some(RespFrame::BulkString(cmd)) if cmd.eq_ignore_ascii_case(b"zrevrank"));
+        let name = if rev { "zrevrank" } else { "zrank" };
+        validate_min_args(&value, &[name], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let member = parse_string(next_arg(&mut args)?)?;
+        let with_score = match args.next().map(parse_string).transpose()? {
+            Some(arg) if arg.eq_ignore_ascii_case("withscore") => true,
+            None => false,
+            _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+        };
+        if args.next().is_some() {
+            return Err(CommandError::InvalidArgument("syntax error".to_string()));
+        }
+
+        Ok(ZRank {
+            key,
+            member,
+            rev,
+            with_score,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zrank_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$5\r\nzrank\r\n$4\r\nzset\r\n$3\r\none\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZRank = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(result.member, "one");
+        assert!(!result.rev);
+        assert!(!result.with_score);
+
+        buf.extend_from_slice(
+            b"*4\r\n$8\r\nZREVRANK\r\n$4\r\nzset\r\n$3\r\none\r\n$9\r\nWITHSCORE\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: ZRank = frame.try_into()?;
+        assert!(result.rev);
+        assert!(result.with_score);
+
+        Ok(())
+    }
+}
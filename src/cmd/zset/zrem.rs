@@ -0,0 +1,53 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    RespArray, RespFrame,
+};
+
+#[derive(Debug)]
+pub struct ZRem {
+    pub(crate) key: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl CommandExecutor for ZRem {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.zrem(&self.key, &self.members) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZRem {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_min_args(&value, &["zrem"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let members = args.map(parse_string).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ZRem { key, members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zrem_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*4\r\n$4\r\nzrem\r\n$4\r\nzset\r\n$3\r\none\r\n$3\r\ntwo\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZRem = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(result.members, vec!["one", "two"]);
+
+        Ok(())
+    }
+}
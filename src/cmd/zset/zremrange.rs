@@ -0,0 +1,87 @@
+use crate::{
+    cmd::{
+        extract_args, next_arg, parse_number, parse_string, validate_command, CommandError,
+        CommandExecutor,
+    },
+    RespArray, RespFrame, ZRangeBy,
+};
+
+use super::{parse_lex_range, parse_score_bound};
+
+/// ZREMRANGEBYSCORE / ZREMRANGEBYRANK / ZREMRANGEBYLEX 共用
+#[derive(Debug)]
+pub struct ZRemRange {
+    pub(crate) key: String,
+    pub(crate) by: ZRangeBy,
+}
+
+impl CommandExecutor for ZRemRange {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        (backend.zremrange(&self.key, &self.by) as i64).into()
+    }
+}
+
+impl TryFrom<RespArray> for ZRemRange {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let name = match value.first() {
+            Some(RespFrame::BulkString(cmd)) => String::from_utf8_lossy(cmd).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+        let name = match name.as_str() {
+            "zremrangebyscore" => "zremrangebyscore",
+            "zremrangebyrank" => "zremrangebyrank",
+            _ => "zremrangebylex",
+        };
+        validate_command(&value, &[name], 3)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let key = parse_string(next_arg(&mut args)?)?;
+        let (min, max) = (next_arg(&mut args)?, next_arg(&mut args)?);
+        let by = match name {
+            "zremrangebyscore" => ZRangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?),
+            "zremrangebyrank" => ZRangeBy::Index(parse_number(min)?, parse_number(max)?),
+            _ => parse_lex_range(min, max)?,
+        };
+
+        Ok(ZRemRange { key, by })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zremrange_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*4\r\n$16\r\nzremrangebyscore\r\n$4\r\nzset\r\n$4\r\n-inf\r\n$2\r\n(5\r\n",
+        );
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZRemRange = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(
+            result.by,
+            ZRangeBy::Score(Bound::Included(f64::NEG_INFINITY), Bound::Excluded(5.0))
+        );
+
+        buf.extend_from_slice(
+            b"*4\r\n$15\r\nZREMRANGEBYRANK\r\n$4\r\nzset\r\n$1\r\n0\r\n$1\r\n1\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: ZRemRange = frame.try_into()?;
+        assert_eq!(result.by, ZRangeBy::Index(0, 1));
+
+        Ok(())
+    }
+}
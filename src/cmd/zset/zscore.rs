@@ -0,0 +1,56 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_command, CommandError, CommandExecutor},
+    RespArray, RespFrame, RespNull,
+};
+
+#[derive(Debug)]
+pub struct ZScore {
+    pub(crate) key: String,
+    pub(crate) member: String,
+}
+
+impl CommandExecutor for ZScore {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        match backend.zscore(&self.key, &self.member) {
+            Some(score) => score.into(),
+            None => RespFrame::Null(RespNull),
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZScore {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        validate_command(&value, &["zscore"], 2)?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        Ok(ZScore {
+            key: parse_string(next_arg(&mut args)?)?,
+            member: parse_string(next_arg(&mut args)?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zscore_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"*3\r\n$6\r\nzscore\r\n$4\r\nzset\r\n$3\r\none\r\n");
+
+        let frame = RespArray::decode(&mut buf)?;
+
+        let result: ZScore = frame.try_into()?;
+        assert_eq!(result.key, "zset");
+        assert_eq!(result.member, "one");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    cmd::{extract_args, next_arg, parse_string, validate_min_args, CommandError, CommandExecutor},
+    Aggregate, RespArray, RespFrame,
+};
+
+use super::{parse_numkeys, parse_score, scored_members_to_frame};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZSetOpKind {
+    Union,
+    Inter,
+    Diff,
+}
+
+/// ZUNION / ZINTER / ZDIFF 以及对应的 STORE 命令共用，destination 不为空时是 STORE 命令
+#[derive(Debug)]
+pub struct ZSetOp {
+    pub(crate) kind: ZSetOpKind,
+    pub(crate) destination: Option<String>,
+    pub(crate) keys: Vec<String>,
+    pub(crate) weights: Vec<f64>,
+    pub(crate) aggregate: Aggregate,
+    pub(crate) with_scores: bool,
+}
+
+impl CommandExecutor for ZSetOp {
+    fn execute(self, backend: &crate::Backend) -> RespFrame {
+        let zset = match self.kind {
+            ZSetOpKind::Union => backend.zunion(&self.keys, &self.weights, self.aggregate),
+            ZSetOpKind::Inter => backend.zinter(&self.keys, &self.weights, self.aggregate),
+            ZSetOpKind::Diff => backend.zdiff(&self.keys),
+        };
+
+        match self.destination {
+            Some(destination) => {
+                let event = match self.kind {
+                    ZSetOpKind::Union => "zunionstore",
+                    ZSetOpKind::Inter => "zinterstore",
+                    ZSetOpKind::Diff => "zdiffstore",
+                };
+                (backend.zstore(destination, zset, event) as i64).into()
+            }
+            None => {
+                let items = zset.iter().map(|(m, s)| (m.to_string(), s)).collect();
+                scored_members_to_frame(items, self.with_scores)
+            }
+        }
+    }
+}
+
+impl TryFrom<RespArray> for ZSetOp {
+    type Error = CommandError;
+    fn try_from(value: RespArray) -> Result<Self, Self::Error> {
+        let name = match value.first() {
+            Some(RespFrame::BulkString(cmd)) => String::from_utf8_lossy(cmd).to_ascii_lowercase(),
+            _ => String::new(),
+        };
+        let (name, kind, store) = match name.as_str() {
+            "zunion" => ("zunion", ZSetOpKind::Union, false),
+            "zunionstore" => ("zunionstore", ZSetOpKind::Union, true),
+            "zinter" => ("zinter", ZSetOpKind::Inter, false),
+            "zinterstore" => ("zinterstore", ZSetOpKind::Inter, true),
+            "zdiff" => ("zdiff", ZSetOpKind::Diff, false),
+            "zdiffstore" => ("zdiffstore", ZSetOpKind::Diff, true),
+            _ => {
+                return Err(CommandError::InvalidCommand(format!(
+                    "Invalid command: expected zunion, zinter or zdiff, got {}",
+                    name
+                )))
+            }
+        };
+        validate_min_args(&value, &[name], if store { 3 } else { 2 })?;
+
+        let mut args = extract_args(value, 1)?.into_iter();
+        let destination = if store {
+            Some(parse_string(next_arg(&mut args)?)?)
+        } else {
+            None
+        };
+        let keys = parse_numkeys(&mut args)?;
+
+        let (mut weights, mut aggregate, mut with_scores) = (vec![], Aggregate::default(), false);
+        while let Some(arg) = args.next() {
+            let arg = parse_string(arg)?.to_ascii_lowercase();
+            match arg.as_str() {
+                "weights" if kind != ZSetOpKind::Diff => {
+                    weights = (0..keys.len())
+                        .map(|_| parse_score(next_arg(&mut args)?))
+                        .collect::<Result<Vec<_>, _>>()?;
+                }
+                "aggregate" if kind != ZSetOpKind::Diff => {
+                    let value = parse_string(next_arg(&mut args)?)?;
+                    aggregate = match value.to_ascii_lowercase().as_str() {
+                        "sum" => Aggregate::Sum,
+                        "min" => Aggregate::Min,
+                        "max" => Aggregate::Max,
+                        _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+                    };
+                }
+                "withscores" if !store => with_scores = true,
+                _ => return Err(CommandError::InvalidArgument("syntax error".to_string())),
+            }
+        }
+
+        Ok(ZSetOp {
+            kind,
+            destination,
+            keys,
+            weights,
+            aggregate,
+            with_scores,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bytes::BytesMut;
+
+    use crate::RespDecode;
+
+    use super::*;
+
+    #[test]
+    fn test_zsetop_from_resp_array() -> Result<()> {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(
+            b"*10\r\n$11\r\nzunionstore\r\n$3\r\ndst\r\n$1\r\n2\r\n$2\r\nz1\r\n$2\r\nz2\r\n$7\r\nWEIGHTS\r\n$1\r\n2\r\n$1\r\n3\r\n$9\r\nAGGREGATE\r\n$3\r\nMAX\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: ZSetOp = frame.try_into()?;
+        assert_eq!(result.kind, ZSetOpKind::Union);
+        assert_eq!(result.destination, Some("dst".to_string()));
+        assert_eq!(result.keys, vec!["z1", "z2"]);
+        assert_eq!(result.weights, vec![2.0, 3.0]);
+        assert_eq!(result.aggregate, Aggregate::Max);
+
+        buf.extend_from_slice(
+            b"*5\r\n$5\r\nzdiff\r\n$1\r\n2\r\n$2\r\nz1\r\n$2\r\nz2\r\n$10\r\nWITHSCORES\r\n",
+        );
+        let frame = RespArray::decode(&mut buf)?;
+        let result: ZSetOp = frame.try_into()?;
+        assert_eq!(result.kind, ZSetOpKind::Diff);
+        assert_eq!(result.destination, None);
+        assert!(result.with_scores);
+
+        Ok(())
+    }
+}
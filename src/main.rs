@@ -1,5 +1,6 @@
 use anyhow::Result;
 use simple_redis::{network, Backend};
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tracing::{info, warn};
 
@@ -11,6 +12,17 @@ async fn main() -> Result<()> {
     info!("Simple-Redis-Server is listening on {}", addr);
 
     let backend = Backend::new();
+    // 定期删除没有被访问的过期 key
+    tokio::spawn({
+        let backend = backend.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(10));
+            loop {
+                interval.tick().await;
+                backend.expire_sweep();
+            }
+        }
+    });
     let listener = TcpListener::bind(addr).await?;
     loop {
         let (stream, raddr) = listener.accept().await?;
@@ -1,13 +1,25 @@
 use crate::{
-    cmd::{Command, CommandExecutor},
-    Backend, RespDecode, RespEncode, RespError, RespFrame,
+    cmd::{BlockingCommandExecutor, Command, CommandExecutor, ConnectionCommand, LockMode},
+    Backend, BulkString, MessageReceiver, RespArray, RespDecode, RespEncode, RespError, RespFrame,
+    RespMap, RespNull, RespPush, SimpleError, SimpleString, Subscriber,
 };
 use anyhow::Result;
 use futures::SinkExt;
-use tokio::net::TcpStream;
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::{
+    net::TcpStream,
+    sync::{RwLockReadGuard, RwLockWriteGuard},
+    time::{timeout_at, Instant},
+};
 use tokio_stream::StreamExt;
 use tokio_util::codec::{Decoder, Encoder, Framed};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug)]
 struct RespFrameCodec;
@@ -18,9 +30,61 @@ struct RedisRequest {
     backend: Backend,
 }
 
+// 连接 id，同一个 channel 的订阅者用它区分
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
 #[derive(Debug)]
 struct RedisResponse {
-    frame: RespFrame,
+    // SUBSCRIBE 等命令对每个 channel 回复一次
+    frames: Vec<RespFrame>,
+}
+
+/// 连接的上下文，同一个连接上的请求共享
+#[derive(Debug)]
+struct Connection {
+    // MULTI 之后排队的命令，None 表示不在事务中
+    transaction: Option<Transaction>,
+    watch: Option<Watch>,
+    id: u64,
+    // HELLO 协商的协议版本，RESP3 用 push 类型推送订阅的消息
+    protocol: i64,
+    // 发布的消息通过它发送给 stream_handler
+    subscriber: Subscriber,
+    subscription: Option<Subscription>,
+    // QUIT 之后回复完就关闭连接
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Transaction {
+    commands: Vec<Queued>,
+    // 排队时有命令校验失败，EXEC 时放弃整个事务
+    aborted: bool,
+}
+
+// 排队的命令，PING 这样只依赖连接状态的命令入队时就确定了回复
+#[derive(Debug)]
+enum Queued {
+    Command(Box<Command>),
+    Reply(RespFrame),
+}
+
+// WATCH 的 key，EXEC、DISCARD、UNWATCH 或者连接断开时取消监视
+#[derive(Debug)]
+struct Watch {
+    backend: Backend,
+    keys: Vec<String>,
+    // 监视的 key 被修改时由 Backend 设置
+    dirty: Arc<AtomicBool>,
+}
+
+// 订阅的 channel 和模式，RESET 或者连接断开时全部退订
+#[derive(Debug)]
+struct Subscription {
+    backend: Backend,
+    id: u64,
+    channels: BTreeSet<String>,
+    patterns: BTreeSet<String>,
 }
 
 pub async fn stream_handler(stream: TcpStream, backend: Backend) -> Result<()> {
@@ -28,32 +92,566 @@ pub async fn stream_handler(stream: TcpStream, backend: Backend) -> Result<()> {
     // call request_handler with the frame
     // send the response back to the stream
     let mut framed = Framed::new(stream, RespFrameCodec);
+    let (mut conn, mut messages) = Connection::new();
     loop {
-        match framed.next().await {
-            Some(Ok(frame)) => {
-                info!("Received frame: {:?}", frame);
-                let request = RedisRequest {
-                    frame,
-                    backend: backend.clone(),
+        tokio::select! {
+            request = framed.next() => match request {
+                Some(Ok(frame)) => {
+                    info!("Received frame: {:?}", frame);
+                    let request = RedisRequest {
+                        frame,
+                        backend: backend.clone(),
+                    };
+                    let response = request_handler(request, &mut conn).await?;
+                    info!("Sending response: {:?}", response.frames);
+                    for frame in response.frames {
+                        framed.feed(frame).await?;
+                    }
+                    framed.flush().await?;
+                    if conn.closed {
+                        return Ok(());
+                    }
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()), // 数据不完整，等待下一次数据
+            },
+            message = messages.recv() => {
+                // 客户端一直不读取时 send 不会返回，等待期间同样需要检查积压的消息
+                let sent = match message {
+                    Some(message) => tokio::select! {
+                        ret = framed.send(conn.push(message.into_frames())) => {
+                            ret?;
+                            true
+                        }
+                        _ = messages.overflowed() => false,
+                    },
+                    None => false,
                 };
-                let response = request_handler(request).await?;
-                info!("Sending response: {:?}", response.frame);
-                // info!("sending: {:?}", response.frame.clone().encode());
-                // info!("Sending response: {:?}", String::from_utf8(response.frame.clone().encode()));
-                framed.send(response.frame.clone()).await?;
+                if !sent {
+                    warn!("Closing connection {}: pubsub output buffer limit reached", conn.id);
+                    return Ok(());
+                }
             }
-            Some(Err(e)) => return Err(e),
-            None => return Ok(()), // 数据不完整，等待下一次数据
         }
     }
 }
 
-async fn request_handler(request: RedisRequest) -> Result<RedisResponse> {
+async fn request_handler(request: RedisRequest, conn: &mut Connection) -> Result<RedisResponse> {
+    let mut response = handle_request(request, conn).await?;
+    // RESP2 没有 double 类型，ZSCORE 等命令返回的分数改成 bulk string
+    if conn.protocol == 2 {
+        response.frames = response.frames.into_iter().map(double_to_bulk).collect();
+    }
+    Ok(response)
+}
+
+async fn handle_request(request: RedisRequest, conn: &mut Connection) -> Result<RedisResponse> {
     let (frame, backend) = (request.frame, request.backend);
-    let cmd = Command::try_from(frame)?;
+    // RESP2 的连接订阅之后只能接收消息，RESP3 可以同时执行其它命令
+    if conn.protocol == 2 && conn.subscribed() {
+        if let Some(name) = ConnectionCommand::rejected_when_subscribed(&frame) {
+            let err = format!(
+                "ERR Can't execute '{}': only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context",
+                name
+            );
+            return Ok(RedisResponse::new(SimpleError::new(err).into()));
+        }
+    }
+    if ConnectionCommand::matches(&frame) {
+        let frames = match ConnectionCommand::try_from(frame) {
+            // 和 Redis 一样，MULTI 之后只有这几个命令立即执行，其它的进入队列或者被拒绝
+            Ok(cmd) if conn.transaction.is_some() && !cmd.runs_in_transaction() => {
+                vec![conn.queue(cmd)]
+            }
+            Ok(cmd) => conn.execute(cmd, &backend).await,
+            Err(e) => {
+                if let Some(transaction) = conn.transaction.as_mut() {
+                    transaction.aborted = true;
+                }
+                vec![SimpleError::new(format!("ERR {}", e)).into()]
+            }
+        };
+        return Ok(RedisResponse { frames });
+    }
+    if let Some(transaction) = conn.transaction.as_mut() {
+        let frame = transaction.queue(frame, &backend);
+        return Ok(RedisResponse::new(frame));
+    }
+
+    // 参数错误只回复错误，不断开连接
+    let mut cmd = match Command::parse(frame, &backend) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            let frame = SimpleError::new(format!("ERR {}", e)).into();
+            return Ok(RedisResponse::new(frame));
+        }
+    };
     info!("Executing command: {:?}", cmd);
-    let frame = cmd.execute(&backend);
-    Ok(RedisResponse { frame })
+    let frame = match cmd.as_blocking() {
+        Some(cmd) => execute_blocking(cmd, &backend).await,
+        None => execute(cmd, &backend).await,
+    };
+    Ok(RedisResponse::new(frame))
+}
+
+fn double_to_bulk(frame: RespFrame) -> RespFrame {
+    match frame {
+        RespFrame::Double(d) => BulkString::new(format_double(d)).into(),
+        RespFrame::Array(mut array) => {
+            array.0 = array.0.into_iter().map(double_to_bulk).collect();
+            array.into()
+        }
+        RespFrame::Map(mut map) => {
+            map.0 = map
+                .0
+                .into_iter()
+                .map(|(k, v)| (k, double_to_bulk(v)))
+                .collect();
+            map.into()
+        }
+        RespFrame::Set(mut set) => {
+            set.0 = set.0.into_iter().map(double_to_bulk).collect();
+            set.into()
+        }
+        frame => frame,
+    }
+}
+
+// 和 Redis 的 %.17g 一样，指数小于 -4 或者不小于 17 时使用科学计数法，例如 1e+20 和 1e-05
+// 有效数字取能还原出同一个浮点数的最短表示
+fn format_double(d: f64) -> String {
+    if d.is_infinite() {
+        return if d > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    if d.is_nan() {
+        return "nan".to_string();
+    }
+    let sci = format!("{:e}", d);
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    if d != 0.0 && !(-4..17).contains(&exp) {
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exp.abs())
+    } else {
+        d.to_string()
+    }
+}
+
+impl RedisResponse {
+    fn new(frame: RespFrame) -> Self {
+        Self {
+            frames: vec![frame],
+        }
+    }
+}
+
+/// 按命令的 LockMode 持有 exec_lock 执行
+async fn execute(cmd: Command, backend: &Backend) -> RespFrame {
+    match cmd.lock_mode() {
+        LockMode::Shared => match lock_shared(backend).await {
+            Ok(_guard) => cmd.execute(backend),
+            Err(busy) => busy,
+        },
+        LockMode::Exclusive => match lock_exclusive(backend).await {
+            Ok(_guard) => execute_off_runtime(backend, move |backend| cmd.execute(backend)).await,
+            Err(busy) => busy,
+        },
+        LockMode::Unlocked => cmd.execute(backend),
+    }
+}
+
+// 脚本和事务可能执行很久，放到阻塞线程池中执行，不占用运行时的工作线程
+// 这样执行期间其它连接仍然可以收到 BUSY，也可以执行 SCRIPT KILL
+async fn execute_off_runtime<F>(backend: &Backend, f: F) -> RespFrame
+where
+    F: FnOnce(&Backend) -> RespFrame + Send + 'static,
+{
+    let backend = backend.clone();
+    match tokio::task::spawn_blocking(move || f(&backend)).await {
+        Ok(frame) => frame,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+fn busy() -> RespFrame {
+    SimpleError::new(
+        "BUSY Redis is busy running a script. You can only call SCRIPT KILL or SHUTDOWN NOSAVE."
+            .to_string(),
+    )
+    .into()
+}
+
+// 脚本执行超过 lua-time-limit 之后不再等待 exec_lock，直接返回 BUSY
+async fn lock_shared(backend: &Backend) -> Result<RwLockReadGuard<'_, ()>, RespFrame> {
+    match backend.exec_lock.try_read() {
+        Ok(guard) => Ok(guard),
+        Err(_) if backend.script_busy() => Err(busy()),
+        Err(_) => Ok(backend.exec_lock.read().await),
+    }
+}
+
+async fn lock_exclusive(backend: &Backend) -> Result<RwLockWriteGuard<'_, ()>, RespFrame> {
+    match backend.exec_lock.try_write() {
+        Ok(guard) => Ok(guard),
+        Err(_) if backend.script_busy() => Err(busy()),
+        Err(_) => Ok(backend.exec_lock.write().await),
+    }
+}
+
+impl Connection {
+    /// 返回的 receiver 接收订阅的消息
+    fn new() -> (Self, MessageReceiver) {
+        let (subscriber, messages) = Subscriber::new();
+        let conn = Self {
+            transaction: None,
+            watch: None,
+            id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            protocol: 2,
+            subscriber,
+            subscription: None,
+            closed: false,
+        };
+        (conn, messages)
+    }
+
+    fn subscribed(&self) -> bool {
+        self.subscription_count() > 0
+    }
+
+    // 确认消息中的订阅数量，重复订阅同一个 channel 或者模式只算一次
+    fn subscription_count(&self) -> i64 {
+        self.subscription
+            .as_ref()
+            .map_or(0, |s| (s.channels.len() + s.patterns.len()) as i64)
+    }
+
+    /// RESP3 编码成 push，RESP2 编码成 array
+    fn push(&self, frames: Vec<RespFrame>) -> RespFrame {
+        if self.protocol == 3 {
+            RespPush::new(frames).into()
+        } else {
+            RespArray::new(frames).into()
+        }
+    }
+
+    async fn execute(&mut self, cmd: ConnectionCommand, backend: &Backend) -> Vec<RespFrame> {
+        match cmd {
+            ConnectionCommand::Subscribe(channels) => self.subscribe(channels, false, backend),
+            ConnectionCommand::Unsubscribe(channels) => self.unsubscribe(channels, false),
+            ConnectionCommand::Psubscribe(patterns) => self.subscribe(patterns, true, backend),
+            ConnectionCommand::Punsubscribe(patterns) => self.unsubscribe(patterns, true),
+            cmd => vec![self.execute_one(cmd, backend).await],
+        }
+    }
+
+    // pattern 为 true 时订阅的是 glob 模式
+    fn subscribe(
+        &mut self,
+        names: Vec<String>,
+        pattern: bool,
+        backend: &Backend,
+    ) -> Vec<RespFrame> {
+        let kind = if pattern { "psubscribe" } else { "subscribe" };
+        let mut frames = Vec::with_capacity(names.len());
+        for name in names {
+            let subscription = self.subscription.get_or_insert_with(|| Subscription {
+                backend: backend.clone(),
+                id: self.id,
+                channels: BTreeSet::new(),
+                patterns: BTreeSet::new(),
+            });
+            if subscription.names(pattern).insert(name.clone()) {
+                let subscriber = self.subscriber.clone();
+                match pattern {
+                    true => backend.psubscribe(name.clone(), self.id, subscriber),
+                    false => backend.subscribe(name.clone(), self.id, subscriber),
+                }
+            }
+            frames.push(self.push(vec![
+                BulkString::from(kind).into(),
+                BulkString::from(name).into(),
+                self.subscription_count().into(),
+            ]));
+        }
+        frames
+    }
+
+    /// 没有指定 channel 或模式时退订所有的 channel 或模式
+    fn unsubscribe(&mut self, names: Vec<String>, pattern: bool) -> Vec<RespFrame> {
+        let kind = if pattern {
+            "punsubscribe"
+        } else {
+            "unsubscribe"
+        };
+        let names = match names.is_empty() {
+            true => self
+                .subscription
+                .as_mut()
+                .map(|s| s.names(pattern).iter().cloned().collect())
+                .unwrap_or_default(),
+            false => names,
+        };
+        if names.is_empty() {
+            return vec![self.push(vec![
+                BulkString::from(kind).into(),
+                RespNull.into(),
+                self.subscription_count().into(),
+            ])];
+        }
+
+        let mut frames = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(subscription) = self.subscription.as_mut() {
+                if subscription.names(pattern).remove(&name) {
+                    subscription.unsubscribe(&name, pattern);
+                }
+            }
+            frames.push(self.push(vec![
+                BulkString::from(kind).into(),
+                BulkString::from(name).into(),
+                self.subscription_count().into(),
+            ]));
+        }
+        frames
+    }
+
+    fn ping(&self, message: Option<Vec<u8>>) -> RespFrame {
+        match message {
+            // RESP2 订阅状态下按消息的格式回复
+            message if self.protocol == 2 && self.subscribed() => {
+                let message = BulkString::new(message.unwrap_or_default());
+                RespArray::new([BulkString::from("pong").into(), message.into()]).into()
+            }
+            Some(message) => BulkString::new(message).into(),
+            None => SimpleString::new("PONG".to_string()).into(),
+        }
+    }
+
+    /// MULTI 中的连接命令：PING、UNWATCH 入队，会修改订阅状态或协议的命令不允许执行
+    /// 事务中不能订阅或切换协议，所以 PING 的回复在入队时就可以确定
+    fn queue(&mut self, cmd: ConnectionCommand) -> RespFrame {
+        let reply = match cmd {
+            ConnectionCommand::Ping(message) => self.ping(message),
+            // EXEC 之后总会取消 WATCH，入队的 UNWATCH 不需要做其它事情
+            ConnectionCommand::Unwatch => SimpleString::new("OK".to_string()).into(),
+            _ => {
+                if let Some(transaction) = self.transaction.as_mut() {
+                    transaction.aborted = true;
+                }
+                return SimpleError::new(
+                    "ERR Command not allowed inside a transaction".to_string(),
+                )
+                .into();
+            }
+        };
+        if let Some(transaction) = self.transaction.as_mut() {
+            transaction.commands.push(Queued::Reply(reply));
+        }
+        SimpleString::new("QUEUED".to_string()).into()
+    }
+
+    async fn execute_one(&mut self, cmd: ConnectionCommand, backend: &Backend) -> RespFrame {
+        match cmd {
+            ConnectionCommand::Multi => {
+                if self.transaction.is_some() {
+                    return SimpleError::new("ERR MULTI calls can not be nested".to_string())
+                        .into();
+                }
+                self.transaction = Some(Transaction::default());
+                SimpleString::new("OK".to_string()).into()
+            }
+            ConnectionCommand::Exec => match self.transaction.take() {
+                Some(transaction) => transaction.execute(backend, self.watch.take()).await,
+                None => SimpleError::new("ERR EXEC without MULTI".to_string()).into(),
+            },
+            ConnectionCommand::Discard => match self.transaction.take() {
+                Some(_) => {
+                    self.watch = None;
+                    SimpleString::new("OK".to_string()).into()
+                }
+                None => SimpleError::new("ERR DISCARD without MULTI".to_string()).into(),
+            },
+            ConnectionCommand::Watch(keys) => {
+                if self.transaction.is_some() {
+                    return SimpleError::new("ERR WATCH inside MULTI is not allowed".to_string())
+                        .into();
+                }
+                let watch = self.watch.get_or_insert_with(|| Watch {
+                    backend: backend.clone(),
+                    keys: Vec::new(),
+                    dirty: Arc::new(AtomicBool::new(false)),
+                });
+                backend.watch(&keys, &watch.dirty);
+                for key in keys {
+                    if !watch.keys.contains(&key) {
+                        watch.keys.push(key);
+                    }
+                }
+                SimpleString::new("OK".to_string()).into()
+            }
+            ConnectionCommand::Unwatch => {
+                self.watch = None;
+                SimpleString::new("OK".to_string()).into()
+            }
+            ConnectionCommand::Ping(message) => self.ping(message),
+            ConnectionCommand::Quit => {
+                self.closed = true;
+                SimpleString::new("OK".to_string()).into()
+            }
+            ConnectionCommand::Reset => {
+                self.transaction = None;
+                self.watch = None;
+                self.subscription = None;
+                self.protocol = 2;
+                SimpleString::new("RESET".to_string()).into()
+            }
+            ConnectionCommand::Hello(protocol) => {
+                let protocol = protocol.unwrap_or(self.protocol);
+                if protocol != 2 && protocol != 3 {
+                    return SimpleError::new("NOPROTO unsupported protocol version".to_string())
+                        .into();
+                }
+                self.protocol = protocol;
+                let mut map = RespMap::new();
+                map.insert("server".to_string(), BulkString::from("redis").into());
+                map.insert(
+                    "version".to_string(),
+                    BulkString::from(env!("CARGO_PKG_VERSION")).into(),
+                );
+                map.insert("proto".to_string(), protocol.into());
+                map.insert("id".to_string(), (self.id as i64).into());
+                map.insert("mode".to_string(), BulkString::from("standalone").into());
+                map.insert("role".to_string(), BulkString::from("master").into());
+                map.into()
+            }
+            ConnectionCommand::Subscribe(_)
+            | ConnectionCommand::Unsubscribe(_)
+            | ConnectionCommand::Psubscribe(_)
+            | ConnectionCommand::Punsubscribe(_) => {
+                unreachable!("handled by Connection::execute")
+            }
+        }
+    }
+}
+
+impl Transaction {
+    /// 校验命令并排队，校验失败时整个事务在 EXEC 时被放弃
+    fn queue(&mut self, frame: RespFrame, backend: &Backend) -> RespFrame {
+        let err = match Command::parse(frame, backend) {
+            Ok(Command::Unrecognized(_)) => "ERR unknown command".to_string(),
+            Ok(cmd) => {
+                self.commands.push(Queued::Command(Box::new(cmd)));
+                return SimpleString::new("QUEUED".to_string()).into();
+            }
+            Err(e) => format!("ERR {}", e),
+        };
+        self.aborted = true;
+        SimpleError::new(err).into()
+    }
+
+    /// 持有写锁依次执行所有命令，阻塞命令在事务中不会阻塞，没有数据时直接返回 nil
+    /// WATCH 的 key 被修改过时不执行，返回 null array
+    async fn execute(self, backend: &Backend, watch: Option<Watch>) -> RespFrame {
+        if self.aborted {
+            return SimpleError::new(
+                "EXECABORT Transaction discarded because of previous errors.".to_string(),
+            )
+            .into();
+        }
+
+        let _guard = match lock_exclusive(backend).await {
+            Ok(guard) => guard,
+            Err(busy) => return busy,
+        };
+        if let Some(watch) = watch {
+            if backend.watched_changed(&watch.keys, &watch.dirty) {
+                // 空数组编码为 *-1，即 null array
+                return RespArray::new([]).into();
+            }
+        }
+        execute_off_runtime(backend, move |backend| {
+            let frames = self
+                .commands
+                .into_iter()
+                .map(|queued| match queued {
+                    Queued::Command(cmd) => cmd.execute_now(backend),
+                    Queued::Reply(frame) => frame,
+                })
+                .collect::<Vec<_>>();
+            RespArray::new(frames).into()
+        })
+        .await
+    }
+}
+
+/// 阻塞命令先在 keys 上排队，再尝试执行，没有数据时等待其它连接写入后被唤醒重试
+async fn execute_blocking(cmd: &mut dyn BlockingCommandExecutor, backend: &Backend) -> RespFrame {
+    {
+        let _guard = backend.exec_lock.read().await;
+        cmd.prepare(backend);
+    }
+    let keys = cmd.keys();
+    let waiter = backend.blocking.register(keys);
+    let deadline = cmd.timeout().map(|timeout| Instant::now() + timeout);
+
+    let frame = loop {
+        let frame = {
+            let _guard = backend.exec_lock.read().await;
+            cmd.try_execute(backend)
+        };
+        if let Some(frame) = frame {
+            break frame;
+        }
+
+        match deadline {
+            Some(deadline) => {
+                if timeout_at(deadline, waiter.notified()).await.is_err() {
+                    break RespFrame::Null(RespNull);
+                }
+            }
+            None => waiter.notified().await,
+        }
+    };
+
+    backend.blocking.unregister(keys, &waiter);
+    // 写入的数据可能还没有被取完，继续唤醒下一个排队的客户端
+    for key in keys {
+        backend.blocking.signal(key);
+    }
+    frame
+}
+
+impl Subscription {
+    fn names(&mut self, pattern: bool) -> &mut BTreeSet<String> {
+        match pattern {
+            true => &mut self.patterns,
+            false => &mut self.channels,
+        }
+    }
+
+    fn unsubscribe(&self, name: &str, pattern: bool) {
+        match pattern {
+            true => self.backend.punsubscribe(name, self.id),
+            false => self.backend.unsubscribe(name, self.id),
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        for channel in &self.channels {
+            self.unsubscribe(channel, false);
+        }
+        for pattern in &self.patterns {
+            self.unsubscribe(pattern, true);
+        }
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.backend.unwatch(&self.keys, &self.dirty);
+    }
 }
 
 impl Encoder<RespFrame> for RespFrameCodec {
@@ -78,3 +676,741 @@ impl Decoder for RespFrameCodec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use anyhow::Result;
+
+    use crate::{
+        cmd::{BZPop, XRead, XReadId},
+        BulkString, PubSubMessage, RespArray, XAddId, ZAddFlags,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_execute_blocking() -> Result<()> {
+        let backend = Backend::new();
+
+        let bzpop = |timeout| BZPop {
+            keys: vec!["zset".to_string()],
+            max: false,
+            timeout,
+        };
+
+        // 超时返回 nil
+        let timeout = Some(Duration::from_millis(10));
+        let ret = execute_blocking(&mut bzpop(timeout), &backend).await;
+        assert_eq!(ret, RespNull.into());
+
+        // 两个客户端先后阻塞，写入两个元素后按阻塞顺序分别得到分数小的和分数大的
+        let first = tokio::spawn({
+            let backend = backend.clone();
+            async move { execute_blocking(&mut bzpop(None), &backend).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second = tokio::spawn({
+            let backend = backend.clone();
+            async move { execute_blocking(&mut bzpop(None), &backend).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let members = vec![(1.0, "a".to_string()), (2.0, "b".to_string())];
+        backend.zadd("zset".to_string(), members, ZAddFlags::default())?;
+
+        let expected = RespArray::new([
+            BulkString::from("zset").into(),
+            BulkString::from("a").into(),
+            1.0.into(),
+        ]);
+        assert_eq!(first.await?, expected.into());
+
+        let expected = RespArray::new([
+            BulkString::from("zset").into(),
+            BulkString::from("b").into(),
+            2.0.into(),
+        ]);
+        assert_eq!(second.await?, expected.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_blocking_xread() -> Result<()> {
+        let backend = Backend::new();
+        let field = vec![("f".to_string(), "v".to_string())];
+        backend.xadd("s".to_string(), XAddId::Auto, field.clone(), false, None)?;
+
+        // "$" 只读取阻塞之后的新消息，所有阻塞的客户端都能读到
+        let xread = || XRead {
+            count: usize::MAX,
+            block: Some(None),
+            keys: vec!["s".to_string()],
+            ids: vec![XReadId::New],
+        };
+        let readers = (0..2)
+            .map(|_| {
+                let backend = backend.clone();
+                tokio::spawn(async move { execute_blocking(&mut xread(), &backend).await })
+            })
+            .collect::<Vec<_>>();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let id = backend
+            .xadd("s".to_string(), XAddId::Auto, field, false, None)?
+            .unwrap();
+        for reader in readers {
+            let ret = match reader.await? {
+                RespFrame::Array(streams) => streams,
+                _ => unreachable!(),
+            };
+            let expected = RespArray::new([
+                BulkString::from(id.to_string()).into(),
+                RespArray::new([BulkString::from("f").into(), BulkString::from("v").into()]).into(),
+            ]);
+            let expected = RespArray::new([
+                BulkString::from("s").into(),
+                RespArray::new([RespFrame::from(expected)]).into(),
+            ]);
+            assert_eq!(ret[0], expected.into());
+        }
+
+        Ok(())
+    }
+
+    fn request(args: &[&str]) -> RespFrame {
+        let args = args
+            .iter()
+            .map(|arg| BulkString::from(*arg).into())
+            .collect::<Vec<RespFrame>>();
+        RespArray::new(args).into()
+    }
+
+    async fn send(conn: &mut Connection, backend: &Backend, args: &[&str]) -> Result<RespFrame> {
+        let mut frames = send_all(conn, backend, args).await?;
+        assert_eq!(frames.len(), 1);
+        Ok(frames.remove(0))
+    }
+
+    async fn send_all(
+        conn: &mut Connection,
+        backend: &Backend,
+        args: &[&str],
+    ) -> Result<Vec<RespFrame>> {
+        let request = RedisRequest {
+            frame: request(args),
+            backend: backend.clone(),
+        };
+        Ok(request_handler(request, conn).await?.frames)
+    }
+
+    fn simple(s: &str) -> RespFrame {
+        SimpleString::new(s.to_string()).into()
+    }
+
+    fn error(s: &str) -> RespFrame {
+        SimpleError::new(s.to_string()).into()
+    }
+
+    fn message(kind: &str, channel: &str, count: i64) -> Vec<RespFrame> {
+        vec![
+            BulkString::from(kind).into(),
+            BulkString::from(channel).into(),
+            count.into(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_publish() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, mut messages) = Connection::new();
+        let (mut other, _) = Connection::new();
+
+        // 每个 channel 回复一次，重复订阅不增加数量
+        let ret = send_all(&mut conn, &backend, &["subscribe", "a", "b", "a"]).await?;
+        let expected: Vec<RespFrame> = vec![
+            RespArray::new(message("subscribe", "a", 1)).into(),
+            RespArray::new(message("subscribe", "b", 2)).into(),
+            RespArray::new(message("subscribe", "a", 2)).into(),
+        ];
+        assert_eq!(ret, expected);
+
+        assert_eq!(
+            send(&mut other, &backend, &["publish", "a", "hi"]).await?,
+            RespFrame::Integer(1)
+        );
+        assert_eq!(
+            send(&mut other, &backend, &["publish", "c", "hi"]).await?,
+            RespFrame::Integer(0)
+        );
+        let expected = PubSubMessage::Message {
+            channel: "a".to_string(),
+            payload: b"hi".to_vec(),
+        };
+        assert_eq!(messages.try_recv(), Some(expected));
+        assert!(messages.try_recv().is_none());
+
+        // RESP2 订阅状态下只能执行订阅相关的命令
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            error("ERR Can't execute 'get': only (P)SUBSCRIBE / (P)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context")
+        );
+        let expected =
+            RespArray::new([BulkString::from("pong").into(), BulkString::from("").into()]);
+        assert_eq!(send(&mut conn, &backend, &["ping"]).await?, expected.into());
+
+        // 不指定 channel 时退订所有的 channel
+        let ret = send_all(&mut conn, &backend, &["unsubscribe", "b"]).await?;
+        let expected: Vec<RespFrame> = vec![RespArray::new(message("unsubscribe", "b", 1)).into()];
+        assert_eq!(ret, expected);
+        let ret = send_all(&mut conn, &backend, &["unsubscribe"]).await?;
+        let expected: Vec<RespFrame> = vec![RespArray::new(message("unsubscribe", "a", 0)).into()];
+        assert_eq!(ret, expected);
+        let expected = RespArray::new([
+            BulkString::from("unsubscribe").into(),
+            RespNull.into(),
+            RespFrame::Integer(0),
+        ]);
+        assert_eq!(
+            send(&mut conn, &backend, &["unsubscribe"]).await?,
+            expected.into()
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            RespNull.into()
+        );
+        assert_eq!(
+            send(&mut other, &backend, &["publish", "a", "hi"]).await?,
+            RespFrame::Integer(0)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_psubscribe() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, mut messages) = Connection::new();
+
+        // channel 和模式一起计数，重复订阅不增加数量
+        let ret = send_all(&mut conn, &backend, &["psubscribe", "events.*", "events.*"]).await?;
+        let expected: Vec<RespFrame> = vec![
+            RespArray::new(message("psubscribe", "events.*", 1)).into(),
+            RespArray::new(message("psubscribe", "events.*", 1)).into(),
+        ];
+        assert_eq!(ret, expected);
+        let ret = send_all(&mut conn, &backend, &["subscribe", "events.login"]).await?;
+        let expected: Vec<RespFrame> =
+            vec![RespArray::new(message("subscribe", "events.login", 2)).into()];
+        assert_eq!(ret, expected);
+
+        // 同时通过 channel 和模式订阅时收到两次
+        assert_eq!(backend.publish("events.login", b"hi".to_vec()), 2);
+        assert_eq!(backend.publish("events.logout", b"bye".to_vec()), 1);
+        assert_eq!(backend.publish("other", b"bye".to_vec()), 0);
+        let message_frames = |message: PubSubMessage| message.into_frames();
+        assert_eq!(
+            message_frames(messages.try_recv().unwrap()),
+            vec![
+                BulkString::from("message").into(),
+                BulkString::from("events.login").into(),
+                BulkString::from("hi").into(),
+            ]
+        );
+        let expected: Vec<RespFrame> = vec![
+            BulkString::from("pmessage").into(),
+            BulkString::from("events.*").into(),
+            BulkString::from("events.login").into(),
+            BulkString::from("hi").into(),
+        ];
+        assert_eq!(message_frames(messages.try_recv().unwrap()), expected);
+        assert!(matches!(
+            messages.try_recv(),
+            Some(PubSubMessage::PMessage { channel, .. }) if channel == "events.logout"
+        ));
+
+        // 退订所有模式之后仍然订阅着 channel
+        let ret = send_all(&mut conn, &backend, &["punsubscribe"]).await?;
+        let expected: Vec<RespFrame> =
+            vec![RespArray::new(message("punsubscribe", "events.*", 1)).into()];
+        assert_eq!(ret, expected);
+        let expected = RespArray::new([
+            BulkString::from("punsubscribe").into(),
+            RespNull.into(),
+            RespFrame::Integer(1),
+        ]);
+        assert_eq!(
+            send(&mut conn, &backend, &["punsubscribe"]).await?,
+            expected.into()
+        );
+        assert!(conn.subscribed());
+        assert_eq!(backend.publish("events.logout", b"bye".to_vec()), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_resp3() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, _messages) = Connection::new();
+
+        assert_eq!(
+            send(&mut conn, &backend, &["hello", "4"]).await?,
+            error("NOPROTO unsupported protocol version")
+        );
+        let ret = send(&mut conn, &backend, &["hello", "3"]).await?;
+        assert!(
+            matches!(ret, RespFrame::Map(map) if map.get("proto") == Some(&RespFrame::Integer(3)))
+        );
+
+        // RESP3 订阅之后仍然可以执行其它命令，确认消息是 push 类型
+        let expected: Vec<RespFrame> = vec![RespPush::new(message("subscribe", "a", 1)).into()];
+        assert_eq!(
+            send_all(&mut conn, &backend, &["subscribe", "a"]).await?,
+            expected
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            RespNull.into()
+        );
+        assert_eq!(send(&mut conn, &backend, &["ping"]).await?, simple("PONG"));
+
+        // RESET 退订所有的 channel 并恢复 RESP2
+        assert_eq!(
+            send(&mut conn, &backend, &["reset"]).await?,
+            simple("RESET")
+        );
+        assert_eq!(conn.protocol, 2);
+        assert_eq!(backend.publish("a", b"hi".to_vec()), 0);
+
+        // 连接断开时退订
+        send(&mut conn, &backend, &["subscribe", "a"]).await?;
+        assert_eq!(backend.publish("a", b"hi".to_vec()), 1);
+        drop(conn);
+        assert_eq!(backend.publish("a", b"hi".to_vec()), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_wrong_type() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+        send(&mut conn, &backend, &["hset", "h", "f", "v"]).await?;
+        send(&mut conn, &backend, &["rpush", "l", "a"]).await?;
+
+        // 每种类型的写命令都不能写入其它类型的 key
+        let writes: &[&[&str]] = &[
+            &["hset", "l", "f", "v"],
+            &["lpush", "h", "a"],
+            &["rpush", "h", "a"],
+            &["sadd", "h", "a"],
+            &["zadd", "h", "1", "a"],
+            &["zincrby", "h", "1", "a"],
+            &["geoadd", "h", "13.361389", "38.115556", "a"],
+            &["xadd", "h", "*", "f", "v"],
+            &["xgroup", "create", "h", "g", "$"],
+            &["pfadd", "h", "a"],
+            &["bf.reserve", "h", "0.01", "100"],
+            &["bf.add", "h", "a"],
+            &["cf.add", "h", "a"],
+            &["cms.initbydim", "h", "10", "5"],
+            &["topk.reserve", "h", "3"],
+            &["json.set", "h", "$", "1"],
+            &["ts.create", "h"],
+            &["ts.add", "h", "1", "1"],
+            &["cl.throttle", "h", "15", "30", "60"],
+        ];
+        for args in writes {
+            let ret = send(&mut conn, &backend, args).await?;
+            assert!(
+                matches!(&ret, RespFrame::Error(e) if e.0.starts_with("WRONGTYPE")),
+                "{:?}: {:?}",
+                args,
+                ret
+            );
+        }
+        assert_eq!(backend.key_type("h"), Some("hash"));
+        assert_eq!(backend.key_type("l"), Some("list"));
+
+        // SET 会覆盖其它类型
+        send(&mut conn, &backend, &["set", "h", "v"]).await?;
+        assert_eq!(backend.key_type("h"), Some("string"));
+        assert!(backend.hmap.get("h").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zset_lex_sentinels() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+        let members = |values: &[&str]| -> RespFrame {
+            RespArray::new(
+                values
+                    .iter()
+                    .map(|&v| BulkString::from(v).into())
+                    .collect::<Vec<RespFrame>>(),
+            )
+            .into()
+        };
+        send(
+            &mut conn,
+            &backend,
+            &["zadd", "lex", "0", "a", "0", "b", "0", "c"],
+        )
+        .await?;
+
+        // "+" 作为下界或者 "-" 作为上界时区间为空
+        let ret = send(&mut conn, &backend, &["zrange", "lex", "-", "+", "bylex"]).await?;
+        assert_eq!(ret, members(&["a", "b", "c"]));
+        let ret = send(&mut conn, &backend, &["zrange", "lex", "+", "-", "bylex"]).await?;
+        assert_eq!(ret, members(&[]));
+        let ret = send(
+            &mut conn,
+            &backend,
+            &["zrange", "lex", "+", "-", "bylex", "rev"],
+        )
+        .await?;
+        assert_eq!(ret, members(&["c", "b", "a"]));
+        let ret = send(
+            &mut conn,
+            &backend,
+            &["zrange", "lex", "-", "+", "bylex", "rev"],
+        )
+        .await?;
+        assert_eq!(ret, members(&[]));
+        let ret = send(&mut conn, &backend, &["zrange", "lex", "[b", "-", "bylex"]).await?;
+        assert_eq!(ret, members(&[]));
+
+        let ret = send(&mut conn, &backend, &["zlexcount", "lex", "-", "+"]).await?;
+        assert_eq!(ret, 3.into());
+        let ret = send(&mut conn, &backend, &["zlexcount", "lex", "+", "-"]).await?;
+        assert_eq!(ret, 0.into());
+        let ret = send(&mut conn, &backend, &["zlexcount", "lex", "+", "+"]).await?;
+        assert_eq!(ret, 0.into());
+        let ret = send(&mut conn, &backend, &["zlexcount", "lex", "-", "-"]).await?;
+        assert_eq!(ret, 0.into());
+
+        let ret = send(&mut conn, &backend, &["zremrangebylex", "lex", "+", "[c"]).await?;
+        assert_eq!(ret, 0.into());
+        let ret = send(&mut conn, &backend, &["zremrangebylex", "lex", "(a", "-"]).await?;
+        assert_eq!(ret, 0.into());
+        assert_eq!(backend.zcard("lex"), 3);
+        let ret = send(&mut conn, &backend, &["zremrangebylex", "lex", "(a", "+"]).await?;
+        assert_eq!(ret, 2.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_double_resp2() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, _messages) = Connection::new();
+        send(&mut conn, &backend, &["zadd", "zset", "1.5", "a"]).await?;
+
+        // RESP2 的分数是 bulk string，RESP3 是 double
+        let ret = send(&mut conn, &backend, &["zscore", "zset", "a"]).await?;
+        assert_eq!(ret, BulkString::from("1.5").into());
+        let ret = send(
+            &mut conn,
+            &backend,
+            &["zrange", "zset", "0", "-1", "withscores"],
+        )
+        .await?;
+        let expected =
+            RespArray::new([BulkString::from("a").into(), BulkString::from("1.5").into()]);
+        assert_eq!(ret, expected.into());
+
+        // 和 Redis 一样格式化很大的数和无穷大
+        for (score, expected) in [
+            ("1e20", "1e+20"),
+            ("1.5e-7", "1.5e-07"),
+            ("0.001", "0.001"),
+            ("123456789", "123456789"),
+            ("inf", "inf"),
+            ("-inf", "-inf"),
+        ] {
+            send(&mut conn, &backend, &["zadd", "zset", score, "b"]).await?;
+            let ret = send(&mut conn, &backend, &["zscore", "zset", "b"]).await?;
+            assert_eq!(ret, BulkString::from(expected).into());
+        }
+
+        send(&mut conn, &backend, &["hello", "3"]).await?;
+        let ret = send(&mut conn, &backend, &["zincrby", "zset", "1", "a"]).await?;
+        assert_eq!(ret, 2.5.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_exec() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            error("ERR EXEC without MULTI")
+        );
+        assert_eq!(send(&mut conn, &backend, &["multi"]).await?, simple("OK"));
+        assert_eq!(
+            send(&mut conn, &backend, &["multi"]).await?,
+            error("ERR MULTI calls can not be nested")
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["set", "k", "v"]).await?,
+            simple("QUEUED")
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            simple("QUEUED")
+        );
+        // 阻塞命令在事务中没有数据时直接返回 nil
+        assert_eq!(
+            send(&mut conn, &backend, &["bzpopmin", "z", "0"]).await?,
+            simple("QUEUED")
+        );
+        // EXEC 之前不会执行
+        assert!(backend.get("k").is_none());
+
+        let expected =
+            RespArray::new([simple("OK"), BulkString::from("v").into(), RespNull.into()]);
+        assert_eq!(send(&mut conn, &backend, &["exec"]).await?, expected.into());
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            BulkString::from("v").into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_abort() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+
+        assert_eq!(
+            send(&mut conn, &backend, &["discard"]).await?,
+            error("ERR DISCARD without MULTI")
+        );
+        send(&mut conn, &backend, &["multi"]).await?;
+        send(&mut conn, &backend, &["set", "k", "v"]).await?;
+        assert_eq!(send(&mut conn, &backend, &["discard"]).await?, simple("OK"));
+        assert!(backend.get("k").is_none());
+
+        // 有命令校验失败时整个事务都不执行
+        send(&mut conn, &backend, &["multi"]).await?;
+        send(&mut conn, &backend, &["set", "k", "v"]).await?;
+        let ret = send(&mut conn, &backend, &["set", "k"]).await?;
+        assert!(matches!(ret, RespFrame::Error(_)));
+        let ret = send(&mut conn, &backend, &["nosuchcommand"]).await?;
+        assert_eq!(ret, error("ERR unknown command"));
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            error("EXECABORT Transaction discarded because of previous errors.")
+        );
+        assert!(backend.get("k").is_none());
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            error("ERR EXEC without MULTI")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_connection_commands() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+
+        // PING 和 UNWATCH 入队，EXEC 时按顺序回复
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["ping"]).await?,
+            simple("QUEUED")
+        );
+        send(&mut conn, &backend, &["set", "k", "v"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["ping", "hi"]).await?,
+            simple("QUEUED")
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["unwatch"]).await?,
+            simple("QUEUED")
+        );
+        let expected = RespArray::new([
+            simple("PONG"),
+            simple("OK"),
+            BulkString::from("hi").into(),
+            simple("OK"),
+        ]);
+        assert_eq!(send(&mut conn, &backend, &["exec"]).await?, expected.into());
+
+        // 订阅和切换协议不允许在事务中执行，整个事务被放弃
+        for args in [
+            &["subscribe", "ch"][..],
+            &["psubscribe", "ch*"],
+            &["hello", "3"],
+        ] {
+            send(&mut conn, &backend, &["multi"]).await?;
+            assert_eq!(
+                send(&mut conn, &backend, args).await?,
+                error("ERR Command not allowed inside a transaction")
+            );
+            assert_eq!(
+                send(&mut conn, &backend, &["exec"]).await?,
+                error("EXECABORT Transaction discarded because of previous errors.")
+            );
+        }
+        assert!(!conn.subscribed());
+        assert_eq!(conn.protocol, 2);
+
+        // RESET 立即执行，放弃事务
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["reset"]).await?,
+            simple("RESET")
+        );
+        assert!(conn.transaction.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_keeps_connection() -> Result<()> {
+        let backend = Backend::new();
+        let mut conn = Connection::new().0;
+
+        let ret = send(&mut conn, &backend, &["set", "k"]).await?;
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.starts_with("ERR ")));
+        assert!(!conn.closed);
+        assert_eq!(
+            send(&mut conn, &backend, &["set", "k", "v"]).await?,
+            simple("OK")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_watch() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, mut other) = (Connection::new().0, Connection::new().0);
+        send(&mut other, &backend, &["set", "stock:42", "10"]).await?;
+
+        // 没有修改时正常执行
+        assert_eq!(
+            send(&mut conn, &backend, &["watch", "stock:42"]).await?,
+            simple("OK")
+        );
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["watch", "stock:42"]).await?,
+            error("ERR WATCH inside MULTI is not allowed")
+        );
+        send(&mut conn, &backend, &["set", "stock:42", "9"]).await?;
+        let expected = RespArray::new([simple("OK")]);
+        assert_eq!(send(&mut conn, &backend, &["exec"]).await?, expected.into());
+
+        // 其它连接修改之后 EXEC 返回 null array，EXEC 之后不再监视
+        send(&mut conn, &backend, &["watch", "stock:42", "other"]).await?;
+        send(&mut other, &backend, &["set", "stock:42", "8"]).await?;
+        send(&mut conn, &backend, &["multi"]).await?;
+        send(&mut conn, &backend, &["set", "stock:42", "7"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            RespArray::new([]).into()
+        );
+        assert_eq!(backend.get("stock:42"), Some(BulkString::from("8").into()));
+        send(&mut conn, &backend, &["multi"]).await?;
+        send(&mut conn, &backend, &["set", "stock:42", "7"]).await?;
+        let expected = RespArray::new([simple("OK")]);
+        assert_eq!(send(&mut conn, &backend, &["exec"]).await?, expected.into());
+
+        // UNWATCH 之后的修改不影响事务
+        send(&mut conn, &backend, &["watch", "stock:42"]).await?;
+        assert_eq!(send(&mut conn, &backend, &["unwatch"]).await?, simple("OK"));
+        send(&mut other, &backend, &["set", "stock:42", "6"]).await?;
+        send(&mut conn, &backend, &["multi"]).await?;
+        send(&mut conn, &backend, &["get", "stock:42"]).await?;
+        let expected = RespArray::new([BulkString::from("6").into()]);
+        assert_eq!(send(&mut conn, &backend, &["exec"]).await?, expected.into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_watch_delete_and_expire() -> Result<()> {
+        let backend = Backend::new();
+        let (mut conn, mut other) = (Connection::new().0, Connection::new().0);
+
+        // 其它类型的写入和删除也会让事务失败
+        send(&mut conn, &backend, &["watch", "zset"]).await?;
+        send(&mut other, &backend, &["zadd", "zset", "1", "a"]).await?;
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            RespArray::new([]).into()
+        );
+
+        send(&mut conn, &backend, &["watch", "zset"]).await?;
+        send(&mut other, &backend, &["del", "zset"]).await?;
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            RespArray::new([]).into()
+        );
+
+        // 监视期间过期的 key 也算被修改
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        backend.set("lock".to_string(), BulkString::from("token").into());
+        backend.expire_at("lock".to_string(), now + 20);
+        send(&mut conn, &backend, &["watch", "lock"]).await?;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        send(&mut conn, &backend, &["multi"]).await?;
+        assert_eq!(
+            send(&mut conn, &backend, &["exec"]).await?,
+            RespArray::new([]).into()
+        );
+
+        Ok(())
+    }
+
+    // 单线程的运行时，脚本不在运行时的线程上执行时其它连接才能执行 SCRIPT KILL
+    #[tokio::test]
+    async fn test_script_busy() -> Result<()> {
+        let backend = Backend::new();
+        backend.set_lua_time_limit(Duration::from_millis(10));
+        let runaway = tokio::spawn({
+            let backend = backend.clone();
+            async move {
+                let mut conn = Connection::new().0;
+                send(&mut conn, &backend, &["eval", "while true do end", "0"]).await
+            }
+        });
+        while !backend.script_busy() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // 脚本执行超时之后其它命令返回 BUSY，SCRIPT KILL 不需要等待脚本
+        let mut conn = Connection::new().0;
+        let ret = send(&mut conn, &backend, &["get", "k"]).await?;
+        assert!(matches!(ret, RespFrame::Error(e) if e.0.starts_with("BUSY")));
+        assert_eq!(
+            send(&mut conn, &backend, &["script", "kill"]).await?,
+            simple("OK")
+        );
+        assert_eq!(
+            runaway.await??,
+            error("ERR Script killed by user with SCRIPT KILL...")
+        );
+        assert_eq!(
+            send(&mut conn, &backend, &["get", "k"]).await?,
+            RespNull.into()
+        );
+
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ mod frame;
 mod integer;
 mod map;
 mod null;
+mod push;
 mod set;
 mod simple_error;
 mod simple_string;
@@ -21,7 +22,7 @@ const CRLF_LEN: usize = CRLF.len();
 
 pub use self::{
     array::RespArray, bulk_string::BulkString, frame::RespFrame, map::RespMap, null::RespNull,
-    set::RespSet, simple_error::SimpleError, simple_string::SimpleString,
+    push::RespPush, set::RespSet, simple_error::SimpleError, simple_string::SimpleString,
 };
 
 #[enum_dispatch]
@@ -130,7 +131,7 @@ fn calc_totoal_length(
     let mut total = end + CRLF_LEN;
     let mut data = &buf[total..];
     match prefix {
-        "*" | "~" => {
+        "*" | "~" | ">" => {
             // find nth CRLF in the buffer, for array and set, we need to find 1 CRLF for each element
             for _ in 0..len {
                 let len = RespFrame::expect_length(data)?;